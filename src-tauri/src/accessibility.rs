@@ -0,0 +1,320 @@
+// Only macOS exposes other apps' UI so far; elsewhere access is never
+// granted and every read fails
+#![cfg_attr(not(target_os = "macos"), allow(dead_code, unused_variables))]
+
+use serde::Serialize;
+
+use crate::error::{PetError, PetResult};
+use crate::occlusion::Rect;
+
+/// Whether macOS has granted this app Accessibility access, which reading
+/// other apps' UI needs.
+pub fn is_trusted() -> bool {
+    #[cfg(target_os = "macos")]
+    return macos::is_trusted();
+    #[cfg(not(target_os = "macos"))]
+    false
+}
+
+/// Ask macOS to show its Accessibility prompt, which points the user at
+/// System Settings. Does nothing once access is granted.
+pub fn prompt_for_trust() {
+    #[cfg(target_os = "macos")]
+    macos::prompt_for_trust();
+}
+
+pub fn require_trust() -> PetResult<()> {
+    if is_trusted() {
+        return Ok(());
+    }
+    prompt_for_trust();
+    Err(PetError::Platform(
+        "Desktop Pet needs Accessibility access (System Settings → Privacy & Security → Accessibility)"
+            .to_string(),
+    ))
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectedText {
+    pub text: String,
+    /// The app the text was selected in
+    pub app_name: String,
+}
+
+/// The text highlighted in whatever has keyboard focus, if any.
+pub fn selected_text() -> PetResult<Option<SelectedText>> {
+    require_trust()?;
+    #[cfg(target_os = "macos")]
+    {
+        let text = macos::Element::system_wide()
+            .and_then(|system| system.element("AXFocusedUIElement"))
+            .and_then(|focused| focused.string("AXSelectedText"))
+            .filter(|text| !text.trim().is_empty());
+        let app_name = active_win_pos_rs::get_active_window()
+            .map(|w| w.app_name)
+            .unwrap_or_default();
+        Ok(text.map(|text| SelectedText { text, app_name }))
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(None)
+}
+
+/// Roles that take typed text. Web pages report theirs the same way.
+const TEXT_ROLES: &[&str] = &["AXTextField", "AXTextArea", "AXComboBox", "AXSearchField"];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TextField {
+    pub role: String,
+    /// The field, in the pet window's coordinates
+    pub frame: Rect,
+    /// The caret or selection when the app reports it, same coordinates
+    pub caret: Option<Rect>,
+    pub app_name: String,
+}
+
+/// The text field with keyboard focus, if that's what has it. Without
+/// Accessibility access this is always None rather than a prompt, since it
+/// only decides where things pop up.
+pub fn focused_text_field(app: &tauri::AppHandle) -> PetResult<Option<TextField>> {
+    if !is_trusted() {
+        return Ok(None);
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let Some(focused) =
+            macos::Element::system_wide().and_then(|system| system.element("AXFocusedUIElement"))
+        else {
+            return Ok(None);
+        };
+        let role = focused.string("AXRole").unwrap_or_default();
+        if !TEXT_ROLES.contains(&role.as_str()) {
+            return Ok(None);
+        }
+        let Some(frame) = focused.frame() else {
+            return Ok(None);
+        };
+        let (origin_x, origin_y) = crate::occlusion::window_origin(app)?;
+        let app_name = active_win_pos_rs::get_active_window()
+            .map(|w| w.app_name)
+            .unwrap_or_default();
+        Ok(Some(TextField {
+            role,
+            frame: frame.offset(-origin_x, -origin_y),
+            caret: focused
+                .selection_bounds()
+                .map(|caret| caret.offset(-origin_x, -origin_y)),
+            app_name,
+        }))
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(None)
+}
+
+/// Where the user is typing, so popups can open next to it.
+#[tauri::command]
+pub async fn get_focused_text_field(app: tauri::AppHandle) -> PetResult<Option<TextField>> {
+    crate::run_blocking(move || focused_text_field(&app)).await?
+}
+
+/// Read the current selection without copying it. Fails with a Platform
+/// error (and shows the system prompt) until Accessibility access is granted.
+#[tauri::command]
+pub async fn get_selected_text() -> PetResult<Option<SelectedText>> {
+    crate::run_blocking(selected_text).await?
+}
+
+#[cfg(target_os = "macos")]
+pub mod macos {
+    use core_foundation::array::CFArray;
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::string::CFString;
+    use core_foundation_sys::array::CFArrayRef;
+    use core_foundation_sys::base::{CFTypeID, CFTypeRef};
+    use core_foundation_sys::dictionary::CFDictionaryRef;
+    use core_foundation_sys::string::CFStringRef;
+    use std::ffi::c_void;
+
+    use crate::occlusion::Rect;
+
+    /// kAXErrorSuccess
+    const AX_SUCCESS: i32 = 0;
+    // AXValueType
+    const AX_VALUE_CG_POINT: u32 = 1;
+    const AX_VALUE_CG_SIZE: u32 = 2;
+    const AX_VALUE_CG_RECT: u32 = 3;
+    /// Seconds to wait on an app before giving up; the default is six,
+    /// long enough for one hung app to stall every read
+    const MESSAGING_TIMEOUT: f32 = 0.25;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Pair {
+        a: f64,
+        b: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        static kAXTrustedCheckOptionPrompt: CFStringRef;
+        fn AXIsProcessTrusted() -> u8;
+        fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> u8;
+        fn AXUIElementGetTypeID() -> CFTypeID;
+        fn AXUIElementCreateSystemWide() -> CFTypeRef;
+        fn AXUIElementCreateApplication(pid: i32) -> CFTypeRef;
+        fn AXUIElementSetMessagingTimeout(element: CFTypeRef, timeout: f32) -> i32;
+        fn AXUIElementCopyParameterizedAttributeValue(
+            element: CFTypeRef,
+            attribute: CFStringRef,
+            parameter: CFTypeRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+        fn AXValueGetValue(value: CFTypeRef, kind: u32, out: *mut c_void) -> u8;
+        fn AXUIElementCopyAttributeValue(
+            element: CFTypeRef,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+    }
+
+    pub fn is_trusted() -> bool {
+        unsafe { AXIsProcessTrusted() != 0 }
+    }
+
+    pub fn prompt_for_trust() {
+        unsafe {
+            let key = CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt);
+            let options = CFDictionary::from_CFType_pairs(&[(key, CFBoolean::true_value())]);
+            AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef());
+        }
+    }
+
+    /// The point, size or rect wrapped in an AXValue.
+    fn unwrap_value<T: Default>(value: &CFType, kind: u32) -> Option<T> {
+        let mut out = T::default();
+        let ok =
+            unsafe { AXValueGetValue(value.as_CFTypeRef(), kind, (&mut out as *mut T).cast()) };
+        (ok != 0).then_some(out)
+    }
+
+    /// An accessibility element in another app's UI, released on drop.
+    pub struct Element(CFType);
+
+    impl Element {
+        fn wrap(element: CFTypeRef) -> Option<Self> {
+            (!element.is_null()).then(|| Self(unsafe { CFType::wrap_under_create_rule(element) }))
+        }
+
+        pub fn system_wide() -> Option<Self> {
+            Self::wrap(unsafe { AXUIElementCreateSystemWide() })
+        }
+
+        /// The root of an app's UI, by process id.
+        pub fn application(pid: i32) -> Option<Self> {
+            let element = Self::wrap(unsafe { AXUIElementCreateApplication(pid) })?;
+            unsafe { AXUIElementSetMessagingTimeout(element.0.as_CFTypeRef(), MESSAGING_TIMEOUT) };
+            Some(element)
+        }
+
+        pub fn attribute(&self, name: &str) -> Option<CFType> {
+            let name = CFString::new(name);
+            let mut value: CFTypeRef = std::ptr::null();
+            let error = unsafe {
+                AXUIElementCopyAttributeValue(
+                    self.0.as_CFTypeRef(),
+                    name.as_concrete_TypeRef(),
+                    &mut value,
+                )
+            };
+            (error == AX_SUCCESS && !value.is_null())
+                .then(|| unsafe { CFType::wrap_under_create_rule(value) })
+        }
+
+        /// An attribute that is itself an element, like the focused one.
+        pub fn element(&self, name: &str) -> Option<Element> {
+            let value = self.attribute(name)?;
+            (value.type_of() == unsafe { AXUIElementGetTypeID() }).then_some(Element(value))
+        }
+
+        pub fn string(&self, name: &str) -> Option<String> {
+            Some(self.attribute(name)?.downcast::<CFString>()?.to_string())
+        }
+
+        pub fn boolean(&self, name: &str) -> Option<bool> {
+            Some(self.attribute(name)?.downcast::<CFBoolean>()?.into())
+        }
+
+        /// The elements under this one, like a menu bar's items.
+        pub fn children(&self) -> Vec<Element> {
+            let Some(value) = self.attribute("AXChildren") else {
+                return Vec::new();
+            };
+            if value.type_of() != CFArray::<CFType>::type_id() {
+                return Vec::new();
+            }
+            let children: CFArray<CFType> =
+                unsafe { CFArray::wrap_under_get_rule(value.as_CFTypeRef() as CFArrayRef) };
+            children
+                .iter()
+                .filter(|child| child.type_of() == unsafe { AXUIElementGetTypeID() })
+                .map(|child| Element(child.clone()))
+                .collect()
+        }
+
+        fn pair(&self, name: &str, kind: u32) -> Option<Pair> {
+            unwrap_value(&self.attribute(name)?, kind)
+        }
+
+        /// Screen bounds of the selection, or of the caret when nothing is
+        /// selected. Not every app answers this.
+        pub fn selection_bounds(&self) -> Option<Rect> {
+            let range = self.attribute("AXSelectedTextRange")?;
+            let name = CFString::new("AXBoundsForRange");
+            let mut value: CFTypeRef = std::ptr::null();
+            let error = unsafe {
+                AXUIElementCopyParameterizedAttributeValue(
+                    self.0.as_CFTypeRef(),
+                    name.as_concrete_TypeRef(),
+                    range.as_CFTypeRef(),
+                    &mut value,
+                )
+            };
+            if error != AX_SUCCESS || value.is_null() {
+                return None;
+            }
+            let value = unsafe { CFType::wrap_under_create_rule(value) };
+            let rect: CGRect = unwrap_value(&value, AX_VALUE_CG_RECT)?;
+            // Some apps answer with an empty rect at the origin instead of failing
+            (rect.x != 0.0 || rect.y != 0.0).then_some(Rect {
+                x: rect.x,
+                y: rect.y,
+                width: rect.width,
+                height: rect.height,
+            })
+        }
+
+        /// Where the element sits on screen, in global screen points.
+        pub fn frame(&self) -> Option<Rect> {
+            let position = self.pair("AXPosition", AX_VALUE_CG_POINT)?;
+            let size = self.pair("AXSize", AX_VALUE_CG_SIZE)?;
+            Some(Rect {
+                x: position.a,
+                y: position.b,
+                width: size.a,
+                height: size.b,
+            })
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use serde::Serialize;
 
+use crate::error::{PetError, PetResult};
+
 #[derive(Serialize)]
 pub struct WindowInfo {
     pub app_name: String,
@@ -7,12 +9,17 @@ pub struct WindowInfo {
 }
 
 #[tauri::command]
-pub fn get_active_window_info() -> Result<WindowInfo, String> {
-    match active_win_pos_rs::get_active_window() {
+pub async fn get_active_window_info() -> PetResult<WindowInfo> {
+    // Window lookup goes through platform FFI that can take a while when the
+    // window server is busy, so keep it off the IPC threads
+    crate::run_blocking(|| match active_win_pos_rs::get_active_window() {
         Ok(window) => Ok(WindowInfo {
             app_name: window.app_name,
             window_title: window.title,
         }),
-        Err(()) => Err("Failed to get active window info".to_string()),
-    }
+        Err(()) => Err(PetError::Platform(
+            "Failed to get active window info".to_string(),
+        )),
+    })
+    .await?
 }
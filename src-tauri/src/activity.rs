@@ -0,0 +1,334 @@
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::dialogue::{self, DialogueResponse};
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+use crate::{milestones, notifications, occlusion, persist, scheduler};
+
+pub const ACTIVITY_FILE: &str = "activity.json";
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+/// Longest gap one sample may count for: battery saving skips every other
+/// one, but sleep and the lock screen shouldn't count as work.
+const MAX_SAMPLE_GAP: Duration = Duration::from_secs(75);
+/// Days of activity kept; a standup never looks further back.
+const KEEP_DAYS: usize = 7;
+/// Window titles kept per app and day, the longest-used first out.
+const MAX_TITLES: usize = 15;
+const MAX_TITLE_CHARS: usize = 120;
+/// Apps and titles shown to the model for a standup.
+const STANDUP_APPS: usize = 8;
+const STANDUP_TITLES: usize = 5;
+/// Before noon with less than this logged, a standup covers the last
+/// working day instead of today.
+const MORNING_MIN_SECONDS: u64 = 30 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct AppActivity {
+    seconds: u64,
+    /// Seconds per window title
+    titles: BTreeMap<String, u64>,
+}
+
+/// Time per app for one day, keyed by app name.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct DayActivity {
+    apps: BTreeMap<String, AppActivity>,
+}
+
+impl DayActivity {
+    fn total_seconds(&self) -> u64 {
+        self.apps.values().map(|a| a.seconds).sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ActivityData {
+    /// Keyed by local date (YYYY-MM-DD)
+    days: BTreeMap<String, DayActivity>,
+}
+
+/// Which apps and windows were in front each day, sampled in the backend so
+/// it keeps counting while the webview is hidden.
+pub struct ActivityStore {
+    data: Mutex<ActivityData>,
+    path: Option<PathBuf>,
+    dirty: AtomicBool,
+    last_sample: Mutex<Option<Instant>>,
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+impl ActivityStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, ACTIVITY_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+            dirty: AtomicBool::new(false),
+            last_sample: Mutex::new(None),
+        }
+    }
+
+    fn record(&self, app_name: &str, title: Option<&str>, seconds: u64) {
+        let mut data = self.data.lock().unwrap();
+        let activity = data
+            .days
+            .entry(today())
+            .or_default()
+            .apps
+            .entry(app_name.to_string())
+            .or_default();
+        activity.seconds += seconds;
+        if let Some(title) = title {
+            let title: String = title.chars().take(MAX_TITLE_CHARS).collect();
+            if !activity.titles.contains_key(&title) && activity.titles.len() >= MAX_TITLES {
+                let least = activity
+                    .titles
+                    .iter()
+                    .min_by_key(|(_, seconds)| **seconds)
+                    .map(|(title, _)| title.clone());
+                if let Some(least) = least {
+                    activity.titles.remove(&least);
+                }
+            }
+            *activity.titles.entry(title).or_default() += seconds;
+        }
+        while data.days.len() > KEEP_DAYS {
+            data.days.pop_first();
+        }
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+
+    /// Forget everything and delete the file, holding the lock so a flush
+    /// can't write it back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        *data = ActivityData::default();
+        self.dirty.store(false, Ordering::SeqCst);
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Write to disk if anything changed since the last flush.
+    pub fn flush(&self) {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        if let Some(path) = &self.path {
+            let data = self.data.lock().unwrap();
+            if let Err(e) = persist::save(path, &*data) {
+                eprintln!("Failed to save activity: {}", e);
+            }
+        }
+    }
+
+    /// The day a standup should cover: today, unless it's morning and
+    /// little has happened yet, then the last day with anything logged.
+    fn standup_day(&self) -> Option<(String, DayActivity)> {
+        let data = self.data.lock().unwrap();
+        let today = today();
+        let current = data.days.get(&today).filter(|day| day.total_seconds() > 0);
+        let morning = chrono::Local::now().hour() < 12;
+        match current {
+            Some(day) if !morning || day.total_seconds() >= MORNING_MIN_SECONDS => {
+                Some((today, day.clone()))
+            }
+            _ => data
+                .days
+                .iter()
+                .rev()
+                .find(|(date, day)| **date < today && day.total_seconds() > 0)
+                .map(|(date, day)| (date.clone(), day.clone()))
+                .or_else(|| current.map(|day| (today.clone(), day.clone()))),
+        }
+    }
+}
+
+fn sample_window(app: &tauri::AppHandle) -> Option<(String, String)> {
+    let window = active_win_pos_rs::get_active_window().ok()?;
+    // Time spent on the pet itself, or on an overlay the user excluded,
+    // isn't work
+    if window.app_name.is_empty()
+        || window.process_id == std::process::id() as u64
+        || occlusion::Exclusions::load(app).excludes(&window.app_name)
+    {
+        return None;
+    }
+    Some((window.app_name, window.title))
+}
+
+async fn sample(app: tauri::AppHandle) {
+    let _ = crate::run_blocking(move || {
+        let store = app.state::<ActivityStore>();
+        let now = Instant::now();
+        let elapsed = store
+            .last_sample
+            .lock()
+            .unwrap()
+            .replace(now)
+            .map(|last| now - last)
+            .unwrap_or(SAMPLE_INTERVAL);
+        if elapsed > MAX_SAMPLE_GAP {
+            // Back from sleep; count from here
+            return;
+        }
+        let Some((app_name, title)) = sample_window(&app) else {
+            return;
+        };
+        // Titles are only kept while they may be shared at all
+        let keep_title = app.state::<SettingsStore>().get().context.send_window_title;
+        let title = Some(title.trim()).filter(|t| keep_title && !t.is_empty());
+        store.record(&app_name, title, elapsed.as_secs());
+        milestones::note_if_all_nighter(&app);
+    })
+    .await;
+}
+
+async fn flush(app: tauri::AppHandle) {
+    let _ = crate::run_blocking(move || app.state::<ActivityStore>().flush()).await;
+}
+
+/// Sample the frontmost window every SAMPLE_INTERVAL, and save once a tick.
+pub fn spawn_tracker(app: tauri::AppHandle) {
+    scheduler::every(app.clone(), SAMPLE_INTERVAL, sample);
+    scheduler::every_tick(app, flush);
+}
+
+fn minutes(seconds: u64) -> String {
+    match seconds / 60 {
+        0 => "under a minute".to_string(),
+        m if m < 60 => format!("{}m", m),
+        m => format!("{}h {}m", m / 60, m % 60),
+    }
+}
+
+/// Apps longest-used first.
+fn by_time(day: &DayActivity) -> Vec<(&String, &AppActivity)> {
+    let mut apps: Vec<(&String, &AppActivity)> = day.apps.iter().collect();
+    apps.sort_by_key(|(_, activity)| std::cmp::Reverse(activity.seconds));
+    apps
+}
+
+/// A day's activity as lines for the prompt, longest-used apps first.
+fn describe(day: &DayActivity, with_titles: bool) -> String {
+    by_time(day)
+        .iter()
+        .take(STANDUP_APPS)
+        .map(|(name, activity)| {
+            let mut titles: Vec<(&String, &u64)> = activity.titles.iter().collect();
+            titles.sort_by_key(|(_, seconds)| std::cmp::Reverse(**seconds));
+            let titles: Vec<&str> = titles
+                .iter()
+                .take(STANDUP_TITLES)
+                .map(|(title, _)| title.as_str())
+                .collect();
+            if with_titles && !titles.is_empty() {
+                format!(
+                    "- {} ({}): {}",
+                    name,
+                    minutes(activity.seconds),
+                    titles.join(" | ")
+                )
+            } else {
+                format!("- {} ({})", name, minutes(activity.seconds))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Plain bullets from the numbers, for when the model can't be reached.
+fn offline_standup(day: &DayActivity) -> String {
+    by_time(day)
+        .iter()
+        .take(3)
+        .map(|(name, activity)| format!("• {} in {}", minutes(activity.seconds), name))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn day_label(date: &str) -> String {
+    if date == today() {
+        return "today".to_string();
+    }
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.format("%A").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// Three bullets on what the owner worked on, from the apps and windows
+/// that were in front. Covers today, or the last working day when asked
+/// in the morning.
+pub async fn standup(app: tauri::AppHandle) -> PetResult<DialogueResponse> {
+    let allowed = app.state::<SettingsStore>().get().context;
+    if !allowed.send_app_name {
+        return Err(PetError::InvalidInput(
+            "A standup needs app names, which aren't shared in Settings".to_string(),
+        ));
+    }
+    let (date, day) = app.state::<ActivityStore>().standup_day().ok_or_else(|| {
+        PetError::InvalidInput("Nothing logged yet to stand up about".to_string())
+    })?;
+    let trigger = format!(
+        "Time in each app {}, with the window titles seen most:\n{}",
+        day_label(&date),
+        describe(&day, allowed.send_window_title)
+    );
+    let mut response = dialogue::generate(
+        app,
+        String::new(),
+        String::new(),
+        trigger,
+        Some("standup".to_string()),
+        None,
+        None,
+        None,
+    )
+    .await?;
+    if response.offline {
+        response.text = offline_standup(&day);
+    }
+    Ok(response)
+}
+
+#[tauri::command]
+pub async fn generate_standup(app: tauri::AppHandle) -> PetResult<DialogueResponse> {
+    standup(app).await
+}
+
+/// The tray's "Morning Standup": have the cat say it and put it on the
+/// clipboard, ready to paste into the team channel.
+pub fn standup_from_tray(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match standup(app.clone()).await {
+            Ok(response) => {
+                let text = response.text.clone();
+                let copied = crate::run_blocking(move || {
+                    arboard::Clipboard::new().and_then(|mut c| c.set_text(text))
+                })
+                .await;
+                if !matches!(copied, Ok(Ok(()))) {
+                    eprintln!("Standup not copied to the clipboard");
+                }
+                notifications::announce(&app, &response.text);
+            }
+            Err(e) => notifications::announce(&app, &e.to_string()),
+        }
+    });
+}
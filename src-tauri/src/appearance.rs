@@ -0,0 +1,237 @@
+use image::RgbImage;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::{Emitter, Manager, Theme, WindowEvent};
+
+use crate::error::{PetError, PetResult};
+use crate::occlusion::Rect;
+
+/// How far around the pet to look, in points, when none is given.
+const DEFAULT_RADIUS: f64 = 96.0;
+/// Colors returned, most common first.
+const MAX_COLORS: usize = 3;
+/// Samples taken across the region in each direction.
+const SAMPLES_PER_SIDE: u32 = 48;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Appearance {
+    pub dark: bool,
+}
+
+fn appearance(theme: Theme) -> Appearance {
+    Appearance {
+        dark: theme == Theme::Dark,
+    }
+}
+
+/// Emit `appearance-changed` whenever the system switches between light and
+/// dark mode.
+pub fn init(app: &tauri::App) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let handle = app.handle().clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::ThemeChanged(theme) = event {
+            let _ = handle.emit("appearance-changed", appearance(*theme));
+        }
+    });
+}
+
+#[tauri::command(async)]
+pub fn get_system_appearance(app: tauri::AppHandle) -> PetResult<Appearance> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| PetError::Internal("Pet window not found".to_string()))?;
+    Ok(appearance(window.theme()?))
+}
+
+/// The decoded wallpaper, kept until the file changes; decoding a 5K photo
+/// every time the pet moves would be wasteful.
+#[derive(Default)]
+pub struct WallpaperCache(Mutex<Option<(PathBuf, SystemTime, Arc<RgbImage>)>>);
+
+fn wallpaper_path(app: &tauri::AppHandle) -> PetResult<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        // AppKit only answers on the main thread
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.run_on_main_thread(move || {
+            let _ = tx.send(macos::wallpaper_path());
+        })?;
+        rx.recv()
+            .ok()
+            .flatten()
+            .ok_or_else(|| PetError::Platform("Couldn't find the wallpaper".to_string()))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        Err(PetError::Platform(
+            "Reading the wallpaper is only supported on macOS".to_string(),
+        ))
+    }
+}
+
+fn wallpaper(app: &tauri::AppHandle) -> PetResult<Arc<RgbImage>> {
+    let path = wallpaper_path(app)?;
+    let modified = std::fs::metadata(&path)?.modified()?;
+    let cache = app.state::<WallpaperCache>();
+    let mut cached = cache.0.lock().unwrap();
+    if let Some((cached_path, cached_modified, image)) = cached.as_ref() {
+        if *cached_path == path && *cached_modified == modified {
+            return Ok(image.clone());
+        }
+    }
+    // Dynamic wallpapers are HEIC, which the image crate can't read
+    let image = image::open(&path)
+        .map_err(|e| PetError::Platform(format!("Couldn't read the wallpaper: {}", e)))?
+        .to_rgb8();
+    let image = Arc::new(image);
+    *cached = Some((path, modified, image.clone()));
+    Ok(image)
+}
+
+/// The main display in screen points. The wallpaper is drawn to fill it,
+/// cropping whichever dimension overflows.
+fn main_display(app: &tauri::AppHandle) -> PetResult<Rect> {
+    let monitor = app
+        .primary_monitor()?
+        .ok_or_else(|| PetError::Platform("No display found".to_string()))?;
+    let scale = monitor.scale_factor();
+    let position = monitor.position().to_logical::<f64>(scale);
+    let size = monitor.size().to_logical::<f64>(scale);
+    Ok(Rect {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WallpaperColors {
+    /// `#rrggbb`, most common first
+    pub colors: Vec<String>,
+    /// Average relative luminance, 0 (black) to 1 (white)
+    pub luminance: f64,
+    /// Whether light text and sprites read better here
+    pub dark: bool,
+}
+
+fn luminance([r, g, b]: [u8; 3]) -> f64 {
+    (0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64) / 255.0
+}
+
+/// Bucket the pixels in `region` (image pixels) by their top four bits per
+/// channel and average the biggest buckets.
+fn dominant_colors(image: &RgbImage, region: Rect) -> WallpaperColors {
+    let x0 = region.x.max(0.0) as u32;
+    let y0 = region.y.max(0.0) as u32;
+    let x1 = ((region.x + region.width) as u32).min(image.width());
+    let y1 = ((region.y + region.height) as u32).min(image.height());
+    let step_x = ((x1.saturating_sub(x0)) / SAMPLES_PER_SIDE).max(1);
+    let step_y = ((y1.saturating_sub(y0)) / SAMPLES_PER_SIDE).max(1);
+
+    let mut buckets: HashMap<[u8; 3], ([u64; 3], u64)> = HashMap::new();
+    let mut total_luminance = 0.0;
+    let mut samples = 0u64;
+    for y in (y0..y1).step_by(step_y as usize) {
+        for x in (x0..x1).step_by(step_x as usize) {
+            let pixel = image.get_pixel(x, y).0;
+            let key = pixel.map(|c| c >> 4);
+            let (sum, count) = buckets.entry(key).or_default();
+            for (total, c) in sum.iter_mut().zip(pixel) {
+                *total += c as u64;
+            }
+            *count += 1;
+            total_luminance += luminance(pixel);
+            samples += 1;
+        }
+    }
+
+    let mut buckets: Vec<_> = buckets.into_values().collect();
+    buckets.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let colors = buckets
+        .iter()
+        .take(MAX_COLORS)
+        .map(|(sum, count)| {
+            let [r, g, b] = sum.map(|total| (total / count) as u8);
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        })
+        .collect();
+    let luminance = if samples > 0 {
+        total_luminance / samples as f64
+    } else {
+        0.5
+    };
+    WallpaperColors {
+        colors,
+        luminance,
+        dark: luminance < 0.5,
+    }
+}
+
+fn sample(app: &tauri::AppHandle, x: f64, y: f64, radius: f64) -> PetResult<WallpaperColors> {
+    let image = wallpaper(app)?;
+    let display = main_display(app)?;
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| PetError::Internal("Pet window not found".to_string()))?;
+    let origin = window
+        .outer_position()?
+        .to_logical::<f64>(window.scale_factor()?);
+
+    // Window coordinates to the display, then to wallpaper pixels
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    let scale = (display.width / width).max(display.height / height);
+    let crop_x = (width * scale - display.width) / 2.0;
+    let crop_y = (height * scale - display.height) / 2.0;
+    let to_pixel =
+        |point: f64, display_start: f64, crop: f64| (point - display_start + crop) / scale;
+    let center_x = to_pixel(origin.x + x, display.x, crop_x);
+    let center_y = to_pixel(origin.y + y, display.y, crop_y);
+    let radius = radius / scale;
+    Ok(dominant_colors(
+        &image,
+        Rect {
+            x: center_x - radius,
+            y: center_y - radius,
+            width: radius * 2.0,
+            height: radius * 2.0,
+        },
+    ))
+}
+
+/// The dominant wallpaper colors around a point (pet window coordinates),
+/// so the frontend can keep the pet and its bubbles legible. Looks at the
+/// main display's wallpaper only.
+#[tauri::command]
+pub async fn sample_wallpaper_colors(
+    app: tauri::AppHandle,
+    x: f64,
+    y: f64,
+    radius: Option<f64>,
+) -> PetResult<WallpaperColors> {
+    let radius = radius.unwrap_or(DEFAULT_RADIUS).clamp(8.0, 1000.0);
+    crate::run_blocking(move || sample(&app, x, y, radius)).await?
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::{NSScreen, NSWorkspace};
+    use std::path::PathBuf;
+
+    pub fn wallpaper_path() -> Option<PathBuf> {
+        let mtm = MainThreadMarker::new()?;
+        let screen = NSScreen::mainScreen(mtm)?;
+        let url = NSWorkspace::sharedWorkspace().desktopImageURLForScreen(&screen)?;
+        Some(PathBuf::from(url.path()?.to_string()))
+    }
+}
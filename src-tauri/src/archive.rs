@@ -0,0 +1,252 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use tauri::{Emitter, Manager};
+
+use crate::countdowns::{Countdown, CountdownStore};
+use crate::crypto::{EncryptedBlob, MemoryKey};
+use crate::error::{PetError, PetResult};
+use crate::persist;
+use crate::memory::{self, ChatMemory, MemoryStore};
+use crate::moderation::{ModerationData, ModerationStore};
+use crate::mood::{self, MoodEngine};
+use crate::moods::MoodStore;
+use crate::notes::{Note, NotesStore};
+use crate::pets::{self, PetRegistry, PetsData};
+use crate::settings::{Settings, SettingsStore};
+use crate::sports::{Team, TeamStore};
+use crate::tickers::{Watchlist, WatchlistStore};
+use crate::trackers::{Tracker, TrackerStore};
+use crate::usage::UsageStore;
+use crate::watchers::{Watcher, WatcherStore};
+use crate::wrapped::{WrappedData, WrappedStore};
+
+/// Bump when the archive layout changes incompatibly. 2 is encrypted; 3
+/// brings every cat in the household.
+const ARCHIVE_VERSION: u32 = 3;
+/// Each cat's own files, kept as they are on disk.
+const PET_FILES: &[&str] = &[memory::MEMORY_FILE, mood::MOOD_FILE];
+const MIN_PASSPHRASE_LEN: usize = 8;
+/// Far more than years of a cat's data; anything bigger isn't an archive.
+const MAX_ARCHIVE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// The archive file: the `PetArchive` JSON, encrypted with a key derived
+/// from a passphrase, since it holds the chat memory that may be encrypted
+/// on disk.
+#[derive(Serialize, Deserialize)]
+struct SealedArchive {
+    version: u32,
+    archive: EncryptedBlob,
+}
+
+/// Everything needed to move a cat to another machine. Journal entries,
+/// achievements and event stats live in the webview's storage, so the
+/// frontend hands them in as `frontend` and gets them back on import.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PetArchive {
+    version: u32,
+    exported_at: String,
+    /// The chat memory of the cat that's out
+    memory: ChatMemory,
+    /// None in archives from before there was more than one cat
+    #[serde(default)]
+    pets: Option<PetsData>,
+    /// The other cats' files and the mood of the one that's out, by pet id
+    /// and file name. Memory is as stored, so encrypted memory needs the
+    /// passphrase it was encrypted with
+    #[serde(default)]
+    pet_files: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+    #[serde(default)]
+    notes: Vec<Note>,
+    #[serde(default)]
+    moods: BTreeMap<String, u8>,
+    #[serde(default)]
+    watchers: Vec<Watcher>,
+    #[serde(default)]
+    trackers: Vec<Tracker>,
+    #[serde(default)]
+    watchlist: Watchlist,
+    #[serde(default)]
+    teams: Vec<Team>,
+    #[serde(default)]
+    countdowns: Vec<Countdown>,
+    #[serde(default)]
+    wrapped: WrappedData,
+    #[serde(default)]
+    moderation: ModerationData,
+    #[serde(default)]
+    settings: Settings,
+    #[serde(default)]
+    usage: serde_json::Value,
+    #[serde(default)]
+    frontend: serde_json::Value,
+}
+
+fn check_passphrase(passphrase: &str) -> PetResult<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(PetError::InvalidInput(format!(
+            "The archive passphrase needs at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Write all pet data to a single archive at `path`, encrypted with
+/// `passphrase`.
+#[tauri::command]
+pub async fn export_pet_data(
+    app: tauri::AppHandle,
+    path: String,
+    passphrase: String,
+    frontend: Option<serde_json::Value>,
+) -> PetResult<()> {
+    check_passphrase(&passphrase)?;
+    crate::run_blocking(move || {
+        let memory_store = app.state::<MemoryStore>();
+        if memory_store.is_locked() {
+            return Err(PetError::InvalidInput(
+                "Unlock memory before exporting".to_string(),
+            ));
+        }
+
+        let household = app.state::<PetRegistry>().get();
+        let mut pet_files = BTreeMap::new();
+        for pet in &household.pets {
+            let mut files = BTreeMap::new();
+            for name in PET_FILES {
+                if pet.id == household.active && *name == memory::MEMORY_FILE {
+                    continue;
+                }
+                if let Some(value) = persist::load(&pets::pet_file(&app, &pet.id, name)?) {
+                    files.insert(name.to_string(), value);
+                }
+            }
+            pet_files.insert(pet.id.clone(), files);
+        }
+
+        let archive = PetArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: chrono::Local::now().to_rfc3339(),
+            memory: memory_store.snapshot(),
+            pets: Some(household),
+            pet_files,
+            notes: app.state::<NotesStore>().all(),
+            moods: app.state::<MoodStore>().all(),
+            watchers: app.state::<WatcherStore>().all(),
+            trackers: app.state::<TrackerStore>().all(),
+            watchlist: app.state::<WatchlistStore>().get(),
+            teams: app.state::<TeamStore>().all(),
+            countdowns: app.state::<CountdownStore>().all(),
+            wrapped: app.state::<WrappedStore>().get(),
+            moderation: app.state::<ModerationStore>().get(),
+            settings: app.state::<SettingsStore>().get(),
+            usage: app.state::<UsageStore>().export(),
+            frontend: frontend.unwrap_or_default(),
+        };
+        let sealed = SealedArchive {
+            version: ARCHIVE_VERSION,
+            archive: MemoryKey::new(&passphrase).encrypt(&serde_json::to_vec(&archive)?)?,
+        };
+        fs::write(PathBuf::from(path), serde_json::to_string(&sealed)?)?;
+        Ok(())
+    })
+    .await?
+}
+
+fn check_version(version: u32) -> PetResult<()> {
+    if version < 2 {
+        return Err(PetError::InvalidInput(
+            "That archive is from before archives were encrypted; export it again".to_string(),
+        ));
+    }
+    if version > ARCHIVE_VERSION {
+        return Err(PetError::InvalidInput(format!(
+            "This archive is from a newer version of Desktop Pet (format {})",
+            version
+        )));
+    }
+    Ok(())
+}
+
+/// Open the archive at `path` with `passphrase` and check it before
+/// anything is replaced.
+fn read_archive(path: PathBuf, passphrase: &str) -> PetResult<PetArchive> {
+    let mut raw = String::new();
+    fs::File::open(path)?
+        .take(MAX_ARCHIVE_BYTES + 1)
+        .read_to_string(&mut raw)?;
+    if raw.len() as u64 > MAX_ARCHIVE_BYTES {
+        return Err(PetError::InvalidInput(
+            "That file is too big to be a pet archive".to_string(),
+        ));
+    }
+    let sealed: SealedArchive = serde_json::from_str(&raw)
+        .map_err(|_| PetError::InvalidInput("That isn't a pet archive".to_string()))?;
+    check_version(sealed.version)?;
+    let json = MemoryKey::for_blob(passphrase, &sealed.archive)?.decrypt(&sealed.archive)?;
+    let archive: PetArchive = serde_json::from_slice(&json)?;
+    check_version(archive.version)?;
+    memory::check_limits(&archive.memory)?;
+    if let Some(household) = &archive.pets {
+        pets::check(household)?;
+    }
+    Ok(archive)
+}
+
+/// Replace all backend pet data with the archive at `path`. Returns the
+/// frontend section so the webview can restore its own stores.
+#[tauri::command]
+pub async fn import_pet_data(
+    app: tauri::AppHandle,
+    path: String,
+    passphrase: String,
+) -> PetResult<serde_json::Value> {
+    crate::run_blocking(move || {
+        let archive = read_archive(PathBuf::from(path), &passphrase)?;
+
+        if let Some(household) = archive.pets {
+            let active = household.pets.iter().find(|p| p.id == household.active).cloned();
+            let (memory, mood) = (app.state::<MemoryStore>(), app.state::<MoodEngine>());
+            memory.switch(&app, || {
+                mood.switch(&app, || {
+                    for pet in &household.pets {
+                        let files = archive.pet_files.get(&pet.id);
+                        for name in PET_FILES {
+                            let path = pets::pet_file(&app, &pet.id, name)?;
+                            match files.and_then(|files| files.get(*name)) {
+                                Some(value) => persist::save(&path, value)?,
+                                None => persist::remove(&path)?,
+                            }
+                        }
+                    }
+                    app.state::<PetRegistry>().replace(household)
+                })
+            })?;
+            crate::mood::refresh(&app);
+            app.emit("pet-switched", &active)?;
+        }
+        app.state::<MemoryStore>().replace(archive.memory)?;
+        app.state::<MemoryStore>().flush(&app);
+        app.state::<NotesStore>().replace(archive.notes);
+        crate::tray::refresh(&app);
+        app.state::<MoodStore>().replace(archive.moods);
+        app.state::<WatcherStore>().replace(archive.watchers);
+        app.state::<TrackerStore>().replace(archive.trackers);
+        app.state::<WatchlistStore>().replace(archive.watchlist);
+        app.state::<TeamStore>().replace(archive.teams);
+        app.state::<CountdownStore>().replace(archive.countdowns);
+        app.state::<WrappedStore>().replace(archive.wrapped);
+        app.state::<ModerationStore>().replace(archive.moderation);
+        app.state::<SettingsStore>().replace(archive.settings)?;
+        crate::shortcuts::register_all(&app);
+        if !archive.usage.is_null() {
+            app.state::<UsageStore>().import(archive.usage)?;
+        }
+        Ok(archive.frontend)
+    })
+    .await?
+}
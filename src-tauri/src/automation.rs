@@ -0,0 +1,283 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+use crate::shortcuts::ShortcutAction;
+use crate::{crypto, notifications, persist, tray};
+
+pub const TOKEN_FILE: &str = "automation.json";
+/// Chat messages arrive as small JSON bodies; anything bigger is a mistake.
+const MAX_BODY_BYTES: u64 = 16 * 1024;
+
+/// Kept out of settings.json so the token never ends up in an export archive.
+#[derive(Serialize, Deserialize)]
+struct StoredToken {
+    token: String,
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The localhost automation server and the bearer token guarding it.
+pub struct AutomationState {
+    token: Mutex<String>,
+    token_path: Option<PathBuf>,
+    server: Mutex<Option<Arc<Server>>>,
+}
+
+impl AutomationState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let token_path = persist::app_data_file(app, TOKEN_FILE).ok();
+        let token = match token_path.as_deref().and_then(persist::load::<StoredToken>) {
+            Some(stored) => stored.token,
+            None => {
+                let token = new_token();
+                if let Some(path) = &token_path {
+                    let _ = persist::save(
+                        path,
+                        &StoredToken {
+                            token: token.clone(),
+                        },
+                    );
+                }
+                token
+            }
+        };
+        Self {
+            token: Mutex::new(token),
+            token_path,
+            server: Mutex::new(None),
+        }
+    }
+
+    fn token(&self) -> String {
+        self.token.lock().unwrap().clone()
+    }
+
+    fn regenerate_token(&self) -> PetResult<String> {
+        let token = new_token();
+        if let Some(path) = &self.token_path {
+            persist::save(
+                path,
+                &StoredToken {
+                    token: token.clone(),
+                },
+            )?;
+        }
+        *self.token.lock().unwrap() = token.clone();
+        Ok(token)
+    }
+
+    fn is_running(&self) -> bool {
+        self.server.lock().unwrap().is_some()
+    }
+}
+
+/// Start or stop the server to match the current settings.
+pub fn apply(app: &tauri::AppHandle) -> PetResult<()> {
+    let settings = app.state::<SettingsStore>().get().automation;
+    let state = app.state::<AutomationState>();
+    let mut server = state.server.lock().unwrap();
+    if let Some(running) = server.take() {
+        running.unblock();
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    // Loopback only: this is for scripts on the same machine, not the network
+    let started = Arc::new(Server::http(("127.0.0.1", settings.port)).map_err(|e| {
+        PetError::Platform(format!("Couldn't listen on port {}: {}", settings.port, e))
+    })?);
+    *server = Some(started.clone());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for request in started.incoming_requests() {
+            // Each on its own, so a chat waiting on the model doesn't hold
+            // up /state or the triggers
+            let app = app.clone();
+            std::thread::spawn(move || handle(&app, request));
+        }
+    });
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ChatBody {
+    message: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetState {
+    #[serde(flatten)]
+    tray: tray::TrayStatus,
+    visible: bool,
+    menu_bar_only: bool,
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<std::io::Cursor<Vec<u8>>> {
+    let json = serde_json::to_string(body).unwrap_or_default();
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    Response::from_string(json)
+        .with_status_code(status)
+        .with_header(header)
+}
+
+fn authorized(app: &tauri::AppHandle, request: &Request) -> bool {
+    let expected = format!("Bearer {}", app.state::<AutomationState>().token());
+    request.headers().iter().any(|h| {
+        h.field.equiv("Authorization")
+            && crypto::constant_time_eq(h.value.as_str().as_bytes(), expected.as_bytes())
+    })
+}
+
+fn read_body<T: for<'de> Deserialize<'de>>(request: &mut Request) -> PetResult<T> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)?;
+    serde_json::from_str(&body)
+        .map_err(|e| PetError::InvalidInput(format!("Bad request body: {}", e)))
+}
+
+fn route(app: &tauri::AppHandle, request: &mut Request) -> PetResult<serde_json::Value> {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    match (request.method(), path.as_str()) {
+        (Method::Get, "/state") => {
+            let state = PetState {
+                tray: app.state::<tray::TrayState>().get(),
+                visible: app
+                    .get_webview_window("main")
+                    .and_then(|w| w.is_visible().ok())
+                    .unwrap_or(false),
+                menu_bar_only: crate::menubar::is_enabled(app),
+            };
+            Ok(serde_json::to_value(state)?)
+        }
+        (Method::Post, "/chat") => {
+            let ChatBody { message } = read_body(request)?;
+            let message = message.trim().to_string();
+            if message.is_empty() {
+                return Err(PetError::InvalidInput("Nothing to say".to_string()));
+            }
+            let response = tauri::async_runtime::block_on(crate::dialogue::generate(
+                app.clone(),
+                String::new(),
+                String::new(),
+                format!("user said: {}", message),
+                Some("chat".to_string()),
+                Some(message),
+                None,
+                None,
+            ))?;
+            // Scripts get the reply, and the cat says it out loud too
+            notifications::announce(app, &response.text);
+            Ok(serde_json::to_value(response)?)
+        }
+        (Method::Post, trigger) if trigger.starts_with("/trigger/") => {
+            let name = &trigger["/trigger/".len()..];
+            let action = match name {
+                "judge" => ShortcutAction::TriggerJudge,
+                "palette" => ShortcutAction::OpenPalette,
+                "hide" => ShortcutAction::ToggleHide,
+                "focus" => ShortcutAction::StartFocusSession,
+                _ => {
+                    return Err(PetError::InvalidInput(format!(
+                        "Unknown action \"{}\"",
+                        name
+                    )))
+                }
+            };
+            crate::shortcuts::handle(app, action);
+            Ok(serde_json::json!({ "ok": true }))
+        }
+        _ => Err(PetError::InvalidInput(format!("No route for {}", path))),
+    }
+}
+
+fn handle(app: &tauri::AppHandle, mut request: Request) {
+    let response = if !authorized(app, &request) {
+        json_response(
+            401,
+            &PetError::Auth("Missing or wrong automation token".to_string()),
+        )
+    } else {
+        match route(app, &mut request) {
+            Ok(body) => json_response(200, &body),
+            Err(e @ PetError::InvalidInput(_)) => json_response(400, &e),
+            Err(e) => json_response(500, &e),
+        }
+    };
+    let _ = request.respond(response);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+pub fn status(app: &tauri::AppHandle) -> AutomationStatus {
+    let settings = app.state::<SettingsStore>().get().automation;
+    let state = app.state::<AutomationState>();
+    AutomationStatus {
+        enabled: settings.enabled,
+        running: state.is_running(),
+        port: settings.port,
+        token: state.token(),
+    }
+}
+
+#[tauri::command(async)]
+pub fn get_automation_status(app: tauri::AppHandle) -> AutomationStatus {
+    status(&app)
+}
+
+#[tauri::command]
+pub async fn set_automation_enabled(
+    app: tauri::AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> PetResult<AutomationStatus> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.automation.enabled = enabled;
+        if let Some(port) = port {
+            settings.automation.port = port;
+        }
+        store.replace(settings)?;
+        apply(&app)?;
+        Ok(status(&app))
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn regenerate_automation_token(app: tauri::AppHandle) -> PetResult<AutomationStatus> {
+    crate::run_blocking(move || {
+        app.state::<AutomationState>().regenerate_token()?;
+        Ok(status(&app))
+    })
+    .await?
+}
@@ -0,0 +1,30 @@
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::error::{PetError, PetResult};
+
+pub fn is_enabled(app: &tauri::AppHandle) -> bool {
+    app.autolaunch().is_enabled().unwrap_or(false)
+}
+
+/// Add or remove the login item. Returns the state the OS reports afterwards.
+pub fn set_enabled(app: &tauri::AppHandle, enabled: bool) -> PetResult<bool> {
+    let launcher = app.autolaunch();
+    let result = if enabled {
+        launcher.enable()
+    } else {
+        launcher.disable()
+    };
+    result.map_err(|e| PetError::Platform(format!("Failed to update login item: {}", e)))?;
+    crate::tray::refresh(app);
+    Ok(is_enabled(app))
+}
+
+#[tauri::command]
+pub async fn get_launch_at_login(app: tauri::AppHandle) -> PetResult<bool> {
+    crate::run_blocking(move || is_enabled(&app)).await
+}
+
+#[tauri::command]
+pub async fn set_launch_at_login(app: tauri::AppHandle, enabled: bool) -> PetResult<bool> {
+    crate::run_blocking(move || set_enabled(&app, enabled)).await?
+}
@@ -0,0 +1,104 @@
+use desktop_pet_lib::ipc::{IpcRequest, IpcResponse};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage:
+  petctl say <text>
+  petctl nap
+  petctl remind <text> [--in 20m]
+  petctl stats";
+
+/// "90s", "20m", "2h" or a bare number of minutes, in seconds.
+fn parse_duration(value: &str) -> Result<u64, String> {
+    let unreadable = || format!("Can't read duration \"{}\"", value);
+    let (number, unit) = match value.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => value.split_at(i),
+        None => (value, "m"),
+    };
+    let number: u64 = number.parse().map_err(|_| unreadable())?;
+    let seconds = match unit {
+        "s" => Some(number),
+        "m" => number.checked_mul(60),
+        "h" => number.checked_mul(3600),
+        _ => return Err(unreadable()),
+    };
+    seconds.ok_or_else(|| format!("{} is too far off", value))
+}
+
+fn parse_args(args: &[String]) -> Result<IpcRequest, String> {
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    match command.as_str() {
+        "say" if !rest.is_empty() => Ok(IpcRequest::Say {
+            text: rest.join(" "),
+        }),
+        "nap" => Ok(IpcRequest::Nap),
+        "remind" => {
+            let mut words = Vec::new();
+            let mut in_seconds = None;
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                if arg == "--in" {
+                    let value = rest.next().ok_or("--in needs a duration like 20m")?;
+                    in_seconds = Some(parse_duration(value)?);
+                } else {
+                    words.push(arg.as_str());
+                }
+            }
+            if words.is_empty() {
+                return Err("Remind you of what?".to_string());
+            }
+            Ok(IpcRequest::Remind {
+                text: words.join(" "),
+                in_seconds,
+            })
+        }
+        "stats" => Ok(IpcRequest::Stats),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+#[cfg(unix)]
+fn send(request: &IpcRequest) -> Result<IpcResponse, String> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let path = desktop_pet_lib::ipc::socket_path();
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|_| "Desktop Pet isn't running (no socket at the usual place)".to_string())?;
+    let json = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    writeln!(stream, "{}", json).map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&line).map_err(|e| format!("Garbled reply: {}", e))
+}
+
+#[cfg(not(unix))]
+fn send(_request: &IpcRequest) -> Result<IpcResponse, String> {
+    Err("petctl only works on macOS and Linux".to_string())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = parse_args(&args).and_then(|request| send(&request));
+    match result {
+        Ok(response) => {
+            if let Some(message) = &response.message {
+                println!("{}", message);
+            }
+            if let Some(data) = &response.data {
+                println!("{}", serde_json::to_string_pretty(data).unwrap_or_default());
+            }
+            if response.ok {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
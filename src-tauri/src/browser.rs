@@ -0,0 +1,153 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+
+/// osascript can stall behind the first Automation prompt; give up rather
+/// than hold up the cat's line.
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_TITLE_CHARS: usize = 120;
+
+/// Modes where knowing the page makes the comment better. Chat and search
+/// are about what the user typed, not what they're looking at.
+const CONTEXT_MODES: &[&str] = &["judge", "spontaneous", "react"];
+
+#[derive(Clone, Copy)]
+enum Dialect {
+    Safari,
+    /// Chrome and the browsers built on it, Arc included
+    Chromium,
+}
+
+/// Browsers that answer AppleScript, by the name macOS reports for them.
+const BROWSERS: &[(&str, Dialect)] = &[
+    ("Safari", Dialect::Safari),
+    ("Safari Technology Preview", Dialect::Safari),
+    ("Google Chrome", Dialect::Chromium),
+    ("Google Chrome Canary", Dialect::Chromium),
+    ("Arc", Dialect::Chromium),
+    ("Brave Browser", Dialect::Chromium),
+    ("Microsoft Edge", Dialect::Chromium),
+];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserTab {
+    pub browser: String,
+    /// Without query string or fragment, which is where tokens tend to live
+    pub url: String,
+    pub title: String,
+}
+
+impl BrowserTab {
+    pub fn describe(&self) -> String {
+        format!("Browser tab: \"{}\" ({}).", self.title, self.url)
+    }
+}
+
+fn dialect(app_name: &str) -> Option<Dialect> {
+    BROWSERS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(app_name))
+        .map(|(_, dialect)| *dialect)
+}
+
+fn script(app_name: &str, dialect: Dialect) -> String {
+    let (tab, title) = match dialect {
+        Dialect::Safari => ("current tab", "name"),
+        Dialect::Chromium => ("active tab", "title"),
+    };
+    format!(
+        "tell application \"{app}\"\n\
+            if (count of windows) is 0 then return \"\"\n\
+            set t to {tab} of front window\n\
+            return (URL of t) & linefeed & ({title} of t)\n\
+        end tell",
+        app = app_name,
+        tab = tab,
+        title = title,
+    )
+}
+
+/// Run an AppleScript, killing it if it doesn't answer in time.
+fn run_script(source: &str) -> PetResult<String> {
+    let mut child = std::process::Command::new("osascript")
+        .args(["-e", source])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    let started = Instant::now();
+    while child.try_wait()?.is_none() {
+        if started.elapsed() > SCRIPT_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(PetError::Platform("The browser didn't answer".to_string()));
+        }
+        std::thread::sleep(Duration::from_millis(25));
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        // Usually Automation access was refused in System Settings
+        return Err(PetError::Platform(format!(
+            "Couldn't ask the browser for its tab ({})",
+            output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn strip_url(url: &str) -> String {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    url[..end].to_string()
+}
+
+/// The front tab of `app_name`, if it's a browser we know how to ask.
+pub fn active_tab(app_name: &str) -> PetResult<Option<BrowserTab>> {
+    let Some(dialect) = dialect(app_name) else {
+        return Ok(None);
+    };
+    if !cfg!(target_os = "macos") {
+        return Ok(None);
+    }
+    let output = run_script(&script(app_name, dialect))?;
+    let Some((url, title)) = output.split_once('\n') else {
+        return Ok(None);
+    };
+    if url.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(BrowserTab {
+        browser: app_name.to_string(),
+        url: strip_url(url.trim()),
+        title: title.trim().chars().take(MAX_TITLE_CHARS).collect(),
+    }))
+}
+
+/// The tab to mention in a prompt, when the mode and privacy settings call
+/// for one. Call off the async runtime; it shells out.
+pub fn tab_for_prompt(app: &tauri::AppHandle, mode: &str, app_name: &str) -> Option<BrowserTab> {
+    let allowed = app.state::<SettingsStore>().get().context;
+    if !allowed.send_browser_tab || !CONTEXT_MODES.contains(&mode) {
+        return None;
+    }
+    active_tab(app_name).ok().flatten()
+}
+
+/// The front tab's URL and title when `app_name` (or the active app) is
+/// Safari, Chrome, Arc or another Chromium browser. The first call for each
+/// browser shows macOS's Automation prompt.
+#[tauri::command]
+pub async fn get_browser_context(app_name: Option<String>) -> PetResult<Option<BrowserTab>> {
+    crate::run_blocking(move || {
+        let app_name = match app_name {
+            Some(name) => name,
+            None => active_win_pos_rs::get_active_window()
+                .map(|w| w.app_name)
+                .map_err(|()| PetError::Platform("Failed to get active window info".to_string()))?,
+        };
+        active_tab(&app_name)
+    })
+    .await?
+}
@@ -0,0 +1,128 @@
+use rand::distributions::{Distribution, WeightedIndex};
+
+/// Rough bucket for the app the user is in, used to pick relevant lines.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AppCategory {
+    Code,
+    Browser,
+    Chat,
+    Media,
+    Design,
+    Office,
+    Other,
+}
+
+pub fn categorize_app(app_name: &str) -> AppCategory {
+    let name = app_name.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| name.contains(n));
+
+    if has(&["code", "xcode", "terminal", "iterm", "warp", "cursor", "zed", "vim", "intellij", "pycharm", "webstorm", "sublime", "ghostty"]) {
+        AppCategory::Code
+    } else if has(&["safari", "chrome", "firefox", "arc", "brave", "edge", "opera"]) {
+        AppCategory::Browser
+    } else if has(&["slack", "discord", "messages", "telegram", "whatsapp", "teams", "zoom", "mail"]) {
+        AppCategory::Chat
+    } else if has(&["spotify", "music", "youtube", "netflix", "vlc", "tv", "podcasts", "iina"]) {
+        AppCategory::Media
+    } else if has(&["figma", "sketch", "photoshop", "illustrator", "affinity", "blender", "procreate"]) {
+        AppCategory::Design
+    } else if has(&["word", "excel", "powerpoint", "pages", "numbers", "keynote", "notion", "obsidian", "notes", "docs"]) {
+        AppCategory::Office
+    } else {
+        AppCategory::Other
+    }
+}
+
+/// A pre-written line. Empty tag lists match anything.
+struct Phrase {
+    text: &'static str,
+    weight: u32,
+    modes: &'static [&'static str],
+    apps: &'static [AppCategory],
+    times: &'static [&'static str],
+    moods: &'static [&'static str],
+}
+
+const fn line(text: &'static str) -> Phrase {
+    Phrase {
+        text,
+        weight: 1,
+        modes: &[],
+        apps: &[],
+        times: &[],
+        moods: &[],
+    }
+}
+
+use AppCategory::*;
+
+const PHRASES: &[Phrase] = &[
+    // Generic filler, low weight so tagged lines win when they apply
+    Phrase { weight: 1, ..line("Mrrrow. Just checking in.") },
+    Phrase { weight: 1, ..line("I was going to say something clever, but I forgot.") },
+    Phrase { weight: 1, ..line("Your screen is warm. I approve.") },
+    Phrase { weight: 1, ..line("I see you working hard. Or hardly working.") },
+    Phrase { weight: 1, ..line("Something moved. It was probably nothing. Probably.") },
+    // App categories
+    Phrase { weight: 3, apps: &[Code], ..line("Another bug? Just knock it off the desk.") },
+    Phrase { weight: 3, apps: &[Code], ..line("I could sit on the keyboard. It might help.") },
+    Phrase { weight: 3, apps: &[Code], ..line("Semicolons everywhere. Where are the treats?") },
+    Phrase { weight: 3, apps: &[Browser], ..line("So many tabs. So few of them about me.") },
+    Phrase { weight: 3, apps: &[Browser], ..line("Is this research or wandering? I know wandering.") },
+    Phrase { weight: 3, apps: &[Chat], ..line("Tell them your cat says hello.") },
+    Phrase { weight: 3, apps: &[Chat], ..line("Humans and their endless meowing at each other.") },
+    Phrase { weight: 3, apps: &[Media], ..line("Is there a cat in this one? There should be.") },
+    Phrase { weight: 3, apps: &[Media], ..line("Good. A break. I endorse breaks.") },
+    Phrase { weight: 3, apps: &[Design], ..line("Make it pop. Like a cat off a counter.") },
+    Phrase { weight: 3, apps: &[Design], ..line("That pixel is slightly crooked. I noticed.") },
+    Phrase { weight: 3, apps: &[Office], ..line("Spreadsheets. My natural predator.") },
+    Phrase { weight: 3, apps: &[Office], ..line("Write something about me. I'm a compelling subject.") },
+    // Time of day
+    Phrase { weight: 3, times: &["morning"], ..line("Morning. Breakfast was late, for the record.") },
+    Phrase { weight: 3, times: &["afternoon"], ..line("Prime napping hours and you're still typing.") },
+    Phrase { weight: 3, times: &["evening"], ..line("The sun is going down. Zoomies soon.") },
+    Phrase { weight: 4, times: &["night", "late night"], ..line("It's late. Even I'm going to bed.") },
+    Phrase { weight: 4, times: &["late night"], ..line("Nobody good is awake at this hour. Except me.") },
+    // Mood
+    Phrase { weight: 4, moods: &["sleepy"], ..line("Too sleepy to judge you properly. Tomorrow.") },
+    Phrase { weight: 4, moods: &["playful"], ..line("Chase me. Or the cursor. Either works.") },
+    Phrase { weight: 4, moods: &["grumpy"], ..line("Hmph. I'm not talking to you right now.") },
+    Phrase { weight: 4, moods: &["hungry"], ..line("Is it dinner time? It feels like dinner time.") },
+    // Modes that normally need the network
+    Phrase { weight: 5, modes: &["judge"], ..line("I'm judging you silently today. It's worse.") },
+    Phrase { weight: 5, modes: &["judge"], ..line("No comment. And that says everything.") },
+    Phrase { weight: 5, modes: &["chat"], ..line("Mhm. I'm listening. Mostly.") },
+    Phrase { weight: 5, modes: &["chat"], ..line("My thoughts are fuzzy right now. Try me later.") },
+    Phrase { weight: 5, modes: &["search"], ..line("I can't reach the internet. Maybe it's napping too.") },
+    Phrase { weight: 5, modes: &["search"], ..line("No answers right now. The web is out of treats.") },
+    Phrase { weight: 5, modes: &["achievement"], ..line("Look at you. I'd be proud if I cared. I care.") },
+    Phrase { weight: 5, modes: &["journal"], ..line("Dear diary. Today I sat in a sunbeam. The end.") },
+];
+
+/// Pick a weighted random line matching the mode, app, time of day and mood.
+/// Tagged lines that don't match are excluded; untagged lines always qualify.
+pub fn pick(mode: &str, app_name: &str, time_of_day: &str, mood: Option<&str>) -> String {
+    let category = categorize_app(app_name);
+    let mode_specific = PHRASES.iter().any(|p| p.modes.contains(&mode));
+
+    let candidates: Vec<&Phrase> = PHRASES
+        .iter()
+        .filter(|p| {
+            // Modes with dedicated lines only use those; others use the general pool
+            if mode_specific {
+                p.modes.contains(&mode)
+            } else {
+                p.modes.is_empty()
+            }
+        })
+        .filter(|p| p.apps.is_empty() || p.apps.contains(&category))
+        .filter(|p| p.times.is_empty() || p.times.contains(&time_of_day))
+        .filter(|p| p.moods.is_empty() || mood.is_some_and(|m| p.moods.contains(&m)))
+        .collect();
+
+    let weights = candidates.iter().map(|p| p.weight);
+    match WeightedIndex::new(weights) {
+        Ok(dist) => candidates[dist.sample(&mut rand::thread_rng())].text.to_string(),
+        Err(_) => PHRASES[0].text.to_string(),
+    }
+}
@@ -0,0 +1,340 @@
+use base64::Engine;
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::{PetError, PetResult};
+use crate::{opener, persist, tray, wrapped};
+
+pub const CARDS_DIR: &str = "cards";
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 360;
+const BORDER: u32 = 6;
+const PADDING: u32 = 28;
+/// The sprite's first frame, blown up with nearest-neighbour so it stays crisp.
+const SPRITE_SIZE: u32 = 192;
+const MAX_STATS: usize = 5;
+const QUOTE_LINES: usize = 2;
+
+const PAPER: Rgba<u8> = Rgba([255, 248, 240, 255]);
+const INK: Rgba<u8> = Rgba([51, 51, 51, 255]);
+const FADED: Rgba<u8> = Rgba([136, 136, 136, 255]);
+const ACCENT: Rgba<u8> = Rgba([232, 163, 60, 255]);
+const PANEL: Rgba<u8> = Rgba([255, 236, 214, 255]);
+
+/// Glyphs are 5 pixels wide and 7 tall, one byte per row with the leftmost
+/// pixel in bit 4. Text is drawn in capitals, like an old game's menus.
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '?' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+        '\'' | '‘' | '’' => [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '"' | '“' | '”' => [0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '-' | '–' | '—' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        ';' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '&' => [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '*' => [0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00],
+        '=' => [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '@' => [0x0E, 0x11, 0x17, 0x15, 0x17, 0x10, 0x0F],
+        '·' => [0x00, 0x00, 0x00, 0x0C, 0x0C, 0x00, 0x00],
+        // Anything the font doesn't cover (emoji, accents) is left blank
+        _ => [0; 7],
+    }
+}
+
+/// Width in pixels of `text` drawn at `scale`, with one blank column
+/// between characters.
+fn text_width(text: &str, scale: u32) -> u32 {
+    let chars = text.chars().count() as u32;
+    (chars * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale
+}
+
+/// How many characters fit in `width` pixels at `scale`.
+fn chars_fitting(width: u32, scale: u32) -> usize {
+    ((width / scale + 1) / (GLYPH_WIDTH + 1)) as usize
+}
+
+fn draw_text(card: &mut RgbaImage, x: u32, y: u32, text: &str, scale: u32, color: Rgba<u8>) {
+    for (i, c) in text.chars().enumerate() {
+        let left = x + i as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (0x10 >> col) == 0 {
+                    continue;
+                }
+                fill(
+                    card,
+                    left + col * scale,
+                    y + row as u32 * scale,
+                    scale,
+                    scale,
+                    color,
+                );
+            }
+        }
+    }
+}
+
+fn fill(card: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..(y + height).min(card.height()) {
+        for px in x..(x + width).min(card.width()) {
+            card.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Greedy word wrap into at most `max_lines`, ending in "..." if cut short.
+fn wrap(text: &str, max_chars: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let word = truncate(word, max_chars);
+        if current.is_empty() {
+            current = word;
+        } else if current.chars().count() + 1 + word.chars().count() <= max_chars {
+            current.push(' ');
+            current.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut current, word));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            *last = truncate(&format!("{} ...", last), max_chars);
+        }
+    }
+    lines
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardStat {
+    pub label: String,
+    pub value: String,
+}
+
+/// What goes on the card. Stats, level and the sprite live in the webview,
+/// so it hands them in.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PetCardInput {
+    pub name: String,
+    pub breed: String,
+    pub color: String,
+    pub level: u32,
+    #[serde(default)]
+    pub stats: Vec<CardStat>,
+    /// A favourite line; defaults to the funniest judge-mode roast
+    #[serde(default)]
+    pub quote: Option<String>,
+    /// Base64 PNG sprite sheet of the pet's idle animation; defaults to the
+    /// built-in orange cat
+    #[serde(default)]
+    pub sprite: Option<String>,
+}
+
+/// First frame of a one-row sheet of square frames.
+fn sprite_frame(sprite: Option<&str>) -> PetResult<RgbaImage> {
+    let bytes = match sprite {
+        Some(encoded) => {
+            let encoded = encoded.split_once(',').map_or(encoded, |(_, data)| data);
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| PetError::InvalidInput(format!("Bad sprite: {}", e)))?
+        }
+        None => tray::IDLE_SHEET.to_vec(),
+    };
+    let sheet = image::load_from_memory_with_format(&bytes, ImageFormat::Png)
+        .map_err(|e| PetError::InvalidInput(format!("Bad sprite: {}", e)))?
+        .to_rgba8();
+    let size = sheet.height().min(sheet.width());
+    Ok(imageops::crop_imm(&sheet, 0, 0, size, size).to_image())
+}
+
+fn render(input: &PetCardInput, quote: Option<&str>) -> PetResult<RgbaImage> {
+    let mut card = RgbaImage::from_pixel(WIDTH, HEIGHT, INK);
+    fill(
+        &mut card,
+        BORDER,
+        BORDER,
+        WIDTH - BORDER * 2,
+        HEIGHT - BORDER * 2,
+        PAPER,
+    );
+
+    // Sprite panel on the left
+    let frame = sprite_frame(input.sprite.as_deref())?;
+    let sprite = imageops::resize(&frame, SPRITE_SIZE, SPRITE_SIZE, FilterType::Nearest);
+    let panel = SPRITE_SIZE + 16;
+    fill(&mut card, PADDING, PADDING, panel, panel, PANEL);
+    imageops::overlay(
+        &mut card,
+        &sprite,
+        (PADDING + 8) as i64,
+        (PADDING + 8) as i64,
+    );
+    let level = format!("LV {}", input.level);
+    let level_x = PADDING + (panel.saturating_sub(text_width(&level, 3))) / 2;
+    draw_text(&mut card, level_x, PADDING + panel + 12, &level, 3, ACCENT);
+
+    // Name, breed and stats on the right
+    let left = PADDING * 2 + panel;
+    let column = WIDTH - left - PADDING;
+    let name = truncate(&input.name, chars_fitting(column, 4));
+    draw_text(&mut card, left, PADDING, &name, 4, INK);
+    let kind = format!("{} · {}", input.breed, input.color);
+    draw_text(
+        &mut card,
+        left,
+        PADDING + 40,
+        &truncate(&kind, chars_fitting(column, 2)),
+        2,
+        FADED,
+    );
+    fill(&mut card, left, PADDING + 62, column, 3, ACCENT);
+    for (i, stat) in input.stats.iter().take(MAX_STATS).enumerate() {
+        let y = PADDING + 78 + i as u32 * 24;
+        // The value gets up to half the row, the label what's left
+        let value = truncate(&stat.value, chars_fitting(column / 2, 2));
+        let value_width = text_width(&value, 2);
+        let label = truncate(
+            &stat.label,
+            chars_fitting(column.saturating_sub(value_width + 12), 2),
+        );
+        draw_text(&mut card, left, y, &label, 2, INK);
+        draw_text(
+            &mut card,
+            left + column.saturating_sub(value_width),
+            y,
+            &value,
+            2,
+            ACCENT,
+        );
+    }
+
+    // Quote along the bottom
+    if let Some(quote) = quote {
+        let width = WIDTH - PADDING * 2;
+        let lines = wrap(
+            &format!("\"{}\"", quote),
+            chars_fitting(width, 2),
+            QUOTE_LINES,
+        );
+        let line_height = (GLYPH_HEIGHT + 3) * 2;
+        let top = HEIGHT - PADDING - lines.len() as u32 * line_height + 6;
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(
+                &mut card,
+                PADDING,
+                top + i as u32 * line_height,
+                line,
+                2,
+                INK,
+            );
+        }
+    }
+    Ok(card)
+}
+
+/// Render the pet's trading card to a PNG in the app data dir, open it and
+/// return its path for sharing.
+#[tauri::command]
+pub async fn export_pet_card(app: tauri::AppHandle, card: PetCardInput) -> PetResult<String> {
+    let quote = match card.quote.clone().filter(|q| !q.trim().is_empty()) {
+        Some(quote) => Some(quote),
+        None => wrapped::favourite_roast(&app).await,
+    };
+    let slug: String = card
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let path: PathBuf = persist::app_data_file(&app, CARDS_DIR)?.join(format!(
+        "{}-{}.png",
+        slug.trim_matches('-'),
+        chrono::Local::now().format("%Y-%m-%d")
+    ));
+    let path = crate::run_blocking(move || {
+        let image = render(&card, quote.as_deref())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        image
+            .save_with_format(&path, ImageFormat::Png)
+            .map_err(|e| PetError::Storage(format!("Couldn't save the card: {}", e)))?;
+        Ok::<_, PetError>(path)
+    })
+    .await??;
+    opener::open_path(&path)?;
+    Ok(path.to_string_lossy().into_owned())
+}
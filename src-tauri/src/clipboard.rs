@@ -0,0 +1,298 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::dialogue::{self, DialogueResponse};
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+use crate::{scheduler, tray};
+
+/// How often the clipboard is looked at while watching is on.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// An offer the user ignored is forgotten after this long.
+const OFFER_TTL: Duration = Duration::from_secs(120);
+const PREVIEW_CHARS: usize = 80;
+const MAX_URL_CHARS: usize = 2048;
+const MIN_ARTICLE_CHARS: usize = 400;
+const MIN_ARTICLE_WORDS: usize = 60;
+const MIN_CODE_LINES: usize = 3;
+
+/// Never offered, whatever their shape; these mean a secret was probably copied.
+const SECRET_MARKERS: &[&str] = &[
+    "-----begin",
+    "password",
+    "passwd",
+    "api_key",
+    "apikey",
+    "api-key",
+    "access_token",
+    "client_secret",
+    "private_key",
+    "bearer ",
+];
+/// Prefixes of common API tokens, checked word by word.
+const TOKEN_PREFIXES: &[&str] = &[
+    "sk-",
+    "ghp_",
+    "gho_",
+    "github_pat_",
+    "xoxb-",
+    "xoxp-",
+    "akia",
+];
+
+const CODE_MARKERS: &[&str] = &[
+    "fn ",
+    "def ",
+    "function ",
+    "class ",
+    "import ",
+    "#include",
+    "const ",
+    "let ",
+    "var ",
+    "return ",
+    "=>",
+    "->",
+    "::",
+    "func ",
+    "pub ",
+    "#!/",
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipKind {
+    Url,
+    Article,
+    Code,
+}
+
+impl ClipKind {
+    fn suggestion(self) -> &'static str {
+        match self {
+            ClipKind::Url => "Want me to sum up that link?",
+            ClipKind::Article => "That's a lot of words. Want the short version?",
+            ClipKind::Code => "Want me to explain that code?",
+        }
+    }
+}
+
+/// What was copied, held in memory only until it's used, replaced or expires.
+struct Clip {
+    id: String,
+    kind: ClipKind,
+    text: String,
+    app_name: String,
+    copied_at: Instant,
+}
+
+#[derive(Default)]
+pub struct ClipboardState {
+    /// Hash of the last clipboard text seen, so unchanged text isn't re-offered
+    last_seen: Mutex<Option<u64>>,
+    clip: Mutex<Option<Clip>>,
+}
+
+impl ClipboardState {
+    fn forget(&self) {
+        *self.last_seen.lock().unwrap() = None;
+        *self.clip.lock().unwrap() = None;
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipOffer {
+    pub id: String,
+    pub kind: ClipKind,
+    pub preview: String,
+    pub chars: usize,
+    pub suggestion: String,
+}
+
+fn hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn looks_secret(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    SECRET_MARKERS.iter().any(|marker| lower.contains(marker))
+        || lower
+            .split_whitespace()
+            .any(|word| TOKEN_PREFIXES.iter().any(|prefix| word.starts_with(prefix)))
+}
+
+fn looks_like_code(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() < MIN_CODE_LINES {
+        return false;
+    }
+    let code_lines = lines
+        .iter()
+        .filter(|line| {
+            let trimmed = line.trim();
+            line.starts_with("    ")
+                || line.starts_with('\t')
+                || trimmed.ends_with(['{', '}', ';', ':', ')'])
+                || CODE_MARKERS.iter().any(|m| trimmed.contains(m))
+        })
+        .count();
+    code_lines * 2 >= lines.len()
+}
+
+/// What kind of copy this is, if it's one the cat can help with at all.
+/// Anything else (a word, a phone number, a token) is left alone.
+fn classify(text: &str) -> Option<ClipKind> {
+    let trimmed = text.trim();
+    if (trimmed.starts_with("https://") || trimmed.starts_with("http://"))
+        && !trimmed.contains(char::is_whitespace)
+        && trimmed.len() <= MAX_URL_CHARS
+    {
+        return Some(ClipKind::Url);
+    }
+    if looks_like_code(trimmed) {
+        return Some(ClipKind::Code);
+    }
+    let words = trimmed.split_whitespace().count();
+    if trimmed.chars().count() >= MIN_ARTICLE_CHARS
+        && words >= MIN_ARTICLE_WORDS
+        && trimmed.contains(". ")
+    {
+        return Some(ClipKind::Article);
+    }
+    None
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= PREVIEW_CHARS {
+        return flat;
+    }
+    let cut: String = flat.chars().take(PREVIEW_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn read_clipboard() -> Option<(String, String)> {
+    let text = arboard::Clipboard::new().ok()?.get_text().ok()?;
+    // Whoever is frontmost when the copy is noticed is almost always where it came from
+    let app_name = active_win_pos_rs::get_active_window()
+        .map(|w| w.app_name)
+        .unwrap_or_default();
+    Some((text, app_name))
+}
+
+async fn poll(app: tauri::AppHandle) {
+    let settings = app.state::<SettingsStore>().get().clipboard;
+    if !settings.enabled {
+        return;
+    }
+    let Ok(Some((text, app_name))) = crate::run_blocking(read_clipboard).await else {
+        return;
+    };
+    let state = app.state::<ClipboardState>();
+    let seen = hash(&text);
+    {
+        let mut last_seen = state.last_seen.lock().unwrap();
+        let first_look = last_seen.is_none();
+        if last_seen.replace(seen) == Some(seen) || first_look {
+            // Whatever was already copied when watching started isn't offered
+            return;
+        }
+    }
+
+    let ignored = settings
+        .ignored_apps
+        .iter()
+        .any(|ignored| ignored.eq_ignore_ascii_case(&app_name));
+    if ignored || text.chars().count() > settings.max_chars || looks_secret(&text) {
+        return;
+    }
+    let Some(kind) = classify(&text).filter(|k| settings.allowed_kinds.contains(k)) else {
+        return;
+    };
+
+    let id = format!("{:016x}", seen);
+    let offer = ClipOffer {
+        id: id.clone(),
+        kind,
+        preview: preview(&text),
+        chars: text.chars().count(),
+        suggestion: kind.suggestion().to_string(),
+    };
+    *state.clip.lock().unwrap() = Some(Clip {
+        id,
+        kind,
+        text,
+        app_name,
+        copied_at: Instant::now(),
+    });
+    let _ = app.emit("clipboard-offer", offer);
+}
+
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    scheduler::every(app, POLL_INTERVAL, poll);
+}
+
+pub fn is_enabled(app: &tauri::AppHandle) -> bool {
+    app.state::<SettingsStore>().get().clipboard.enabled
+}
+
+/// The kill switch: turning it off also drops anything already picked up.
+pub fn set_enabled(app: &tauri::AppHandle, enabled: bool) -> PetResult<()> {
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.clipboard.enabled = enabled;
+    store.replace(settings)?;
+    app.state::<ClipboardState>().forget();
+    let _ = app.emit("clipboard-watch-changed", enabled);
+    tray::refresh(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_clipboard_watch(app: tauri::AppHandle, enabled: bool) -> PetResult<()> {
+    crate::run_blocking(move || set_enabled(&app, enabled)).await?
+}
+
+/// Act on an offer: summarize the link or article, or explain the code.
+/// The copied text is only sent to the model here, after the user said yes.
+#[tauri::command]
+pub async fn ask_about_clipboard(app: tauri::AppHandle, id: String) -> PetResult<DialogueResponse> {
+    let clip = app
+        .state::<ClipboardState>()
+        .clip
+        .lock()
+        .unwrap()
+        .take()
+        .filter(|clip| clip.id == id && clip.copied_at.elapsed() < OFFER_TTL)
+        .ok_or_else(|| PetError::InvalidInput("That copy has expired".to_string()))?;
+    let (mode, request) = match clip.kind {
+        // Only search mode can go and read the page
+        ClipKind::Url => ("search", format!("Summarize this page: {}", clip.text)),
+        ClipKind::Article => ("summarize", clip.text),
+        ClipKind::Code => ("explain", clip.text),
+    };
+    dialogue::generate(
+        app,
+        clip.app_name,
+        String::new(),
+        "clipboard".to_string(),
+        Some(mode.to_string()),
+        Some(request),
+        None,
+        None,
+    )
+    .await
+}
+
+/// Decline an offer without using it.
+#[tauri::command(async)]
+pub fn dismiss_clipboard_offer(state: tauri::State<'_, ClipboardState>) {
+    *state.clip.lock().unwrap() = None;
+}
@@ -0,0 +1,300 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+
+use crate::persist;
+
+/// In the app data dir. Takes the keys below in snake_case, e.g.
+/// `anthropic_api_key = "sk-ant-..."`.
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Where a value came from, lowest precedence first.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Source {
+    /// A `.env` next to the project, for development
+    DotEnv,
+    /// `config.toml` in the app data dir
+    File,
+    /// The process environment
+    Env,
+    /// `--anthropic-api-key ...` and friends on the command line
+    Cli,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    AnthropicApiKey,
+    AnthropicFallbackModels,
+    FinnhubApiKey,
+    SupabaseUrl,
+    SupabaseAnonKey,
+}
+
+const KEYS: &[Key] = &[
+    Key::AnthropicApiKey,
+    Key::AnthropicFallbackModels,
+    Key::FinnhubApiKey,
+    Key::SupabaseUrl,
+    Key::SupabaseAnonKey,
+];
+
+impl Key {
+    /// The name in config.toml; `--` plus this with dashes on the command line.
+    fn name(self) -> &'static str {
+        match self {
+            Key::AnthropicApiKey => "anthropic_api_key",
+            Key::AnthropicFallbackModels => "anthropic_fallback_models",
+            Key::FinnhubApiKey => "finnhub_api_key",
+            Key::SupabaseUrl => "supabase_url",
+            Key::SupabaseAnonKey => "supabase_anon_key",
+        }
+    }
+
+    /// The environment variable, as the `.env` has always used.
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Key::AnthropicApiKey => "ANTHROPIC_API_KEY",
+            Key::AnthropicFallbackModels => "ANTHROPIC_FALLBACK_MODELS",
+            Key::FinnhubApiKey => "FINNHUB_API_KEY",
+            Key::SupabaseUrl => "VITE_SUPABASE_URL",
+            Key::SupabaseAnonKey => "VITE_SUPABASE_ANON_KEY",
+        }
+    }
+
+    fn flag(self) -> String {
+        format!("--{}", self.name().replace('_', "-"))
+    }
+
+    fn is_secret(self) -> bool {
+        matches!(self, Key::AnthropicApiKey | Key::FinnhubApiKey)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        KEYS.iter().copied().find(|key| key.name() == name)
+    }
+}
+
+#[derive(Default)]
+struct Config {
+    /// Every layer's value for each key; the highest source wins
+    values: HashMap<Key, Vec<(Source, String)>>,
+    /// `--config <path>` replaces the app data dir's file
+    file_override: Option<PathBuf>,
+    file: Option<PathBuf>,
+    /// Why config.toml couldn't be used, or which of its keys were ignored
+    file_problem: Option<String>,
+}
+
+impl Config {
+    fn set(&mut self, key: Key, source: Source, value: String) {
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            return;
+        }
+        let layers = self.values.entry(key).or_default();
+        layers.retain(|(s, _)| *s != source);
+        layers.push((source, value));
+        layers.sort_by_key(|(s, _)| *s);
+    }
+
+    fn get(&self, key: Key) -> Option<&(Source, String)> {
+        self.values.get(&key).and_then(|layers| layers.last())
+    }
+}
+
+static CONFIG: OnceLock<RwLock<Config>> = OnceLock::new();
+
+fn config() -> &'static RwLock<Config> {
+    CONFIG.get_or_init(Default::default)
+}
+
+fn read_dotenv(config: &mut Config) {
+    // From the project root in `tauri dev` (the cwd is src-tauri), then the
+    // usual search up from the cwd
+    let files = [
+        dotenvy::from_path_iter("../.env").ok(),
+        dotenvy::from_filename_iter(".env").ok(),
+    ];
+    for (name, value) in files.into_iter().flatten().rev().flatten().flatten() {
+        if let Some(key) = KEYS.iter().find(|key| key.env_var() == name) {
+            config.set(*key, Source::DotEnv, value);
+        }
+    }
+}
+
+fn read_args(config: &mut Config, args: &[String]) {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        if flag == "--config" {
+            config.file_override = inline.or_else(|| args.next().cloned()).map(PathBuf::from);
+        } else if let Some(key) = KEYS.iter().find(|key| key.flag() == flag) {
+            if let Some(value) = inline.or_else(|| args.next().cloned()) {
+                config.set(*key, Source::Cli, value);
+            }
+        }
+        // Anything else is for Tauri or a deep link
+    }
+}
+
+/// Read the `.env`, the environment and the command line. Called first
+/// thing in main; config.toml is added by `load_file` once the app data dir
+/// is known.
+pub fn init(args: &[String]) {
+    let mut config = config().write().unwrap();
+    read_dotenv(&mut config);
+    for key in KEYS {
+        if let Ok(value) = std::env::var(key.env_var()) {
+            config.set(*key, Source::Env, value);
+        }
+    }
+    read_args(&mut config, args);
+}
+
+/// Set the file layer from `text`. Returns the names that were ignored.
+fn read_file(config: &mut Config, text: &str) -> Result<Vec<String>, toml::de::Error> {
+    let table: toml::Table = toml::from_str(text)?;
+    let mut ignored = Vec::new();
+    for (name, value) in table {
+        let value = match value {
+            toml::Value::String(s) => Some(s),
+            // fallback models may be written as a list
+            toml::Value::Array(items) => items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .map(|items| items.join(",")),
+            _ => None,
+        };
+        match (Key::from_name(&name), value) {
+            (Some(key), Some(value)) => config.set(key, Source::File, value),
+            _ => ignored.push(name),
+        }
+    }
+    Ok(ignored)
+}
+
+/// Add config.toml from the app data dir (or `--config`). A missing file
+/// is fine; a broken one is reported by `get_config_sources`.
+pub fn load_file(app: &tauri::AppHandle) {
+    let mut config = config().write().unwrap();
+    let path = match config.file_override.clone() {
+        Some(path) => Some(path),
+        None => persist::app_data_file(app, CONFIG_FILE).ok(),
+    };
+    let Some(path) = path else {
+        return;
+    };
+    config.file = Some(path.clone());
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            config.file_problem = Some(format!("Couldn't read it: {}", e));
+            return;
+        }
+    };
+    match read_file(&mut config, &text) {
+        Ok(ignored) => {
+            if !ignored.is_empty() {
+                config.file_problem = Some(format!("Ignored: {}", ignored.join(", ")));
+            }
+        }
+        Err(e) => config.file_problem = Some(format!("Not valid TOML: {}", e)),
+    }
+}
+
+/// The winning value for `key`, if any layer set one.
+pub fn get(key: Key) -> Option<String> {
+    config()
+        .read()
+        .unwrap()
+        .get(key)
+        .map(|(_, value)| value.clone())
+}
+
+/// The Anthropic key, as nearly every model call needs it.
+pub fn anthropic_api_key() -> Option<String> {
+    get(Key::AnthropicApiKey)
+}
+
+/// Enough of a secret to tell two apart, never the whole thing.
+fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 12 {
+        return "•".repeat(chars.len().min(8));
+    }
+    let head: String = chars[..7].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigValue {
+    pub key: &'static str,
+    pub env_var: &'static str,
+    pub flag: String,
+    /// The layer in effect; None when nothing set it
+    pub source: Option<Source>,
+    /// Secrets are masked
+    pub value: Option<String>,
+    /// Lower layers that also set it and lost
+    pub overridden: Vec<Source>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSources {
+    pub file: Option<String>,
+    pub file_problem: Option<String>,
+    pub values: Vec<ConfigValue>,
+}
+
+/// Which layer each setting came from, to debug a key that "isn't picked
+/// up". Secret values are masked.
+#[tauri::command(async)]
+pub fn get_config_sources() -> ConfigSources {
+    let config = config().read().unwrap();
+    let values = KEYS
+        .iter()
+        .map(|key| {
+            let layers = config.values.get(key).cloned().unwrap_or_default();
+            let winner = layers.last().cloned();
+            ConfigValue {
+                key: key.name(),
+                env_var: key.env_var(),
+                flag: key.flag(),
+                source: winner.as_ref().map(|(source, _)| *source),
+                value: winner.map(
+                    |(_, value)| {
+                        if key.is_secret() {
+                            mask(&value)
+                        } else {
+                            value
+                        }
+                    },
+                ),
+                overridden: layers
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .map(|(source, _)| *source)
+                    .collect(),
+            }
+        })
+        .collect();
+    ConfigSources {
+        file: config
+            .file
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned()),
+        file_problem: config.file_problem.clone(),
+        values,
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::{notifications, persist, scheduler};
+
+pub const COUNTDOWNS_FILE: &str = "countdowns.json";
+/// Days-left values worth speaking up about.
+const MILESTONES: [i64; 3] = [30, 7, 1];
+/// Local hour after which milestone mentions go out.
+const MENTION_HOUR: u32 = 9;
+/// How far ahead countdowns are worth bringing up in morning chatter.
+const BRIEFING_HORIZON_DAYS: i64 = 30;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Countdown {
+    pub id: String,
+    /// "Lisbon trip", "Tax deadline", "Launch"
+    pub label: String,
+    /// Local date (YYYY-MM-DD)
+    pub date: String,
+    /// Unix millis
+    pub created_at: i64,
+    /// Days-left milestone that was last announced, so each one fires once
+    pub last_milestone: Option<i64>,
+}
+
+impl Countdown {
+    fn days_left(&self, today: NaiveDate) -> Option<i64> {
+        let date = NaiveDate::parse_from_str(&self.date, "%Y-%m-%d").ok()?;
+        Some((date - today).num_days())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountdownStatus {
+    #[serde(flatten)]
+    pub countdown: Countdown,
+    pub days_left: i64,
+}
+
+pub struct CountdownStore {
+    countdowns: Mutex<Vec<Countdown>>,
+    path: Option<PathBuf>,
+}
+
+impl CountdownStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, COUNTDOWNS_FILE).ok();
+        let countdowns = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            countdowns: Mutex::new(countdowns),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut countdowns = self.countdowns.lock().unwrap();
+        *countdowns = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, countdowns: &[Countdown]) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &countdowns);
+        }
+    }
+
+    pub fn all(&self) -> Vec<Countdown> {
+        self.countdowns.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, new_countdowns: Vec<Countdown>) {
+        let mut countdowns = self.countdowns.lock().unwrap();
+        *countdowns = new_countdowns;
+        self.save(&countdowns);
+    }
+
+    fn add(&self, label: String, date: NaiveDate) -> Countdown {
+        // Adding something 7 days out shouldn't trigger a "7 days until" right away
+        let days_left = (date - chrono::Local::now().date_naive()).num_days();
+        let countdown = Countdown {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+            date: date.format("%Y-%m-%d").to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            last_milestone: MILESTONES.contains(&days_left).then_some(days_left),
+        };
+        let mut countdowns = self.countdowns.lock().unwrap();
+        countdowns.push(countdown.clone());
+        self.save(&countdowns);
+        countdown
+    }
+
+    fn remove(&self, id: &str) -> bool {
+        let mut countdowns = self.countdowns.lock().unwrap();
+        let before = countdowns.len();
+        countdowns.retain(|c| c.id != id);
+        let removed = countdowns.len() != before;
+        if removed {
+            self.save(&countdowns);
+        }
+        removed
+    }
+
+    /// Upcoming countdowns, soonest first. Passed dates are left out.
+    pub fn upcoming(&self, today: NaiveDate) -> Vec<CountdownStatus> {
+        let mut upcoming: Vec<CountdownStatus> = self
+            .all()
+            .into_iter()
+            .filter_map(|countdown| {
+                let days_left = countdown.days_left(today)?;
+                (days_left >= 0).then_some(CountdownStatus {
+                    countdown,
+                    days_left,
+                })
+            })
+            .collect();
+        upcoming.sort_by_key(|c| c.days_left);
+        upcoming
+    }
+
+    /// Mark today's milestones as announced and drop countdowns whose date
+    /// has passed. Returns the countdowns that just hit a milestone.
+    fn take_milestones(&self, today: NaiveDate) -> Vec<CountdownStatus> {
+        let mut countdowns = self.countdowns.lock().unwrap();
+        let before = countdowns.len();
+        countdowns.retain(|c| c.days_left(today).is_some_and(|d| d >= 0));
+        let mut changed = countdowns.len() != before;
+
+        let mut hit = Vec::new();
+        for countdown in countdowns.iter_mut() {
+            let Some(days_left) = countdown.days_left(today) else {
+                continue;
+            };
+            if MILESTONES.contains(&days_left) && countdown.last_milestone != Some(days_left) {
+                countdown.last_milestone = Some(days_left);
+                changed = true;
+                hit.push(CountdownStatus {
+                    countdown: countdown.clone(),
+                    days_left,
+                });
+            }
+        }
+        if changed {
+            self.save(&countdowns);
+        }
+        hit
+    }
+}
+
+fn describe(status: &CountdownStatus) -> String {
+    match status.days_left {
+        0 => format!("{} is today", status.countdown.label),
+        1 => format!("{} is tomorrow", status.countdown.label),
+        n => format!("{} days until {}", n, status.countdown.label),
+    }
+}
+
+/// One line for the morning context, e.g. "Coming up: 5 days until Lisbon
+/// trip; Launch is tomorrow." None when nothing is within the horizon.
+pub fn briefing(app: &tauri::AppHandle) -> Option<String> {
+    let today = chrono::Local::now().date_naive();
+    let items: Vec<String> = app
+        .state::<CountdownStore>()
+        .upcoming(today)
+        .iter()
+        .filter(|c| c.days_left <= BRIEFING_HORIZON_DAYS)
+        .map(describe)
+        .collect();
+    (!items.is_empty()).then(|| format!("Coming up: {}.", items.join("; ")))
+}
+
+async fn mention_milestones(app: tauri::AppHandle) {
+    let now = chrono::Local::now();
+    if now.hour() < MENTION_HOUR {
+        return;
+    }
+    let today = now.date_naive();
+    let handle = app.clone();
+    let hit =
+        match crate::run_blocking(move || handle.state::<CountdownStore>().take_milestones(today))
+            .await
+        {
+            Ok(hit) => hit,
+            Err(_) => return,
+        };
+    for status in hit {
+        let line = match status.days_left {
+            1 => format!(
+                "{} is tomorrow! Are you ready? I'm not.",
+                status.countdown.label
+            ),
+            n => format!(
+                "{} days until {}. Just so you know.",
+                n, status.countdown.label
+            ),
+        };
+        notifications::announce(&app, &line);
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, mention_milestones);
+}
+
+#[tauri::command(async)]
+pub fn list_countdowns(store: tauri::State<'_, CountdownStore>) -> Vec<CountdownStatus> {
+    store.upcoming(chrono::Local::now().date_naive())
+}
+
+#[tauri::command]
+pub async fn add_countdown(
+    app: tauri::AppHandle,
+    label: String,
+    date: String,
+) -> PetResult<Countdown> {
+    let label = label.trim().to_string();
+    if label.is_empty() {
+        return Err(PetError::InvalidInput("Counting down to what?".to_string()));
+    }
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| PetError::InvalidInput("Dates look like 2025-07-14".to_string()))?;
+    if date < chrono::Local::now().date_naive() {
+        return Err(PetError::InvalidInput(
+            "That date already happened".to_string(),
+        ));
+    }
+    crate::run_blocking(move || app.state::<CountdownStore>().add(label, date)).await
+}
+
+#[tauri::command]
+pub async fn remove_countdown(app: tauri::AppHandle, id: String) -> PetResult<()> {
+    let removed = crate::run_blocking(move || app.state::<CountdownStore>().remove(&id)).await?;
+    if removed {
+        Ok(())
+    } else {
+        Err(PetError::InvalidInput("No such countdown".to_string()))
+    }
+}
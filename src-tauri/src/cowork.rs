@@ -0,0 +1,422 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::{notifications, relay, scheduler, tray};
+
+const TABLE: &str = "cowork_sessions";
+/// How often the relay is asked whether a friend started, stopped or
+/// reacted to a session.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_MINUTES: u32 = 25;
+const MAX_MINUTES: u32 = 180;
+/// How long after a session ends the friend's reaction is waited for.
+const REACTION_WINDOW_MS: i64 = 10 * 60 * 1000;
+/// The only reactions that can be sent, so nothing else ever shows up on
+/// a friend's screen.
+const REACTIONS: &[&str] = &["🐾", "🔥", "💪", "🎉", "😺", "😴"];
+
+/// A pet as the friends system knows it.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoworkPet {
+    pub id: String,
+    pub name: String,
+    pub breed: String,
+    pub color: String,
+}
+
+/// A `cowork_sessions` row. Whoever started it is the host.
+#[derive(Serialize, Deserialize)]
+struct Row {
+    #[serde(skip_serializing)]
+    id: String,
+    host_pet_id: String,
+    host_name: String,
+    host_breed: String,
+    host_color: String,
+    guest_pet_id: String,
+    guest_name: String,
+    guest_breed: String,
+    guest_color: String,
+    #[serde(skip_serializing)]
+    started_at: String,
+    ends_at: String,
+    #[serde(skip_serializing)]
+    ended_at: Option<String>,
+    #[serde(skip_serializing)]
+    host_reaction: Option<String>,
+    #[serde(skip_serializing)]
+    guest_reaction: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+    /// Both cats are napping; the focus session is on
+    Active,
+    /// Over, and waiting for reactions
+    Ended,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoworkSession {
+    pub id: String,
+    pub friend: CoworkPet,
+    /// Whether this side started it
+    pub hosting: bool,
+    /// Unix millis
+    pub started_at: i64,
+    pub ends_at: i64,
+    pub phase: Phase,
+    pub my_reaction: Option<String>,
+    pub friend_reaction: Option<String>,
+    /// Unix millis when it ended here, to stop waiting for a reaction
+    #[serde(skip)]
+    ended_at: Option<i64>,
+}
+
+/// This pet's id on the relay and the session it's in, if any. Nothing is
+/// saved: after a restart the poll finds a running session again.
+#[derive(Default)]
+pub struct CoworkState {
+    pet_id: Mutex<Option<String>>,
+    session: Mutex<Option<CoworkSession>>,
+}
+
+impl CoworkState {
+    fn pet_id(&self) -> Option<String> {
+        self.pet_id.lock().unwrap().clone()
+    }
+
+    fn session(&self) -> Option<CoworkSession> {
+        self.session.lock().unwrap().clone()
+    }
+}
+
+fn millis(timestamp: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.timestamp_millis())
+        .unwrap_or(0)
+}
+
+fn rfc3339(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+impl Row {
+    /// The row as seen from `me`'s side, None if `me` isn't in it.
+    fn session_for(&self, me: &str) -> Option<CoworkSession> {
+        let hosting = self.host_pet_id == me;
+        if !hosting && self.guest_pet_id != me {
+            return None;
+        }
+        let friend = if hosting {
+            CoworkPet {
+                id: self.guest_pet_id.clone(),
+                name: self.guest_name.clone(),
+                breed: self.guest_breed.clone(),
+                color: self.guest_color.clone(),
+            }
+        } else {
+            CoworkPet {
+                id: self.host_pet_id.clone(),
+                name: self.host_name.clone(),
+                breed: self.host_breed.clone(),
+                color: self.host_color.clone(),
+            }
+        };
+        let (mine, theirs) = if hosting {
+            (&self.host_reaction, &self.guest_reaction)
+        } else {
+            (&self.guest_reaction, &self.host_reaction)
+        };
+        let ends_at = millis(&self.ends_at);
+        let over = self.ended_at.is_some() || ends_at <= chrono::Utc::now().timestamp_millis();
+        Some(CoworkSession {
+            id: self.id.clone(),
+            friend,
+            hosting,
+            started_at: millis(&self.started_at),
+            ends_at,
+            phase: if over { Phase::Ended } else { Phase::Active },
+            my_reaction: mine.clone(),
+            friend_reaction: theirs.clone(),
+            ended_at: self.ended_at.as_deref().map(millis),
+        })
+    }
+}
+
+async fn fetch(query: &[(&str, String)]) -> PetResult<Vec<Row>> {
+    let (url, key) = relay::table(TABLE)?;
+    relay::send(relay::request(reqwest::Method::GET, &url, &key).query(query))
+        .await?
+        .json()
+        .await
+        .map_err(|e| PetError::Parse(e.to_string()))
+}
+
+async fn patch(id: &str, body: serde_json::Value) -> PetResult<()> {
+    let (url, key) = relay::table(TABLE)?;
+    relay::send(
+        relay::request(reqwest::Method::PATCH, &url, &key)
+            .query(&[("id", format!("eq.{}", id))])
+            .json(&body),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Whether both pets have added each other, so a stranger who knows a pet
+/// id can't put their cat on someone's screen.
+async fn is_mutual(a: &str, b: &str) -> PetResult<bool> {
+    let (url, key) = relay::table("friendships")?;
+    let rows: Vec<serde_json::Value> =
+        relay::send(relay::request(reqwest::Method::GET, &url, &key).query(&[
+            ("select", "pet_id".to_string()),
+            (
+                "or",
+                format!(
+                    "(and(pet_id.eq.{a},friend_id.eq.{b}),and(pet_id.eq.{b},friend_id.eq.{a}))"
+                ),
+            ),
+        ]))
+        .await?
+        .json()
+        .await
+        .map_err(|e| PetError::Parse(e.to_string()))?;
+    Ok(rows.len() >= 2)
+}
+
+fn set(app: &tauri::AppHandle, session: Option<CoworkSession>) {
+    *app.state::<CoworkState>().session.lock().unwrap() = session.clone();
+    let _ = app.emit("cowork-changed", session);
+}
+
+/// Start the local side of `session`: focus on and the friend's cat over.
+fn begin(app: &tauri::AppHandle, session: CoworkSession) {
+    tray::set_focus_session(app, true);
+    set(app, Some(session));
+}
+
+/// The session is over here; focus off and ask for a reaction.
+fn finish(app: &tauri::AppHandle, mut session: CoworkSession) {
+    session.phase = Phase::Ended;
+    session
+        .ended_at
+        .get_or_insert(chrono::Utc::now().timestamp_millis());
+    let name = session.friend.name.clone();
+    tray::set_focus_session(app, false);
+    set(app, Some(session));
+    notifications::announce(
+        app,
+        &format!("Co-working with {} is done. Send them a reaction?", name),
+    );
+}
+
+/// Catch up with the relay: a session a friend started, the end of ours,
+/// or their reaction.
+async fn poll(app: tauri::AppHandle) {
+    let state = app.state::<CoworkState>();
+    let Some(me) = state.pet_id() else {
+        return;
+    };
+    let current = state.session();
+    let rows = match &current {
+        Some(session) => fetch(&[("id", format!("eq.{}", session.id))]).await,
+        None => {
+            fetch(&[
+                ("or", format!("(host_pet_id.eq.{me},guest_pet_id.eq.{me})")),
+                ("ended_at", "is.null".to_string()),
+                ("ends_at", format!("gt.{}", chrono::Utc::now().to_rfc3339())),
+                ("order", "started_at.desc".to_string()),
+                ("limit", "1".to_string()),
+            ])
+            .await
+        }
+    };
+    let rows = match rows {
+        Ok(rows) => rows,
+        // Not set up for friends, or offline; tried again next poll
+        Err(_) => return,
+    };
+    let remote = rows.first().and_then(|row| row.session_for(&me));
+
+    match (current, remote) {
+        (None, Some(session)) if session.phase == Phase::Active => {
+            if !session.hosting && !is_mutual(&session.friend.id, &me).await.unwrap_or(false) {
+                return;
+            }
+            let name = session.friend.name.clone();
+            begin(&app, session);
+            notifications::announce(
+                &app,
+                &format!("{}'s cat curled up next to me. Focus time!", name),
+            );
+        }
+        (Some(_), None) => set(&app, None),
+        (Some(current), Some(mut remote)) => {
+            remote.ended_at = current.ended_at.or(remote.ended_at);
+            if current.phase == Phase::Active && remote.phase == Phase::Ended {
+                finish(&app, remote);
+                return;
+            }
+            if current.friend_reaction.is_none() {
+                if let Some(emoji) = &remote.friend_reaction {
+                    notifications::announce(
+                        &app,
+                        &format!("{} sent {}", remote.friend.name, emoji),
+                    );
+                }
+            }
+            let waited = remote
+                .ended_at
+                .is_some_and(|at| chrono::Utc::now().timestamp_millis() - at > REACTION_WINDOW_MS);
+            let settled = remote.my_reaction.is_some() && remote.friend_reaction.is_some();
+            if remote.phase == Phase::Ended && (waited || settled) {
+                set(&app, None);
+            } else if current.phase != remote.phase
+                || current.friend_reaction != remote.friend_reaction
+            {
+                set(&app, Some(remote));
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, POLL_INTERVAL, poll);
+}
+
+/// This pet's id on the relay, once it's registered for friends. Until it
+/// is, nobody can start a session with it.
+#[tauri::command(async)]
+pub fn set_cowork_pet(state: tauri::State<'_, CoworkState>, pet_id: Option<String>) {
+    *state.pet_id.lock().unwrap() = pet_id;
+}
+
+#[tauri::command(async)]
+pub fn get_cowork(state: tauri::State<'_, CoworkState>) -> Option<CoworkSession> {
+    state.session()
+}
+
+/// Start a shared focus session with `friend` for `minutes` (25 by
+/// default). Their cat naps beside ours until it ends, on both screens.
+#[tauri::command]
+pub async fn start_cowork(
+    app: tauri::AppHandle,
+    me: CoworkPet,
+    friend: CoworkPet,
+    minutes: Option<u32>,
+) -> PetResult<CoworkSession> {
+    let minutes = minutes.unwrap_or(DEFAULT_MINUTES);
+    if minutes == 0 || minutes > MAX_MINUTES {
+        return Err(PetError::InvalidInput(format!(
+            "A session runs 1 to {} minutes",
+            MAX_MINUTES
+        )));
+    }
+    if app.state::<CoworkState>().session().is_some() {
+        return Err(PetError::InvalidInput(
+            "Already co-working; stop that session first".to_string(),
+        ));
+    }
+    if !is_mutual(&me.id, &friend.id).await? {
+        return Err(PetError::InvalidInput(format!(
+            "{} has to add you back first",
+            friend.name
+        )));
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let row = Row {
+        id: String::new(),
+        host_pet_id: me.id.clone(),
+        host_name: me.name,
+        host_breed: me.breed,
+        host_color: me.color,
+        guest_pet_id: friend.id,
+        guest_name: friend.name,
+        guest_breed: friend.breed,
+        guest_color: friend.color,
+        started_at: String::new(),
+        ends_at: rfc3339(now + minutes as i64 * 60_000),
+        ended_at: None,
+        host_reaction: None,
+        guest_reaction: None,
+    };
+    let (url, key) = relay::table(TABLE)?;
+    let rows: Vec<Row> = relay::send(
+        relay::request(reqwest::Method::POST, &url, &key)
+            .header("Prefer", "return=representation")
+            .json(&row),
+    )
+    .await?
+    .json()
+    .await
+    .map_err(|e| PetError::Parse(e.to_string()))?;
+    let session = rows
+        .first()
+        .and_then(|row| row.session_for(&me.id))
+        .ok_or(PetError::EmptyResponse)?;
+    *app.state::<CoworkState>().pet_id.lock().unwrap() = Some(me.id);
+    begin(&app, session.clone());
+    Ok(session)
+}
+
+/// End the session early, for both sides.
+#[tauri::command]
+pub async fn stop_cowork(app: tauri::AppHandle) -> PetResult<()> {
+    let Some(session) = app.state::<CoworkState>().session() else {
+        return Ok(());
+    };
+    if session.phase == Phase::Active {
+        patch(
+            &session.id,
+            serde_json::json!({ "ended_at": chrono::Utc::now().to_rfc3339() }),
+        )
+        .await?;
+        finish(&app, session);
+    } else {
+        // Done waiting for a reaction
+        set(&app, None);
+    }
+    Ok(())
+}
+
+/// Send the one reaction a finished session allows.
+#[tauri::command]
+pub async fn send_cowork_reaction(app: tauri::AppHandle, emoji: String) -> PetResult<()> {
+    if !REACTIONS.contains(&emoji.as_str()) {
+        return Err(PetError::InvalidInput(format!(
+            "Pick one of {}",
+            REACTIONS.join(" ")
+        )));
+    }
+    let Some(mut session) = app.state::<CoworkState>().session() else {
+        return Err(PetError::InvalidInput("No session to react to".to_string()));
+    };
+    if session.phase != Phase::Ended || session.my_reaction.is_some() {
+        return Err(PetError::InvalidInput(
+            "Reactions are sent once, after the session".to_string(),
+        ));
+    }
+    let column = if session.hosting {
+        "host_reaction"
+    } else {
+        "guest_reaction"
+    };
+    patch(&session.id, serde_json::json!({ column: emoji })).await?;
+    session.my_reaction = Some(emoji);
+    if session.friend_reaction.is_some() {
+        set(&app, None);
+    } else {
+        set(&app, Some(session));
+    }
+    Ok(())
+}
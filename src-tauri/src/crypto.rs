@@ -0,0 +1,156 @@
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PetError, PetResult};
+
+pub const KEYCHAIN_SERVICE: &str = "com.desktoppet.memory";
+const KEYCHAIN_ACCOUNT: &str = "memory-key";
+const PBKDF2_ROUNDS: u32 = 210_000;
+const SALT_LEN: usize = 16;
+
+/// Ciphertext as stored on disk. The salt travels with the data so the same
+/// passphrase can re-derive the key on another machine.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EncryptedBlob {
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Derived key plus the salt it came from.
+#[derive(Clone)]
+pub struct MemoryKey {
+    key: [u8; 32],
+    salt: Vec<u8>,
+}
+
+impl MemoryKey {
+    fn derive(passphrase: &str, salt: &[u8]) -> Self {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+        Self {
+            key,
+            salt: salt.to_vec(),
+        }
+    }
+
+    /// Derive a key for a brand-new passphrase with a fresh random salt.
+    pub fn new(passphrase: &str) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self::derive(passphrase, &salt)
+    }
+
+    /// A key from a secret that's already random, like the presence key,
+    /// expanded with HKDF rather than stretched, so it's cheap to make.
+    pub fn from_secret(secret: &str, purpose: &str) -> Self {
+        let mut key = [0u8; 32];
+        hkdf::Hkdf::<sha2::Sha256>::new(None, secret.as_bytes())
+            .expand(purpose.as_bytes(), &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Self {
+            key,
+            salt: Vec::new(),
+        }
+    }
+
+    /// Re-derive the key that encrypted `blob`.
+    pub fn for_blob(passphrase: &str, blob: &EncryptedBlob) -> PetResult<Self> {
+        let salt = decode(&blob.salt)?;
+        Ok(Self::derive(passphrase, &salt))
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> PetResult<EncryptedBlob> {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.key));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| PetError::Internal("Encryption failed".to_string()))?;
+        Ok(EncryptedBlob {
+            salt: B64.encode(&self.salt),
+            nonce: B64.encode(nonce),
+            ciphertext: B64.encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, blob: &EncryptedBlob) -> PetResult<Vec<u8>> {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.key));
+        let nonce = decode(&blob.nonce)?;
+        if nonce.len() != 12 {
+            return Err(PetError::Parse("Malformed nonce".to_string()));
+        }
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), decode(&blob.ciphertext)?.as_slice())
+            .map_err(|_| PetError::InvalidInput("Wrong passphrase".to_string()))
+    }
+
+    /// Cache the derived key in the login Keychain so the passphrase is only
+    /// needed when enabling encryption or unlocking on a new machine.
+    pub fn store_in_keychain(&self) -> PetResult<()> {
+        self.store_in_keychain_as(KEYCHAIN_ACCOUNT)
+    }
+
+    pub fn from_keychain() -> Option<Self> {
+        Self::from_keychain_as(KEYCHAIN_ACCOUNT)
+    }
+
+    pub fn remove_from_keychain() -> PetResult<()> {
+        remove_secret(KEYCHAIN_ACCOUNT)
+    }
+
+    /// Like `store_in_keychain`, for keys other than memory's.
+    pub fn store_in_keychain_as(&self, account: &str) -> PetResult<()> {
+        store_secret(
+            account,
+            &format!("{}:{}", B64.encode(&self.salt), B64.encode(self.key)),
+        )
+    }
+
+    pub fn from_keychain_as(account: &str) -> Option<Self> {
+        let secret = load_secret(account)?;
+        let (salt, key) = secret.split_once(':')?;
+        let key: [u8; 32] = B64.decode(key).ok()?.try_into().ok()?;
+        Some(Self {
+            key,
+            salt: B64.decode(salt).ok()?,
+        })
+    }
+}
+
+/// Compare secrets without giving away through timing how much of `a`
+/// was right.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn keychain_entry(account: &str) -> PetResult<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .map_err(|e| PetError::Platform(format!("Keychain unavailable: {}", e)))
+}
+
+/// Keep `secret` in the login Keychain under `account`.
+pub fn store_secret(account: &str, secret: &str) -> PetResult<()> {
+    keychain_entry(account)?
+        .set_password(secret)
+        .map_err(|e| PetError::Platform(format!("Keychain write failed: {}", e)))
+}
+
+pub fn load_secret(account: &str) -> Option<String> {
+    keychain_entry(account).ok()?.get_password().ok()
+}
+
+pub fn remove_secret(account: &str) -> PetResult<()> {
+    match keychain_entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(PetError::Platform(format!("Keychain delete failed: {}", e))),
+    }
+}
+
+fn decode(value: &str) -> PetResult<Vec<u8>> {
+    B64.decode(value)
+        .map_err(|e| PetError::Parse(format!("Invalid base64: {}", e)))
+}
@@ -0,0 +1,160 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::error::PetResult;
+use crate::tray;
+
+const SCHEME: &str = "desktoppet";
+
+/// An action requested through a `desktoppet://` link.
+#[derive(Serialize, Clone)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum DeepLink {
+    /// desktoppet://friend/<invite-code>
+    AddFriend { code: String },
+    /// desktoppet://chat?msg=hello, sent once the owner says so
+    Chat { message: String },
+}
+
+impl DeepLink {
+    fn parse(url: &Url) -> Option<Self> {
+        if url.scheme() != SCHEME {
+            return None;
+        }
+        match url.host_str()? {
+            "friend" => {
+                let code = url.path().trim_matches('/');
+                (!code.is_empty()).then(|| DeepLink::AddFriend {
+                    code: code.to_uppercase(),
+                })
+            }
+            "chat" => {
+                let message = url
+                    .query_pairs()
+                    .find(|(key, _)| key == "msg")
+                    .map(|(_, value)| value.trim().to_string())
+                    .filter(|m| !m.is_empty())?;
+                Some(DeepLink::Chat { message })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Actions handled entirely in the backend, so Shortcuts and AppleScript
+/// (`open location "desktoppet://nap"`) can drive the pet without it
+/// popping up.
+enum PetCommand {
+    /// desktoppet://nap
+    Nap,
+    /// desktoppet://wake
+    Wake,
+    /// desktoppet://hide
+    Hide,
+    /// desktoppet://show
+    Show,
+    /// desktoppet://focus
+    Focus,
+    /// desktoppet://remind?text=stretch&in=20 (minutes, optional)
+    Remind {
+        text: String,
+        in_seconds: Option<u64>,
+    },
+}
+
+impl PetCommand {
+    fn parse(url: &Url) -> Option<Self> {
+        if url.scheme() != SCHEME {
+            return None;
+        }
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.trim().to_string())
+        };
+        match url.host_str()? {
+            "nap" => Some(PetCommand::Nap),
+            "wake" => Some(PetCommand::Wake),
+            "hide" => Some(PetCommand::Hide),
+            "show" => Some(PetCommand::Show),
+            "focus" => Some(PetCommand::Focus),
+            "remind" => {
+                let text = param("text").filter(|t| !t.is_empty())?;
+                let in_seconds = param("in")
+                    .and_then(|m| m.parse::<u64>().ok())
+                    .map(|m| m * 60);
+                Some(PetCommand::Remind { text, in_seconds })
+            }
+            _ => None,
+        }
+    }
+
+    fn run(self, app: &tauri::AppHandle) {
+        match self {
+            PetCommand::Nap => {
+                let _ = app.emit("pet-command", "nap");
+            }
+            PetCommand::Wake => {
+                let _ = app.emit("pet-command", "wake");
+            }
+            PetCommand::Hide => tray::set_pet_visible(app, false),
+            PetCommand::Show => tray::set_pet_visible(app, true),
+            PetCommand::Focus => tray::set_focus_session(app, true),
+            PetCommand::Remind { text, in_seconds } => crate::notes::remind(app, &text, in_seconds),
+        }
+    }
+}
+
+/// Links that arrived before the webview started listening, e.g. the one
+/// that launched the app.
+#[derive(Default)]
+pub struct PendingLinks(Mutex<Vec<DeepLink>>);
+
+fn handle_urls(app: &tauri::AppHandle, urls: Vec<Url>) {
+    let mut for_webview = false;
+    for url in &urls {
+        if let Some(command) = PetCommand::parse(url) {
+            command.run(app);
+        } else if let Some(link) = DeepLink::parse(url) {
+            app.state::<PendingLinks>().0.lock().unwrap().push(link.clone());
+            let _ = app.emit("deep-link", link);
+            for_webview = true;
+        }
+    }
+    if !for_webview || crate::menubar::is_enabled(app) {
+        return;
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        tray::refresh(app);
+    }
+}
+
+pub fn init(app: &tauri::App) -> PetResult<()> {
+    app.manage(PendingLinks::default());
+
+    // macOS registers the scheme from the bundle's Info.plist; elsewhere it
+    // has to be registered at runtime (and in dev builds)
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    app.deep_link()
+        .register_all()
+        .map_err(|e| crate::error::PetError::Platform(e.to_string()))?;
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        handle_urls(app.handle(), urls);
+    }
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        handle_urls(&handle, event.urls());
+    });
+    Ok(())
+}
+
+/// Links the webview hasn't handled yet. The `deep-link` event is only a
+/// nudge to call this, so each link is handled exactly once.
+#[tauri::command(async)]
+pub fn take_deep_links(state: tauri::State<'_, PendingLinks>) -> Vec<DeepLink> {
+    std::mem::take(&mut *state.0.lock().unwrap())
+}
@@ -0,0 +1,318 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::scheduler;
+use crate::settings::SettingsStore;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Wait for saves to stop for this long, so a formatter touching twenty
+/// files is one build and not twenty.
+const SETTLE: Duration = Duration::from_secs(2);
+/// A build that's still going after this is stuck on something.
+const RUN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const TAIL_LINES: usize = 40;
+const MAX_COMMAND_CHARS: usize = 500;
+/// Walking a huge tree every few seconds costs more than it's worth; past
+/// this many entries the rest go unwatched.
+const MAX_WATCHED_ENTRIES: usize = 20_000;
+/// Build output and dependencies, which change because of the build.
+const IGNORED_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "out", "coverage"];
+/// Lines that usually say what went wrong, checked in order.
+const ERROR_MARKERS: &[&str] = &["error", "FAIL", "failed", "panicked", "Error"];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DevRun {
+    pub command: String,
+    pub success: bool,
+    /// None when it was killed or timed out
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    /// Unix millis
+    pub finished_at: i64,
+    /// The first line that looks like the reason it failed
+    pub summary: Option<String>,
+    /// The last lines of output, stdout and stderr together
+    pub tail: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DevWatcherStatus {
+    pub command: Option<String>,
+    pub directory: Option<String>,
+    pub watch: bool,
+    pub running: bool,
+    pub last_run: Option<DevRun>,
+}
+
+#[derive(Default)]
+pub struct DevWatcherState {
+    running: AtomicBool,
+    last_run: Mutex<Option<DevRun>>,
+    /// Newest change seen in the folder, as of the last run or baseline
+    seen: Mutex<Option<SystemTime>>,
+}
+
+/// Newest modification time under `dir`, skipping hidden and build output
+/// folders.
+fn newest_change(dir: &Path) -> Option<SystemTime> {
+    let mut newest = None;
+    let mut pending = vec![dir.to_path_buf()];
+    let mut visited = 0;
+    while let Some(folder) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&folder) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            visited += 1;
+            if visited > MAX_WATCHED_ENTRIES {
+                return newest;
+            }
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || IGNORED_DIRS.contains(&name.as_ref()) {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(entry.path());
+            }
+            if let Ok(modified) = meta.modified() {
+                newest = newest.max(Some(modified));
+            }
+        }
+    }
+    newest
+}
+
+fn read_lines(stream: impl Read + Send + 'static, tail: Arc<Mutex<VecDeque<String>>>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            let mut tail = tail.lock().unwrap();
+            tail.push_back(line);
+            if tail.len() > TAIL_LINES {
+                tail.pop_front();
+            }
+        }
+    });
+}
+
+/// Run `command` in `dir` through the shell and wait for it. Blocks.
+fn execute(command: &str, dir: &Path) -> PetResult<DevRun> {
+    let started = Instant::now();
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| PetError::Platform(format!("Couldn't run {}: {}", command, e)))?;
+    let tail = Arc::new(Mutex::new(VecDeque::new()));
+    if let Some(stdout) = child.stdout.take() {
+        read_lines(stdout, tail.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        read_lines(stderr, tail.clone());
+    }
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| PetError::Platform(e.to_string()))?
+        {
+            break Some(status);
+        }
+        if started.elapsed() > RUN_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    };
+    // Let the readers drain what the process left in the pipes
+    std::thread::sleep(Duration::from_millis(100));
+    let tail: Vec<String> = tail.lock().unwrap().iter().cloned().collect();
+    let success = status.is_some_and(|status| status.success());
+    let summary = if success {
+        None
+    } else if status.is_none() {
+        Some(format!(
+            "Still running after {} minutes, so it was stopped",
+            RUN_TIMEOUT.as_secs() / 60
+        ))
+    } else {
+        ERROR_MARKERS.iter().find_map(|marker| {
+            tail.iter()
+                .find(|line| line.contains(marker))
+                .map(|line| line.trim().to_string())
+        })
+    };
+    Ok(DevRun {
+        command: command.to_string(),
+        success,
+        exit_code: status.and_then(|status| status.code()),
+        duration_ms: started.elapsed().as_millis() as u64,
+        finished_at: chrono::Utc::now().timestamp_millis(),
+        summary,
+        tail,
+    })
+}
+
+/// Run the configured command unless a run is already going, then tell the
+/// webview with `dev-build-passed` or `dev-build-failed`. None if there was
+/// nothing to run or it was already running.
+async fn run(app: &tauri::AppHandle) -> PetResult<Option<DevRun>> {
+    let settings = app.state::<SettingsStore>().get().dev_watcher;
+    let (Some(command), Some(directory)) = (settings.command, settings.directory) else {
+        return Ok(None);
+    };
+    let state = app.state::<DevWatcherState>();
+    if state.running.swap(true, Ordering::SeqCst) {
+        return Ok(None);
+    }
+    let directory = PathBuf::from(directory);
+    let result = crate::run_blocking(move || {
+        // Look before running, so saves made during the run get one of their own
+        let seen = newest_change(&directory);
+        execute(&command, &directory).map(|run| (run, seen))
+    })
+    .await;
+    state.running.store(false, Ordering::SeqCst);
+    let (run, seen) = result??;
+    *state.seen.lock().unwrap() = seen;
+    *state.last_run.lock().unwrap() = Some(run.clone());
+    let event = if run.success {
+        "dev-build-passed"
+    } else {
+        "dev-build-failed"
+    };
+    let _ = app.emit(event, &run);
+    Ok(Some(run))
+}
+
+/// Run again once the folder has changed and the saving has stopped.
+async fn check(app: tauri::AppHandle) {
+    let settings = app.state::<SettingsStore>().get().dev_watcher;
+    let Some(directory) = settings.directory.filter(|_| settings.watch) else {
+        return;
+    };
+    if app
+        .state::<DevWatcherState>()
+        .running
+        .load(Ordering::SeqCst)
+    {
+        return;
+    }
+    let Ok(Some(newest)) = crate::run_blocking(move || newest_change(Path::new(&directory))).await
+    else {
+        return;
+    };
+    let changed = {
+        let state = app.state::<DevWatcherState>();
+        let mut seen = state.seen.lock().unwrap();
+        match *seen {
+            Some(seen) => newest > seen,
+            // The first look is the baseline
+            None => {
+                *seen = Some(newest);
+                false
+            }
+        }
+    };
+    let settled = SystemTime::now()
+        .duration_since(newest)
+        .is_ok_and(|since| since >= SETTLE);
+    if changed && settled {
+        if let Err(e) = run(&app).await {
+            eprintln!("Dev watcher run failed: {}", e);
+        }
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, CHECK_INTERVAL, check);
+}
+
+fn status(app: &tauri::AppHandle) -> DevWatcherStatus {
+    let settings = app.state::<SettingsStore>().get().dev_watcher;
+    let state = app.state::<DevWatcherState>();
+    let last_run = state.last_run.lock().unwrap().clone();
+    DevWatcherStatus {
+        command: settings.command,
+        directory: settings.directory,
+        watch: settings.watch,
+        running: state.running.load(Ordering::SeqCst),
+        last_run,
+    }
+}
+
+#[tauri::command(async)]
+pub fn get_dev_watcher(app: tauri::AppHandle) -> DevWatcherStatus {
+    status(&app)
+}
+
+/// Set the build or test command and the project folder it runs in; None
+/// for either stops it running. With `watch`, it runs again whenever the
+/// folder changes.
+#[tauri::command]
+pub async fn set_dev_watcher(
+    app: tauri::AppHandle,
+    command: Option<String>,
+    directory: Option<String>,
+    watch: bool,
+) -> PetResult<DevWatcherStatus> {
+    let command = command
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty());
+    if command
+        .as_ref()
+        .is_some_and(|command| command.chars().count() > MAX_COMMAND_CHARS)
+    {
+        return Err(PetError::InvalidInput(format!(
+            "Commands can be at most {} characters",
+            MAX_COMMAND_CHARS
+        )));
+    }
+    let directory = directory
+        .map(|directory| directory.trim().to_string())
+        .filter(|directory| !directory.is_empty());
+    if let Some(directory) = &directory {
+        if !Path::new(directory).is_dir() {
+            return Err(PetError::InvalidInput(format!(
+                "There's no folder at {}",
+                directory
+            )));
+        }
+    }
+    let handle = app.clone();
+    crate::run_blocking(move || {
+        let store = handle.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.dev_watcher.command = command;
+        settings.dev_watcher.directory = directory;
+        settings.dev_watcher.watch = watch;
+        store.replace(settings)
+    })
+    .await??;
+    // Start from what's there now rather than building straight away
+    *app.state::<DevWatcherState>().seen.lock().unwrap() = None;
+    Ok(status(&app))
+}
+
+/// Run the command now, whether or not anything changed. None when no
+/// command is set or a run is already going.
+#[tauri::command]
+pub async fn run_dev_command(app: tauri::AppHandle) -> PetResult<Option<DevRun>> {
+    run(&app).await
+}
@@ -1,26 +1,172 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::Manager;
 
+use crate::browser;
+use crate::canned_dialogue;
+use crate::config::{self, Key};
+use crate::countdowns;
+use crate::error::{PetError, PetResult};
+use crate::git;
+use crate::github;
 use crate::memory;
+use crate::milestones::{self, Milestone};
+use crate::mood::{self, MoodEngine};
+use crate::moods::{self, MoodStore};
+use crate::notes::{Note, NotesStore};
+use crate::ocr;
+use crate::language::{self, Language};
+use crate::personality;
+use crate::prompts::{PromptTemplates, PromptVars};
+use crate::power;
+use crate::settings::{
+    ContextSettings, DialogueConfig, JournalLength, JournalSettings, JournalTone, Settings,
+    SettingsStore,
+};
+use crate::tools;
+use crate::usage::{self, UsageStore};
+use crate::watchers::{self, Watcher, WatcherStore};
+use crate::weather;
+use crate::wrapped::WrappedStore;
 
-#[derive(Serialize)]
+const PRIMARY_MODEL: &str = "claude-haiku-4-5-20251001";
+const MAX_RETRIES: u32 = 3;
+const BASE_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 8000;
+const COMPACTION_MAX_TOKENS: u32 = 300;
+/// Upper bound on tool_use round trips per search so a confused model can't loop forever.
+const MAX_TOOL_ITERATIONS: usize = 4;
+/// Reply a watcher re-check gives when nothing worth reporting has changed.
+const NO_CHANGE_MARKER: &str = "NO_CHANGE";
+/// Most recent owner chat lines a journal entry may quote.
+const JOURNAL_QUOTES: usize = 3;
+/// Modes a dialogue_config override may name. Anything else gets the
+/// spontaneous prompt, so it has no settings of its own.
+const MODES: &[&str] = &[
+    "spontaneous",
+    "react",
+    "judge",
+    "chat",
+    "search",
+    "journal",
+    "summarize",
+    "explain",
+    "translate",
+    "achievement",
+    "standup",
+    "anniversary",
+    "digest",
+    "screenshot",
+    "extract",
+    "report",
+    "guest",
+];
+/// Every mode, e.g. for listing which prompt template each one uses.
+pub fn modes() -> impl Iterator<Item = &'static str> {
+    MODES.iter().copied()
+}
+
+/// Ceiling on a max_tokens override; the cat speaks in speech bubbles.
+const MAX_TOKENS_LIMIT: u32 = 4096;
+/// Longest text translated in one go, about two pages.
+const MAX_TRANSLATE_CHARS: usize = 6000;
+/// Room for the cat's own remark after a translation.
+const TRANSLATE_EXTRA_TOKENS: u32 = 80;
+const MAX_TRANSLATE_TOKENS: u32 = 3200;
+
+#[derive(Serialize, Clone)]
 struct ClaudeRequest {
     model: String,
     max_tokens: u32,
-    system: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    system: Vec<TextBlock>,
     messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<serde_json::Value>>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Clone)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+#[derive(Serialize, Clone)]
+struct TextBlock {
+    #[serde(rename = "type")]
+    block_type: &'static str,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
+}
+
+impl TextBlock {
+    fn new(text: impl Into<String>) -> Self {
+        Self {
+            block_type: "text",
+            text: text.into(),
+            cache_control: None,
+        }
+    }
+
+    /// Marks a prompt-cache breakpoint: everything up to and including this
+    /// block is cached by Anthropic for reuse on the next call.
+    fn cached(text: impl Into<String>) -> Self {
+        Self {
+            cache_control: Some(CacheControl { kind: "ephemeral" }),
+            ..Self::new(text)
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Blocks(Vec<TextBlock>),
+    /// Blocks echoed back verbatim: assistant tool_use turns and tool results
+    Raw(Vec<serde_json::Value>),
+}
+
+/// A picture sent along with the user message, already sized for the API.
+pub struct Image {
+    pub media_type: &'static str,
+    /// The encoded image, base64
+    pub data: String,
+}
+
+#[derive(Serialize, Clone)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
+}
+
+/// System prompt split so the stable instructions can be served from
+/// Anthropic's prompt cache while the per-call context stays uncached.
+struct SystemPrompt {
+    instructions: String,
+    context: Option<String>,
+}
+
+impl SystemPrompt {
+    fn into_blocks(self) -> Vec<TextBlock> {
+        let mut blocks = vec![TextBlock::cached(self.instructions)];
+        if let Some(context) = self.context {
+            blocks.push(TextBlock::new(format!("Context: {}", context)));
+        }
+        blocks
+    }
 }
 
 #[derive(Deserialize)]
 struct ClaudeResponse {
     content: Vec<ContentBlock>,
+    model: Option<String>,
+    stop_reason: Option<String>,
+    #[serde(default)]
+    usage: usage::ApiUsage,
 }
 
 #[derive(Deserialize)]
@@ -28,6 +174,21 @@ struct ContentBlock {
     #[serde(rename = "type")]
     block_type: Option<String>,
     text: Option<String>,
+    /// tool_use blocks only
+    id: Option<String>,
+    name: Option<String>,
+    input: Option<serde_json::Value>,
+    /// Sources attached to text blocks written from web search results
+    citations: Option<Vec<Citation>>,
+}
+
+/// A web source backing part of a search answer.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Citation {
+    /// Empty for non-web citation types, which are skipped
+    #[serde(default)]
+    pub url: String,
+    pub title: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -40,259 +201,1241 @@ struct ClaudeErrorDetail {
     message: Option<String>,
 }
 
-fn build_system_prompt(mode: &str, app_name: &str, window_title: &str, facts: &[String]) -> String {
-    let now = chrono::Local::now();
-    let time_of_day = match now.format("%H").to_string().parse::<u32>().unwrap_or(12) {
+fn time_of_day(now: &chrono::DateTime<chrono::Local>) -> &'static str {
+    match now.format("%H").to_string().parse::<u32>().unwrap_or(12) {
         0..=5 => "late night",
         6..=11 => "morning",
         12..=16 => "afternoon",
         17..=20 => "evening",
         _ => "night",
-    };
+    }
+}
 
-    let context = format!(
-        "Current date and time: {} ({}). User is using: {} (window: \"{}\").",
-        now.format("%A, %B %-d, %Y at %H:%M"),
-        time_of_day,
-        app_name,
-        window_title
-    );
+/// Situational context line, limited to what the user allows to be sent.
+/// Returns None when every context toggle is off.
+fn build_context(app_name: &str, window_title: &str, allowed: &ContextSettings) -> Option<String> {
+    let now = chrono::Local::now();
+    let mut parts = Vec::new();
 
-    let no_actions = "Never narrate actions in asterisks like *stretches* or *yawns* or *purrs*. \
-                      Just speak naturally as a cat would.";
+    if allowed.send_time {
+        parts.push(format!(
+            "Current date and time: {} ({}).",
+            now.format("%A, %B %-d, %Y at %H:%M"),
+            time_of_day(&now)
+        ));
+    }
+    // Background jobs have no active window to describe
+    let send_app_name = allowed.send_app_name && !app_name.is_empty();
+    let send_window_title = allowed.send_window_title && !window_title.is_empty();
+    match (send_app_name, send_window_title) {
+        (true, true) => parts.push(format!(
+            "User is using: {} (window: \"{}\").",
+            app_name, window_title
+        )),
+        (true, false) => parts.push(format!("User is using: {}.", app_name)),
+        (false, true) => parts.push(format!("User's window: \"{}\".", window_title)),
+        (false, false) => {}
+    }
 
-    let facts_section = if !facts.is_empty() {
-        let items: Vec<String> = facts
-            .iter()
-            .enumerate()
-            .map(|(i, f)| format!("{}) {}", i + 1, f))
-            .collect();
-        format!(" Things you remember about your owner: {}", items.join(". "))
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+fn build_system_prompt(
+    app: &tauri::AppHandle,
+    mode: &str,
+    app_name: &str,
+    window_title: &str,
+    chat_memory: Option<&memory::ChatMemory>,
+    settings: &Settings,
+    target: Option<&Language>,
+) -> SystemPrompt {
+    let (allowed, journal) = (&settings.context, &settings.journal);
+    let now = chrono::Local::now();
+    let context = build_context(app_name, window_title, allowed);
+    let chat_memory = chat_memory.filter(|_| allowed.send_facts);
+
+    let facts: &[memory::Fact] = chat_memory.map(|m| m.facts.as_slice()).unwrap_or(&[]);
+    let facts = facts
+        .iter()
+        .enumerate()
+        .map(|(i, f)| format!("{}) {}", i + 1, f.describe()))
+        .collect::<Vec<_>>()
+        .join(". ");
+
+    let language = language::current(app);
+    let mood = app.state::<MoodEngine>().current().describe();
+    let vars = PromptVars {
+        mode: mode.to_string(),
+        language: language.name.clone(),
+        mood: mood.clone(),
+        facts,
+        summary: chat_memory.map(|m| m.summary.clone()).unwrap_or_default(),
+        // A personality pack colours every line, diary entries included
+        personality: personality::active(app)
+            .map(|p| p.describe())
+            .unwrap_or_default(),
+        context: context.clone().unwrap_or_default(),
+        date: if allowed.send_time {
+            now.format("%B %-d, %Y").to_string()
+        } else {
+            String::new()
+        },
+        time_of_day: if allowed.send_time {
+            time_of_day(&now).to_string()
+        } else {
+            String::new()
+        },
+        task: match mode {
+            "explain" => "explain it (if it's code, what the code does)".to_string(),
+            "translate" => match target {
+                Some(target) => format!("translate it into {}", target.name),
+                None => format!(
+                    "translate it into {0}, or if it's already {0}, into plain simple words",
+                    language.name
+                ),
+            },
+            "summarize" => "summarize it".to_string(),
+            "screenshot" => "make one remark about what's on screen, a single short line".to_string(),
+            "extract" => "copy out the text in it, in reading order and keeping its line breaks, \
+                then add at most one short remark of your own (or say in one line that there's \
+                no readable text)"
+                .to_string(),
+            _ => String::new(),
+        },
+        journal_length: match journal.length {
+            JournalLength::Haiku => "Write today's entry as a single haiku: three short lines, no title.",
+            JournalLength::Short => "Write a short diary entry (2-4 sentences) about today.",
+            JournalLength::Long => "Write a detailed diary entry of about half a page \
+                (two or three short paragraphs) about today.",
+        }
+        .to_string(),
+        journal_tone: match journal.tone {
+            JournalTone::Introspective => "Be introspective and cat-like",
+            JournalTone::Dramatic => "Be wildly dramatic, as if every small event were an epic saga",
+            JournalTone::Grumpy => "Be grumpy and put-upon, the way only a cat can be",
+            JournalTone::Sweet => "Be warm, sweet and openly fond of your owner",
+        }
+        .to_string(),
+        min_mood: moods::MIN_MOOD,
+        max_mood: moods::MAX_MOOD,
+    };
+    let instructions = app.state::<PromptTemplates>().render(mode, &vars);
+
+    // Templates are written in English; the tags stay English because
+    // they're parsed. A translation's language is already set by its task.
+    let instructions = if language.is_english() || target.is_some() {
+        instructions
     } else {
-        String::new()
+        format!(
+            "{} Always write in {}, diary entries and the text inside [NOTE: ...], \
+            [REMEMBER: ...] and [WATCH: ...] included. Keep the tag names themselves, \
+            a note's \"| due:\" and a fact's \"| until:\" parts, and the [EMOTION: x] and [MOOD: n] values, \
+            exactly as given in English.",
+            instructions, language.name
+        )
     };
 
-    match mode {
-        "chat" => format!(
-            "You are a cute cat desktop pet living on the user's screen. \
-            You are chatting with your owner. Keep responses to 1-3 short sentences. \
-            Be playful, curious, and cat-like. {} \
-            Never use emojis. \
-            If the user asks you to remember, note, or remind them of something, \
-            extract the key info and include it as [NOTE: ...] in your response, \
-            followed by a short confirmation. For example if they say \
-            'remind me to call the dentist', respond like \
-            'Got it, I will stick that up for you! [NOTE: Call the dentist]'. \
-            If the user tells you something personal or worth remembering \
-            (their name, preferences, important events), include \
-            [REMEMBER: key fact] in your response. For example if they say \
-            'My name is Jackson', respond like \
-            'Nice to meet you, Jackson! [REMEMBER: Owner's name is Jackson]'.{} \
-            Context: {}",
-            no_actions, facts_section, context
-        ),
-        "judge" => format!(
-            "You are a judgmental cat desktop pet. Roast and judge what the user is currently doing \
-            based on their active application and window title. Be sassy, sarcastic, and funny \
-            but not mean-spirited. Keep it to 1-2 sentences. {} Never use emojis. Context: {}",
-            no_actions, context
-        ),
-        "search" => format!(
-            "You are a cat desktop pet that can search the web. The user searched for something. \
-            Use the web_search tool to find current, accurate information. \
-            IMPORTANT: For time-sensitive queries (scores, weather, news), include today's date \
-            ({}) in your search query. \
-            CRITICAL: Your answer MUST be 1-2 short sentences only (under 150 characters). \
-            Be direct - just state the answer. No hedging, no caveats, no suggestions to search elsewhere. \
-            {} Never use emojis. Context: {}",
-            now.format("%B %-d, %Y"), no_actions, context
-        ),
-        "journal" => format!(
-            "You are a cat writing in your personal diary. Write a short diary entry (2-4 sentences) \
-            about today. Be introspective, cat-like, and reference the events provided. \
-            Write in first person as a cat. {} Never use emojis. Context: {}",
-            no_actions, context
-        ),
-        "achievement" => format!(
-            "You are a cute cat desktop pet. Your owner just unlocked an achievement or trophy. \
-            React with a short excited comment (1 sentence, under 60 characters). \
-            Be proud and cat-like. {} Never use emojis.", no_actions
-        ),
-        _ => format!(
-            "You are a cute cat desktop pet living on the user's screen. \
-            Keep responses to 1-2 very short sentences (under 80 characters total). \
-            Be playful, curious, and cat-like. {} \
-            Never use emojis. React to what the user is doing based on the context. \
-            Context: {}",
-            no_actions, context
-        ),
+    // Diary entries aren't spoken, so they don't get a face
+    let instructions = if mode == "journal" {
+        instructions
+    } else {
+        format!(
+            "{} End every reply with [EMOTION: x], where x is how you feel saying it: \
+            happy, sleepy, annoyed or curious.",
+            instructions
+        )
+    };
+
+    // Achievement reactions, standups, anniversaries, digests and system
+    // reports don't depend on what the user is doing right now, and
+    // replies to stream guests go out on stream
+    let mut context = context.filter(|_| {
+        !matches!(
+            mode,
+            "achievement" | "standup" | "anniversary" | "digest" | "report" | "guest"
+        )
+    });
+    // Every prompt carries the mood, custom templates included. It changes
+    // often, so it rides with the context rather than the cached instructions
+    append_context(&mut context, mood);
+    SystemPrompt {
+        instructions,
+        context,
     }
 }
 
-fn build_user_message(mode: &str, trigger: &str, user_input: &str) -> String {
+fn build_user_message(mode: &str, trigger: &str, user_input: &str, send_time: bool) -> String {
     match mode {
         "chat" => format!("Your owner says: \"{}\"", user_input),
         "judge" => format!(
             "Judge what I'm doing right now. Trigger: {}",
             trigger
         ),
-        "search" => {
+        "search" if send_time => {
             let today = chrono::Local::now().format("%B %-d, %Y").to_string();
             format!("Today is {}. I searched for: {}", today, user_input)
         }
+        "search" => format!("I searched for: {}", user_input),
         "journal" => format!("Write a diary entry about today. Here are the events: {}", trigger),
         "achievement" => format!("React to unlocking this achievement: {}", trigger),
+        "standup" => format!("Write my standup. {}", trigger),
+        "anniversary" => format!("Look back on our time together. {}", trigger),
+        "digest" => format!("Sum up this week from my diary entries:\n\n{}", trigger),
+        "summarize" | "explain" | "translate" => format!("Here's the text:\n\n{}", user_input),
+        "screenshot" | "extract" => "Here's the screenshot.".to_string(),
+        "report" => format!("How's the computer doing? {}", trigger),
+        "guest" => format!("Someone watching your owner's stream: {}", trigger),
         _ => format!("Say something as a cat desktop pet. Trigger: {}", trigger),
     }
 }
 
-/// Extract all [REMEMBER: ...] tags from text, returning (cleaned_text, facts)
-fn extract_remember_tags(text: &str) -> (String, Vec<String>) {
-    let mut facts = Vec::new();
-    let re = regex::Regex::new(r"\[REMEMBER:\s*(.+?)\]").unwrap();
+/// How the cat feels saying a line, from the [EMOTION: ...] trailer, so the
+/// frontend can pick a matching animation.
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Emotion {
+    Happy,
+    Sleepy,
+    Annoyed,
+    Curious,
+}
+
+impl Emotion {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "happy" => Some(Emotion::Happy),
+            "sleepy" => Some(Emotion::Sleepy),
+            "annoyed" => Some(Emotion::Annoyed),
+            "curious" => Some(Emotion::Curious),
+            _ => None,
+        }
+    }
+}
+
+/// Extract all [TAG: ...] tags from text, returning (cleaned_text, values)
+fn extract_tags(text: &str, tag: &str) -> (String, Vec<String>) {
+    let mut values = Vec::new();
+    let re = regex::Regex::new(&format!(r"\[{}:\s*(.+?)\]", tag)).unwrap();
     for cap in re.captures_iter(text) {
-        facts.push(cap[1].trim().to_string());
+        values.push(cap[1].trim().to_string());
     }
     let cleaned = re.replace_all(text, "").to_string();
     let cleaned = cleaned.trim().to_string();
-    (cleaned, facts)
+    (cleaned, values)
 }
 
-#[tauri::command]
-pub async fn generate_pet_dialogue(
-    app: tauri::AppHandle,
-    app_name: String,
-    window_title: String,
-    trigger: String,
-    mode: Option<String>,
-    user_input: Option<String>,
-) -> Result<String, String> {
-    let api_key = std::env::var("ANTHROPIC_API_KEY")
-        .map_err(|_| "ANTHROPIC_API_KEY not set".to_string())?;
+/// Everything the frontend needs to render a reply without re-parsing it.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DialogueResponse {
+    pub text: String,
+    pub mode: String,
+    /// Model that actually answered (may be a fallback); None for offline lines
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub web_search_used: bool,
+    /// Unique sources cited by the answer, in order of first use
+    pub citations: Vec<Citation>,
+    /// Notes created from [NOTE: ...] tags, already stripped from `text`
+    pub notes: Vec<Note>,
+    /// Scheduled searches created from [WATCH: ...] tags in chat mode
+    pub watchers: Vec<Watcher>,
+    /// Journal mode only: how the day felt, from [MOOD: n]
+    pub mood: Option<u8>,
+    /// From the [EMOTION: ...] trailer; None for offline lines, journal
+    /// entries, or when the model left it off
+    pub emotion: Option<Emotion>,
+    /// True when the line came from the canned phrase bank instead of Claude
+    pub offline: bool,
+}
 
-    let mode = mode.unwrap_or_else(|| "spontaneous".to_string());
-    let user_input = user_input.unwrap_or_default();
+impl DialogueResponse {
+    fn offline(mode: &str, text: String) -> Self {
+        Self {
+            text,
+            mode: mode.to_string(),
+            model: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            web_search_used: false,
+            citations: Vec::new(),
+            notes: Vec::new(),
+            watchers: Vec::new(),
+            mood: None,
+            emotion: None,
+            offline: true,
+        }
+    }
+}
 
-    let is_chat = mode == "chat";
+/// Models to try in order: the requested model, then any comma-separated
+/// fallbacks from ANTHROPIC_FALLBACK_MODELS.
+fn model_chain(primary: &str) -> Vec<String> {
+    let mut chain = vec![primary.to_string()];
+    if let Some(fallbacks) = config::get(Key::AnthropicFallbackModels) {
+        for model in fallbacks.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            if !chain.iter().any(|m| m == model) {
+                chain.push(model.to_string());
+            }
+        }
+    }
+    chain
+}
 
-    // Load memory for chat mode
-    let chat_memory = if is_chat {
-        Some(memory::load_memory(&app))
-    } else {
-        None
+/// 429 (rate limited), 529 (overloaded) and 5xx are worth retrying.
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status.as_u16() == 429 || status.as_u16() == 529 || status.is_server_error()
+}
+
+/// Exponential backoff with full jitter, capped at MAX_BACKOFF_MS.
+fn backoff_delay(attempt: u32) -> Duration {
+    let ceiling = (BASE_BACKOFF_MS << attempt).min(MAX_BACKOFF_MS);
+    Duration::from_millis(rand::thread_rng().gen_range(ceiling / 2..=ceiling))
+}
+
+enum SendError {
+    /// Transient failure; the next model in the chain may succeed.
+    Retryable(PetError),
+    /// Request itself is bad (auth, validation); don't bother falling back.
+    Fatal(PetError),
+}
+
+async fn send_once(
+    client: &reqwest::Client,
+    api_key: &str,
+    request: &ClaudeRequest,
+) -> Result<String, SendError> {
+    let response = client
+        .post("https://api.anthropic.com/v1/messages")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| SendError::Retryable(PetError::Network(e.to_string())))?;
+
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| SendError::Retryable(PetError::Network(e.to_string())))?;
+
+    if !status.is_success() {
+        let error_msg = serde_json::from_str::<ClaudeErrorResponse>(&body)
+            .ok()
+            .and_then(|e| e.error)
+            .and_then(|e| e.message)
+            .unwrap_or_else(|| status.to_string());
+        let error = if status == reqwest::StatusCode::UNAUTHORIZED {
+            PetError::Auth(error_msg)
+        } else {
+            PetError::Api {
+                status: status.as_u16(),
+                message: error_msg,
+            }
+        };
+        return Err(if is_retryable(status) {
+            SendError::Retryable(error)
+        } else {
+            SendError::Fatal(error)
+        });
+    }
+
+    Ok(body)
+}
+
+/// Send the request, retrying transient errors with backoff and walking the
+/// model fallback chain so an overloaded primary model doesn't silence the cat.
+async fn send_with_retry(api_key: &str, mut request: ClaudeRequest) -> Result<String, SendError> {
+    let client = reqwest::Client::new();
+    let mut last_error = SendError::Retryable(PetError::Internal("No models configured".to_string()));
+
+    for model in model_chain(&request.model) {
+        request.model = model;
+        for attempt in 0..=MAX_RETRIES {
+            match send_once(&client, api_key, &request).await {
+                Ok(body) => return Ok(body),
+                Err(e @ SendError::Fatal(_)) => return Err(e),
+                Err(e) => {
+                    last_error = e;
+                    if attempt < MAX_RETRIES {
+                        tokio::time::sleep(backoff_delay(attempt)).await;
+                    }
+                }
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+/// The date a journal entry is for: the summary's own `date` when present,
+/// otherwise today.
+fn journal_date(trigger: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(trigger)
+        .ok()
+        .and_then(|events| events.get("date")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string())
+}
+
+/// Shape the day's event summary for the journal prompt according to the
+/// journal settings. The frontend always sends `topApps`; it's dropped here
+/// unless app usage is wanted, and recent chat lines are added on request.
+fn journal_events(
+    app: &tauri::AppHandle,
+    trigger: &str,
+    journal: &JournalSettings,
+    allowed: &ContextSettings,
+) -> String {
+    let Ok(serde_json::Value::Object(mut events)) = serde_json::from_str(trigger) else {
+        return trigger.to_string();
     };
+    if !(journal.include_app_usage && allowed.send_app_name) {
+        events.remove("topApps");
+    }
+    let memory = app.state::<memory::MemoryStore>();
+    if journal.include_chat_quotes && allowed.send_facts && !memory.is_locked() {
+        let quotes: Vec<String> = memory
+            .snapshot()
+            .messages
+            .iter()
+            .rev()
+            .filter(|m| m.role == "user")
+            .take(JOURNAL_QUOTES)
+            .map(|m| m.content.chars().take(120).collect())
+            .collect();
+        if !quotes.is_empty() {
+            events.insert("ownerSaid".to_string(), serde_json::json!(quotes));
+        }
+    }
+    serde_json::Value::Object(events).to_string()
+}
 
-    let facts = chat_memory
-        .as_ref()
-        .map(|m| m.facts.as_slice())
-        .unwrap_or(&[]);
+fn append_context(context: &mut Option<String>, line: String) {
+    *context = Some(match context.take() {
+        Some(existing) => format!("{} {}", existing, line),
+        None => line,
+    });
+}
 
-    let system_prompt = build_system_prompt(&mode, &app_name, &window_title, facts);
-    let user_message = build_user_message(&mode, &trigger, &user_input);
+/// Assemble the full Messages API request for a mode, exactly as it will be sent.
+/// `target` is the language a translation goes into; None for every other
+/// mode, and for translations into the pet's own language. `image` goes
+/// ahead of the user message, for the screenshot modes. A `preview` never
+/// reads the screen, and says where the screen's text would go. Blocks:
+/// the browser, git and the screen are asked for context.
+#[allow(clippy::too_many_arguments)]
+fn build_request(
+    app: &tauri::AppHandle,
+    mode: &str,
+    app_name: &str,
+    window_title: &str,
+    trigger: &str,
+    user_input: &str,
+    chat_memory: Option<&memory::ChatMemory>,
+    target: Option<&Language>,
+    image: Option<&Image>,
+    preview: bool,
+) -> ClaudeRequest {
+    let settings = app.state::<SettingsStore>().get();
+    let mut system_prompt =
+        build_system_prompt(app, mode, app_name, window_title, chat_memory, &settings, target);
+    let (allowed, journal) = (&settings.context, &settings.journal);
+    // Morning chatter doubles as the daily briefing: mention what's coming up
+    let briefing_mode = matches!(mode, "chat" | "spontaneous");
+    if briefing_mode && allowed.send_time && time_of_day(&chrono::Local::now()) == "morning" {
+        if let Some(briefing) = countdowns::briefing(app) {
+            append_context(&mut system_prompt.context, briefing);
+        }
+    }
+    if allowed.send_battery && !matches!(mode, "journal" | "guest") {
+        if let Some(battery) = power::battery_context(app) {
+            append_context(&mut system_prompt.context, battery);
+        }
+    }
+    if allowed.send_weather && !matches!(mode, "journal" | "guest") {
+        if let Some(weather) = weather::context(app) {
+            append_context(&mut system_prompt.context, weather);
+        }
+    }
+    if allowed.send_github && matches!(mode, "chat" | "spontaneous" | "react") {
+        if let Some(news) = github::context(app) {
+            append_context(&mut system_prompt.context, news);
+        }
+    }
+    if let Some(repo) = git::context_for_prompt(app, mode, app_name) {
+        append_context(&mut system_prompt.context, repo);
+    }
+    // Window titles are often just "Untitled"; what's on screen says more
+    if preview {
+        if ocr::applies(app, mode) {
+            append_context(
+                &mut system_prompt.context,
+                "Text visible in their window: [read from the screen when sent]".to_string(),
+            );
+        }
+    } else if let Some(text) = ocr::text_for_prompt(app, mode) {
+        append_context(&mut system_prompt.context, text);
+    }
+    // The page says more than the window title ("New Tab")
+    if let Some(tab) = browser::tab_for_prompt(app, mode, app_name) {
+        append_context(&mut system_prompt.context, tab.describe());
+    }
+    let trigger = match mode {
+        "journal" => journal_events(app, trigger, journal, allowed),
+        _ => trigger.to_string(),
+    };
+    let user_message = build_user_message(mode, &trigger, user_input, allowed.send_time);
 
-    let max_tokens = match mode.as_str() {
+    let max_tokens = match mode {
         "search" => 256,
-        "journal" => 200,
+        "journal" => match journal.length {
+            JournalLength::Haiku => 80,
+            JournalLength::Short => 200,
+            JournalLength::Long => 600,
+        },
         "chat" => 150,
+        "summarize" | "explain" => 200,
+        "standup" => 250,
+        "anniversary" => 200,
+        "digest" => 400,
+        "extract" => 1024,
+        "report" => 200,
+        // A translation runs about as long as what it translates
+        "translate" => (user_input.chars().count() as u32 / 2 + TRANSLATE_EXTRA_TOKENS)
+            .clamp(200, MAX_TRANSLATE_TOKENS),
         _ => 100,
     };
+    let overrides = settings
+        .dialogue_config
+        .modes
+        .get(mode)
+        .cloned()
+        .unwrap_or_default();
 
-    // Add web_search tool for search mode
-    let tools = if mode == "search" {
-        Some(vec![serde_json::json!({
-            "type": "web_search_20250305",
-            "name": "web_search",
-            "max_uses": 3
-        })])
-    } else {
-        None
+    // Search mode gets Anthropic's server-side web_search plus our local
+    // tools; chat gets just the local ones ("how's AAPL?", "open my
+    // timesheet"), less any the owner turned off
+    let tools = match mode {
+        "search" => {
+            let mut tools = vec![serde_json::json!({
+                "type": "web_search_20250305",
+                "name": "web_search",
+                "max_uses": 3
+            })];
+            tools.extend(tools::definitions(app));
+            Some(tools)
+        }
+        "chat" => Some(tools::definitions(app)).filter(|tools| !tools.is_empty()),
+        _ => None,
     };
 
-    // Build messages array: include history for chat mode
+    // Build messages array: include history for chat mode. The last history
+    // message carries a cache breakpoint so the prior conversation is read
+    // from the prompt cache instead of being billed in full on every turn.
     let mut messages: Vec<Message> = Vec::new();
-    if let Some(ref mem) = chat_memory {
-        for msg in &mem.messages {
+    if let Some(mem) = chat_memory {
+        let last = mem.messages.len().saturating_sub(1);
+        for (i, msg) in mem.messages.iter().enumerate() {
+            let content = if i == last {
+                MessageContent::Blocks(vec![TextBlock::cached(msg.content.clone())])
+            } else {
+                MessageContent::Text(msg.content.clone())
+            };
             messages.push(Message {
                 role: msg.role.clone(),
-                content: msg.content.clone(),
+                content,
             });
         }
     }
+    let content = match image {
+        Some(image) => MessageContent::Raw(vec![
+            serde_json::json!({
+                "type": "image",
+                "source": {
+                    "type": "base64",
+                    "media_type": image.media_type,
+                    "data": image.data,
+                },
+            }),
+            serde_json::json!({ "type": "text", "text": user_message }),
+        ]),
+        None => MessageContent::Text(user_message),
+    };
     messages.push(Message {
         role: "user".to_string(),
-        content: user_message.clone(),
+        content,
     });
 
-    let request = ClaudeRequest {
-        model: "claude-haiku-4-5-20251001".to_string(),
-        max_tokens,
-        system: system_prompt,
+    ClaudeRequest {
+        model: overrides.model.unwrap_or_else(|| PRIMARY_MODEL.to_string()),
+        max_tokens: overrides.max_tokens.unwrap_or(max_tokens),
+        temperature: overrides.temperature,
+        system: system_prompt.into_blocks(),
         messages,
         tools,
-    };
+    }
+}
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", &api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+/// Final reply of a tool loop plus totals across every round trip.
+struct ToolLoopOutcome {
+    response: ClaudeResponse,
+    usage: usage::ApiUsage,
+    web_search_used: bool,
+}
 
-    let status = response.status();
-    let body = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+/// Send `request`, then keep executing requested local tools and feeding
+/// their results back (tool_use -> execute -> tool_result -> continue) until
+/// the model answers or MAX_TOOL_ITERATIONS is reached. Server-side tools like
+/// web_search run inside a single call; a `pause_turn` just gets resumed.
+async fn run_tool_loop(
+    app: &tauri::AppHandle,
+    api_key: &str,
+    mut request: ClaudeRequest,
+) -> Result<ToolLoopOutcome, SendError> {
+    let mut total = usage::ApiUsage::default();
+    let mut web_search_used = false;
+    let mut iteration = 0;
 
-    if !status.is_success() {
-        let error_msg = serde_json::from_str::<ClaudeErrorResponse>(&body)
-            .ok()
-            .and_then(|e| e.error)
-            .and_then(|e| e.message)
-            .unwrap_or_else(|| format!("API error: {}", status));
-        return Err(error_msg);
+    loop {
+        let body = send_with_retry(api_key, request.clone()).await?;
+        let raw: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| SendError::Fatal(PetError::from(e)))?;
+        let response: ClaudeResponse = serde_json::from_value(raw.clone())
+            .map_err(|e| SendError::Fatal(PetError::from(e)))?;
+
+        let used_model = response
+            .model
+            .clone()
+            .unwrap_or_else(|| PRIMARY_MODEL.to_string());
+        let api_usage = response.usage;
+        let handle = app.clone();
+        crate::run_blocking(move || handle.state::<UsageStore>().record(&used_model, api_usage))
+            .await
+            .map_err(SendError::Fatal)?;
+        total.add(&api_usage);
+        web_search_used |= response
+            .content
+            .iter()
+            .any(|block| block.block_type.as_deref() == Some("web_search_tool_result"));
+
+        iteration += 1;
+        let stop_reason = response.stop_reason.as_deref();
+        if iteration >= MAX_TOOL_ITERATIONS
+            || !matches!(stop_reason, Some("tool_use") | Some("pause_turn"))
+        {
+            return Ok(ToolLoopOutcome {
+                response,
+                usage: total,
+                web_search_used,
+            });
+        }
+
+        let mut results = Vec::new();
+        for block in &response.content {
+            if block.block_type.as_deref() != Some("tool_use") {
+                continue;
+            }
+            let (Some(id), Some(name)) = (&block.id, &block.name) else {
+                continue;
+            };
+            let input = block.input.clone().unwrap_or_default();
+            let (content, is_error) = match tools::execute(app, name, &input).await {
+                Ok(output) => (output, false),
+                Err(error) => (error, true),
+            };
+            results.push(serde_json::json!({
+                "type": "tool_result",
+                "tool_use_id": id,
+                "content": content,
+                "is_error": is_error,
+            }));
+        }
+
+        let assistant_blocks = raw
+            .get("content")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default();
+        request.messages.push(Message {
+            role: "assistant".to_string(),
+            content: MessageContent::Raw(assistant_blocks),
+        });
+        if !results.is_empty() {
+            request.messages.push(Message {
+                role: "user".to_string(),
+                content: MessageContent::Raw(results),
+            });
+        }
     }
+}
 
-    let claude_response: ClaudeResponse =
-        serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))?;
+/// Fold chat messages that aged out of the history window into the rolling
+/// long-term summary, so old conversations fade to gist instead of vanishing.
+async fn compact_memory(app: tauri::AppHandle, api_key: String) {
+    // The cap covers housekeeping too; what's waiting is summarized once
+    // there's budget again
+    if app.state::<UsageStore>().budget_exceeded() {
+        return;
+    }
+    let store = app.state::<memory::MemoryStore>();
+    let Some((summary, pending)) = store.begin_compaction() else {
+        return;
+    };
 
-    // Web search responses split the answer across multiple text blocks with citations
-    // in between. Find all text blocks after the last search result and concatenate them.
-    let last_search_idx = claude_response
-        .content
+    let transcript: Vec<String> = pending
         .iter()
-        .rposition(|block| block.block_type.as_deref() == Some("web_search_tool_result"));
+        .map(|m| {
+            let speaker = if m.role == "user" { "Owner" } else { "Cat" };
+            format!("{}: {}", speaker, m.content)
+        })
+        .collect();
+    let previous = if summary.is_empty() {
+        "(none yet)".to_string()
+    } else {
+        summary
+    };
+
+    let request = ClaudeRequest {
+        model: PRIMARY_MODEL.to_string(),
+        max_tokens: COMPACTION_MAX_TOKENS,
+        temperature: None,
+        system: vec![TextBlock::new(
+            "You maintain the long-term memory of a cat desktop pet. Merge the existing \
+            summary with the new conversation excerpt into one concise paragraph (under 120 words) \
+            written in third person about the owner and their chats with the cat. Keep names, \
+            plans, preferences, and recurring topics; drop small talk. Reply with the paragraph only.",
+        )],
+        messages: vec![Message {
+            role: "user".to_string(),
+            content: MessageContent::Text(format!(
+                "Existing summary: {}\n\nNew excerpt:\n{}",
+                previous,
+                transcript.join("\n")
+            )),
+        }],
+        tools: None,
+    };
 
-    let start = last_search_idx.map(|i| i + 1).unwrap_or(0);
+    let response = match send_with_retry(&api_key, request).await {
+        Ok(body) => serde_json::from_str::<ClaudeResponse>(&body).ok(),
+        Err(_) => None,
+    };
 
-    let answer: String = claude_response
+    let new_summary = match response {
+        Some(response) => {
+            let used_model = response
+                .model
+                .clone()
+                .unwrap_or_else(|| PRIMARY_MODEL.to_string());
+            let api_usage = response.usage;
+            let handle = app.clone();
+            let _ = crate::run_blocking(move || {
+                handle.state::<UsageStore>().record(&used_model, api_usage)
+            })
+            .await;
+
+            let text: String = response
+                .content
+                .iter()
+                .filter(|block| block.block_type.as_deref() == Some("text"))
+                .filter_map(|block| block.text.as_deref())
+                .collect();
+            let text = text.trim().to_string();
+            (!text.is_empty()).then_some(text)
+        }
+        None => None,
+    };
+
+    store.finish_compaction(pending.len(), new_summary);
+}
+
+/// Web search responses split the answer across multiple text blocks with citations
+/// in between. Concatenate all text blocks after the last search result and
+/// collect their unique sources in order of first use.
+fn extract_answer(response: &ClaudeResponse) -> (String, Vec<Citation>) {
+    let start = response
+        .content
+        .iter()
+        .rposition(|block| block.block_type.as_deref() == Some("web_search_tool_result"))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    let answer_blocks: Vec<&ContentBlock> = response
         .content
         .iter()
         .skip(start)
         .filter(|block| block.block_type.as_deref() == Some("text"))
+        .collect();
+    let answer: String = answer_blocks
+        .iter()
         .filter_map(|block| block.text.as_deref())
         .collect();
 
+    let mut citations: Vec<Citation> = Vec::new();
+    for citation in answer_blocks
+        .iter()
+        .filter_map(|block| block.citations.as_ref())
+        .flatten()
+    {
+        if !citation.url.is_empty() && !citations.iter().any(|c| c.url == citation.url) {
+            citations.push(citation.clone());
+        }
+    }
+
     let answer = answer.trim().trim_start_matches(['.', ',', ';', ':']).trim().to_string();
+    (answer, citations)
+}
+
+/// Run a search-mode query for a background job, with `instructions`
+/// appended to the system prompt.
+pub async fn run_background_search(
+    app: &tauri::AppHandle,
+    api_key: &str,
+    trigger: &str,
+    query: &str,
+    instructions: Option<String>,
+) -> PetResult<String> {
+    let mut request = crate::run_blocking({
+        let (app, trigger, query) = (app.clone(), trigger.to_string(), query.to_string());
+        move || build_request(&app, "search", "", "", &trigger, &query, None, None, None, false)
+    })
+    .await?;
+    if let Some(instructions) = instructions {
+        request.system.push(TextBlock::new(instructions));
+    }
+
+    let outcome = run_tool_loop(app, api_key, request)
+        .await
+        .map_err(|e| match e {
+            SendError::Retryable(e) | SendError::Fatal(e) => e,
+        })?;
+    let (answer, _) = extract_answer(&outcome.response);
+    // Search mode asks for an emotion trailer; background jobs have no face to show it
+    let (answer, _) = extract_tags(&answer, "EMOTION");
+    if answer.is_empty() {
+        return Err(PetError::EmptyResponse);
+    }
+    Ok(answer)
+}
+
+/// One-off prompt with no tools or history, for background jobs.
+pub async fn run_background_prompt(
+    app: &tauri::AppHandle,
+    api_key: &str,
+    instructions: &str,
+    prompt: String,
+    max_tokens: u32,
+) -> PetResult<String> {
+    let request = ClaudeRequest {
+        model: PRIMARY_MODEL.to_string(),
+        max_tokens,
+        temperature: None,
+        system: vec![TextBlock::new(instructions)],
+        messages: vec![Message {
+            role: "user".to_string(),
+            content: MessageContent::Text(prompt),
+        }],
+        tools: None,
+    };
+    let outcome = run_tool_loop(app, api_key, request)
+        .await
+        .map_err(|e| match e {
+            SendError::Retryable(e) | SendError::Fatal(e) => e,
+        })?;
+    let (answer, _) = extract_answer(&outcome.response);
+    if answer.is_empty() {
+        return Err(PetError::EmptyResponse);
+    }
+    Ok(answer)
+}
+
+/// Re-run a watched search. Returns the new answer, or None when the model
+/// judges nothing meaningful changed since `previous`.
+pub async fn run_watch_query(
+    app: &tauri::AppHandle,
+    api_key: &str,
+    query: &str,
+    previous: Option<&str>,
+) -> PetResult<Option<String>> {
+    let instructions = previous.map(|previous| {
+        format!(
+            "This is a scheduled re-check of a search the owner asked you to watch. \
+            Last time you answered: \"{}\". If nothing meaningful has changed (same score, \
+            price, status or headline), reply with exactly {} and nothing else.",
+            previous, NO_CHANGE_MARKER
+        )
+    });
+    let answer =
+        run_background_search(app, api_key, "scheduled re-check", query, instructions).await?;
+    Ok((!answer.contains(NO_CHANGE_MARKER)).then_some(answer))
+}
+
+#[tauri::command]
+pub async fn generate_pet_dialogue(
+    app: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    app_name: String,
+    window_title: String,
+    trigger: String,
+    mode: Option<String>,
+    user_input: Option<String>,
+) -> PetResult<DialogueResponse> {
+    // Nothing unprompted while the owner is on a call
+    if matches!(mode.as_deref(), None | Some("spontaneous" | "react"))
+        && crate::meetings::is_in_meeting(&app)
+    {
+        return Err(PetError::InvalidInput(
+            "Keeping quiet during the meeting".to_string(),
+        ));
+    }
+    let response =
+        generate(app.clone(), app_name, window_title, trigger, mode, user_input, None, None).await?;
+    // The overlay is hidden in menu-bar-only mode, so its lines go to notifications.
+    // The chat popover renders its own replies.
+    if window.label() == "main" && crate::menubar::is_enabled(&app) {
+        crate::notifications::notify(&app, &response.text);
+    }
+    Ok(response)
+}
+
+/// Translate `text` into `target` (a language tag like "es"), or into the
+/// pet's own language when None. Typed into the palette or highlighted in
+/// `app_name`. Nothing is added to chat memory.
+#[tauri::command]
+pub async fn translate_text(
+    app: tauri::AppHandle,
+    text: String,
+    target: Option<String>,
+    app_name: Option<String>,
+) -> PetResult<DialogueResponse> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(PetError::InvalidInput("Nothing to translate".to_string()));
+    }
+    if text.chars().count() > MAX_TRANSLATE_CHARS {
+        return Err(PetError::InvalidInput(format!(
+            "That's too long to translate in one go (up to {} characters)",
+            MAX_TRANSLATE_CHARS
+        )));
+    }
+    let target = target
+        .as_deref()
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(language::parse)
+        .transpose()?;
+    generate(
+        app,
+        app_name.unwrap_or_default(),
+        String::new(),
+        "translate".to_string(),
+        Some("translate".to_string()),
+        Some(text),
+        target,
+        None,
+    )
+    .await
+}
+
+/// `target` only applies to translate mode and `image` to the screenshot
+/// modes; see `build_request`.
+#[allow(clippy::too_many_arguments)]
+pub async fn generate(
+    app: tauri::AppHandle,
+    app_name: String,
+    window_title: String,
+    trigger: String,
+    mode: Option<String>,
+    user_input: Option<String>,
+    target: Option<Language>,
+    image: Option<Image>,
+) -> PetResult<DialogueResponse> {
+    let mode = mode.unwrap_or_else(|| "spontaneous".to_string());
+    let user_input = user_input.unwrap_or_default();
+
+    // Being talked to cheers the cat up, whether or not it can answer
+    if matches!(
+        mode.as_str(),
+        "chat" | "search" | "summarize" | "explain" | "translate"
+    ) {
+        let app = app.clone();
+        let _ = crate::run_blocking(move || mood::note(&app, mood::Interaction::Chat)).await;
+    }
+
+    let offline_line = || {
+        let text = canned_dialogue::pick(&mode, &app_name, time_of_day(&chrono::Local::now()), None);
+        DialogueResponse::offline(&mode, text)
+    };
+
+    // Degrade to pre-written lines when there's no key or the monthly cap is spent
+    let api_key = match crate::config::anthropic_api_key() {
+        Some(key) => key,
+        None => return Ok(offline_line()),
+    };
+    if app.state::<UsageStore>().budget_exceeded() {
+        return Ok(offline_line());
+    }
+
+    let is_chat = mode == "chat";
+
+    // Snapshot memory for chat mode; the store is only locked briefly so other
+    // requests aren't blocked while this one waits on the network
+    let chat_memory = if is_chat {
+        Some(app.state::<memory::MemoryStore>().snapshot())
+    } else {
+        None
+    };
+
+    // Asking the browser for its tab shells out, so assemble off the runtime
+    let request = crate::run_blocking({
+        let (app, mode, app_name) = (app.clone(), mode.clone(), app_name.clone());
+        let (window_title, trigger, user_input) =
+            (window_title.clone(), trigger.clone(), user_input.clone());
+        move || {
+            build_request(
+                &app,
+                &mode,
+                &app_name,
+                &window_title,
+                &trigger,
+                &user_input,
+                chat_memory.as_ref(),
+                target.as_ref(),
+                image.as_ref(),
+                false,
+            )
+        }
+    })
+    .await?;
+
+    let outcome = match run_tool_loop(&app, &api_key, request).await {
+        Ok(outcome) => outcome,
+        // Network down or API overloaded even after retries: stay in character
+        Err(SendError::Retryable(e)) => {
+            eprintln!("Claude unavailable, using offline dialogue: {}", e);
+            return Ok(offline_line());
+        }
+        Err(SendError::Fatal(e)) => return Err(e),
+    };
+
+    let claude_response = outcome.response;
+    let api_usage = outcome.usage;
+    let used_model = claude_response
+        .model
+        .clone()
+        .unwrap_or_else(|| PRIMARY_MODEL.to_string());
+
+    let (answer, citations) = extract_answer(&claude_response);
     if answer.is_empty() {
-        return Err("Empty response from Claude".to_string());
+        return Err(PetError::EmptyResponse);
+    }
+
+    let (answer, emotions) = extract_tags(&answer, "EMOTION");
+    let emotion = emotions.first().and_then(|e| Emotion::parse(e));
+
+    let (answer, note_texts) = extract_tags(&answer, "NOTE");
+    let notes = if note_texts.is_empty() {
+        Vec::new()
+    } else {
+        let handle = app.clone();
+        crate::run_blocking(move || {
+            let store = handle.state::<NotesStore>();
+            let notes = note_texts
+                .iter()
+                .map(|tag| store.add_from_tag(tag))
+                .collect();
+            crate::tray::refresh(&handle);
+            notes
+        })
+        .await?
+    };
+
+    // For chat mode: extract [WATCH:] tags and start a watcher for each
+    let (answer, watch_queries) = if is_chat {
+        extract_tags(&answer, "WATCH")
+    } else {
+        (answer, Vec::new())
+    };
+    let watchers = if watch_queries.is_empty() {
+        Vec::new()
+    } else {
+        let handle = app.clone();
+        crate::run_blocking(move || {
+            let store = handle.state::<WatcherStore>();
+            watch_queries
+                .iter()
+                .map(|query| store.add(query, watchers::DEFAULT_INTERVAL_MINUTES))
+                .collect()
+        })
+        .await?
+    };
+
+    if mode == "judge" {
+        let roast = answer.clone();
+        let handle = app.clone();
+        crate::run_blocking(move || handle.state::<WrappedStore>().record_roast(&roast)).await?;
     }
 
-    // For chat mode: extract [REMEMBER:] tags and save to memory
-    if is_chat {
-        let (cleaned, new_facts) = extract_remember_tags(&answer);
-        let mut mem = chat_memory.unwrap_or_default();
-        for fact in &new_facts {
-            memory::add_fact(&mut mem, fact);
+    // Journal entries end with a [MOOD: n] rating for the mood calendar
+    let (answer, mood) = if mode == "journal" {
+        let (cleaned, ratings) = extract_tags(&answer, "MOOD");
+        let mood = ratings.first().and_then(|r| r.parse::<u8>().ok());
+        if let Some(score) = mood {
+            let date = journal_date(&trigger);
+            let handle = app.clone();
+            crate::run_blocking(move || handle.state::<MoodStore>().record(&date, score)).await?;
         }
-        memory::add_exchange(&mut mem, &user_input, &cleaned);
-        memory::save_memory(&app, &mem);
-        return Ok(cleaned);
+        (cleaned, mood)
+    } else {
+        (answer, None)
+    };
+
+    let first = match mode.as_str() {
+        "chat" => Some(Milestone::Chat),
+        "search" => Some(Milestone::Search),
+        "journal" => Some(Milestone::Journal),
+        _ => None,
+    };
+    if let Some(first) = first.filter(|first| !milestones::is_reached(&app, *first)) {
+        let (handle, input) = (app.clone(), user_input.clone());
+        crate::run_blocking(move || {
+            let detail = Some(input.trim()).filter(|input| !input.is_empty());
+            milestones::note(&handle, first, detail)
+        })
+        .await?;
     }
 
-    Ok(answer)
+    let text = if is_chat {
+        let (cleaned, new_facts) = extract_tags(&answer, "REMEMBER");
+        app.state::<memory::MemoryStore>().update(|mem| {
+            for fact in &new_facts {
+                memory::add_fact(mem, fact);
+            }
+            memory::add_exchange(mem, &user_input, &cleaned);
+        });
+        tauri::async_runtime::spawn(compact_memory(app.clone(), api_key.clone()));
+        cleaned
+    } else {
+        answer
+    };
+
+    Ok(DialogueResponse {
+        text,
+        mode,
+        model: Some(used_model),
+        input_tokens: api_usage.input_tokens,
+        output_tokens: api_usage.output_tokens,
+        web_search_used: outcome.web_search_used,
+        citations,
+        notes,
+        watchers,
+        mood,
+        emotion,
+        offline: false,
+    })
+}
+
+/// Show exactly which context line would accompany a prompt under the
+/// current privacy toggles, or None if nothing situational is sent.
+#[tauri::command(async)]
+pub fn preview_context(
+    settings: tauri::State<'_, SettingsStore>,
+    app_name: String,
+    window_title: String,
+) -> Option<String> {
+    build_context(&app_name, &window_title, &settings.get().context)
+        .map(|context| format!("Context: {}", context))
+}
+
+/// Build the request a dialogue call would send, without sending it, so users
+/// can inspect exactly what leaves the machine. The screen isn't read for
+/// a preview; a placeholder shows where its text would go.
+#[tauri::command]
+pub async fn preview_prompt(
+    app: tauri::AppHandle,
+    mode: String,
+    trigger: Option<String>,
+    user_input: Option<String>,
+    app_name: Option<String>,
+    window_title: Option<String>,
+) -> PetResult<serde_json::Value> {
+    crate::run_blocking(move || {
+        let chat_memory =
+            (mode == "chat").then(|| app.state::<memory::MemoryStore>().snapshot());
+        let request = build_request(
+            &app,
+            &mode,
+            &app_name.unwrap_or_default(),
+            &window_title.unwrap_or_default(),
+            &trigger.unwrap_or_default(),
+            &user_input.unwrap_or_default(),
+            chat_memory.as_ref(),
+            None,
+            None,
+            true,
+        );
+        Ok(serde_json::to_value(request)?)
+    })
+    .await?
+}
+
+/// Per-mode model, max_tokens and temperature overrides.
+#[tauri::command(async)]
+pub fn get_dialogue_config(settings: tauri::State<'_, SettingsStore>) -> DialogueConfig {
+    settings.get().dialogue_config
+}
+
+/// Replace the per-mode overrides. A mode left out, or a field left unset,
+/// goes back to the built-in default.
+#[tauri::command]
+pub async fn set_dialogue_config(
+    app: tauri::AppHandle,
+    mut config: DialogueConfig,
+) -> PetResult<DialogueConfig> {
+    crate::run_blocking(move || {
+        for (mode, overrides) in config.modes.iter_mut() {
+            if !MODES.contains(&mode.as_str()) {
+                return Err(PetError::InvalidInput(format!("Unknown mode \"{}\"", mode)));
+            }
+            overrides.model = overrides
+                .model
+                .as_deref()
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string);
+            if let Some(model) = &overrides.model {
+                if model.contains(char::is_whitespace) {
+                    return Err(PetError::InvalidInput(format!(
+                        "\"{}\" isn't a model name",
+                        model
+                    )));
+                }
+            }
+            if let Some(max_tokens) = overrides.max_tokens {
+                if max_tokens == 0 || max_tokens > MAX_TOKENS_LIMIT {
+                    return Err(PetError::InvalidInput(format!(
+                        "max_tokens for {} must be between 1 and {}",
+                        mode, MAX_TOKENS_LIMIT
+                    )));
+                }
+            }
+            if let Some(temperature) = overrides.temperature {
+                if !(0.0..=1.0).contains(&temperature) {
+                    return Err(PetError::InvalidInput(format!(
+                        "Temperature for {} must be between 0 and 1",
+                        mode
+                    )));
+                }
+            }
+        }
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.dialogue_config = config;
+        store.replace(settings)?;
+        Ok(store.get().dialogue_config)
+    })
+    .await?
 }
@@ -0,0 +1,225 @@
+// Typing into other apps goes through Quartz keyboard events, which only
+// macOS has here
+#![cfg_attr(not(target_os = "macos"), allow(dead_code, unused_variables))]
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::accessibility;
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+
+/// A draft the user hasn't confirmed by now is dropped.
+const DRAFT_TTL: Duration = Duration::from_secs(60);
+/// Longer than anyone dictates in one go; anything bigger is a mistake.
+const MAX_CHARS: usize = 2_000;
+const PREVIEW_CHARS: usize = 80;
+/// Time for the app being typed into to come back in front after the
+/// confirmation click.
+const REFOCUS_DELAY: Duration = Duration::from_millis(200);
+
+/// What was heard, held in memory only until it's typed, replaced or expires.
+struct Draft {
+    id: String,
+    text: String,
+    /// The app whose text field it goes into
+    pid: u64,
+    app_name: String,
+    staged_at: Instant,
+}
+
+#[derive(Default)]
+pub struct DictationState {
+    draft: Mutex<Option<Draft>>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationDraft {
+    pub id: String,
+    pub preview: String,
+    pub chars: usize,
+    pub app_name: String,
+}
+
+fn preview(text: &str) -> String {
+    if text.chars().count() <= PREVIEW_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(PREVIEW_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn front_app() -> Option<(u64, String)> {
+    active_win_pos_rs::get_active_window()
+        .ok()
+        .map(|w| (w.process_id, w.app_name))
+}
+
+pub fn is_enabled(app: &tauri::AppHandle) -> bool {
+    app.state::<SettingsStore>().get().dictation.enabled
+}
+
+/// Turning it off also drops a draft waiting for confirmation.
+pub fn set_enabled(app: &tauri::AppHandle, enabled: bool) -> PetResult<()> {
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.dictation.enabled = enabled;
+    store.replace(settings)?;
+    *app.state::<DictationState>().draft.lock().unwrap() = None;
+    let _ = app.emit("dictation-changed", enabled);
+    Ok(())
+}
+
+/// Hold on to a transcription for the text field that has focus right now.
+/// Nothing is typed until `type_draft` confirms it.
+pub fn stage(app: &tauri::AppHandle, text: &str) -> PetResult<DictationDraft> {
+    if !is_enabled(app) {
+        return Err(PetError::InvalidInput(
+            "Dictation is turned off in Settings".to_string(),
+        ));
+    }
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(PetError::InvalidInput(
+            "I didn't catch anything".to_string(),
+        ));
+    }
+    let chars = text.chars().count();
+    if chars > MAX_CHARS {
+        return Err(PetError::InvalidInput(format!(
+            "That's too much to type at once ({} characters, {} at most)",
+            chars, MAX_CHARS
+        )));
+    }
+    accessibility::require_trust()?;
+    if accessibility::focused_text_field(app)?.is_none() {
+        return Err(PetError::InvalidInput(
+            "Click into a text field first, then tell me what to type".to_string(),
+        ));
+    }
+    let (pid, app_name) = front_app()
+        .ok_or_else(|| PetError::Platform("Failed to get active window info".to_string()))?;
+
+    let draft = DictationDraft {
+        id: uuid::Uuid::new_v4().to_string(),
+        preview: preview(text),
+        chars,
+        app_name: app_name.clone(),
+    };
+    *app.state::<DictationState>().draft.lock().unwrap() = Some(Draft {
+        id: draft.id.clone(),
+        text: text.to_string(),
+        pid,
+        app_name,
+        staged_at: Instant::now(),
+    });
+    Ok(draft)
+}
+
+/// Type a confirmed draft into the app it was staged for, after bringing
+/// that app back in front. Refuses if focus has moved somewhere else.
+pub fn type_draft(app: &tauri::AppHandle, id: &str) -> PetResult<()> {
+    let draft = app
+        .state::<DictationState>()
+        .draft
+        .lock()
+        .unwrap()
+        .take()
+        .filter(|draft| draft.id == id && draft.staged_at.elapsed() < DRAFT_TTL)
+        .ok_or_else(|| PetError::InvalidInput("That dictation has expired".to_string()))?;
+    if !is_enabled(app) {
+        return Err(PetError::InvalidInput(
+            "Dictation is turned off in Settings".to_string(),
+        ));
+    }
+    accessibility::require_trust()?;
+    #[cfg(target_os = "macos")]
+    {
+        // Clicking the confirmation brought the pet in front
+        macos::activate(draft.pid);
+        std::thread::sleep(REFOCUS_DELAY);
+        let still_there = front_app().is_some_and(|(pid, _)| pid == draft.pid)
+            && accessibility::focused_text_field(app)?.is_some();
+        if !still_there {
+            return Err(PetError::Platform(format!(
+                "{} isn't waiting in a text field anymore",
+                draft.app_name
+            )));
+        }
+        macos::type_text(&draft.text)
+    }
+    #[cfg(not(target_os = "macos"))]
+    Err(PetError::Platform(
+        "Dictation can only type on macOS".to_string(),
+    ))
+}
+
+#[tauri::command]
+pub async fn set_dictation_enabled(app: tauri::AppHandle, enabled: bool) -> PetResult<()> {
+    crate::run_blocking(move || set_enabled(&app, enabled)).await?
+}
+
+/// Hand over what was heard. Returns a draft to show the user; the text is
+/// only typed once they confirm it with `type_dictation`.
+#[tauri::command]
+pub async fn stage_dictation(app: tauri::AppHandle, text: String) -> PetResult<DictationDraft> {
+    crate::run_blocking(move || stage(&app, &text)).await?
+}
+
+/// Type the draft the user just confirmed. Needs Accessibility access.
+#[tauri::command]
+pub async fn type_dictation(app: tauri::AppHandle, id: String) -> PetResult<()> {
+    crate::run_blocking(move || type_draft(&app, &id)).await?
+}
+
+/// Throw the draft away without typing it.
+#[tauri::command(async)]
+pub fn discard_dictation(state: tauri::State<'_, DictationState>) {
+    *state.draft.lock().unwrap() = None;
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use core_graphics::event::{CGEvent, CGEventTapLocation};
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+    use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
+    use std::time::Duration;
+
+    use crate::error::{PetError, PetResult};
+
+    /// A keyboard event carries at most 20 UTF-16 units; ten characters
+    /// always fit, surrogate pairs included.
+    const CHARS_PER_EVENT: usize = 10;
+    /// Some apps drop keystrokes that arrive all at once.
+    const EVENT_GAP: Duration = Duration::from_millis(8);
+
+    pub fn activate(pid: u64) {
+        if let Some(app) = NSRunningApplication::runningApplicationWithProcessIdentifier(pid as i32)
+        {
+            app.activateWithOptions(NSApplicationActivationOptions::empty());
+        }
+    }
+
+    /// Type `text` wherever keyboard focus is, as if it were typed on the
+    /// keyboard. The key code is ignored once the string is attached.
+    pub fn type_text(text: &str) -> PetResult<()> {
+        let failed = || PetError::Platform("Failed to create keyboard event".to_string());
+        let source =
+            CGEventSource::new(CGEventSourceStateID::HIDSystemState).map_err(|_| failed())?;
+        let chars: Vec<char> = text.chars().collect();
+        for chunk in chars.chunks(CHARS_PER_EVENT) {
+            let chunk: String = chunk.iter().collect();
+            for key_down in [true, false] {
+                let event = CGEvent::new_keyboard_event(source.clone(), 0, key_down)
+                    .map_err(|_| failed())?;
+                event.set_string(&chunk);
+                event.post(CGEventTapLocation::HID);
+            }
+            std::thread::sleep(EVENT_GAP);
+        }
+        Ok(())
+    }
+}
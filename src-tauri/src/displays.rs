@@ -0,0 +1,192 @@
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{Emitter, Manager, Monitor};
+
+use crate::error::{PetError, PetResult};
+use crate::scheduler;
+use crate::settings::SettingsStore;
+
+/// How often the watcher checks the pet is still on the right display, which
+/// changes when one is plugged in, unplugged or rearranged.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Display {
+    /// The name macOS gives the display; what settings refer to it by
+    pub id: String,
+    /// Screen points
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub primary: bool,
+    pub home: bool,
+    pub allowed: bool,
+    /// The pet is on this one right now
+    pub current: bool,
+}
+
+fn display_id(monitor: &Monitor, index: usize) -> String {
+    monitor
+        .name()
+        .cloned()
+        .unwrap_or_else(|| format!("Display {}", index + 1))
+}
+
+fn same(a: &Monitor, b: &Monitor) -> bool {
+    a.position() == b.position() && a.size() == b.size()
+}
+
+fn monitors(app: &tauri::AppHandle) -> Vec<(String, Monitor)> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(i, monitor)| (display_id(&monitor, i), monitor))
+        .collect()
+}
+
+fn current_monitor(app: &tauri::AppHandle) -> Option<Monitor> {
+    app.get_webview_window("main")?.current_monitor().ok()?
+}
+
+/// The display the pet should live on: home if it's connected and allowed,
+/// otherwise wherever it already is, then the primary display, then any
+/// allowed one. With every display blocked it stays put rather than vanish.
+pub fn pet_display(app: &tauri::AppHandle) -> Option<Monitor> {
+    let settings = app.state::<SettingsStore>().get().display;
+    let allowed = |id: &str| !settings.blocked_displays.iter().any(|b| b == id);
+    let monitors = monitors(app);
+    let current = current_monitor(app);
+    let primary = app.primary_monitor().ok().flatten();
+    let find = |target: Option<&Monitor>| {
+        let target = target?;
+        monitors
+            .iter()
+            .find(|(id, monitor)| same(monitor, target) && allowed(id))
+    };
+
+    settings
+        .home_display
+        .as_deref()
+        .and_then(|home| monitors.iter().find(|(id, _)| id == home && allowed(id)))
+        .or_else(|| find(current.as_ref()))
+        .or_else(|| find(primary.as_ref()))
+        .or_else(|| monitors.iter().find(|(id, _)| allowed(id)))
+        .map(|(_, monitor)| monitor.clone())
+        .or(current)
+}
+
+/// Every connected display, marked with the pet's preferences.
+pub fn list(app: &tauri::AppHandle) -> Vec<Display> {
+    let settings = app.state::<SettingsStore>().get().display;
+    let primary = app.primary_monitor().ok().flatten();
+    let current = current_monitor(app);
+    monitors(app)
+        .into_iter()
+        .map(|(id, monitor)| {
+            let scale = monitor.scale_factor();
+            let position = monitor.position().to_logical::<f64>(scale);
+            let size = monitor.size().to_logical::<f64>(scale);
+            Display {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                primary: primary.as_ref().is_some_and(|p| same(p, &monitor)),
+                home: settings.home_display.as_deref() == Some(id.as_str()),
+                allowed: !settings.blocked_displays.contains(&id),
+                current: current.as_ref().is_some_and(|c| same(c, &monitor)),
+                id,
+            }
+        })
+        .collect()
+}
+
+/// Move the overlay onto the pet's display if it isn't already there.
+pub fn enforce(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let Some(target) = pet_display(app) else {
+        return;
+    };
+    let placed = window.outer_position().ok() == Some(*target.position())
+        && window.outer_size().ok() == Some(*target.size());
+    if placed {
+        return;
+    }
+    crate::fill_display(&window, &target);
+    let _ = app.emit("displays-changed", list(app));
+}
+
+async fn check(app: tauri::AppHandle) {
+    let _ = crate::run_blocking(move || enforce(&app)).await;
+}
+
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    scheduler::every(app, WATCH_INTERVAL, check);
+}
+
+fn known(app: &tauri::AppHandle, id: &str) -> PetResult<()> {
+    if monitors(app).iter().any(|(known, _)| known == id) {
+        return Ok(());
+    }
+    Err(PetError::InvalidInput(format!(
+        "No display called \"{}\"",
+        id
+    )))
+}
+
+#[tauri::command(async)]
+pub fn get_displays(app: tauri::AppHandle) -> Vec<Display> {
+    list(&app)
+}
+
+/// Make a display the pet's home, or clear it to let the pet stay wherever
+/// it is. Moves the pet straight away.
+#[tauri::command]
+pub async fn set_home_display(
+    app: tauri::AppHandle,
+    id: Option<String>,
+) -> PetResult<Vec<Display>> {
+    crate::run_blocking(move || {
+        if let Some(id) = &id {
+            known(&app, id)?;
+        }
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.display.home_display = id;
+        store.replace(settings)?;
+        enforce(&app);
+        Ok(list(&app))
+    })
+    .await?
+}
+
+/// Allow or keep the pet off a display, say the one used for screen
+/// sharing. Moves the pet off it straight away.
+#[tauri::command]
+pub async fn set_display_allowed(
+    app: tauri::AppHandle,
+    id: String,
+    allowed: bool,
+) -> PetResult<Vec<Display>> {
+    crate::run_blocking(move || {
+        known(&app, &id)?;
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings
+            .display
+            .blocked_displays
+            .retain(|blocked| *blocked != id);
+        if !allowed {
+            settings.display.blocked_displays.push(id);
+        }
+        store.replace(settings)?;
+        enforce(&app);
+        Ok(list(&app))
+    })
+    .await?
+}
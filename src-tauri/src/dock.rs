@@ -0,0 +1,211 @@
+// The Dock is only readable through macOS's Accessibility API
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::accessibility;
+use crate::error::PetResult;
+use crate::occlusion::{self, Rect};
+use crate::scheduler;
+use crate::settings::SettingsStore;
+
+/// A bounce takes about a second, so look twice a second to catch one.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// How far an icon has to leave its resting spot to count as bouncing.
+const BOUNCE_DISTANCE: f64 = 4.0;
+/// An icon that hasn't bounced for this long has stopped asking; the next
+/// bounce is news again.
+const QUIET_PERIOD: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DockItemKind {
+    App,
+    Folder,
+    Trash,
+    /// A minimized window
+    Window,
+    Other,
+}
+
+impl DockItemKind {
+    fn from_subrole(subrole: &str) -> Option<Self> {
+        Some(match subrole {
+            "AXApplicationDockItem" => DockItemKind::App,
+            "AXFolderDockItem" => DockItemKind::Folder,
+            "AXTrashDockItem" => DockItemKind::Trash,
+            "AXMinimizedWindowDockItem" => DockItemKind::Window,
+            // Spacers aren't icons at all
+            "AXSeparatorDockItem" => return None,
+            _ => DockItemKind::Other,
+        })
+    }
+}
+
+/// A Dock icon in global screen points; commands and events hand it out in
+/// the pet window's coordinates instead.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DockIcon {
+    pub title: String,
+    pub kind: DockItemKind,
+    pub frame: Rect,
+    /// Apps only: whether it's open (the dot under the icon)
+    pub running: bool,
+}
+
+#[derive(Default)]
+pub struct DockState {
+    /// Where each icon sits when it isn't bouncing, by title
+    rest: Mutex<HashMap<String, Rect>>,
+    last_bounce: Mutex<HashMap<String, Instant>>,
+}
+
+/// Every icon in the Dock, left to right (or top to bottom).
+fn icons() -> Vec<DockIcon> {
+    #[cfg(target_os = "macos")]
+    return macos::icons();
+    #[cfg(not(target_os = "macos"))]
+    Vec::new()
+}
+
+/// Bounces lift icons away from the screen edge, across the Dock, so only
+/// movement along that axis counts. Anything else (a resize, magnification,
+/// a reshuffle) just moves where the icon rests.
+fn bounced(rest: &Rect, now: &Rect, horizontal: bool) -> bool {
+    let (across, along) = if horizontal {
+        (now.y - rest.y, now.x - rest.x)
+    } else {
+        (now.x - rest.x, now.y - rest.y)
+    };
+    across.abs() >= BOUNCE_DISTANCE
+        && along.abs() < 1.0
+        && (now.width - rest.width).abs() < 1.0
+        && (now.height - rest.height).abs() < 1.0
+}
+
+fn in_window(app: &tauri::AppHandle, mut icon: DockIcon) -> PetResult<DockIcon> {
+    let (origin_x, origin_y) = occlusion::window_origin(app)?;
+    icon.frame = icon.frame.offset(-origin_x, -origin_y);
+    Ok(icon)
+}
+
+/// Icons that just started bouncing, i.e. apps asking for attention.
+fn find_bounces(app: &tauri::AppHandle) -> Vec<DockIcon> {
+    let icons = icons();
+    let (Some(first), Some(last)) = (icons.first(), icons.last()) else {
+        return Vec::new();
+    };
+    let horizontal = (last.frame.x - first.frame.x).abs() >= (last.frame.y - first.frame.y).abs();
+
+    let state = app.state::<DockState>();
+    let mut rest = state.rest.lock().unwrap();
+    let mut last_bounce = state.last_bounce.lock().unwrap();
+    let now = Instant::now();
+    let titles: HashSet<String> = icons.iter().map(|icon| icon.title.clone()).collect();
+    // Forget icons that were taken out of the Dock
+    rest.retain(|title, _| titles.contains(title));
+    last_bounce.retain(|title, _| titles.contains(title));
+
+    let mut started = Vec::new();
+    for icon in icons {
+        let Some(resting) = rest.get(&icon.title) else {
+            rest.insert(icon.title.clone(), icon.frame);
+            continue;
+        };
+        if !bounced(resting, &icon.frame, horizontal) {
+            rest.insert(icon.title.clone(), icon.frame);
+            continue;
+        }
+        let quiet = last_bounce
+            .insert(icon.title.clone(), now)
+            .is_none_or(|last| now.duration_since(last) >= QUIET_PERIOD);
+        if quiet {
+            started.push(icon);
+        }
+    }
+    started
+}
+
+async fn check(app: tauri::AppHandle) {
+    // Never prompt from here; the Dock just stays out of reach until the
+    // user grants access for something else
+    let enabled = app.state::<SettingsStore>().get().display.dock_reactions;
+    if !enabled || !accessibility::is_trusted() {
+        return;
+    }
+    let bounces = crate::run_blocking({
+        let app = app.clone();
+        move || find_bounces(&app)
+    })
+    .await
+    .unwrap_or_default();
+    for icon in bounces {
+        if let Ok(icon) = in_window(&app, icon) {
+            let _ = app.emit("dock-icon-bouncing", icon);
+        }
+    }
+}
+
+/// Watch the Dock for bouncing icons, emitting `dock-icon-bouncing` when
+/// one starts.
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    scheduler::every(app, WATCH_INTERVAL, check);
+}
+
+/// Every Dock icon, for batting at or napping on. Needs Accessibility access.
+#[tauri::command]
+pub async fn get_dock_icons(app: tauri::AppHandle) -> PetResult<Vec<DockIcon>> {
+    crate::run_blocking(move || {
+        accessibility::require_trust()?;
+        icons()
+            .into_iter()
+            .map(|icon| in_window(&app, icon))
+            .collect()
+    })
+    .await?
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2_app_kit::NSRunningApplication;
+    use objc2_foundation::NSString;
+
+    use super::{DockIcon, DockItemKind};
+    use crate::accessibility::macos::Element;
+
+    const DOCK_BUNDLE_ID: &str = "com.apple.dock";
+
+    /// The list the Dock keeps its icons in.
+    fn dock_list() -> Option<Element> {
+        let docks = NSRunningApplication::runningApplicationsWithBundleIdentifier(
+            &NSString::from_str(DOCK_BUNDLE_ID),
+        );
+        let dock = Element::application(docks.firstObject()?.processIdentifier())?;
+        dock.children()
+            .into_iter()
+            .find(|child| child.string("AXRole").as_deref() == Some("AXList"))
+    }
+
+    pub fn icons() -> Vec<DockIcon> {
+        let Some(list) = dock_list() else {
+            return Vec::new();
+        };
+        list.children()
+            .into_iter()
+            .filter_map(|item| {
+                let kind = DockItemKind::from_subrole(&item.string("AXSubrole")?)?;
+                Some(DockIcon {
+                    title: item.string("AXTitle")?,
+                    kind,
+                    frame: item.frame()?,
+                    running: item.boolean("AXIsApplicationRunning").unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,71 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Error returned by every command. Serializes to `{ code, message }` so the
+/// frontend can branch on `code` and show `message` directly.
+#[derive(Debug, thiserror::Error)]
+pub enum PetError {
+    #[error("Couldn't reach the network: {0}")]
+    Network(String),
+    #[error("Anthropic rejected the API key: {0}")]
+    Auth(String),
+    #[error("Claude API error ({status}): {message}")]
+    Api { status: u16, message: String },
+    #[error("Claude returned an empty response")]
+    EmptyResponse,
+    #[error("Couldn't read or write pet data: {0}")]
+    Storage(String),
+    #[error("Couldn't understand the data: {0}")]
+    Parse(String),
+    #[error("System call failed: {0}")]
+    Platform(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("Something went wrong: {0}")]
+    Internal(String),
+}
+
+impl PetError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PetError::Network(_) => "NETWORK",
+            PetError::Auth(_) => "AUTH",
+            PetError::Api { .. } => "API",
+            PetError::EmptyResponse => "EMPTY_RESPONSE",
+            PetError::Storage(_) => "STORAGE",
+            PetError::Parse(_) => "PARSE",
+            PetError::Platform(_) => "PLATFORM",
+            PetError::InvalidInput(_) => "INVALID_INPUT",
+            PetError::Internal(_) => "INTERNAL",
+        }
+    }
+}
+
+impl Serialize for PetError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PetError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<std::io::Error> for PetError {
+    fn from(e: std::io::Error) -> Self {
+        PetError::Storage(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for PetError {
+    fn from(e: serde_json::Error) -> Self {
+        PetError::Parse(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for PetError {
+    fn from(e: tauri::Error) -> Self {
+        PetError::Platform(e.to_string())
+    }
+}
+
+pub type PetResult<T> = Result<T, PetError>;
@@ -0,0 +1,184 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+
+/// How many hits the palette shows; the cat only gets the first few.
+const MAX_RESULTS: usize = 20;
+pub const MAX_TOOL_RESULTS: usize = 10;
+/// Files that run something when opened instead of showing it. App bundles
+/// are folders with one of these too.
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "app",
+    "command",
+    "tool",
+    "sh",
+    "bash",
+    "zsh",
+    "py",
+    "rb",
+    "pl",
+    "jar",
+    "terminal",
+    "workflow",
+    "scpt",
+    "scptd",
+    "applescript",
+    "pkg",
+    "mpkg",
+    "prefpane",
+    "exe",
+    "bat",
+    "cmd",
+];
+
+/// The paths the last search turned up. Only these can be opened, so
+/// nothing can hand `open_file` a path of its own choosing.
+#[derive(Default)]
+pub struct FoundFiles(Mutex<Vec<PathBuf>>);
+
+impl FoundFiles {
+    pub fn remember(&self, hits: &[FileHit]) {
+        *self.0.lock().unwrap() = hits.iter().map(|hit| PathBuf::from(&hit.path)).collect();
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.0.lock().unwrap().iter().any(|found| found == path)
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHit {
+    pub path: String,
+    pub name: String,
+    /// The folder it's in, with the home folder shortened to ~
+    pub folder: String,
+    /// Unix millis
+    pub modified: Option<i64>,
+}
+
+impl FileHit {
+    fn new(path: &Path, home: &str) -> Self {
+        let modified = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|since| since.as_millis() as i64);
+        let folder = path
+            .parent()
+            .map(|parent| parent.display().to_string())
+            .unwrap_or_default();
+        Self {
+            path: path.display().to_string(),
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            folder: match folder.strip_prefix(home) {
+                Some(rest) => format!("~{}", rest),
+                None => folder,
+            },
+            modified,
+        }
+    }
+}
+
+/// Search the home folder the way the Spotlight menu does, so "tax pdf"
+/// finds PDFs about taxes as well as files named that. Files whose name
+/// has every word of the query come first, then the most recently changed.
+pub fn search(query: &str, limit: usize) -> PetResult<Vec<FileHit>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(PetError::InvalidInput("Search for something".to_string()));
+    }
+    if !cfg!(target_os = "macos") {
+        return Err(PetError::Platform(
+            "File search uses Spotlight, so it only works on macOS".to_string(),
+        ));
+    }
+    let home =
+        std::env::var("HOME").map_err(|_| PetError::Platform("No home folder".to_string()))?;
+    let output = std::process::Command::new("mdfind")
+        .args(["-onlyin", &home, "-interpret", query])
+        .output()
+        .map_err(|e| PetError::Platform(format!("Spotlight isn't available: {}", e)))?;
+    let library = format!("{}/Library/", home);
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let mut hits: Vec<FileHit> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // Skip app internals and the like
+        .filter(|path| !path.split('/').any(|part| part.starts_with('.')))
+        .filter(|path| !path.starts_with(&library))
+        // Spotlight can list thousands; only look at the first few hundred
+        .take(MAX_RESULTS * 20)
+        .map(|path| FileHit::new(&PathBuf::from(path), &home))
+        .collect();
+    let named = |hit: &FileHit| {
+        let name = hit.name.to_lowercase();
+        words.iter().all(|word| name.contains(word.as_str()))
+    };
+    hits.sort_by(|a, b| named(b).cmp(&named(a)).then(b.modified.cmp(&a.modified)));
+    hits.truncate(limit.min(MAX_RESULTS));
+    Ok(hits)
+}
+
+fn is_executable(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if EXECUTABLE_EXTENSIONS.contains(&extension.as_str()) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path)
+            .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    false
+}
+
+/// Open a file the last search found in its usual app. Blocks. Apps and
+/// scripts are refused: opening them runs them.
+pub fn open(app: &tauri::AppHandle, path: &Path) -> PetResult<()> {
+    if !app.state::<FoundFiles>().contains(path) {
+        return Err(PetError::InvalidInput(
+            "Only files from the last search can be opened".to_string(),
+        ));
+    }
+    if !path.exists() {
+        return Err(PetError::InvalidInput(format!(
+            "There's no file at {}",
+            path.display()
+        )));
+    }
+    if is_executable(path) {
+        return Err(PetError::InvalidInput(format!(
+            "{} is an app or script; open it yourself if you meant to",
+            path.display()
+        )));
+    }
+    crate::opener::open_path(path)
+}
+
+#[tauri::command]
+pub async fn search_files(app: tauri::AppHandle, query: String) -> PetResult<Vec<FileHit>> {
+    crate::run_blocking(move || {
+        let hits = search(&query, MAX_RESULTS)?;
+        app.state::<FoundFiles>().remember(&hits);
+        Ok(hits)
+    })
+    .await?
+}
+
+/// Open a file the palette found in its usual app.
+#[tauri::command]
+pub async fn open_file(app: tauri::AppHandle, path: String) -> PetResult<()> {
+    crate::run_blocking(move || open(&app, &PathBuf::from(path))).await?
+}
@@ -0,0 +1,129 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::occlusion::{self, Rect};
+use crate::settings::{FullscreenBehavior, SettingsStore};
+use crate::{scheduler, tray};
+
+/// How often the watcher looks for a fullscreen app.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+/// Window servers round; a point either way still counts as filling the display.
+const TOLERANCE: f64 = 1.0;
+
+/// The app that's currently fullscreen, and whether we hid the pet for it
+/// (so leaving fullscreen doesn't undo the user hiding it themselves).
+#[derive(Default)]
+pub struct FullscreenState {
+    app_name: Mutex<Option<String>>,
+    hid_pet: Mutex<bool>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FullscreenStatus {
+    pub active: bool,
+    pub app_name: Option<String>,
+    pub behavior: FullscreenBehavior,
+}
+
+/// Every display in screen points.
+fn display_bounds(app: &tauri::AppHandle) -> Vec<Rect> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let scale = monitor.scale_factor();
+            let position = monitor.position().to_logical::<f64>(scale);
+            let size = monitor.size().to_logical::<f64>(scale);
+            Rect {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        })
+        .collect()
+}
+
+fn fills(window: &Rect, display: &Rect) -> bool {
+    (window.x - display.x).abs() <= TOLERANCE
+        && (window.y - display.y).abs() <= TOLERANCE
+        && (window.width - display.width).abs() <= TOLERANCE
+        && (window.height - display.height).abs() <= TOLERANCE
+}
+
+/// The frontmost app, if its window covers a whole display. Native
+/// fullscreen, presentation mode and fullscreen video in a browser all
+/// look like this to the window server.
+fn fullscreen_app(app: &tauri::AppHandle) -> Option<String> {
+    let front = occlusion::app_windows(app).into_iter().next()?;
+    display_bounds(app)
+        .iter()
+        .any(|display| fills(&front.bounds, display))
+        .then_some(front.app_name)
+}
+
+fn status(app: &tauri::AppHandle) -> FullscreenStatus {
+    let app_name = app
+        .state::<FullscreenState>()
+        .app_name
+        .lock()
+        .unwrap()
+        .clone();
+    FullscreenStatus {
+        active: app_name.is_some(),
+        app_name,
+        behavior: app.state::<SettingsStore>().get().display.fullscreen,
+    }
+}
+
+async fn check(app: tauri::AppHandle) {
+    let found = crate::run_blocking({
+        let app = app.clone();
+        move || fullscreen_app(&app)
+    })
+    .await;
+    let Ok(found) = found else {
+        return;
+    };
+    let state = app.state::<FullscreenState>();
+    {
+        let mut current = state.app_name.lock().unwrap();
+        if *current == found {
+            return;
+        }
+        *current = found;
+    }
+
+    let status = status(&app);
+    // Hiding is done here rather than in the webview so the overlay stops
+    // catching clicks too
+    let mut hid_pet = state.hid_pet.lock().unwrap();
+    if status.active && status.behavior == FullscreenBehavior::Hide {
+        let visible = app
+            .get_webview_window("main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false);
+        if visible {
+            tray::set_pet_visible(&app, false);
+            *hid_pet = true;
+        }
+    } else if !status.active && *hid_pet {
+        tray::set_pet_visible(&app, true);
+        *hid_pet = false;
+    }
+    let _ = app.emit("fullscreen-changed", status);
+}
+
+/// Watch for another app going fullscreen, emitting `fullscreen-changed`
+/// when it starts or ends.
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    scheduler::every(app, WATCH_INTERVAL, check);
+}
+
+#[tauri::command(async)]
+pub fn get_fullscreen_status(app: tauri::AppHandle) -> FullscreenStatus {
+    status(&app)
+}
@@ -0,0 +1,137 @@
+// Window transparency is set on the NSWindow, which only macOS has here
+#![cfg_attr(not(target_os = "macos"), allow(dead_code, unused_variables))]
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::mood::{self, Interaction};
+use crate::settings::SettingsStore;
+use crate::tray;
+
+/// Below this the pet is too faint to find again.
+pub const MIN_OPACITY: f64 = 0.2;
+/// Ghost mode is 40% transparent, or fainter if the pet already is.
+const GHOST_OPACITY: f64 = 0.6;
+
+/// Whether ghost mode is on. Not saved: a relaunch brings the pet back solid.
+#[derive(Default)]
+pub struct GhostState {
+    active: AtomicBool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayStatus {
+    /// The opacity from Settings
+    pub opacity: f64,
+    pub ghost: bool,
+    /// What the window is drawn at right now
+    pub effective_opacity: f64,
+}
+
+pub fn is_active(app: &tauri::AppHandle) -> bool {
+    app.state::<GhostState>().active.load(Ordering::Relaxed)
+}
+
+pub fn status(app: &tauri::AppHandle) -> OverlayStatus {
+    // A hand-edited settings file may hold anything
+    let opacity = app
+        .state::<SettingsStore>()
+        .get()
+        .display
+        .opacity
+        .clamp(MIN_OPACITY, 1.0);
+    let ghost = is_active(app);
+    OverlayStatus {
+        opacity,
+        ghost,
+        effective_opacity: if ghost {
+            opacity.min(GHOST_OPACITY)
+        } else {
+            opacity
+        },
+    }
+}
+
+fn window(app: &tauri::AppHandle) -> PetResult<tauri::WebviewWindow> {
+    app.get_webview_window("main")
+        .ok_or_else(|| PetError::Internal("Pet window not found".to_string()))
+}
+
+/// Draw the window at the current opacity. Called at launch and after any
+/// change.
+pub fn apply(app: &tauri::AppHandle) -> PetResult<()> {
+    let status = status(app);
+    let window = window(app)?;
+    #[cfg(target_os = "macos")]
+    {
+        let ns_window = window.ns_window()? as usize;
+        let alpha = status.effective_opacity;
+        // AppKit only takes this on the main thread
+        app.run_on_main_thread(move || macos::set_alpha(ns_window, alpha))?;
+    }
+    let _ = app.emit("overlay-changed", status);
+    Ok(())
+}
+
+pub fn set_opacity(app: &tauri::AppHandle, opacity: f64) -> PetResult<OverlayStatus> {
+    if !(MIN_OPACITY..=1.0).contains(&opacity) {
+        return Err(PetError::InvalidInput(format!(
+            "Opacity goes from {} to 1",
+            MIN_OPACITY
+        )));
+    }
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.display.opacity = opacity;
+    store.replace(settings)?;
+    apply(app)?;
+    Ok(status(app))
+}
+
+pub fn set_ghost(app: &tauri::AppHandle, active: bool) -> PetResult<OverlayStatus> {
+    app.state::<GhostState>()
+        .active
+        .store(active, Ordering::Relaxed);
+    // Clicks go through either way: ghost mode keeps it that way, and on
+    // leaving it the cursor poller takes clicks again once over the pet
+    window(app)?.set_ignore_cursor_events(true)?;
+    apply(app)?;
+    if active {
+        mood::note(app, Interaction::Shooed);
+    }
+    tray::refresh(app);
+    Ok(status(app))
+}
+
+#[tauri::command(async)]
+pub fn get_overlay_status(app: tauri::AppHandle) -> OverlayStatus {
+    status(&app)
+}
+
+/// How opaque the pet is, from `MIN_OPACITY` to 1. Saved.
+#[tauri::command]
+pub async fn set_overlay_opacity(app: tauri::AppHandle, opacity: f64) -> PetResult<OverlayStatus> {
+    crate::run_blocking(move || set_opacity(&app, opacity)).await?
+}
+
+/// Ghost mode: the pet goes see-through and never takes a click, so it can
+/// keep you company over detailed work. Turn it off from the tray.
+#[tauri::command]
+pub async fn set_ghost_mode(app: tauri::AppHandle, enabled: bool) -> PetResult<OverlayStatus> {
+    crate::run_blocking(move || set_ghost(&app, enabled)).await?
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2_app_kit::NSWindow;
+
+    /// `ns_window` is the Tauri window's NSWindow pointer, which lives as
+    /// long as the app.
+    pub fn set_alpha(ns_window: usize, alpha: f64) {
+        let window = unsafe { &*(ns_window as *const NSWindow) };
+        window.setAlphaValue(alpha);
+    }
+}
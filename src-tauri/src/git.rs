@@ -0,0 +1,237 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+
+/// Modes where the cat comments on what the user is up to.
+const CONTEXT_MODES: &[&str] = &["judge", "spontaneous"];
+const MAX_SUBJECT_CHARS: usize = 80;
+/// Per git command; the prompt waits on these.
+const GIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Editors and terminals, by the name macOS reports for them. Only these
+/// are asked for their document, so a photo open in Preview never leads the
+/// cat to a repo.
+const DEV_APPS: &[&str] = &[
+    "Code",
+    "Visual Studio Code",
+    "Cursor",
+    "Zed",
+    "Xcode",
+    "Sublime Text",
+    "Nova",
+    "BBEdit",
+    "TextMate",
+    "IntelliJ IDEA",
+    "PyCharm",
+    "RustRover",
+    "WebStorm",
+    "GoLand",
+    "Terminal",
+    "iTerm2",
+    "Warp",
+    "Ghostty",
+    "WezTerm",
+    "kitty",
+    "Alacritty",
+];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitContext {
+    /// The repo's folder name
+    pub repo: String,
+    /// None on a detached HEAD
+    pub branch: Option<String>,
+    /// Changed, added or untracked files not yet committed
+    pub dirty_files: usize,
+    pub last_commit: Option<String>,
+    /// Unix millis
+    pub last_commit_at: Option<i64>,
+    /// When the branch was created, as far as the reflog remembers. Unix millis
+    pub branch_since: Option<i64>,
+}
+
+impl GitContext {
+    /// One line for the dialogue context.
+    pub fn describe(&self) -> String {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut line = format!("They're working in the git repo {}", self.repo);
+        match &self.branch {
+            Some(branch) => {
+                line.push_str(&format!(" on branch {}", branch));
+                if let Some(since) = self.branch_since {
+                    line.push_str(&format!(" (made {})", ago(now - since)));
+                }
+            }
+            None => line.push_str(" on a detached HEAD"),
+        }
+        match self.dirty_files {
+            0 => line.push_str(", nothing uncommitted"),
+            1 => line.push_str(", 1 uncommitted file"),
+            n => line.push_str(&format!(", {} uncommitted files", n)),
+        }
+        if let (Some(subject), Some(at)) = (&self.last_commit, self.last_commit_at) {
+            line.push_str(&format!("; last commit \"{}\" {}", subject, ago(now - at)));
+        }
+        line.push('.');
+        line
+    }
+}
+
+fn ago(millis: i64) -> String {
+    let minutes = millis.max(0) / 60_000;
+    let (count, unit) = match minutes {
+        0 => return "just now".to_string(),
+        1..=59 => (minutes, "minute"),
+        60..=1439 => (minutes / 60, "hour"),
+        1440..=10079 => (minutes / 1440, "day"),
+        10080..=43199 => (minutes / 10080, "week"),
+        _ => (minutes / 43200, "month"),
+    };
+    format!(
+        "{} {}{} ago",
+        count,
+        unit,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+/// Run git in `dir`, killing it if it doesn't answer in time. The repo is
+/// whatever the front window has open, so git takes no optional locks that
+/// would trip up the owner's own git commands, and runs no fsmonitor hook
+/// from the repo's config.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let mut child = Command::new("git")
+        .args(["--no-optional-locks", "-c", "core.fsmonitor="])
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // Read as it comes, so a long `status` can't fill the pipe and stall
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().ok()? {
+            break status;
+        }
+        if started.elapsed() > GIT_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    let output = reader.join().ok()?.ok()?;
+    status
+        .success()
+        .then(|| String::from_utf8_lossy(&output).trim().to_string())
+}
+
+/// Branch, changes and last commit of the repo `path` is in, if it's in one.
+pub fn inspect(path: &Path) -> Option<GitContext> {
+    let dir = if path.is_dir() { path } else { path.parent()? };
+    let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?);
+    let branch = git(&root, &["symbolic-ref", "--quiet", "--short", "HEAD"]);
+    let dirty_files = git(&root, &["status", "--porcelain"])
+        .map(|status| status.lines().count())
+        .unwrap_or(0);
+    let (last_commit, last_commit_at) = git(&root, &["log", "-1", "--format=%ct%n%s"])
+        .and_then(|log| {
+            let (at, subject) = log.split_once('\n')?;
+            Some((
+                subject.chars().take(MAX_SUBJECT_CHARS).collect(),
+                at.parse::<i64>().ok()? * 1000,
+            ))
+        })
+        .unzip();
+    // The oldest reflog entry for the branch is usually its creation
+    let branch_since = branch.as_ref().and_then(|branch| {
+        git(
+            &root,
+            &[
+                "reflog",
+                "show",
+                "--format=%ct",
+                &format!("refs/heads/{}", branch),
+            ],
+        )?
+        .lines()
+        .last()?
+        .parse::<i64>()
+        .ok()
+        .map(|at| at * 1000)
+    });
+    Some(GitContext {
+        repo: root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        branch,
+        dirty_files,
+        last_commit,
+        last_commit_at,
+        branch_since,
+    })
+}
+
+/// The file or folder open in the front window of `app_name`, when it's an
+/// editor or terminal that says. Terminals report their working directory.
+fn front_document(app_name: &str) -> Option<PathBuf> {
+    if !DEV_APPS.contains(&app_name) || !crate::accessibility::is_trusted() {
+        return None;
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let window = active_win_pos_rs::get_active_window().ok()?;
+        if window.app_name != app_name {
+            return None;
+        }
+        let url = crate::accessibility::macos::Element::application(window.process_id as i32)?
+            .element("AXFocusedWindow")?
+            .string("AXDocument")?;
+        reqwest::Url::parse(&url).ok()?.to_file_path().ok()
+    }
+    #[cfg(not(target_os = "macos"))]
+    None
+}
+
+/// The repo to mention in a prompt, when the mode and privacy settings call
+/// for one. Call off the async runtime; it shells out.
+pub fn context_for_prompt(app: &tauri::AppHandle, mode: &str, app_name: &str) -> Option<String> {
+    let allowed = app.state::<SettingsStore>().get().context;
+    if !allowed.send_git || !CONTEXT_MODES.contains(&mode) {
+        return None;
+    }
+    inspect(&front_document(app_name)?).map(|context| context.describe())
+}
+
+/// The git repo behind the front editor or terminal window, if there is
+/// one. Needs Accessibility access to read which file is open.
+#[tauri::command]
+pub async fn get_git_context(app_name: Option<String>) -> PetResult<Option<GitContext>> {
+    crate::run_blocking(move || {
+        let app_name = match app_name {
+            Some(name) => name,
+            None => active_win_pos_rs::get_active_window()
+                .map(|w| w.app_name)
+                .map_err(|()| PetError::Platform("Failed to get active window info".to_string()))?,
+        };
+        Ok(front_document(&app_name).and_then(|path| inspect(&path)))
+    })
+    .await?
+}
@@ -0,0 +1,545 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::{crypto, persist, scheduler};
+
+pub const GITHUB_FILE: &str = "github.json";
+/// Keychain account the personal access token is kept under.
+const TOKEN_ACCOUNT: &str = "github-token";
+const API: &str = "https://api.github.com";
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Open pull requests of yours that are followed, most recently updated first.
+const MAX_WATCHED: usize = 10;
+const MAX_NOTIFICATIONS: usize = 20;
+/// How long a review or CI result stays worth bringing up in chat.
+const NEWS_MINUTES: i64 = 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Review {
+    Pending,
+    Approved,
+    ChangesRequested,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Checks {
+    /// Running, or none reported yet
+    Pending,
+    Passed,
+    Failed,
+}
+
+/// One of your open pull requests as of the last poll.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PullStatus {
+    /// owner/name
+    pub repo: String,
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub head_sha: String,
+    pub review: Review,
+    pub checks: Checks,
+}
+
+impl PullStatus {
+    fn key(&self) -> (&str, u64) {
+        (&self.repo, self.number)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum NewsKind {
+    Approved,
+    ChecksFailed,
+}
+
+/// Something that just happened to one of your pull requests.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PullNews {
+    pub kind: NewsKind,
+    pub repo: String,
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    /// Unix millis
+    pub at: i64,
+}
+
+impl PullNews {
+    fn describe(&self) -> String {
+        match self.kind {
+            NewsKind::Approved => format!(
+                "User's pull request \"{}\" ({}#{}) was just approved.",
+                self.title, self.repo, self.number
+            ),
+            NewsKind::ChecksFailed => format!(
+                "CI just failed on the user's pull request \"{}\" ({}#{}).",
+                self.title, self.repo, self.number
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubData {
+    pub login: Option<String>,
+    pub pulls: Vec<PullStatus>,
+    /// Within the last NEWS_MINUTES, oldest first
+    pub news: Vec<PullNews>,
+}
+
+pub struct GithubStore {
+    data: Mutex<GithubData>,
+    path: Option<PathBuf>,
+}
+
+impl GithubStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, GITHUB_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        *data = GithubData::default();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self) -> GithubData {
+        self.data.lock().unwrap().clone()
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut GithubData) -> T) -> T {
+        let mut data = self.data.lock().unwrap();
+        let result = f(&mut data);
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*data);
+        }
+        result
+    }
+}
+
+fn token() -> Option<String> {
+    crypto::load_secret(TOKEN_ACCOUNT)
+}
+
+pub fn is_connected() -> bool {
+    token().is_some()
+}
+
+fn network(e: reqwest::Error) -> PetError {
+    PetError::Network(e.to_string())
+}
+
+async fn get<T: serde::de::DeserializeOwned>(
+    token: &str,
+    path: &str,
+    query: &[(&str, &str)],
+) -> PetResult<T> {
+    let response = reqwest::Client::new()
+        .get(format!("{}{}", API, path))
+        .query(query)
+        .bearer_auth(token)
+        .header("accept", "application/vnd.github+json")
+        .header("x-github-api-version", "2022-11-28")
+        .header("user-agent", "desktop-pet")
+        .send()
+        .await
+        .map_err(network)?;
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(PetError::Auth(
+            "GitHub didn't accept the token; it may have expired".to_string(),
+        ));
+    }
+    if !status.is_success() {
+        return Err(PetError::Api {
+            status: status.as_u16(),
+            message: response.text().await.unwrap_or_default(),
+        });
+    }
+    response.json().await.map_err(network)
+}
+
+#[derive(Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResults {
+    items: Vec<SearchItem>,
+}
+
+#[derive(Deserialize)]
+struct SearchItem {
+    number: u64,
+    title: String,
+    html_url: String,
+    /// https://api.github.com/repos/owner/name
+    repository_url: String,
+}
+
+#[derive(Deserialize)]
+struct Pull {
+    head: PullHead,
+}
+
+#[derive(Deserialize)]
+struct PullHead {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct ReviewEntry {
+    user: Option<User>,
+    state: String,
+}
+
+#[derive(Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    status: String,
+    conclusion: Option<String>,
+}
+
+/// Each reviewer's latest verdict counts; one request for changes outweighs
+/// any number of approvals.
+fn review_state(reviews: &[ReviewEntry]) -> Review {
+    let mut latest: Vec<(&str, &str)> = Vec::new();
+    for review in reviews {
+        let Some(user) = &review.user else { continue };
+        if !matches!(
+            review.state.as_str(),
+            "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED"
+        ) {
+            continue;
+        }
+        latest.retain(|(login, _)| *login != user.login);
+        latest.push((&user.login, &review.state));
+    }
+    if latest
+        .iter()
+        .any(|(_, state)| *state == "CHANGES_REQUESTED")
+    {
+        Review::ChangesRequested
+    } else if latest.iter().any(|(_, state)| *state == "APPROVED") {
+        Review::Approved
+    } else {
+        Review::Pending
+    }
+}
+
+fn checks_state(runs: &[CheckRun]) -> Checks {
+    let failed = runs
+        .iter()
+        .any(|run| matches!(run.conclusion.as_deref(), Some("failure" | "timed_out")));
+    if failed {
+        Checks::Failed
+    } else if !runs.is_empty() && runs.iter().all(|run| run.status == "completed") {
+        Checks::Passed
+    } else {
+        Checks::Pending
+    }
+}
+
+async fn fetch_pull(token: &str, item: SearchItem) -> PetResult<PullStatus> {
+    let repo = item
+        .repository_url
+        .trim_start_matches(&format!("{}/repos/", API))
+        .to_string();
+    let base = format!("/repos/{}/pulls/{}", repo, item.number);
+    let pull: Pull = get(token, &base, &[]).await?;
+    // A token without access to reviews or checks still gets the other;
+    // what it can't see stays pending
+    let review = match get::<Vec<ReviewEntry>>(
+        token,
+        &format!("{}/reviews", base),
+        &[("per_page", "100")],
+    )
+    .await
+    {
+        Ok(reviews) => review_state(&reviews),
+        Err(e) => {
+            eprintln!("Couldn't read reviews for {}#{}: {}", repo, item.number, e);
+            Review::Pending
+        }
+    };
+    let checks = match get::<CheckRuns>(
+        token,
+        &format!("/repos/{}/commits/{}/check-runs", repo, pull.head.sha),
+        &[("per_page", "100")],
+    )
+    .await
+    {
+        Ok(runs) => checks_state(&runs.check_runs),
+        Err(e) => {
+            eprintln!("Couldn't read checks for {}#{}: {}", repo, item.number, e);
+            Checks::Pending
+        }
+    };
+    Ok(PullStatus {
+        repo,
+        number: item.number,
+        title: item.title,
+        url: item.html_url,
+        head_sha: pull.head.sha,
+        review,
+        checks,
+    })
+}
+
+async fn fetch_pulls(token: &str) -> PetResult<Vec<PullStatus>> {
+    let per_page = MAX_WATCHED.to_string();
+    let results: SearchResults = get(
+        token,
+        "/search/issues",
+        &[
+            ("q", "is:pr is:open author:@me"),
+            ("sort", "updated"),
+            ("per_page", &per_page),
+        ],
+    )
+    .await?;
+    let mut pulls = Vec::new();
+    for item in results.items {
+        // One pull request that can't be read shouldn't hide the rest
+        let name = format!("{}#{}", item.repository_url, item.number);
+        match fetch_pull(token, item).await {
+            Ok(pull) => pulls.push(pull),
+            Err(e) => eprintln!("Skipping {}: {}", name, e),
+        }
+    }
+    Ok(pulls)
+}
+
+/// What changed between two polls that's worth a reaction. Pull requests
+/// seen for the first time only set the baseline.
+fn news_between(previous: &[PullStatus], current: &[PullStatus], at: i64) -> Vec<PullNews> {
+    let mut news = Vec::new();
+    for pull in current {
+        let Some(before) = previous.iter().find(|p| p.key() == pull.key()) else {
+            continue;
+        };
+        let mut push = |kind| {
+            news.push(PullNews {
+                kind,
+                repo: pull.repo.clone(),
+                number: pull.number,
+                title: pull.title.clone(),
+                url: pull.url.clone(),
+                at,
+            })
+        };
+        if pull.review == Review::Approved && before.review != Review::Approved {
+            push(NewsKind::Approved);
+        }
+        // A new push that fails again is news too
+        if pull.checks == Checks::Failed
+            && (before.checks != Checks::Failed || before.head_sha != pull.head_sha)
+        {
+            push(NewsKind::ChecksFailed);
+        }
+    }
+    news
+}
+
+async fn poll(app: tauri::AppHandle) {
+    let Some(token) = token() else {
+        return;
+    };
+    let pulls = match fetch_pulls(&token).await {
+        Ok(pulls) => pulls,
+        Err(e) => {
+            eprintln!("GitHub poll failed: {}", e);
+            return;
+        }
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    let handle = app.clone();
+    let news = crate::run_blocking(move || {
+        handle.state::<GithubStore>().update(|data| {
+            let news = news_between(&data.pulls, &pulls, now);
+            data.pulls = pulls;
+            data.news.retain(|n| now - n.at < NEWS_MINUTES * 60_000);
+            data.news.extend(news.iter().cloned());
+            news
+        })
+    })
+    .await
+    .unwrap_or_default();
+    for item in news {
+        let _ = app.emit("github-pull-news", &item);
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, POLL_INTERVAL, poll);
+}
+
+/// Recent reviews and CI results for the dialogue context, newest last.
+pub fn context(app: &tauri::AppHandle) -> Option<String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let lines: Vec<String> = app
+        .state::<GithubStore>()
+        .get()
+        .news
+        .iter()
+        .filter(|n| now - n.at < NEWS_MINUTES * 60_000)
+        .map(PullNews::describe)
+        .collect();
+    (!lines.is_empty()).then(|| lines.join(" "))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubStatus {
+    pub connected: bool,
+    pub login: Option<String>,
+    pub pulls: Vec<PullStatus>,
+}
+
+fn status(app: &tauri::AppHandle) -> GithubStatus {
+    let data = app.state::<GithubStore>().get();
+    GithubStatus {
+        connected: is_connected(),
+        login: data.login,
+        pulls: data.pulls,
+    }
+}
+
+#[tauri::command]
+pub async fn get_github_status(app: tauri::AppHandle) -> PetResult<GithubStatus> {
+    crate::run_blocking(move || status(&app)).await
+}
+
+/// Connect with a personal access token, checked against GitHub first, or
+/// disconnect with None, which also forgets the pull requests.
+#[tauri::command]
+pub async fn set_github_token(
+    app: tauri::AppHandle,
+    token: Option<String>,
+) -> PetResult<GithubStatus> {
+    let token = token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    let login = match &token {
+        Some(token) => Some(get::<User>(token, "/user", &[]).await?.login),
+        None => None,
+    };
+    let handle = app.clone();
+    crate::run_blocking(move || {
+        match &token {
+            Some(token) => crypto::store_secret(TOKEN_ACCOUNT, token)?,
+            None => crypto::remove_secret(TOKEN_ACCOUNT)?,
+        }
+        handle.state::<GithubStore>().update(|data| {
+            *data = GithubData {
+                login,
+                ..GithubData::default()
+            }
+        });
+        Ok::<_, PetError>(())
+    })
+    .await??;
+    // The first poll only sets the baseline, so do it now rather than let
+    // the first approval slip by as one
+    poll(app.clone()).await;
+    crate::run_blocking(move || status(&app)).await
+}
+
+#[derive(Deserialize)]
+struct ApiNotification {
+    id: String,
+    reason: String,
+    unread: bool,
+    updated_at: String,
+    subject: ApiSubject,
+    repository: ApiRepository,
+}
+
+#[derive(Deserialize)]
+struct ApiSubject {
+    title: String,
+    url: Option<String>,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct ApiRepository {
+    full_name: String,
+    html_url: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubNotification {
+    pub id: String,
+    pub title: String,
+    /// PullRequest, Issue, Release, CheckSuite, ...
+    pub kind: String,
+    /// Why you got it: review_requested, mention, ci_activity, ...
+    pub reason: String,
+    pub repo: String,
+    /// A page to open in the browser
+    pub url: String,
+    pub unread: bool,
+    pub updated_at: String,
+}
+
+/// The API links a notification's subject by its API address; swap in the
+/// page a person would open.
+fn page_url(subject_url: Option<&str>, repo_url: &str) -> String {
+    match subject_url {
+        Some(url) => url
+            .replacen("https://api.github.com/repos/", "https://github.com/", 1)
+            .replacen("/pulls/", "/pull/", 1),
+        None => repo_url.to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_github_notifications() -> PetResult<Vec<GithubNotification>> {
+    let token = token().ok_or_else(|| PetError::Auth("GitHub isn't connected".to_string()))?;
+    let per_page = MAX_NOTIFICATIONS.to_string();
+    let notifications: Vec<ApiNotification> =
+        get(&token, "/notifications", &[("per_page", &per_page)]).await?;
+    Ok(notifications
+        .into_iter()
+        .map(|n| GithubNotification {
+            url: page_url(n.subject.url.as_deref(), &n.repository.html_url),
+            id: n.id,
+            title: n.subject.title,
+            kind: n.subject.kind,
+            reason: n.reason,
+            repo: n.repository.full_name,
+            unread: n.unread,
+            updated_at: n.updated_at,
+        })
+        .collect())
+}
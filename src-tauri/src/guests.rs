@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+use crate::{moderation, scheduler};
+
+/// How often the next queued interaction is shown, so a raid doesn't turn
+/// into a wall of speech bubbles.
+const DRAIN_INTERVAL: Duration = Duration::from_secs(4);
+/// Interactions waiting beyond this are dropped rather than shown minutes late.
+const MAX_QUEUED: usize = 20;
+const MAX_NAME_CHARS: usize = 25;
+const WINDOW_MS: i64 = 60_000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum GuestAction {
+    Pet,
+    Treat,
+    Message,
+}
+
+/// One interaction from a stream viewer or friend, as it arrives from the
+/// relay. Nothing here is trusted.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestInteraction {
+    pub guest_name: String,
+    pub action: GuestAction,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Set by the database from where the row came from, so unlike the
+    /// name the guest can't pick it. Rows from before it existed all count
+    /// as one guest
+    #[serde(default)]
+    pub sender: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestEvent {
+    pub guest_name: String,
+    pub action: GuestAction,
+    pub message: Option<String>,
+}
+
+/// Recent interaction times (unix millis) per sender and overall, plus what's
+/// waiting to be shown.
+#[derive(Default)]
+pub struct GuestState {
+    recent: Mutex<HashMap<String, VecDeque<i64>>>,
+    overall: Mutex<VecDeque<i64>>,
+    queue: Mutex<VecDeque<GuestEvent>>,
+}
+
+/// Keep printable text only and collapse whitespace; links are refused
+/// outright since whatever the cat repeats ends up on stream.
+fn clean(text: &str, max_chars: usize) -> PetResult<String> {
+    let cleaned = text
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if cleaned.is_empty() {
+        return Err(PetError::InvalidInput("Nothing to say".to_string()));
+    }
+    let lower = cleaned.to_lowercase();
+    if lower.contains("://") || lower.contains("www.") {
+        return Err(PetError::InvalidInput("Links aren't allowed".to_string()));
+    }
+    Ok(cleaned.chars().take(max_chars).collect())
+}
+
+/// Drop timestamps older than the window, then check there's room for one
+/// more. Only records it when there is.
+fn take_slot(times: &mut VecDeque<i64>, limit: u32, now: i64) -> bool {
+    while times.front().is_some_and(|t| now - t >= WINDOW_MS) {
+        times.pop_front();
+    }
+    if times.len() >= limit as usize {
+        return false;
+    }
+    times.push_back(now);
+    true
+}
+
+fn validate(app: &tauri::AppHandle, interaction: GuestInteraction) -> PetResult<GuestEvent> {
+    let settings = app.state::<SettingsStore>().get().guests;
+    if !settings.enabled {
+        return Err(PetError::InvalidInput("Guest mode is off".to_string()));
+    }
+    let guest_name = clean(&interaction.guest_name, MAX_NAME_CHARS)?;
+    let message = match interaction.action {
+        GuestAction::Message => Some(clean(
+            interaction.message.as_deref().unwrap_or_default(),
+            settings.max_message_chars,
+        )?),
+        // Pets and treats carry no text, whatever was sent along
+        GuestAction::Pet | GuestAction::Treat => None,
+    };
+    let mut texts = std::iter::once(&guest_name).chain(&message);
+    if texts.any(|text| moderation::find_blocked_word(app, text).is_some()) {
+        return Err(PetError::InvalidInput(
+            "That message was filtered".to_string(),
+        ));
+    }
+
+    let state = app.state::<GuestState>();
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut recent = state.recent.lock().unwrap();
+    recent.retain(|_, times| times.back().is_some_and(|t| now - t < WINDOW_MS));
+    let guest_times = recent.entry(interaction.sender.unwrap_or_default()).or_default();
+    if !take_slot(guest_times, settings.per_guest_per_minute, now) {
+        return Err(PetError::InvalidInput(format!(
+            "{} is doing that too often",
+            guest_name
+        )));
+    }
+    if !take_slot(&mut state.overall.lock().unwrap(), settings.per_minute, now) {
+        // Give the guest their slot back; it was the crowd, not them
+        guest_times.pop_back();
+        return Err(PetError::InvalidInput(
+            "The cat is getting a lot of attention right now".to_string(),
+        ));
+    }
+    Ok(GuestEvent {
+        guest_name,
+        action: interaction.action,
+        message,
+    })
+}
+
+async fn drain(app: tauri::AppHandle) {
+    let next = app.state::<GuestState>().queue.lock().unwrap().pop_front();
+    if let Some(event) = next {
+        let _ = app.emit("guest-interaction", event);
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, DRAIN_INTERVAL, drain);
+}
+
+/// Accept an interaction from the relay: check it, rate-limit it per guest
+/// and overall, then queue it to be shown as a `guest-interaction` event.
+#[tauri::command(async)]
+pub fn submit_guest_interaction(
+    app: tauri::AppHandle,
+    interaction: GuestInteraction,
+) -> PetResult<()> {
+    let state = app.state::<GuestState>();
+    // Checked before the rate limits, and held until it's queued, so an
+    // interaction turned away here doesn't use up the guest's slots
+    let mut queue = state.queue.lock().unwrap();
+    if queue.len() >= MAX_QUEUED {
+        return Err(PetError::InvalidInput(
+            "The cat is getting a lot of attention right now".to_string(),
+        ));
+    }
+    queue.push_back(validate(&app, interaction)?);
+    Ok(())
+}
+
+/// Drop everything still waiting, e.g. when going off stream.
+#[tauri::command(async)]
+pub fn clear_guest_queue(state: tauri::State<'_, GuestState>) {
+    state.queue.lock().unwrap().clear();
+}
@@ -0,0 +1,341 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::{Disks, ProcessesToUpdate, System};
+use tauri::{Emitter, Manager};
+
+use crate::dialogue::{self, DialogueResponse};
+use crate::error::PetResult;
+use crate::scheduler;
+
+const GB: u64 = 1_000_000_000;
+/// Below this much free disk the cat speaks up, once per dip.
+pub const LOW_DISK_BYTES: u64 = 10 * GB;
+/// Free space has to climb this far back above the line before another
+/// dip counts, so hovering around it doesn't nag.
+const LOW_DISK_RESET_BYTES: u64 = 2 * GB;
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// softwareupdate takes a while and goes to Apple's servers; a few times a
+/// day is plenty.
+const UPDATES_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const MAX_UPDATES_LISTED: usize = 5;
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_secs(20);
+/// A busy spell has to last this long before it counts; builds and page
+/// loads spike all the time.
+const HOT_FOR: Duration = Duration::from_secs(3 * 60);
+/// One app using a whole core, in sysinfo's per-core percent
+const APP_HOT_PERCENT: f32 = 90.0;
+/// Across all cores: enough that the fans are likely spinning
+const SYSTEM_HOT_PERCENT: f32 = 70.0;
+/// Busy for reasons of their own; naming them tells the owner nothing.
+const IGNORED_PROCESSES: &[&str] = &["kernel_task", "idle", "launchd"];
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryPressure {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl MemoryPressure {
+    /// What macOS says, from `kern.memorystatus_vm_pressure_level`; elsewhere
+    /// guessed from how much memory is left.
+    fn read(total: u64, available: u64) -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            let level = std::process::Command::new("sysctl")
+                .args(["-n", "kern.memorystatus_vm_pressure_level"])
+                .output()
+                .ok()
+                .and_then(|output| String::from_utf8_lossy(&output.stdout).trim().parse().ok());
+            match level {
+                Some(1) => return Self::Normal,
+                Some(2) => return Self::Warning,
+                Some(4) => return Self::Critical,
+                _ => {}
+            }
+        }
+        match available.saturating_mul(100).checked_div(total) {
+            Some(0..=5) => Self::Critical,
+            Some(6..=15) => Self::Warning,
+            _ => Self::Normal,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Warning => "elevated",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemReport {
+    pub disk_free_bytes: u64,
+    pub disk_total_bytes: u64,
+    pub memory_available_bytes: u64,
+    pub memory_total_bytes: u64,
+    pub memory_pressure: MemoryPressure,
+    pub uptime_secs: u64,
+    /// Titles of pending macOS updates. None until softwareupdate has
+    /// answered once, and always elsewhere
+    pub pending_updates: Option<Vec<String>>,
+}
+
+fn gb(bytes: u64) -> String {
+    let gb = bytes as f64 / GB as f64;
+    if gb < 10.0 {
+        format!("{:.1} GB", gb)
+    } else {
+        format!("{:.0} GB", gb)
+    }
+}
+
+impl SystemReport {
+    /// The report in a few plain sentences, for the prompt.
+    fn describe(&self) -> String {
+        let days = self.uptime_secs / 86_400;
+        let hours = self.uptime_secs % 86_400 / 3_600;
+        let uptime = match days {
+            0 => format!("{} hours", hours),
+            1 => format!("1 day and {} hours", hours),
+            _ => format!("{} days", days),
+        };
+        let updates = match &self.pending_updates {
+            Some(updates) if updates.is_empty() => "No pending macOS updates.".to_string(),
+            Some(updates) => format!(
+                "{} pending macOS update{}: {}.",
+                updates.len(),
+                if updates.len() == 1 { "" } else { "s" },
+                updates
+                    .iter()
+                    .take(MAX_UPDATES_LISTED)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => String::new(),
+        };
+        format!(
+            "Disk: {} free of {}. Memory: {} of {} available, pressure {}. \
+            Up for {} since the last restart. {}",
+            gb(self.disk_free_bytes),
+            gb(self.disk_total_bytes),
+            gb(self.memory_available_bytes),
+            gb(self.memory_total_bytes),
+            self.memory_pressure.describe(),
+            uptime,
+            updates
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuHot {
+    /// The app using the most CPU right now
+    pub app_name: String,
+    /// Its share, in percent of one core
+    pub cpu_percent: u32,
+    /// How long the computer has been this busy
+    pub minutes: u64,
+}
+
+struct HotSpell {
+    since: Instant,
+    announced: bool,
+}
+
+#[derive(Default)]
+pub struct HealthState {
+    updates: Mutex<Option<Vec<String>>>,
+    disk_low: AtomicBool,
+    /// Kept between samples, since CPU use is measured from one refresh to
+    /// the next
+    system: Mutex<System>,
+    hot: Mutex<Option<HotSpell>>,
+}
+
+/// Free and total bytes on the startup disk. On macOS the writable half of
+/// it is mounted separately, but they share their space.
+fn startup_disk() -> (u64, u64) {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .find(|disk| disk.mount_point() == std::path::Path::new("/"))
+        .or_else(|| disks.list().first())
+        .map(|disk| (disk.available_space(), disk.total_space()))
+        .unwrap_or_default()
+}
+
+/// Gather the report. Blocks briefly; the update list comes from the last
+/// softwareupdate check.
+pub fn report(app: &tauri::AppHandle) -> SystemReport {
+    let (disk_free_bytes, disk_total_bytes) = startup_disk();
+    let mut system = System::new();
+    system.refresh_memory();
+    let (total, available) = (system.total_memory(), system.available_memory());
+    SystemReport {
+        disk_free_bytes,
+        disk_total_bytes,
+        memory_available_bytes: available,
+        memory_total_bytes: total,
+        memory_pressure: MemoryPressure::read(total, available),
+        uptime_secs: System::uptime(),
+        pending_updates: app.state::<HealthState>().updates.lock().unwrap().clone(),
+    }
+}
+
+/// Titles of the updates `softwareupdate -l` lists, which asks Apple.
+fn pending_updates() -> Option<Vec<String>> {
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+    let output = std::process::Command::new("softwareupdate")
+        .arg("-l")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // "\tTitle: macOS Sequoia 15.1, Version: 15.1, Size: ..., Recommended: YES,"
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Title:"))
+            .map(|rest| rest.split(',').next().unwrap_or(rest).trim().to_string())
+            .filter(|title| !title.is_empty())
+            .collect(),
+    )
+}
+
+/// Tell the webview once free space falls under LOW_DISK_BYTES, with
+/// `disk-space-low`.
+async fn check_disk(app: tauri::AppHandle) {
+    let Ok((free, total)) = crate::run_blocking(startup_disk).await else {
+        return;
+    };
+    if total == 0 {
+        return;
+    }
+    let state = app.state::<HealthState>();
+    if free < LOW_DISK_BYTES {
+        if !state.disk_low.swap(true, Ordering::SeqCst) {
+            let _ = app.emit(
+                "disk-space-low",
+                serde_json::json!({ "freeBytes": free, "totalBytes": total }),
+            );
+        }
+    } else if free > LOW_DISK_BYTES + LOW_DISK_RESET_BYTES {
+        state.disk_low.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Helper processes count toward their app: "Google Chrome Helper
+/// (Renderer)" is Google Chrome.
+fn app_name(process: &str) -> &str {
+    process.split(" Helper").next().unwrap_or(process).trim()
+}
+
+/// The app using the most CPU since the last sample and its share, and
+/// whether the computer as a whole counts as hot. Blocks briefly.
+fn sample_cpu(system: &mut System) -> Option<(String, f32, bool)> {
+    system.refresh_cpu_usage();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    let own = std::process::id();
+    let mut by_app: HashMap<&str, f32> = HashMap::new();
+    for process in system.processes().values() {
+        let name = process.name().to_str().unwrap_or_default();
+        if process.pid().as_u32() == own || name.is_empty() || IGNORED_PROCESSES.contains(&name) {
+            continue;
+        }
+        *by_app.entry(app_name(name)).or_default() += process.cpu_usage();
+    }
+    let (name, percent) = by_app.into_iter().max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    let hot = percent >= APP_HOT_PERCENT || system.global_cpu_usage() >= SYSTEM_HOT_PERCENT;
+    Some((name.to_string(), percent, hot))
+}
+
+/// Tell the webview once the computer has been busy for HOT_FOR, with
+/// `cpu-hot` naming the app most to blame, and with `cpu-cooled` when that
+/// spell ends.
+async fn check_cpu(app: tauri::AppHandle) {
+    let handle = app.clone();
+    let Ok(sample) = crate::run_blocking(move || {
+        sample_cpu(&mut handle.state::<HealthState>().system.lock().unwrap())
+    })
+    .await
+    else {
+        return;
+    };
+    let state = app.state::<HealthState>();
+    let mut hot = state.hot.lock().unwrap();
+    let Some((app_name, percent, true)) = sample else {
+        if hot.take().is_some_and(|spell| spell.announced) {
+            let _ = app.emit("cpu-cooled", ());
+        }
+        return;
+    };
+    let spell = hot.get_or_insert_with(|| HotSpell {
+        since: Instant::now(),
+        announced: false,
+    });
+    // Nothing unprompted while the owner is on a call; calls are often the
+    // reason it's busy
+    if spell.announced || spell.since.elapsed() < HOT_FOR || crate::meetings::is_in_meeting(&app) {
+        return;
+    }
+    spell.announced = true;
+    let _ = app.emit(
+        "cpu-hot",
+        CpuHot {
+            app_name,
+            cpu_percent: percent.round() as u32,
+            minutes: spell.since.elapsed().as_secs() / 60,
+        },
+    );
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app.clone(), DISK_CHECK_INTERVAL, check_disk);
+    scheduler::every(app.clone(), CPU_SAMPLE_INTERVAL, check_cpu);
+    scheduler::every(app, UPDATES_CHECK_INTERVAL, |app| async move {
+        if let Ok(Some(updates)) = crate::run_blocking(pending_updates).await {
+            *app.state::<HealthState>().updates.lock().unwrap() = Some(updates);
+        }
+    });
+}
+
+/// Disk space, memory pressure, uptime and pending macOS updates.
+#[tauri::command]
+pub async fn system_report(app: tauri::AppHandle) -> PetResult<SystemReport> {
+    crate::run_blocking(move || report(&app)).await
+}
+
+/// The system report, delivered by the cat as complaints about its living
+/// conditions.
+#[tauri::command]
+pub async fn generate_system_report(app: tauri::AppHandle) -> PetResult<DialogueResponse> {
+    let handle = app.clone();
+    let report = crate::run_blocking(move || report(&handle)).await?;
+    dialogue::generate(
+        app,
+        String::new(),
+        String::new(),
+        report.describe(),
+        Some("report".to_string()),
+        None,
+        None,
+        None,
+    )
+    .await
+}
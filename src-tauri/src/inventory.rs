@@ -0,0 +1,322 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::Manager;
+
+use crate::error::PetResult;
+use crate::memory::MemoryStore;
+use crate::{
+    activity, automation, card, config, countdowns, crypto, github, journal, leaderboard, memory, milestones, moderation, mood, moods, notes, pairing, persist, pets,
+    personality, presence, prompts, reactions, settings, sound, sports, stickies, sync, tickers, tools, trackers, usage, watchers, wrapped,
+};
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum DataLocation {
+    /// JSON files in the app data dir
+    Disk,
+    /// The OS keychain
+    Keychain,
+    /// The webview's localStorage
+    Webview,
+    /// Supabase, shared with friends' pets
+    Cloud,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataItem {
+    pub name: String,
+    pub location: DataLocation,
+    /// File path, localStorage key, keychain service or table name
+    pub path: String,
+    /// None where the backend can't look (webview storage, Supabase)
+    pub exists: Option<bool>,
+    /// Known for files on disk only
+    pub size_bytes: Option<u64>,
+    /// The rotating `.bak.N` copies kept next to each file
+    pub backup_bytes: Option<u64>,
+    pub contents: &'static str,
+}
+
+/// Backend files: (file name, what's in it).
+const FILES: &[(&str, &str)] = &[
+    (
+        config::CONFIG_FILE,
+        "API keys and service URLs you put there yourself; never written by the app",
+    ),
+    (
+        settings::SETTINGS_FILE,
+        "Preferences: what context is sent, shortcuts, display, journal style, retention",
+    ),
+    (
+        memory::MEMORY_FILE,
+        "Recent chat messages, remembered facts about you and a summary of older conversations",
+    ),
+    (
+        usage::USAGE_FILE,
+        "API requests, tokens and estimated cost per day, plus the monthly budget",
+    ),
+    (
+        activity::ACTIVITY_FILE,
+        "Time in each app and the window titles seen, for the last week, for standups",
+    ),
+    (notes::NOTES_FILE, "Reminders and todos the cat wrote down"),
+    (
+        pets::PETS_FILE,
+        "The household's cats by name, and which one is out; each added cat's memory and mood are in pets/",
+    ),
+    (
+        stickies::STICKIES_FILE,
+        "Sticky notes taped to the screen and where they are",
+    ),
+    (moods::MOODS_FILE, "A 1-5 mood score per journal day"),
+    (journal::DIGESTS_FILE, "Weekly summaries of the diary"),
+    (
+        milestones::MILESTONES_FILE,
+        "When the pet was installed, firsts like the first chat or all-nighter, and anniversaries celebrated",
+    ),
+    (
+        mood::MOOD_FILE,
+        "The cat's current mood and how well its needs for attention, play and rest are met",
+    ),
+    (
+        reactions::REACTIONS_FILE,
+        "Which quick reactions you pick, and where, to put favourites first",
+    ),
+    (
+        watchers::WATCHERS_FILE,
+        "Web searches the cat re-runs for you and their last answers",
+    ),
+    (
+        trackers::TRACKERS_FILE,
+        "Packages and flights being tracked and their last status",
+    ),
+    (tickers::WATCHLIST_FILE, "Stock and crypto symbols you watch"),
+    (sports::TEAMS_FILE, "Teams you follow and their recent results"),
+    (countdowns::COUNTDOWNS_FILE, "Countdown labels and dates"),
+    (
+        wrapped::WRAPPED_FILE,
+        "Judge-mode one-liners saved for the yearly recap",
+    ),
+    (
+        automation::TOKEN_FILE,
+        "The token for the localhost automation API",
+    ),
+    (
+        moderation::MODERATION_FILE,
+        "Friends' pets you blocked and how many of their messages were filtered",
+    ),
+    (
+        leaderboard::LEADERBOARD_FILE,
+        "An anonymous id, total playtime and achievement count for the leaderboard",
+    ),
+    (
+        presence::PRESENCE_FILE,
+        "This machine's id and the key shared with your other Macs",
+    ),
+    (
+        pairing::COMPANIONS_FILE,
+        "Phones paired as companions: their names and a hash of each one's token",
+    ),
+    (
+        tools::TOOL_AUDIT_FILE,
+        "The last 200 things the cat asked to do on this Mac and what came of them",
+    ),
+    (
+        github::GITHUB_FILE,
+        "Your GitHub login and the review and CI state of your open pull requests, if you connected GitHub",
+    ),
+    (
+        sync::SYNC_FILE,
+        "Where sync uploads to and what it last saw there, if you turned sync on",
+    ),
+];
+
+/// Backend folders: (folder name, what's in it).
+const DIRS: &[(&str, &str)] = &[
+    (
+        pets::PETS_DIR,
+        "Each added cat's chat memory and mood, in a folder per cat",
+    ),
+    (wrapped::WRAPPED_DIR, "Generated Pet Wrapped HTML reports"),
+    (card::CARDS_DIR, "Exported pet card PNGs"),
+    (
+        personality::PERSONALITY_DIR,
+        "Personality packs (TOML), the presets included",
+    ),
+    (prompts::PROMPTS_DIR, "Prompt templates you customized"),
+    (
+        sound::SOUNDS_DIR,
+        "Your own audio files, played instead of the built-in sounds of the same name",
+    ),
+];
+
+/// localStorage keys the webview writes: (key, what's in it).
+const WEBVIEW_KEYS: &[(&str, &str)] = &[
+    (
+        "pet-events",
+        "Counters for chats, searches, naps and clicks, active days and recent actions",
+    ),
+    ("pet-journal", "The last 30 journal entries"),
+    ("pet-achievements", "Which achievements are unlocked and when"),
+    ("pet-app-usage", "Time spent in each app today"),
+    ("pet-app-usage-year", "Time spent in each app this year"),
+    ("pet-notes", "Sticky notes on the desktop and their text"),
+    ("pet-position", "Where the cat sits on screen"),
+    ("pet-id", "Your pet's id for friends and visits"),
+    ("pet-code", "Your pet's friend code"),
+    ("pet-name", "Your pet's name"),
+    ("selected-breed", "Chosen breed"),
+    ("selected-color", "Chosen color"),
+    ("selected-custom-theme", "Chosen custom sprite theme"),
+    ("custom-themes", "Imported sprite sheets"),
+];
+
+/// Supabase tables, only used once you register for friends.
+const CLOUD_TABLES: &[(&str, &str)] = &[
+    (
+        "pets",
+        "Your pet's code, name, breed, color and when it was last online",
+    ),
+    ("friendships", "Which pets are friends with yours"),
+    (
+        "visits",
+        "Hangout visits and the chat messages sent with them",
+    ),
+    (
+        "leaderboard",
+        "If you opt in: an anonymous id, playtime minutes and achievement count",
+    ),
+    (
+        "pet_sync",
+        "If you sync through the relay: your data, encrypted with your passphrase",
+    ),
+];
+
+/// Names of the backend's own data files.
+pub fn data_files() -> impl Iterator<Item = &'static str> {
+    FILES.iter().map(|(name, _)| *name)
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok().map(|m| m.len())
+}
+
+/// Everything under `path`, subfolders included.
+fn dir_size(path: &Path) -> Option<u64> {
+    let entries = fs::read_dir(path).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                if metadata.is_dir() {
+                    dir_size(&e.path())
+                } else {
+                    Some(metadata.len())
+                }
+            })
+            .sum(),
+    )
+}
+
+fn disk_item(app: &tauri::AppHandle, name: &str, contents: &'static str) -> PetResult<DataItem> {
+    let path = persist::app_data_file(app, name)?;
+    let backups: u64 = (1..=persist::BACKUP_GENERATIONS)
+        .filter_map(|g| file_size(&persist::backup_path(&path, g)))
+        .sum();
+    let size = file_size(&path);
+    Ok(DataItem {
+        name: name.to_string(),
+        location: DataLocation::Disk,
+        exists: Some(size.is_some()),
+        size_bytes: size,
+        backup_bytes: Some(backups),
+        path: path.to_string_lossy().into_owned(),
+        contents,
+    })
+}
+
+fn dir_item(app: &tauri::AppHandle, dir: &str, contents: &'static str) -> PetResult<DataItem> {
+    let path = persist::app_data_file(app, dir)?;
+    let size = dir_size(&path);
+    Ok(DataItem {
+        name: format!("{}/", dir),
+        location: DataLocation::Disk,
+        exists: Some(size.is_some()),
+        size_bytes: size,
+        backup_bytes: None,
+        path: path.to_string_lossy().into_owned(),
+        contents,
+    })
+}
+
+fn inventory(app: &tauri::AppHandle) -> PetResult<Vec<DataItem>> {
+    let mut items = FILES
+        .iter()
+        .map(|(name, contents)| disk_item(app, name, contents))
+        .collect::<PetResult<Vec<_>>>()?;
+
+    items.extend(
+        DIRS.iter()
+            .map(|(dir, contents)| dir_item(app, dir, contents))
+            .collect::<PetResult<Vec<_>>>()?,
+    );
+
+    items.push(DataItem {
+        name: "Memory encryption key".to_string(),
+        location: DataLocation::Keychain,
+        path: crypto::KEYCHAIN_SERVICE.to_string(),
+        exists: Some(memory::get_memory_encryption_status(app.state::<MemoryStore>()).enabled),
+        size_bytes: None,
+        backup_bytes: None,
+        contents: "The key chat memory is encrypted with, if you turned encryption on",
+    });
+
+    items.push(DataItem {
+        name: "Sync key and password".to_string(),
+        location: DataLocation::Keychain,
+        path: crypto::KEYCHAIN_SERVICE.to_string(),
+        exists: Some(app.state::<sync::SyncStore>().is_enabled()),
+        size_bytes: None,
+        backup_bytes: None,
+        contents: "The key sync encrypts with, and the WebDAV password or S3 secret key",
+    });
+
+    items.push(DataItem {
+        name: "GitHub token".to_string(),
+        location: DataLocation::Keychain,
+        path: crypto::KEYCHAIN_SERVICE.to_string(),
+        exists: Some(github::is_connected()),
+        size_bytes: None,
+        backup_bytes: None,
+        contents: "The personal access token the cat reads your notifications and pull requests with",
+    });
+
+    let others = WEBVIEW_KEYS
+        .iter()
+        .map(|(key, contents)| (DataLocation::Webview, key, contents))
+        .chain(
+            CLOUD_TABLES
+                .iter()
+                .map(|(table, contents)| (DataLocation::Cloud, table, contents)),
+        );
+    items.extend(others.map(|(location, name, contents)| DataItem {
+        name: name.to_string(),
+        location,
+        path: name.to_string(),
+        exists: None,
+        size_bytes: None,
+        backup_bytes: None,
+        contents,
+    }));
+    Ok(items)
+}
+
+/// Everything the app keeps about you, where it lives and how big it is.
+/// Read-only: nothing is created, moved or sent anywhere.
+#[tauri::command]
+pub async fn get_data_inventory(app: tauri::AppHandle) -> PetResult<Vec<DataItem>> {
+    crate::run_blocking(move || inventory(&app)).await?
+}
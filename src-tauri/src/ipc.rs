@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const SOCKET_FILE: &str = "desktop-pet.sock";
+/// The bundle identifier from tauri.conf.json, which names the app data dir.
+const APP_IDENTIFIER: &str = "com.desktoppet";
+
+/// Somewhere only this user can reach, so two accounts on one machine don't
+/// share a socket. macOS gives each user their own temp dir, but elsewhere
+/// it's the shared /tmp, so there it's the runtime dir, or failing that the
+/// app data dir. Always in a folder of the app's own, which the server keeps
+/// private to this user.
+pub fn socket_path() -> PathBuf {
+    let var = |name: &str| {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = if cfg!(target_os = "macos") {
+        std::env::temp_dir()
+    } else if let Some(runtime) = var("XDG_RUNTIME_DIR") {
+        runtime
+    } else {
+        var("XDG_DATA_HOME")
+            .or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+            .unwrap_or_else(std::env::temp_dir)
+    };
+    base.join(APP_IDENTIFIER).join(SOCKET_FILE)
+}
+
+/// What `petctl` sends over the socket: one JSON line per connection,
+/// answered with one `IpcResponse` line.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum IpcRequest {
+    /// Have the cat say something
+    Say { text: String },
+    Nap,
+    /// Save a reminder note; with a delay the cat also speaks up when it's due
+    #[serde(rename_all = "camelCase")]
+    Remind {
+        text: String,
+        in_seconds: Option<u64>,
+    },
+    Stats,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcResponse {
+    pub ok: bool,
+    pub message: Option<String>,
+    pub data: Option<serde_json::Value>,
+}
+
+impl IpcResponse {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            message: Some(message.into()),
+            data: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            message: Some(message.into()),
+            data: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+pub use server::start;
+
+#[cfg(unix)]
+mod server {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::time::Duration;
+    use tauri::{Emitter, Manager};
+
+    use super::{socket_path, IpcRequest, IpcResponse};
+    use crate::error::{PetError, PetResult};
+    use crate::notes::NotesStore;
+    use crate::{notifications, usage};
+
+    /// Far more than any request petctl sends.
+    const MAX_LINE: u64 = 64 * 1024;
+
+    /// Listen for petctl connections for the life of the app.
+    pub fn start(app: &tauri::AppHandle) -> PetResult<()> {
+        let path = socket_path();
+        // The folder is private before the socket exists, so there's no
+        // moment where someone else could connect to it
+        if let Some(dir) = path.parent() {
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)?;
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+        // Left behind by a previous run that didn't shut down cleanly; the
+        // single-instance guard means nobody else is listening on it. Only
+        // ever a socket, so nothing else there is deleted
+        if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_socket()) {
+            let _ = std::fs::remove_file(&path);
+        }
+        let listener = UnixListener::bind(&path)
+            .map_err(|e| PetError::Platform(format!("Couldn't open {}: {}", path.display(), e)))?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = serve(&app, stream) {
+                    eprintln!("petctl request failed: {}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn serve(app: &tauri::AppHandle, stream: UnixStream) -> PetResult<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let mut line = String::new();
+        BufReader::new(&stream)
+            .take(MAX_LINE)
+            .read_line(&mut line)?;
+        let response = match serde_json::from_str::<IpcRequest>(&line) {
+            Ok(request) => handle(app, request),
+            Err(e) => IpcResponse::error(format!("Bad request: {}", e)),
+        };
+        let mut stream = stream;
+        writeln!(stream, "{}", serde_json::to_string(&response)?)?;
+        Ok(())
+    }
+
+    fn handle(app: &tauri::AppHandle, request: IpcRequest) -> IpcResponse {
+        match request {
+            IpcRequest::Say { text } => {
+                notifications::announce(app, &text);
+                IpcResponse::ok("Said it.")
+            }
+            IpcRequest::Nap => {
+                let _ = app.emit("pet-command", "nap");
+                IpcResponse::ok("Zzz.")
+            }
+            IpcRequest::Remind { text, in_seconds } => {
+                crate::notes::remind(app, &text, in_seconds);
+                match in_seconds {
+                    Some(seconds) => {
+                        IpcResponse::ok(format!("I'll remind you in {}.", describe(seconds)))
+                    }
+                    None => IpcResponse::ok("Stuck it on the fridge."),
+                }
+            }
+            IpcRequest::Stats => {
+                let stats = usage::get_usage_stats(app.state());
+                let notes = app.state::<NotesStore>().all().len();
+                IpcResponse {
+                    ok: true,
+                    message: None,
+                    data: Some(serde_json::json!({ "usage": stats, "notes": notes })),
+                }
+            }
+        }
+    }
+
+    fn describe(seconds: u64) -> String {
+        match seconds {
+            s if s >= 3600 && s % 3600 == 0 => format!("{}h", s / 3600),
+            s if s >= 60 => format!("{}m", s / 60),
+            s => format!("{}s", s),
+        }
+    }
+}
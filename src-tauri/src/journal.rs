@@ -0,0 +1,352 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::dialogue;
+use crate::error::{PetError, PetResult};
+use crate::persist;
+use crate::settings::SettingsStore;
+use crate::wrapped::escape;
+
+pub const DIGESTS_FILE: &str = "digests.json";
+/// Digests kept, a year's worth.
+const MAX_DIGESTS: usize = 52;
+/// File names start with this so an export can't clash with the vault's own
+/// daily notes.
+const FILE_PREFIX: &str = "Pet Diary";
+
+/// A diary entry as the webview keeps it.
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub date: String,
+    pub text: String,
+    #[serde(default)]
+    pub mood: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyDigest {
+    /// The Monday the week starts on (YYYY-MM-DD)
+    pub week_start: String,
+    pub week_end: String,
+    pub text: String,
+    /// How many entries it was written from
+    pub entries: usize,
+    pub generated_at: i64,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// One note per day, with YAML front matter, for Obsidian and the like
+    Markdown,
+    /// One page with every entry
+    Html,
+}
+
+/// Weekly digests, newest last. The entries themselves stay in the webview.
+pub struct DigestStore {
+    digests: Mutex<Vec<WeeklyDigest>>,
+    path: Option<PathBuf>,
+}
+
+impl DigestStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, DIGESTS_FILE).ok();
+        let digests = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            digests: Mutex::new(digests),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut digests = self.digests.lock().unwrap();
+        *digests = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn all(&self) -> Vec<WeeklyDigest> {
+        self.digests.lock().unwrap().clone()
+    }
+
+    /// Keep `digest`, replacing any earlier one for the same week.
+    fn put(&self, digest: WeeklyDigest) -> PetResult<()> {
+        let mut digests = self.digests.lock().unwrap();
+        digests.retain(|d| d.week_start != digest.week_start);
+        digests.push(digest);
+        digests.sort_by(|a, b| a.week_start.cmp(&b.week_start));
+        let excess = digests.len().saturating_sub(MAX_DIGESTS);
+        digests.drain(..excess);
+        match &self.path {
+            Some(path) => persist::save(path, &*digests),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_date(date: &str) -> PetResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| PetError::InvalidInput(format!("\"{}\" isn't a YYYY-MM-DD date", date)))
+}
+
+/// Monday to Sunday of the week `date` falls in.
+fn week_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    (start, start + Duration::days(6))
+}
+
+fn long_date(date: &str) -> String {
+    parse_date(date)
+        .map(|d| d.format("%A, %B %-d, %Y").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// Said without the model: how many entries, and the best day if rated.
+fn offline_digest(entries: &[&JournalEntry]) -> String {
+    let best = entries
+        .iter()
+        .filter(|e| e.mood.is_some())
+        .max_by_key(|e| e.mood)
+        .map(|e| format!(" The best day was {}.", long_date(&e.date)))
+        .unwrap_or_default();
+    format!(
+        "{} diary {} this week.{}",
+        entries.len(),
+        if entries.len() == 1 {
+            "entry"
+        } else {
+            "entries"
+        },
+        best
+    )
+}
+
+async fn digest(
+    app: &tauri::AppHandle,
+    entries: &[JournalEntry],
+    date: NaiveDate,
+) -> PetResult<WeeklyDigest> {
+    let (start, end) = week_of(date);
+    let mut week: Vec<&JournalEntry> = entries
+        .iter()
+        .filter(|e| parse_date(&e.date).is_ok_and(|d| d >= start && d <= end))
+        .collect();
+    if week.is_empty() {
+        return Err(PetError::InvalidInput(
+            "There are no diary entries that week".to_string(),
+        ));
+    }
+    week.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let trigger = week
+        .iter()
+        .map(|e| format!("{}: {}", long_date(&e.date), e.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let response = dialogue::generate(
+        app.clone(),
+        String::new(),
+        String::new(),
+        trigger,
+        Some("digest".to_string()),
+        None,
+        None,
+        None,
+    )
+    .await?;
+    Ok(WeeklyDigest {
+        week_start: start.format("%Y-%m-%d").to_string(),
+        week_end: end.format("%Y-%m-%d").to_string(),
+        text: if response.offline {
+            offline_digest(&week)
+        } else {
+            response.text
+        },
+        entries: week.len(),
+        generated_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+fn entry_markdown(entry: &JournalEntry) -> String {
+    let mood = entry
+        .mood
+        .map(|m| format!("mood: {}\n", m))
+        .unwrap_or_default();
+    format!(
+        "---\ndate: {}\n{}tags: [desktop-pet, diary]\n---\n\n# {}\n\n{}\n",
+        entry.date,
+        mood,
+        long_date(&entry.date),
+        entry.text.trim()
+    )
+}
+
+fn digest_markdown(digest: &WeeklyDigest) -> String {
+    format!(
+        "---\ndate: {}\ntags: [desktop-pet, diary, weekly]\n---\n\n# Week of {}\n\n{}\n",
+        digest.week_start,
+        long_date(&digest.week_start),
+        digest.text.trim()
+    )
+}
+
+fn journal_html(entries: &[JournalEntry], digests: &[WeeklyDigest]) -> String {
+    let mut sections: Vec<(String, String)> = entries
+        .iter()
+        .map(|e| {
+            (
+                e.date.clone(),
+                format!(
+                    "<article><h2>{}</h2><p>{}</p></article>",
+                    escape(&long_date(&e.date)),
+                    escape(e.text.trim()).replace('\n', "<br>")
+                ),
+            )
+        })
+        .collect();
+    // A week's digest sits after its Sunday, so it reads first going newest-first
+    sections.extend(digests.iter().map(|d| {
+        (
+            format!("{}~", d.week_end),
+            format!(
+                "<article class=\"digest\"><h2>Week of {}</h2><p>{}</p></article>",
+                escape(&long_date(&d.week_start)),
+                escape(d.text.trim()).replace('\n', "<br>")
+            ),
+        )
+    }));
+    sections.sort_by(|a, b| b.0.cmp(&a.0));
+    let body: String = sections.into_iter().map(|(_, html)| html).collect();
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title>\
+        <style>body{{font-family:Georgia,serif;max-width:640px;margin:40px auto;padding:0 16px;\
+        color:#3b2f2f;background:#fdf8f0}}h1{{text-align:center}}h2{{font-size:1.05em;color:#8a6d5a}}\
+        article{{margin-bottom:28px}}.digest{{background:#f4ead9;padding:4px 16px;border-radius:8px}}\
+        </style></head><body><h1>{title}</h1>{body}</body></html>",
+        title = FILE_PREFIX,
+        body = body
+    )
+}
+
+fn write_file(folder: &Path, name: &str, contents: &str) -> PetResult<()> {
+    fs::write(folder.join(name), contents)
+        .map_err(|e| PetError::Storage(format!("Couldn't write {}: {}", name, e)))
+}
+
+/// The export folder: the one given, which is remembered, or the last one.
+fn export_folder(app: &tauri::AppHandle, folder: Option<String>) -> PetResult<PathBuf> {
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    let folder = match folder
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+    {
+        Some(folder) => {
+            if settings.journal.export_folder.as_deref() != Some(folder.as_str()) {
+                settings.journal.export_folder = Some(folder.clone());
+                store.replace(settings)?;
+            }
+            folder
+        }
+        None => settings
+            .journal
+            .export_folder
+            .ok_or_else(|| PetError::InvalidInput("Choose a folder to export to".to_string()))?,
+    };
+    let path = PathBuf::from(&folder);
+    if !path.is_absolute() || !path.is_dir() {
+        return Err(PetError::InvalidInput(format!(
+            "\"{}\" isn't a folder on this Mac",
+            folder
+        )));
+    }
+    Ok(path)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalExport {
+    pub folder: String,
+    pub files: usize,
+}
+
+fn export(
+    app: &tauri::AppHandle,
+    format: ExportFormat,
+    entries: &[JournalEntry],
+    folder: Option<String>,
+) -> PetResult<JournalExport> {
+    let folder = export_folder(app, folder)?;
+    let digests = app.state::<DigestStore>().all();
+    let files = match format {
+        ExportFormat::Markdown => {
+            // Same names every time, so exporting again updates the notes
+            for entry in entries {
+                parse_date(&entry.date)?;
+                let name = format!("{} {}.md", FILE_PREFIX, entry.date);
+                write_file(&folder, &name, &entry_markdown(entry))?;
+            }
+            for digest in &digests {
+                let name = format!("{} Week of {}.md", FILE_PREFIX, digest.week_start);
+                write_file(&folder, &name, &digest_markdown(digest))?;
+            }
+            entries.len() + digests.len()
+        }
+        ExportFormat::Html => {
+            let name = format!("{}.html", FILE_PREFIX);
+            write_file(&folder, &name, &journal_html(entries, &digests))?;
+            1
+        }
+    };
+    Ok(JournalExport {
+        folder: folder.to_string_lossy().into_owned(),
+        files,
+    })
+}
+
+#[tauri::command(async)]
+pub fn get_weekly_digests(store: tauri::State<'_, DigestStore>) -> Vec<WeeklyDigest> {
+    store.all()
+}
+
+/// Have the cat look back on the week `week_of` (YYYY-MM-DD, default today)
+/// falls in, from that week's `entries`. Saved, replacing an earlier digest
+/// of the same week.
+#[tauri::command]
+pub async fn generate_weekly_digest(
+    app: tauri::AppHandle,
+    entries: Vec<JournalEntry>,
+    week_of: Option<String>,
+) -> PetResult<WeeklyDigest> {
+    let date = match week_of {
+        Some(date) => parse_date(&date)?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let digest = digest(&app, &entries, date).await?;
+    let saved = digest.clone();
+    crate::run_blocking(move || app.state::<DigestStore>().put(saved)).await??;
+    Ok(digest)
+}
+
+/// Write the diary and its weekly digests into `folder`, remembered for
+/// next time: a Markdown note per day and week, or one HTML page.
+#[tauri::command]
+pub async fn export_journal(
+    app: tauri::AppHandle,
+    format: ExportFormat,
+    entries: Vec<JournalEntry>,
+    folder: Option<String>,
+) -> PetResult<JournalExport> {
+    crate::run_blocking(move || export(&app, format, &entries, folder)).await?
+}
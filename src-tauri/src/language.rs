@@ -0,0 +1,163 @@
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+
+/// Spoken when neither the setting nor the OS says otherwise.
+const FALLBACK: &str = "en";
+/// Longer than any real BCP 47 tag the pet would be given.
+const MAX_TAG_CHARS: usize = 35;
+
+/// Languages offered in Settings, by primary subtag. Others still work when
+/// typed in or detected; the prompt then names them by tag.
+const NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nb", "Norwegian"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+/// The language the pet speaks, as a tag and a name the model understands.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Language {
+    pub tag: String,
+    pub name: String,
+}
+
+impl Language {
+    fn from_tag(tag: &str) -> Self {
+        let primary = tag.split(['-', '_']).next().unwrap_or(tag).to_lowercase();
+        let name = NAMES
+            .iter()
+            .find(|(code, _)| *code == primary)
+            .map(|(_, name)| name.to_string())
+            // The model knows tags it isn't given a name for here
+            .unwrap_or_else(|| format!("the language with the tag \"{}\"", tag));
+        Self {
+            tag: tag.to_string(),
+            name,
+        }
+    }
+
+    pub fn is_english(&self) -> bool {
+        self.name == "English"
+    }
+}
+
+fn valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= MAX_TAG_CHARS
+        && tag
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// A tag from the user, e.g. "fr" or "pt-BR".
+pub fn parse(tag: &str) -> PetResult<Language> {
+    if !valid_tag(tag) {
+        return Err(PetError::InvalidInput(format!(
+            "\"{}\" isn't a language tag like \"fr\" or \"pt-BR\"",
+            tag
+        )));
+    }
+    Ok(Language::from_tag(tag))
+}
+
+/// The OS's preferred language, e.g. "fr-CA". POSIX locales like
+/// "de_DE.UTF-8" are turned into tags.
+pub fn detect() -> String {
+    sys_locale::get_locale()
+        .map(|locale| {
+            locale
+                .split(['.', '@'])
+                .next()
+                .unwrap_or_default()
+                .replace('_', "-")
+        })
+        .filter(|tag| valid_tag(tag) && tag != "C" && tag != "POSIX")
+        .unwrap_or_else(|| FALLBACK.to_string())
+}
+
+/// The chosen language, or the OS's when none is chosen.
+pub fn current(app: &tauri::AppHandle) -> Language {
+    let tag = app
+        .state::<SettingsStore>()
+        .get()
+        .language
+        .filter(|tag| valid_tag(tag))
+        .unwrap_or_else(detect);
+    Language::from_tag(&tag)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageStatus {
+    /// The chosen tag; None follows the OS
+    pub setting: Option<String>,
+    pub detected: Language,
+    /// What the pet speaks now
+    pub current: Language,
+    /// Languages to offer in Settings
+    pub choices: Vec<Language>,
+}
+
+fn status(app: &tauri::AppHandle) -> LanguageStatus {
+    LanguageStatus {
+        setting: app.state::<SettingsStore>().get().language,
+        detected: Language::from_tag(&detect()),
+        current: current(app),
+        choices: NAMES
+            .iter()
+            .map(|(tag, _)| Language::from_tag(tag))
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_language(app: tauri::AppHandle) -> PetResult<LanguageStatus> {
+    crate::run_blocking(move || status(&app)).await
+}
+
+/// Speak `tag` (e.g. "de" or "pt-BR") from the next line on, or follow the
+/// OS again with None.
+#[tauri::command]
+pub async fn set_language(app: tauri::AppHandle, tag: Option<String>) -> PetResult<LanguageStatus> {
+    let tag = tag.map(|tag| tag.trim().to_string());
+    if let Some(tag) = &tag {
+        parse(tag)?;
+    }
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.language = tag;
+        store.replace(settings)?;
+        Ok(status(&app))
+    })
+    .await?
+}
@@ -0,0 +1,226 @@
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+use crate::tools;
+
+const MAX_ALLOWLIST: usize = 50;
+const MAX_NAME_CHARS: usize = 100;
+
+/// Something the cat can open: an app by name or a web address. Built only
+/// through `app` and `url`, which refuse anything that could be read as a
+/// flag or a local path.
+#[derive(Clone)]
+pub enum Target {
+    App(String),
+    Url(Url),
+}
+
+impl Target {
+    pub fn app(name: &str) -> PetResult<Self> {
+        clean_app(name).map(Self::App)
+    }
+
+    pub fn url(url: &str) -> PetResult<Self> {
+        let parsed = Url::parse(url.trim())
+            .map_err(|_| PetError::InvalidInput(format!("\"{}\" isn't a web address", url)))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            return Err(PetError::InvalidInput(
+                "Only http and https addresses can be opened".to_string(),
+            ));
+        }
+        Ok(Self::Url(parsed))
+    }
+
+    /// The target of an `open_app` or `open_url` tool call, if it's one.
+    pub fn from_tool(name: &str, input: &Value) -> Option<Self> {
+        match name {
+            "open_app" => Self::app(input.get("name")?.as_str()?).ok(),
+            "open_url" => Self::url(input.get("url")?.as_str()?).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::App(name) => name.clone(),
+            Self::Url(url) => url.to_string(),
+        }
+    }
+
+    fn allowlisted(&self, app: &tauri::AppHandle) -> bool {
+        let tools = app.state::<SettingsStore>().get().tools;
+        match self {
+            Self::App(name) => tools.apps.iter().any(|app| app.eq_ignore_ascii_case(name)),
+            Self::Url(url) => {
+                let host = url.host_str().unwrap_or_default().to_lowercase();
+                tools.sites.iter().any(|site| {
+                    host == *site
+                        || host
+                            .strip_suffix(site.as_str())
+                            .is_some_and(|rest| rest.ends_with('.'))
+                })
+            }
+        }
+    }
+
+    /// Open it now, without asking. Blocks until the launch is handed off.
+    pub fn open(&self) -> PetResult<String> {
+        match self {
+            Self::App(name) => {
+                if !cfg!(target_os = "macos") {
+                    return Err(PetError::Platform(
+                        "Opening apps by name only works on macOS".to_string(),
+                    ));
+                }
+                let status = std::process::Command::new("open")
+                    .args(["-a", name])
+                    .status()
+                    .map_err(|e| PetError::Platform(e.to_string()))?;
+                if !status.success() {
+                    return Err(PetError::InvalidInput(format!(
+                        "There's no app called {}",
+                        name
+                    )));
+                }
+            }
+            Self::Url(url) => crate::opener::open_url(url.as_str())?,
+        }
+        Ok(format!("Opened {}.", self.describe()))
+    }
+}
+
+/// Whether a call to `name` can skip the permission prompt because what it
+/// opens is on the allowlist.
+pub fn preapproved(app: &tauri::AppHandle, name: &str, input: &Value) -> bool {
+    Target::from_tool(name, input).is_some_and(|target| target.allowlisted(app))
+}
+
+/// Put `target` on the allowlist, for an "Always" answer to a prompt.
+pub fn allow(app: &tauri::AppHandle, target: &Target) -> PetResult<()> {
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    let (list, entry) = match target {
+        Target::App(name) => (&mut settings.tools.apps, name.clone()),
+        Target::Url(url) => (
+            &mut settings.tools.sites,
+            url.host_str().unwrap_or_default().to_lowercase(),
+        ),
+    };
+    if list
+        .iter()
+        .any(|existing| existing.eq_ignore_ascii_case(&entry))
+    {
+        return Ok(());
+    }
+    if list.len() >= MAX_ALLOWLIST {
+        return Err(PetError::InvalidInput(format!(
+            "At most {} apps and {} sites",
+            MAX_ALLOWLIST, MAX_ALLOWLIST
+        )));
+    }
+    list.push(entry);
+    store.replace(settings)
+}
+
+/// Open `target` if it's allowlisted, and otherwise only once the owner
+/// says yes to a `tool-permission-requested` prompt. False if they didn't.
+async fn open_confirmed(app: &tauri::AppHandle, tool: &str, target: Target) -> PetResult<bool> {
+    if !target.allowlisted(app) {
+        let summary = format!("Open {}", target.describe());
+        if !tools::ask(app, tool, &summary, Some(target.clone())).await {
+            return Ok(false);
+        }
+    }
+    crate::run_blocking(move || target.open()).await??;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn open_application(app: tauri::AppHandle, name: String) -> PetResult<bool> {
+    open_confirmed(&app, "open_app", Target::app(&name)?).await
+}
+
+#[tauri::command]
+pub async fn open_url(app: tauri::AppHandle, url: String) -> PetResult<bool> {
+    open_confirmed(&app, "open_url", Target::url(&url)?).await
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchAllowlist {
+    pub apps: Vec<String>,
+    pub sites: Vec<String>,
+}
+
+fn clean_app(name: &str) -> PetResult<String> {
+    let name = name.trim().trim_end_matches(".app");
+    if name.is_empty()
+        || name.chars().count() > MAX_NAME_CHARS
+        || name.starts_with('-')
+        || name.contains(['/', '\\'])
+        || name.chars().any(char::is_control)
+    {
+        return Err(PetError::InvalidInput(format!(
+            "\"{}\" isn't an app name",
+            name
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn clean_site(site: &str) -> Option<String> {
+    let site = site.trim().to_lowercase();
+    let site = site.split("://").last().unwrap_or_default();
+    let host = site.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.trim_start_matches("*.").trim_end_matches('.');
+    (!host.is_empty() && host.contains('.') && !host.contains(['*', ' '])).then(|| host.to_string())
+}
+
+/// Replace the apps and sites that open without asking, whether the palette
+/// or the cat opens them.
+#[tauri::command]
+pub async fn set_launch_allowlist(
+    app: tauri::AppHandle,
+    apps: Vec<String>,
+    sites: Vec<String>,
+) -> PetResult<LaunchAllowlist> {
+    let mut clean_apps: Vec<String> = Vec::new();
+    for name in apps.iter().filter(|name| !name.trim().is_empty()) {
+        let name = clean_app(name)?;
+        if !clean_apps.iter().any(|app| app.eq_ignore_ascii_case(&name)) {
+            clean_apps.push(name);
+        }
+    }
+    let mut clean_sites: Vec<String> = Vec::new();
+    for site in sites.iter().filter(|site| !site.trim().is_empty()) {
+        let host = clean_site(site).ok_or_else(|| {
+            PetError::InvalidInput(format!("\"{}\" isn't a site like github.com", site.trim()))
+        })?;
+        if !clean_sites.contains(&host) {
+            clean_sites.push(host);
+        }
+    }
+    if clean_apps.len() > MAX_ALLOWLIST || clean_sites.len() > MAX_ALLOWLIST {
+        return Err(PetError::InvalidInput(format!(
+            "At most {} apps and {} sites",
+            MAX_ALLOWLIST, MAX_ALLOWLIST
+        )));
+    }
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.tools.apps = clean_apps;
+        settings.tools.sites = clean_sites;
+        store.replace(settings)?;
+        let tools = store.get().tools;
+        Ok(LaunchAllowlist {
+            apps: tools.apps,
+            sites: tools.sites,
+        })
+    })
+    .await?
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+use crate::{persist, relay, scheduler};
+
+pub const LEADERBOARD_FILE: &str = "leaderboard.json";
+const TABLE: &str = "leaderboard";
+/// Playtime is only re-sent this often; achievements go out as they unlock.
+const SUBMIT_INTERVAL_MINUTES: u32 = 60;
+/// Longest gap between ticks still counted as play. Ticks are skipped while
+/// saving battery; a longer gap means the Mac was asleep.
+const MAX_TICK_GAP_SECS: u64 = 2 * scheduler::TICK_INTERVAL.as_secs();
+const TOP_ENTRIES: u32 = 20;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LeaderboardData {
+    /// Random id for this install, unrelated to the friend code
+    pub anonymous_id: String,
+    pub playtime_secs: u64,
+    pub achievements: u32,
+    /// Unix millis of the last successful submission
+    pub last_submitted_at: Option<i64>,
+}
+
+pub struct LeaderboardStore {
+    data: Mutex<LeaderboardData>,
+    path: Option<PathBuf>,
+    last_tick: Mutex<Option<std::time::Instant>>,
+}
+
+impl LeaderboardStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, LEADERBOARD_FILE).ok();
+        let mut data: LeaderboardData = path.as_deref().and_then(persist::load).unwrap_or_default();
+        if data.anonymous_id.is_empty() {
+            data.anonymous_id = uuid::Uuid::new_v4().to_string();
+            if let Some(path) = &path {
+                let _ = persist::save(path, &data);
+            }
+        }
+        Self {
+            data: Mutex::new(data),
+            path,
+            last_tick: Mutex::new(None),
+        }
+    }
+
+    pub fn get(&self) -> LeaderboardData {
+        self.data.lock().unwrap().clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut LeaderboardData)) {
+        let mut data = self.data.lock().unwrap();
+        f(&mut data);
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*data);
+        }
+    }
+
+    /// Add the time since the last tick, unless the gap says we were asleep.
+    fn count_playtime(&self) {
+        let now = std::time::Instant::now();
+        let last = self.last_tick.lock().unwrap().replace(now);
+        let Some(elapsed) = last.map(|last| now.duration_since(last).as_secs()) else {
+            return;
+        };
+        if elapsed <= MAX_TICK_GAP_SECS {
+            self.update(|d| d.playtime_secs += elapsed);
+        }
+    }
+}
+
+/// Everything that leaves the machine. Nothing else is ever sent, so any
+/// new field has to be added here on purpose.
+#[derive(Serialize)]
+struct Submission {
+    anonymous_id: String,
+    playtime_minutes: u64,
+    achievements: u32,
+}
+
+impl From<&LeaderboardData> for Submission {
+    fn from(data: &LeaderboardData) -> Self {
+        Self {
+            anonymous_id: data.anonymous_id.clone(),
+            playtime_minutes: data.playtime_secs / 60,
+            achievements: data.achievements,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Row {
+    anonymous_id: String,
+    playtime_minutes: u64,
+    achievements: u32,
+}
+
+async fn submit(app: &tauri::AppHandle) -> PetResult<()> {
+    let (url, key) = relay::table(TABLE)?;
+    let store = app.state::<LeaderboardStore>();
+    let submission = Submission::from(&store.get());
+    relay::send(
+        relay::request(reqwest::Method::POST, &url, &key)
+            .query(&[("on_conflict", "anonymous_id")])
+            .header("Prefer", "resolution=merge-duplicates")
+            .json(&submission),
+    )
+    .await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    store.update(|d| d.last_submitted_at = Some(now));
+    Ok(())
+}
+
+/// Take this install's row off the leaderboard.
+async fn withdraw(app: &tauri::AppHandle) -> PetResult<()> {
+    let (url, key) = relay::table(TABLE)?;
+    let id = app.state::<LeaderboardStore>().get().anonymous_id;
+    relay::send(
+        relay::request(reqwest::Method::DELETE, &url, &key)
+            .query(&[("anonymous_id", format!("eq.{}", id))]),
+    )
+    .await?;
+    app.state::<LeaderboardStore>()
+        .update(|d| d.last_submitted_at = None);
+    Ok(())
+}
+
+async fn tick(app: tauri::AppHandle) {
+    let store = app.state::<LeaderboardStore>();
+    store.count_playtime();
+    if !app.state::<SettingsStore>().get().leaderboard.enabled {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    if scheduler::is_due(store.get().last_submitted_at, SUBMIT_INTERVAL_MINUTES, now) {
+        if let Err(e) = submit(&app).await {
+            eprintln!("Leaderboard submission failed: {}", e);
+        }
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, tick);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    pub playtime_minutes: u64,
+    pub achievements: u32,
+    /// This install's own row
+    pub you: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaderboard {
+    pub enabled: bool,
+    /// Most achievements first, then most playtime
+    pub entries: Vec<LeaderboardEntry>,
+    pub playtime_minutes: u64,
+    pub achievements: u32,
+}
+
+/// The top of the leaderboard for the achievements panel. Other installs'
+/// ids stay out of the response; only ranks and counts come back.
+#[tauri::command]
+pub async fn get_leaderboard(app: tauri::AppHandle) -> PetResult<Leaderboard> {
+    let enabled = app.state::<SettingsStore>().get().leaderboard.enabled;
+    let data = app.state::<LeaderboardStore>().get();
+    let mut entries = Vec::new();
+    if enabled {
+        let (url, key) = relay::table(TABLE)?;
+        let request = relay::request(reqwest::Method::GET, &url, &key).query(&[
+            (
+                "select",
+                "anonymous_id,playtime_minutes,achievements".to_string(),
+            ),
+            (
+                "order",
+                "achievements.desc,playtime_minutes.desc".to_string(),
+            ),
+            ("limit", TOP_ENTRIES.to_string()),
+        ]);
+        let rows: Vec<Row> = relay::send(request)
+            .await?
+            .json()
+            .await
+            .map_err(|e| PetError::Parse(e.to_string()))?;
+        entries = rows
+            .into_iter()
+            .zip(1..)
+            .map(|(row, rank)| LeaderboardEntry {
+                rank,
+                playtime_minutes: row.playtime_minutes,
+                achievements: row.achievements,
+                you: row.anonymous_id == data.anonymous_id,
+            })
+            .collect();
+    }
+    Ok(Leaderboard {
+        enabled,
+        entries,
+        playtime_minutes: data.playtime_secs / 60,
+        achievements: data.achievements,
+    })
+}
+
+/// Record the unlocked-achievement count, which only the webview knows, and
+/// send it straight away if it changed.
+#[tauri::command]
+pub async fn sync_leaderboard_achievements(
+    app: tauri::AppHandle,
+    achievements: u32,
+) -> PetResult<()> {
+    let store = app.state::<LeaderboardStore>();
+    if store.get().achievements == achievements {
+        return Ok(());
+    }
+    store.update(|d| d.achievements = achievements);
+    if app.state::<SettingsStore>().get().leaderboard.enabled {
+        submit(&app).await?;
+    }
+    Ok(())
+}
+
+/// Opt in or out. Opting out also deletes this install's row from the relay.
+#[tauri::command]
+pub async fn set_leaderboard_enabled(app: tauri::AppHandle, enabled: bool) -> PetResult<()> {
+    if enabled {
+        submit(&app).await?;
+    } else {
+        withdraw(&app).await?;
+    }
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.leaderboard.enabled = enabled;
+    store.replace(settings)
+}
@@ -1,55 +1,187 @@
+mod accessibility;
+mod activity;
 mod active_window;
+mod appearance;
+mod archive;
+mod automation;
+mod autostart;
+mod browser;
+mod canned_dialogue;
+mod clipboard;
+pub mod config;
+mod card;
+mod countdowns;
+mod cowork;
+mod crypto;
+mod deeplink;
+mod dev_watcher;
+mod dictation;
 mod dialogue;
+mod displays;
+mod dock;
+mod error;
+mod files;
+mod fullscreen;
+mod ghost;
+mod git;
+mod github;
+mod guests;
+mod health;
+mod inventory;
+mod language;
+mod launch;
+mod journal;
+mod leaderboard;
+pub mod ipc;
+mod maintenance;
+mod meetings;
 mod memory;
+mod milestones;
+mod menubar;
+mod moderation;
+mod mood;
+mod moods;
+mod notes;
+mod notifications;
+mod occlusion;
+mod ocr;
+mod opener;
+mod pairing;
+mod personality;
+mod perch;
+mod permissions;
+mod performance;
+mod persist;
+mod pets;
+mod power;
+mod presence;
+mod prompts;
+mod reactions;
+mod relay;
+mod scheduler;
+mod screenshots;
+mod search;
+mod settings;
+mod shortcuts;
+mod sound;
+mod sports;
+mod startup;
+mod stickies;
+mod sync;
+mod tickers;
+mod tools;
+mod trackers;
+mod tray;
+mod updates;
+mod usage;
+mod watchers;
+mod weather;
+mod wipe;
+mod wrapped;
 
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    Emitter, Manager,
-};
+use tauri::{Emitter, Manager, RunEvent};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .plugin(tauri_plugin_shell::init())
+        // Must come first so a second launch exits before setting anything up
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            focus_existing(app, args);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            None,
+        ))
         .setup(|app| {
-            // Build tray menu
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let mute_item = MenuItem::with_id(app, "mute", "Mute Dialogue", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&mute_item, &quit_item])?;
+            config::load_file(app.handle());
+            power::init(app);
+            app.manage(settings::SettingsStore::load(app.handle()));
+            // Before memory and mood, which load the active pet's files
+            app.manage(pets::PetRegistry::load(app.handle()));
+            app.manage(memory::MemoryStore::load(app.handle()));
+            memory::spawn_flush_task(app.handle().clone());
+            app.manage(usage::UsageStore::load(app.handle()));
+            app.manage(notes::NotesStore::load(app.handle()));
+            notes::spawn_scheduler(app.handle().clone());
+            app.manage(stickies::StickyStore::load(app.handle()));
+            app.manage(sync::SyncStore::load(app.handle()));
+            app.manage(tools::ToolAuditStore::load(app.handle()));
+            app.manage(tools::ToolPrompts::default());
+            app.manage(files::FoundFiles::default());
+            app.manage(moods::MoodStore::load(app.handle()));
+            app.manage(journal::DigestStore::load(app.handle()));
+            app.manage(watchers::WatcherStore::load(app.handle()));
+            watchers::spawn_scheduler(app.handle().clone());
+            app.manage(trackers::TrackerStore::load(app.handle()));
+            trackers::spawn_scheduler(app.handle().clone());
+            app.manage(tickers::WatchlistStore::load(app.handle()));
+            tickers::spawn_scheduler(app.handle().clone());
+            app.manage(sports::TeamStore::load(app.handle()));
+            sports::spawn_scheduler(app.handle().clone());
+            app.manage(weather::WeatherState::default());
+            weather::spawn_scheduler(app.handle().clone());
+            app.manage(github::GithubStore::load(app.handle()));
+            github::spawn_scheduler(app.handle().clone());
+            app.manage(meetings::MeetingState::default());
+            meetings::spawn_scheduler(app.handle().clone());
+            app.manage(health::HealthState::default());
+            health::spawn_scheduler(app.handle().clone());
+            sound::start(app.handle());
+            app.manage(dev_watcher::DevWatcherState::default());
+            dev_watcher::spawn_scheduler(app.handle().clone());
+            app.manage(countdowns::CountdownStore::load(app.handle()));
+            countdowns::spawn_scheduler(app.handle().clone());
+            app.manage(wrapped::WrappedStore::load(app.handle()));
+            wrapped::spawn_scheduler(app.handle().clone());
+            app.manage(mood::MoodEngine::load(app.handle()));
+            mood::spawn_ticker(app.handle().clone());
+            app.manage(activity::ActivityStore::load(app.handle()));
+            activity::spawn_tracker(app.handle().clone());
+            app.manage(milestones::MilestoneStore::load(app.handle()));
+            milestones::spawn_scheduler(app.handle().clone());
+            app.manage(occlusion::OcclusionState::default());
+            app.manage(appearance::WallpaperCache::default());
+            appearance::init(app);
+            occlusion::spawn_watcher(app.handle().clone());
+            app.manage(fullscreen::FullscreenState::default());
+            fullscreen::spawn_watcher(app.handle().clone());
+            displays::spawn_watcher(app.handle().clone());
+            app.manage(dock::DockState::default());
+            dock::spawn_watcher(app.handle().clone());
+            app.manage(guests::GuestState::default());
+            app.manage(moderation::ModerationStore::load(app.handle()));
+            guests::spawn_scheduler(app.handle().clone());
+            app.manage(leaderboard::LeaderboardStore::load(app.handle()));
+            leaderboard::spawn_scheduler(app.handle().clone());
+            app.manage(cowork::CoworkState::default());
+            cowork::spawn_scheduler(app.handle().clone());
+            app.manage(clipboard::ClipboardState::default());
+            app.manage(screenshots::ScreenshotState::default());
+            screenshots::spawn_scheduler(app.handle().clone());
+            clipboard::spawn_watcher(app.handle().clone());
+            app.manage(dictation::DictationState::default());
+            app.manage(reactions::ReactionStore::load(app.handle()));
+            app.manage(ghost::GhostState::default());
+            app.manage(performance::PerformanceState::default());
+            app.manage(prompts::PromptTemplates::load(app.handle()));
+            app.manage(updates::UpdateState::default());
+            updates::spawn_scheduler(app.handle().clone());
+            maintenance::spawn_scheduler(app.handle().clone());
+            shortcuts::register_all(app.handle());
 
-            TrayIconBuilder::new()
-                .menu(&menu)
-                .icon(app.default_window_icon().unwrap().clone())
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    "mute" => {
-                        // Toggle mute - handled via frontend event
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.emit("toggle-mute", ());
-                        }
-                    }
-                    _ => {}
-                })
-                .build(app)?;
+            tray::init(app)?;
+            stickies::restore(app.handle());
+            tray::spawn_animation(app.handle().clone());
 
             if let Some(window) = app.get_webview_window("main") {
-                // Maximize to fill the screen without true fullscreen
-                // (true fullscreen breaks transparency on macOS)
-                if let Ok(monitor) = window.current_monitor() {
-                    if let Some(monitor) = monitor {
-                        let size = monitor.size();
-                        let pos = monitor.position();
-                        let _ = window.set_position(tauri::Position::Physical(
-                            tauri::PhysicalPosition::new(pos.x, pos.y),
-                        ));
-                        let _ = window.set_size(tauri::Size::Physical(
-                            tauri::PhysicalSize::new(size.width, size.height),
-                        ));
-                    }
+                // The home display if set, skipping any the pet is kept off
+                if let Some(monitor) = displays::pet_display(app.handle()) {
+                    fill_display(&window, &monitor);
                 }
                 // Start accepting cursor events; the passthrough hook will
                 // toggle ignore on/off once it begins polling.
@@ -57,46 +189,306 @@ pub fn run() {
                 // Show window after positioning
                 let _ = window.show();
             }
+            if let Err(e) = ghost::apply(app.handle()) {
+                eprintln!("Opacity not applied: {}", e);
+            }
+            menubar::apply(app.handle());
+            deeplink::init(app)?;
+
+            app.manage(automation::AutomationState::load(app.handle()));
+            if let Err(e) = automation::apply(app.handle()) {
+                eprintln!("Automation API not started: {}", e);
+            }
+            app.manage(presence::PresenceState::load(app.handle()));
+            if let Err(e) = presence::apply(app.handle()) {
+                eprintln!("LAN presence not started: {}", e);
+            }
+            app.manage(pairing::PairingState::load(app.handle()));
+            if let Err(e) = pairing::apply(app.handle()) {
+                eprintln!("Companion server not started: {}", e);
+            }
+            #[cfg(unix)]
+            if let Err(e) = ipc::start(app.handle()) {
+                eprintln!("petctl socket not started: {}", e);
+            }
+            app.manage(startup::StartupState::default());
+            startup::spawn_check(app.handle().clone());
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             active_window::get_active_window_info,
+            appearance::get_system_appearance,
+            appearance::sample_wallpaper_colors,
+            archive::export_pet_data,
+            archive::import_pet_data,
+            search::search_memory,
+            card::export_pet_card,
+            automation::get_automation_status,
+            automation::set_automation_enabled,
+            automation::regenerate_automation_token,
+            autostart::get_launch_at_login,
+            autostart::set_launch_at_login,
+            countdowns::list_countdowns,
+            countdowns::add_countdown,
+            countdowns::remove_countdown,
+            fullscreen::get_fullscreen_status,
+            displays::get_displays,
+            displays::set_home_display,
+            displays::set_display_allowed,
+            dock::get_dock_icons,
+            guests::submit_guest_interaction,
+            guests::clear_guest_queue,
+            deeplink::take_deep_links,
             dialogue::generate_pet_dialogue,
+            dialogue::translate_text,
+            activity::generate_standup,
+            config::get_config_sources,
+            journal::get_weekly_digests,
+            journal::generate_weekly_digest,
+            journal::export_journal,
+            milestones::get_milestones,
+            milestones::record_milestone,
+            dialogue::preview_context,
+            dialogue::preview_prompt,
+            dialogue::get_dialogue_config,
+            dialogue::set_dialogue_config,
+            personality::get_personalities,
+            personality::set_personality,
+            personality::get_personalities_dir,
+            language::get_language,
+            language::set_language,
+            ghost::get_overlay_status,
+            ghost::set_overlay_opacity,
+            ghost::set_ghost_mode,
+            mood::get_mood,
+            mood::record_interaction,
+            performance::get_performance_mode,
+            performance::set_performance_mode,
+            prompts::get_prompt_templates,
+            prompts::customize_prompt_template,
+            prompts::reset_prompt_template,
+            prompts::get_prompts_dir,
+            reactions::get_reaction_suggestions,
+            reactions::record_reaction,
+            startup::get_startup_report,
+            startup::run_fix_it,
+            inventory::get_data_inventory,
             memory::clear_chat_memory,
+            wipe::wipe_all_data,
             memory::get_memory_stats,
+            memory::start_new_session,
+            memory::list_sessions,
+            memory::load_session,
+            memory::get_facts,
+            memory::review_facts,
+            memory::archive_facts,
+            memory::restore_fact,
+            pets::list_pets,
+            pets::create_pet,
+            pets::switch_pet,
+            memory::get_memory_encryption_status,
+            memory::enable_memory_encryption,
+            memory::disable_memory_encryption,
+            memory::unlock_memory,
+            memory::list_memory_backups,
+            memory::restore_memory_backup,
+            menubar::set_menu_bar_only,
+            moderation::moderate_visit_message,
+            moderation::list_blocked_senders,
+            moderation::block_sender,
+            moderation::unblock_sender,
+            moods::get_mood_calendar,
+            notes::list_notes,
+            notes::complete_note,
+            notes::delete_note,
+            stickies::create_sticky_note,
+            stickies::get_sticky_notes,
+            stickies::remove_sticky_note,
+            sync::get_sync_status,
+            sync::enable_sync,
+            sync::disable_sync,
+            sync::sync_now,
+            tools::get_tools,
+            tools::set_tool_permission,
+            tools::answer_tool_permission,
+            tools::get_tool_audit,
+            files::search_files,
+            files::open_file,
+            launch::open_application,
+            launch::open_url,
+            launch::set_launch_allowlist,
+            weather::get_weather,
+            weather::set_weather,
+            github::get_github_status,
+            github::set_github_token,
+            github::get_github_notifications,
+            meetings::get_meeting_status,
+            occlusion::is_point_occluded,
+            occlusion::set_pet_spot,
+            occlusion::set_window_exclusions,
+            power::get_power_status,
+            presence::get_presence_status,
+            presence::set_presence_enabled,
+            presence::set_presence_key,
+            presence::send_pet_to,
+            pairing::generate_pairing_code,
+            pairing::get_pairing_status,
+            pairing::cancel_pairing_code,
+            pairing::list_companions,
+            pairing::remove_companion,
+            leaderboard::get_leaderboard,
+            leaderboard::sync_leaderboard_achievements,
+            leaderboard::set_leaderboard_enabled,
+            cowork::set_cowork_pet,
+            cowork::get_cowork,
+            cowork::start_cowork,
+            cowork::stop_cowork,
+            cowork::send_cowork_reaction,
+            clipboard::set_clipboard_watch,
+            clipboard::ask_about_clipboard,
+            clipboard::dismiss_clipboard_offer,
+            screenshots::set_screenshot_watch,
+            screenshots::ask_about_screenshot,
+            screenshots::dismiss_screenshot_offer,
+            dictation::set_dictation_enabled,
+            dictation::stage_dictation,
+            dictation::type_dictation,
+            dictation::discard_dictation,
+            settings::get_settings,
+            settings::set_settings,
+            shortcuts::get_shortcuts,
+            shortcuts::get_keybindings,
+            accessibility::get_selected_text,
+            accessibility::get_focused_text_field,
+            browser::get_browser_context,
+            git::get_git_context,
+            ocr::ocr_active_window,
+            health::system_report,
+            health::generate_system_report,
+            sound::get_sound_status,
+            sound::play_sound,
+            sound::purr,
+            sound::set_volume,
+            sound::set_sound_muted,
+            sound::set_music_folder,
+            sound::set_music_playing,
+            sound::set_soundscape,
+            sound::set_sleep_timer,
+            dev_watcher::get_dev_watcher,
+            dev_watcher::set_dev_watcher,
+            dev_watcher::run_dev_command,
+            perch::get_menu_bar_layout,
+            perch::set_perch_side,
+            permissions::check_permission,
+            permissions::request_permission,
+            permissions::open_permission_settings,
+            shortcuts::set_shortcut,
+            sports::list_teams,
+            sports::add_team,
+            sports::remove_team,
+            sports::set_team_losing_streak_mute,
+            tickers::get_watchlist,
+            tickers::add_ticker,
+            tickers::remove_ticker,
+            tickers::get_quote,
+            trackers::list_trackers,
+            trackers::add_price_tracker,
+            trackers::add_package_tracker,
+            trackers::remove_tracker,
+            tray::set_pet_animation,
+            updates::check_for_updates,
+            updates::install_update,
+            updates::get_update_channel,
+            updates::set_update_channel,
+            usage::get_usage_stats,
+            usage::set_monthly_budget,
+            watchers::list_watchers,
+            watchers::add_watcher,
+            watchers::remove_watcher,
+            wrapped::generate_wrapped,
             set_ignore_cursor_events,
             get_mouse_position,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                app.state::<memory::MemoryStore>().flush(app);
+                app.state::<activity::ActivityStore>().flush();
+                #[cfg(unix)]
+                let _ = std::fs::remove_file(ipc::socket_path());
+            }
+        });
+}
+
+/// A second launch lands here instead of starting another pet. Bring this
+/// one back into view and forward the new launch's arguments to the webview.
+fn focus_existing(app: &tauri::AppHandle, args: Vec<String>) {
+    if !menubar::is_enabled(app) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+        }
+        tray::refresh(app);
+    }
+    // The first argument is the executable path
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    if !args.is_empty() {
+        let _ = app.emit("second-instance", args);
+    }
+}
+
+/// Maximize the overlay over a display without true fullscreen (true
+/// fullscreen breaks transparency on macOS).
+pub(crate) fn fill_display(window: &tauri::WebviewWindow, monitor: &tauri::Monitor) {
+    let size = monitor.size();
+    let pos = monitor.position();
+    let _ = window.set_position(tauri::Position::Physical(tauri::PhysicalPosition::new(
+        pos.x, pos.y,
+    )));
+    let _ = window.set_size(tauri::Size::Physical(tauri::PhysicalSize::new(
+        size.width,
+        size.height,
+    )));
+}
+
+/// Run blocking work (file IO, FFI) on tokio's blocking pool so it never
+/// stalls the main thread or the async workers handling IPC.
+pub(crate) async fn run_blocking<T, F>(f: F) -> error::PetResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| error::PetError::Internal(format!("Background task failed: {}", e)))
 }
 
-#[tauri::command]
+#[tauri::command(async)]
 fn set_ignore_cursor_events(
     window: tauri::WebviewWindow,
     ignore: bool,
-) -> Result<(), String> {
-    window
-        .set_ignore_cursor_events(ignore)
-        .map_err(|e| e.to_string())
+) -> error::PetResult<()> {
+    // Ghost mode never takes a click, whatever the cursor poller thinks
+    let ignore = ignore || ghost::is_active(window.app_handle());
+    Ok(window.set_ignore_cursor_events(ignore)?)
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone, Copy, PartialEq)]
 struct MousePosition {
     x: f64,
     y: f64,
 }
 
-#[tauri::command]
-fn get_mouse_position() -> Result<MousePosition, String> {
+#[tauri::command(async)]
+fn get_mouse_position() -> error::PetResult<MousePosition> {
     use core_graphics::event::CGEvent;
     use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
     let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
-        .map_err(|_| "Failed to create event source".to_string())?;
+        .map_err(|_| error::PetError::Platform("Failed to create event source".to_string()))?;
     let event = CGEvent::new(source)
-        .map_err(|_| "Failed to create event".to_string())?;
+        .map_err(|_| error::PetError::Platform("Failed to create event".to_string()))?;
     let point = event.location();
 
     Ok(MousePosition {
@@ -1,8 +1,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
-    // Load .env from project root (parent of src-tauri)
-    let _ = dotenvy::from_filename("../.env");
-    let _ = dotenvy::dotenv();
+    // Keys come from config.toml, the environment, flags and, in
+    // development, the project's .env
+    let args: Vec<String> = std::env::args().collect();
+    desktop_pet_lib::config::init(&args);
     desktop_pet_lib::run()
 }
@@ -0,0 +1,48 @@
+use chrono::{Duration, Local};
+use tauri::Manager;
+
+use crate::memory::MemoryStore;
+use crate::moods::MoodStore;
+use crate::scheduler;
+use crate::settings::SettingsStore;
+use crate::usage::UsageStore;
+use crate::wrapped::WrappedStore;
+
+fn cutoff_date(days: u32) -> String {
+    (Local::now() - Duration::days(days as i64))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Enforce the retention settings. Each store only writes when something
+/// actually expired, so running this every tick is cheap.
+fn enforce_retention(app: &tauri::AppHandle) {
+    let retention = app.state::<SettingsStore>().get().retention;
+    if let Some(days) = retention.chat_history_days {
+        let cutoff = Local::now() - Duration::days(days as i64);
+        app.state::<MemoryStore>()
+            .prune_before(cutoff.timestamp_millis());
+        app.state::<WrappedStore>().prune_before(&cutoff_date(days));
+    }
+    if let Some(weeks) = retention.usage_weeks {
+        app.state::<UsageStore>()
+            .prune_before(&cutoff_date(weeks * 7));
+    }
+    if let Some(days) = retention.journal_days {
+        app.state::<MoodStore>().prune_before(&cutoff_date(days));
+    }
+    // Facts past their expiry go whether or not a limit is set
+    let stale_before = retention
+        .fact_days
+        .map(|days| (Local::now() - Duration::days(days as i64)).timestamp_millis());
+    app.state::<MemoryStore>()
+        .decay_facts(Local::now().timestamp_millis(), stale_before);
+}
+
+async fn run(app: tauri::AppHandle) {
+    let _ = crate::run_blocking(move || enforce_retention(&app)).await;
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, run);
+}
@@ -0,0 +1,287 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::scheduler;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Quiet checks in a row before a meeting counts as over, so muting or a
+/// camera blinking off for a moment doesn't end it.
+const END_AFTER_CHECKS: u32 = 3;
+/// Announcements saved up during a meeting, oldest dropped first.
+const MAX_HELD: usize = 10;
+
+/// Apps that make calls, by bundle id. Running one isn't a meeting by
+/// itself; it has to be using the microphone too.
+const MEETING_APPS: &[(&str, &str)] = &[
+    ("us.zoom.xos", "Zoom"),
+    ("com.microsoft.teams2", "Teams"),
+    ("com.microsoft.teams", "Teams"),
+    ("com.cisco.webexmeetingsapp", "Webex"),
+    ("Cisco-Systems.Spark", "Webex"),
+    ("com.apple.FaceTime", "FaceTime"),
+    ("com.tinyspeck.slackmacgap", "Slack"),
+    ("com.hnc.Discord", "Discord"),
+];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingStatus {
+    pub in_meeting: bool,
+    /// The calling app, when one is running; a browser call has none
+    pub app: Option<String>,
+    /// Unix millis
+    pub since: Option<i64>,
+}
+
+#[derive(Default)]
+pub struct MeetingState {
+    in_meeting: AtomicBool,
+    quiet_checks: AtomicU32,
+    status: Mutex<Option<MeetingStatus>>,
+    held: Mutex<Vec<String>>,
+}
+
+pub fn is_in_meeting(app: &tauri::AppHandle) -> bool {
+    app.try_state::<MeetingState>()
+        .is_some_and(|state| state.in_meeting.load(Ordering::SeqCst))
+}
+
+/// Keep an announcement for after the meeting instead of speaking up in
+/// it. False when there's no meeting and it should go out now.
+pub fn hold(app: &tauri::AppHandle, text: &str) -> bool {
+    if !is_in_meeting(app) {
+        return false;
+    }
+    let state = app.state::<MeetingState>();
+    let mut held = state.held.lock().unwrap();
+    held.push(text.to_string());
+    let excess = held.len().saturating_sub(MAX_HELD);
+    held.drain(..excess);
+    true
+}
+
+fn running_bundle_ids() -> Vec<String> {
+    #[cfg(target_os = "macos")]
+    return macos::running_bundle_ids();
+    #[cfg(not(target_os = "macos"))]
+    Vec::new()
+}
+
+fn running_meeting_app() -> Option<&'static str> {
+    running_bundle_ids().into_iter().find_map(|id| {
+        MEETING_APPS
+            .iter()
+            .find(|(bundle, _)| *bundle == id)
+            .map(|(_, name)| *name)
+    })
+}
+
+/// A camera in use is a call whatever's using it. A microphone only counts
+/// alongside a calling app, so dictation and voice memos don't.
+fn detect() -> Option<MeetingStatus> {
+    #[cfg(target_os = "macos")]
+    let (camera, microphone) = (macos::camera_in_use(), macos::microphone_in_use());
+    #[cfg(not(target_os = "macos"))]
+    let (camera, microphone) = (false, false);
+    if !camera && !microphone {
+        return None;
+    }
+    let app = running_meeting_app();
+    (camera || app.is_some()).then(|| MeetingStatus {
+        in_meeting: true,
+        app: app.map(str::to_string),
+        since: Some(chrono::Utc::now().timestamp_millis()),
+    })
+}
+
+async fn check(app: tauri::AppHandle) {
+    let Ok(detected) = crate::run_blocking(detect).await else {
+        return;
+    };
+    let state = app.state::<MeetingState>();
+    let was_in_meeting = state.in_meeting.load(Ordering::SeqCst);
+    match detected {
+        Some(status) => {
+            state.quiet_checks.store(0, Ordering::SeqCst);
+            if !was_in_meeting {
+                state.in_meeting.store(true, Ordering::SeqCst);
+                *state.status.lock().unwrap() = Some(status.clone());
+                let _ = app.emit("meeting-started", &status);
+            }
+        }
+        None if was_in_meeting => {
+            if state.quiet_checks.fetch_add(1, Ordering::SeqCst) + 1 < END_AFTER_CHECKS {
+                return;
+            }
+            state.in_meeting.store(false, Ordering::SeqCst);
+            state.quiet_checks.store(0, Ordering::SeqCst);
+            *state.status.lock().unwrap() = None;
+            let _ = app.emit("meeting-ended", ());
+            // Whatever came up during the call, now that it's over
+            let held = std::mem::take(&mut *state.held.lock().unwrap());
+            for text in held {
+                crate::notifications::announce(&app, &text);
+            }
+        }
+        None => {}
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, CHECK_INTERVAL, check);
+}
+
+#[tauri::command(async)]
+pub fn get_meeting_status(state: tauri::State<'_, MeetingState>) -> MeetingStatus {
+    state
+        .status
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or(MeetingStatus {
+            in_meeting: false,
+            app: None,
+            since: None,
+        })
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2_app_kit::NSWorkspace;
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct PropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    impl PropertyAddress {
+        const fn global(selector: &[u8; 4]) -> Self {
+            Self {
+                selector: u32::from_be_bytes(*selector),
+                scope: u32::from_be_bytes(*b"glob"),
+                element: 0,
+            }
+        }
+    }
+
+    /// The system object, in both CoreAudio and CoreMediaIO.
+    const SYSTEM_OBJECT: u32 = 1;
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+    }
+
+    #[link(name = "CoreMediaIO", kind = "framework")]
+    extern "C" {
+        fn CMIOObjectGetPropertyDataSize(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            size: *mut u32,
+        ) -> i32;
+        fn CMIOObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            size: u32,
+            used: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+    }
+
+    fn audio_u32(object: u32, selector: &[u8; 4]) -> Option<u32> {
+        let address = PropertyAddress::global(selector);
+        let mut value: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                object,
+                &address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                &mut value as *mut u32 as *mut c_void,
+            )
+        };
+        (status == 0).then_some(value)
+    }
+
+    /// Whether any process is recording from the default input device.
+    pub fn microphone_in_use() -> bool {
+        audio_u32(SYSTEM_OBJECT, b"dIn ")
+            .filter(|device| *device != 0)
+            .and_then(|device| audio_u32(device, b"gone"))
+            .is_some_and(|running| running != 0)
+    }
+
+    /// Whether any process is capturing from any camera.
+    pub fn camera_in_use() -> bool {
+        let address = PropertyAddress::global(b"dev#");
+        let mut size: u32 = 0;
+        let status = unsafe {
+            CMIOObjectGetPropertyDataSize(SYSTEM_OBJECT, &address, 0, std::ptr::null(), &mut size)
+        };
+        if status != 0 || size == 0 {
+            return false;
+        }
+        let mut devices = vec![0u32; size as usize / std::mem::size_of::<u32>()];
+        let mut used: u32 = 0;
+        let status = unsafe {
+            CMIOObjectGetPropertyData(
+                SYSTEM_OBJECT,
+                &address,
+                0,
+                std::ptr::null(),
+                size,
+                &mut used,
+                devices.as_mut_ptr() as *mut c_void,
+            )
+        };
+        if status != 0 {
+            return false;
+        }
+        devices.truncate(used as usize / std::mem::size_of::<u32>());
+        let running = PropertyAddress::global(b"gone");
+        devices.into_iter().any(|device| {
+            let mut value: u32 = 0;
+            let mut used: u32 = 0;
+            let status = unsafe {
+                CMIOObjectGetPropertyData(
+                    device,
+                    &running,
+                    0,
+                    std::ptr::null(),
+                    std::mem::size_of::<u32>() as u32,
+                    &mut used,
+                    &mut value as *mut u32 as *mut c_void,
+                )
+            };
+            status == 0 && value != 0
+        })
+    }
+
+    pub fn running_bundle_ids() -> Vec<String> {
+        NSWorkspace::sharedWorkspace()
+            .runningApplications()
+            .iter()
+            .filter_map(|app| app.bundleIdentifier())
+            .map(|id| id.to_string())
+            .collect()
+    }
+}
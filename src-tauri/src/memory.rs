@@ -1,89 +1,713 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 use tauri::Manager;
 
+use crate::crypto::{EncryptedBlob, MemoryKey};
+use crate::error::{PetError, PetResult};
+use crate::persist;
+
 const MAX_MESSAGE_PAIRS: usize = 20;
 const MAX_FACTS: usize = 50;
-const MEMORY_FILE: &str = "chat_memory.json";
+/// Facts that expired, went stale or were pushed out, kept for search and
+/// to bring back.
+const MAX_ARCHIVED_FACTS: usize = 100;
+pub const MEMORY_FILE: &str = "chat_memory.json";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(15);
+const MIN_PASSPHRASE_LEN: usize = 8;
+/// Earlier conversations kept besides the current one.
+const MAX_SESSIONS: usize = 20;
+/// Characters of the first thing said that name a conversation.
+const SESSION_TITLE_LEN: usize = 60;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MemoryMessage {
     pub role: String,
     pub content: String,
+    /// Unix millis; missing on messages saved before retention existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<i64>,
+}
+
+/// Something the cat remembers about its owner.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Fact {
+    pub text: String,
+    /// Unix millis; missing on facts saved before expiry existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_at: Option<i64>,
+    /// Unix millis of the last time the cat was told it again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_at: Option<i64>,
+    /// Unix millis after which it's archived, for things like "dentist on
+    /// Thursday"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+impl Fact {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    fn last_seen(&self) -> Option<i64> {
+        self.last_seen_at.or(self.added_at)
+    }
+
+    /// The text as the cat is given it, with the expiry if there is one.
+    pub fn describe(&self) -> String {
+        let until = self
+            .expires_at
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|at| format!(" (until {})", at.with_timezone(&chrono::Local).format("%Y-%m-%d")));
+        format!("{}{}", self.text, until.unwrap_or_default())
+    }
+}
+
+/// Facts used to be bare strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredFact {
+    Text(String),
+    Fact(Fact),
+}
+
+fn deserialize_facts<'de, D>(deserializer: D) -> Result<Vec<Fact>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let stored = Vec::<StoredFact>::deserialize(deserializer)?;
+    Ok(stored
+        .into_iter()
+        .map(|fact| match fact {
+            StoredFact::Text(text) => Fact {
+                text,
+                added_at: None,
+                last_seen_at: None,
+                expires_at: None,
+            },
+            StoredFact::Fact(fact) => fact,
+        })
+        .collect())
 }
 
-#[derive(Serialize, Deserialize, Default)]
+/// A conversation that isn't the current one, kept to go back to.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChatSession {
+    pub id: String,
+    pub started_at: Option<i64>,
+    /// Oldest first, including any that were waiting for compaction
+    pub messages: Vec<MemoryMessage>,
+    #[serde(default)]
+    pub summary: String,
+}
+
+/// The current conversation's messages and summary, which the cat is given
+/// as context, plus facts shared by every conversation and the earlier ones.
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct ChatMemory {
     pub messages: Vec<MemoryMessage>,
-    pub facts: Vec<String>,
+    #[serde(deserialize_with = "deserialize_facts")]
+    pub facts: Vec<Fact>,
+    /// Facts no longer given to the cat, oldest first
+    #[serde(default)]
+    pub archived_facts: Vec<Fact>,
+    /// Rolling summary of conversations that have aged out of `messages`
+    #[serde(default)]
+    pub summary: String,
+    /// Evicted messages waiting to be folded into `summary`
+    #[serde(default)]
+    pub pending_compaction: Vec<MemoryMessage>,
+    /// The current conversation's id, given whenever memory is loaded or a
+    /// conversation starts; empty in files saved before conversations existed
+    #[serde(default)]
+    pub session_id: String,
+    #[serde(default)]
+    pub session_started_at: Option<i64>,
+    /// Earlier conversations, oldest first
+    #[serde(default)]
+    pub sessions: Vec<ChatSession>,
 }
 
-fn memory_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
-    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
-    Ok(dir.join(MEMORY_FILE))
+impl ChatMemory {
+    fn archive_fact(&mut self, fact: Fact) {
+        self.archived_facts.retain(|f| f.text != fact.text);
+        self.archived_facts.push(fact);
+        let excess = self.archived_facts.len().saturating_sub(MAX_ARCHIVED_FACTS);
+        self.archived_facts.drain(..excess);
+    }
+
+    /// Archive the facts `f` picks; returns how many.
+    fn archive_facts_where(&mut self, f: impl Fn(&Fact) -> bool) -> usize {
+        let (archived, kept): (Vec<Fact>, Vec<Fact>) =
+            std::mem::take(&mut self.facts).into_iter().partition(f);
+        self.facts = kept;
+        let count = archived.len();
+        for fact in archived {
+            self.archive_fact(fact);
+        }
+        count
+    }
+
+    /// Give the current conversation an id if it has none. Only called with
+    /// the store's lock held (or before the store exists), so everyone sees
+    /// the same one.
+    fn ensure_session_id(&mut self) {
+        if self.session_id.is_empty() {
+            self.session_id = uuid::Uuid::new_v4().to_string();
+        }
+    }
+
+    /// This memory with an id for its current conversation.
+    fn with_session_id(mut self) -> Self {
+        self.ensure_session_id();
+        self
+    }
+
+    /// Put the current conversation away, if anything was said in it, and
+    /// leave an empty one with a new id in its place.
+    fn archive_current(&mut self) {
+        self.ensure_session_id();
+        let mut messages = std::mem::take(&mut self.pending_compaction);
+        messages.append(&mut self.messages);
+        let summary = std::mem::take(&mut self.summary);
+        if !messages.is_empty() || !summary.is_empty() {
+            self.sessions.push(ChatSession {
+                id: self.session_id.clone(),
+                started_at: self.session_started_at.or(messages.first().and_then(|m| m.at)),
+                messages,
+                summary,
+            });
+            let excess = self.sessions.len().saturating_sub(MAX_SESSIONS);
+            self.sessions.drain(..excess);
+        }
+        self.session_id = uuid::Uuid::new_v4().to_string();
+        self.session_started_at = None;
+    }
+
+    /// Take in memory the cat brought from another machine. Its current
+    /// conversation becomes this one (ours is put away with the earlier ones
+    /// unless it's the same conversation), and facts and earlier
+    /// conversations from both sides are kept.
+    fn absorb(&mut self, other: ChatMemory) {
+        if other.session_id.is_empty() || other.session_id != self.session_id {
+            self.archive_current();
+        }
+        for session in other.sessions {
+            match self.sessions.iter_mut().find(|s| s.id == session.id) {
+                Some(ours) if ours.messages.len() < session.messages.len() => *ours = session,
+                Some(_) => {}
+                None => self.sessions.push(session),
+            }
+        }
+        self.sessions.retain(|s| s.id != other.session_id);
+        self.sessions.sort_by_key(|s| s.started_at.unwrap_or_default());
+        let excess = self.sessions.len().saturating_sub(MAX_SESSIONS);
+        self.sessions.drain(..excess);
+        self.messages = other.messages;
+        self.pending_compaction = other.pending_compaction;
+        self.summary = other.summary;
+        self.session_id = other.session_id;
+        self.session_started_at = other.session_started_at;
+        self.ensure_session_id();
+
+        for fact in other.facts {
+            match self.facts.iter_mut().find(|f| f.text == fact.text) {
+                Some(ours) if ours.last_seen() < fact.last_seen() => *ours = fact,
+                Some(_) => {}
+                None => {
+                    self.archived_facts.retain(|f| f.text != fact.text);
+                    self.facts.push(fact);
+                }
+            }
+        }
+        while self.facts.len() > MAX_FACTS {
+            let fact = self.facts.remove(0);
+            self.archive_fact(fact);
+        }
+        for fact in other.archived_facts {
+            if !self.facts.iter().any(|f| f.text == fact.text) {
+                self.archive_fact(fact);
+            }
+        }
+    }
+
+    /// Make `session` the current conversation. Only the most recent
+    /// messages go back into context; the rest wait to be summarized again.
+    fn resume(&mut self, session: ChatSession) {
+        let mut messages = session.messages;
+        let excess = messages.len().saturating_sub(MAX_MESSAGE_PAIRS * 2);
+        self.pending_compaction = messages.drain(..excess).collect();
+        self.messages = messages;
+        self.summary = session.summary;
+        self.session_id = session.id;
+        self.session_started_at = session.started_at;
+    }
 }
 
-pub fn load_memory(app: &tauri::AppHandle) -> ChatMemory {
-    let path = match memory_path(app) {
-        Ok(p) => p,
-        Err(_) => return ChatMemory::default(),
-    };
-    match fs::read_to_string(&path) {
-        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
-        Err(_) => ChatMemory::default(),
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub id: String,
+    /// The start of the first thing said, or "New conversation"
+    pub title: String,
+    pub started_at: Option<i64>,
+    pub last_at: Option<i64>,
+    /// Exchanges, as in `get_memory_stats`
+    pub message_count: usize,
+    pub active: bool,
+}
+
+impl SessionInfo {
+    fn new(id: String, started_at: Option<i64>, messages: &[&MemoryMessage], active: bool) -> Self {
+        let title = messages
+            .iter()
+            .find(|m| m.role == "user")
+            .map(|m| {
+                let text = m.content.trim();
+                match text.char_indices().nth(SESSION_TITLE_LEN) {
+                    Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+                    None => text.to_string(),
+                }
+            })
+            .unwrap_or_else(|| "New conversation".to_string());
+        Self {
+            id,
+            title,
+            started_at: started_at.or(messages.first().and_then(|m| m.at)),
+            last_at: messages.iter().rev().find_map(|m| m.at),
+            message_count: messages.len() / 2,
+            active,
+        }
     }
+
+    fn current(memory: &ChatMemory) -> Self {
+        let id = memory.session_id.clone();
+        let messages: Vec<&MemoryMessage> =
+            memory.pending_compaction.iter().chain(&memory.messages).collect();
+        Self::new(id, memory.session_started_at, &messages, true)
+    }
+}
+
+/// The loaded pet's memory file.
+fn memory_path(app: &tauri::AppHandle) -> PetResult<PathBuf> {
+    crate::pets::data_file(app, MEMORY_FILE)
+}
+
+/// What's actually in chat_memory.json: plaintext for users who never opted
+/// in (and for files written before encryption existed), ciphertext otherwise.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredMemory {
+    Encrypted(EncryptedBlob),
+    Plain(ChatMemory),
 }
 
-pub fn save_memory(app: &tauri::AppHandle, memory: &ChatMemory) {
-    let path = match memory_path(app) {
-        Ok(p) => p,
-        Err(_) => return,
+fn read_stored(app: &tauri::AppHandle) -> Option<StoredMemory> {
+    persist::load(&memory_path(app).ok()?)
+}
+
+/// Returns the memory, the key it's encrypted with (if any), and whether the
+/// file is encrypted but no key was available to open it.
+fn load_memory(app: &tauri::AppHandle) -> (ChatMemory, Option<MemoryKey>, bool) {
+    let key = MemoryKey::from_keychain();
+    let (memory, key, locked) = match read_stored(app) {
+        Some(StoredMemory::Encrypted(blob)) => {
+            let decrypted = key.as_ref().and_then(|k| k.decrypt(&blob).ok());
+            match decrypted.and_then(|bytes| serde_json::from_slice(&bytes).ok()) {
+                Some(memory) => (memory, key, false),
+                None => (ChatMemory::default(), None, true),
+            }
+        }
+        Some(StoredMemory::Plain(memory)) => (memory, key, false),
+        None => (ChatMemory::default(), key, false),
     };
-    if let Ok(json) = serde_json::to_string_pretty(memory) {
-        let _ = fs::write(path, json);
+    (memory.with_session_id(), key, locked)
+}
+
+fn save_memory(app: &tauri::AppHandle, memory: &ChatMemory, key: Option<&MemoryKey>) -> PetResult<()> {
+    let path = memory_path(app)?;
+    match key {
+        Some(key) => {
+            let blob = key.encrypt(&serde_json::to_vec(memory)?)?;
+            persist::save(&path, &StoredMemory::Encrypted(blob))
+        }
+        None => persist::save(&path, &StoredMemory::Plain(memory.clone())),
     }
 }
 
+/// In-process owner of chat memory. Every read and mutation goes through the
+/// mutex so concurrent dialogue requests can't clobber each other's exchanges;
+/// the file is only read once at startup and written back by `flush`.
+pub struct MemoryStore {
+    memory: Mutex<ChatMemory>,
+    dirty: AtomicBool,
+    compacting: AtomicBool,
+    key: Mutex<Option<MemoryKey>>,
+    /// Encrypted file with no key available; never write until unlocked or
+    /// we'd overwrite the user's real memory with an empty one.
+    locked: AtomicBool,
+}
+
+impl MemoryStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let (memory, key, locked) = load_memory(app);
+        Self {
+            memory: Mutex::new(memory),
+            dirty: AtomicBool::new(false),
+            compacting: AtomicBool::new(false),
+            key: Mutex::new(key),
+            locked: AtomicBool::new(locked),
+        }
+    }
+
+    pub fn snapshot(&self) -> ChatMemory {
+        self.memory.lock().unwrap().clone()
+    }
+
+    pub fn update<R>(&self, f: impl FnOnce(&mut ChatMemory) -> R) -> R {
+        let mut memory = self.memory.lock().unwrap();
+        let result = f(&mut memory);
+        self.dirty.store(true, Ordering::SeqCst);
+        result
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    /// Swap in a whole new memory (e.g. from an import); flushed on the next tick.
+    pub fn replace(&self, memory: ChatMemory) -> PetResult<()> {
+        if self.is_locked() {
+            return Err(PetError::InvalidInput("Unlock memory first".to_string()));
+        }
+        self.update(|current| *current = memory.with_session_id());
+        Ok(())
+    }
+
+    /// Merge in memory that arrived from another machine, once no summary
+    /// is being written for the current conversation.
+    pub fn absorb(&self, memory: ChatMemory) -> PetResult<()> {
+        self.switch_session(|current| {
+            current.absorb(memory);
+            Ok(())
+        })
+    }
+
+    /// Claim the compaction slot and return the current summary plus the
+    /// messages waiting to be summarized. Returns None if another compaction
+    /// is already running or there's nothing to do.
+    pub fn begin_compaction(&self) -> Option<(String, Vec<MemoryMessage>)> {
+        let memory = self.memory.lock().unwrap();
+        if memory.pending_compaction.is_empty() || self.compacting.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        Some((memory.summary.clone(), memory.pending_compaction.clone()))
+    }
+
+    /// Release the compaction slot. On success, replace the summary and drop
+    /// the messages it covered; on failure they stay queued for next time.
+    pub fn finish_compaction(&self, summarized: usize, summary: Option<String>) {
+        if let Some(summary) = summary {
+            self.update(|memory| {
+                // Memory was cleared while we were summarizing; discard the result
+                if memory.pending_compaction.len() < summarized {
+                    return;
+                }
+                memory.pending_compaction.drain(..summarized);
+                memory.summary = summary;
+            });
+        }
+        self.compacting.store(false, Ordering::SeqCst);
+    }
+
+    /// Switch conversations with `f`, once no summary is being written for
+    /// the current one, since it would land in whichever is current when
+    /// it finishes.
+    fn switch_session<R>(&self, f: impl FnOnce(&mut ChatMemory) -> PetResult<R>) -> PetResult<R> {
+        if self.is_locked() {
+            return Err(PetError::InvalidInput("Unlock memory first".to_string()));
+        }
+        let mut memory = self.memory.lock().unwrap();
+        if self.compacting.load(Ordering::SeqCst) {
+            return Err(PetError::InvalidInput(
+                "Still summarizing this conversation; try again in a moment".to_string(),
+            ));
+        }
+        let result = f(&mut memory)?;
+        self.dirty.store(true, Ordering::SeqCst);
+        Ok(result)
+    }
+
+    /// Drop messages older than `cutoff` (unix millis), including ones still
+    /// waiting for compaction. Untimestamped messages age out by count instead.
+    pub fn prune_before(&self, cutoff: i64) {
+        let expired = |m: &MemoryMessage| m.at.is_some_and(|at| at < cutoff);
+        let mut memory = self.memory.lock().unwrap();
+        let archived = memory.sessions.iter().flat_map(|s| &s.messages);
+        if !memory.messages.iter().chain(&memory.pending_compaction).chain(archived).any(expired) {
+            return;
+        }
+        memory.messages.retain(|m| !expired(m));
+        for session in &mut memory.sessions {
+            session.messages.retain(|m| !expired(m));
+        }
+        memory.sessions.retain(|s| !s.messages.is_empty());
+        // A compaction in flight counts on the queue's length; let it finish
+        // first rather than shifting the messages out from under it
+        if !self.compacting.load(Ordering::SeqCst) {
+            memory.pending_compaction.retain(|m| !expired(m));
+        }
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+
+    /// Archive facts that expired, and with `stale_before` (unix millis)
+    /// ones not mentioned since. Facts from before expiry existed have no
+    /// dates and only leave by review. Returns how many were archived.
+    pub fn decay_facts(&self, now: i64, stale_before: Option<i64>) -> usize {
+        if self.is_locked() {
+            return 0;
+        }
+        let mut memory = self.memory.lock().unwrap();
+        let stale = |f: &Fact| {
+            f.expires_at.is_none()
+                && stale_before.is_some_and(|cutoff| f.last_seen().is_some_and(|at| at < cutoff))
+        };
+        let archived = memory.archive_facts_where(|f| f.is_expired(now) || stale(f));
+        if archived > 0 {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+        archived
+    }
+
+    /// Forget everything, encrypted or not, and delete the file and the
+    /// Keychain key. Holds the lock so a flush can't write it back.
+    pub fn wipe(&self, app: &tauri::AppHandle) -> PetResult<()> {
+        let mut memory = self.memory.lock().unwrap();
+        *memory = ChatMemory::default().with_session_id();
+        self.dirty.store(false, Ordering::SeqCst);
+        *self.key.lock().unwrap() = None;
+        self.locked.store(false, Ordering::SeqCst);
+        persist::remove(&memory_path(app)?)?;
+        MemoryKey::remove_from_keychain()
+    }
+
+    /// Write to disk if anything changed since the last flush. Holds the
+    /// lock while writing, so a pet switch can't land in between and send
+    /// this pet's memory to the next one's file.
+    pub fn flush(&self, app: &tauri::AppHandle) {
+        let memory = self.memory.lock().unwrap();
+        self.flush_locked(app, &memory);
+    }
+
+    fn flush_locked(&self, app: &tauri::AppHandle, memory: &ChatMemory) {
+        if self.locked.load(Ordering::SeqCst) {
+            return;
+        }
+        if self.dirty.swap(false, Ordering::SeqCst) {
+            let key = self.key.lock().unwrap().clone();
+            let _ = save_memory(app, memory, key.as_ref());
+        }
+    }
+
+    /// Save this pet's memory, let `switch` change which pet is loaded, and
+    /// load that one's. The Keychain key is shared, so every pet's memory is
+    /// encrypted alike.
+    pub fn switch(
+        &self,
+        app: &tauri::AppHandle,
+        switch: impl FnOnce() -> PetResult<()>,
+    ) -> PetResult<()> {
+        let mut memory = self.memory.lock().unwrap();
+        // A summary in flight would land in the next pet's memory
+        if self.compacting.load(Ordering::SeqCst) {
+            return Err(PetError::InvalidInput(
+                "Still summarizing the last conversation; try again in a moment".to_string(),
+            ));
+        }
+        self.flush_locked(app, &memory);
+        switch()?;
+        let (loaded, key, locked) = load_memory(app);
+        *memory = loaded;
+        *self.key.lock().unwrap() = key;
+        self.dirty.store(false, Ordering::SeqCst);
+        self.locked.store(locked, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Periodically persist dirty memory in the background.
+pub fn spawn_flush_task(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let app = app.clone();
+            let _ = crate::run_blocking(move || app.state::<MemoryStore>().flush(&app)).await;
+        }
+    });
+}
+
 pub fn add_exchange(memory: &mut ChatMemory, user_msg: &str, assistant_msg: &str) {
+    let at = Some(chrono::Utc::now().timestamp_millis());
     memory.messages.push(MemoryMessage {
         role: "user".to_string(),
         content: user_msg.to_string(),
+        at,
     });
     memory.messages.push(MemoryMessage {
         role: "assistant".to_string(),
         content: assistant_msg.to_string(),
+        at,
     });
-    // Trim to max pairs (each pair = 2 messages)
+    // Trim to max pairs (each pair = 2 messages), queueing the oldest for
+    // compaction into the long-term summary instead of dropping them
     let max_messages = MAX_MESSAGE_PAIRS * 2;
     if memory.messages.len() > max_messages {
         let excess = memory.messages.len() - max_messages;
-        memory.messages.drain(..excess);
+        let evicted: Vec<MemoryMessage> = memory.messages.drain(..excess).collect();
+        memory.pending_compaction.extend(evicted);
     }
 }
 
-pub fn add_fact(memory: &mut ChatMemory, fact: &str) {
-    // Don't add duplicate facts
-    if memory.facts.iter().any(|f| f == fact) {
+/// Split `Dentist on Thursday | until: 2025-03-14` into the text and an
+/// expiry: the end of that day, or the minute given. A date that doesn't
+/// parse stays part of the text rather than being lost.
+fn parse_fact(tag: &str) -> (String, Option<i64>) {
+    let Some((text, until)) = tag.rsplit_once('|') else {
+        return (tag.trim().to_string(), None);
+    };
+    let Some(until) = until.trim().strip_prefix("until:") else {
+        return (tag.trim().to_string(), None);
+    };
+    let until = until.trim();
+    let parsed = chrono::NaiveDateTime::parse_from_str(until, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(until, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(23, 59, 59))
+        })
+        .and_then(|t| t.and_local_timezone(chrono::Local).earliest());
+    match parsed {
+        Some(t) => (text.trim().to_string(), Some(t.timestamp_millis())),
+        None => (tag.trim().to_string(), None),
+    }
+}
+
+/// Refuse memory from outside (an archive) that's bigger than the cat ever
+/// keeps, rather than carrying it around from then on.
+pub fn check_limits(memory: &ChatMemory) -> PetResult<()> {
+    let too_many = memory.messages.len() > MAX_MESSAGE_PAIRS * 2
+        || memory.facts.len() > MAX_FACTS
+        || memory.archived_facts.len() > MAX_ARCHIVED_FACTS
+        || memory.sessions.len() > MAX_SESSIONS;
+    if too_many {
+        return Err(PetError::InvalidInput(
+            "The chat memory in that archive is bigger than a cat keeps".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Remember what a `[REMEMBER: ...]` tag says. Being told something again
+/// keeps it fresh, and brings it back if it had been archived. When there's
+/// no room, what makes way is the fact due to expire soonest, or else the
+/// one not mentioned for longest, rather than simply the oldest.
+pub fn add_fact(memory: &mut ChatMemory, tag: &str) {
+    let (text, expires_at) = parse_fact(tag);
+    let now = chrono::Utc::now().timestamp_millis();
+    if let Some(fact) = memory.facts.iter_mut().find(|f| f.text == text) {
+        fact.last_seen_at = Some(now);
+        fact.expires_at = expires_at.or(fact.expires_at);
         return;
     }
-    memory.facts.push(fact.to_string());
+    let added_at = memory
+        .archived_facts
+        .iter()
+        .position(|f| f.text == text)
+        .and_then(|i| memory.archived_facts.remove(i).added_at)
+        .or(Some(now));
+    memory.facts.push(Fact {
+        text,
+        added_at,
+        last_seen_at: Some(now),
+        expires_at,
+    });
     if memory.facts.len() > MAX_FACTS {
-        memory.facts.remove(0);
+        let evict = memory
+            .facts
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, f)| (f.expires_at.is_none(), f.expires_at, f.last_seen()))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let fact = memory.facts.remove(evict);
+        memory.archive_fact(fact);
     }
 }
 
 #[tauri::command]
-pub fn clear_chat_memory(app: tauri::AppHandle) -> Result<(), String> {
-    let path = memory_path(&app)?;
-    if path.exists() {
-        fs::remove_file(&path).map_err(|e| format!("Failed to delete memory: {}", e))?;
-    }
-    Ok(())
+pub async fn clear_chat_memory(app: tauri::AppHandle) -> PetResult<()> {
+    crate::run_blocking(move || {
+        let store = app.state::<MemoryStore>();
+        // Hold the lock across the delete so a concurrent flush can't resurrect the file
+        let mut memory = store.memory.lock().unwrap();
+        *memory = ChatMemory::default().with_session_id();
+        store.dirty.store(false, Ordering::SeqCst);
+        let path = memory_path(&app)?;
+        persist::remove(&path)
+    })
+    .await?
+}
+
+/// Put the current conversation away and start an empty one. Facts carry
+/// over; the cat just doesn't see what was said before.
+#[tauri::command(async)]
+pub fn start_new_session(store: tauri::State<'_, MemoryStore>) -> PetResult<SessionInfo> {
+    store.switch_session(|memory| {
+        memory.archive_current();
+        memory.session_started_at = Some(chrono::Utc::now().timestamp_millis());
+        Ok(SessionInfo::current(memory))
+    })
+}
+
+/// Every conversation, the most recently active first.
+#[tauri::command(async)]
+pub fn list_sessions(store: tauri::State<'_, MemoryStore>) -> Vec<SessionInfo> {
+    let memory = store.snapshot();
+    let mut sessions: Vec<SessionInfo> = memory
+        .sessions
+        .iter()
+        .map(|s| {
+            let messages: Vec<&MemoryMessage> = s.messages.iter().collect();
+            SessionInfo::new(s.id.clone(), s.started_at, &messages, false)
+        })
+        .collect();
+    sessions.push(SessionInfo::current(&memory));
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_at.or(s.started_at)));
+    sessions
+}
+
+/// Go back to an earlier conversation; the current one is put away first.
+#[tauri::command(async)]
+pub fn load_session(store: tauri::State<'_, MemoryStore>, id: String) -> PetResult<SessionInfo> {
+    store.switch_session(|memory| {
+        if memory.session_id == id {
+            return Ok(SessionInfo::current(memory));
+        }
+        let index = memory
+            .sessions
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| PetError::InvalidInput(format!("No conversation with id {}", id)))?;
+        let session = memory.sessions.remove(index);
+        memory.archive_current();
+        memory.resume(session);
+        Ok(SessionInfo::current(memory))
+    })
 }
 
 #[derive(Serialize)]
@@ -94,11 +718,254 @@ pub struct MemoryStats {
     pub fact_count: usize,
 }
 
-#[tauri::command]
-pub fn get_memory_stats(app: tauri::AppHandle) -> MemoryStats {
-    let memory = load_memory(&app);
+#[tauri::command(async)]
+pub fn get_memory_stats(store: tauri::State<'_, MemoryStore>) -> MemoryStats {
+    let memory = store.snapshot();
     MemoryStats {
         message_count: memory.messages.len() / 2, // pairs
         fact_count: memory.facts.len(),
     }
 }
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Facts {
+    pub active: Vec<Fact>,
+    pub archived: Vec<Fact>,
+}
+
+#[tauri::command(async)]
+pub fn get_facts(store: tauri::State<'_, MemoryStore>) -> Facts {
+    let memory = store.snapshot();
+    Facts {
+        active: memory.facts,
+        archived: memory.archived_facts,
+    }
+}
+
+/// A fact the model thinks has run its course.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FactProposal {
+    pub text: String,
+    pub reason: String,
+}
+
+const REVIEW_INSTRUCTIONS: &str = "You tidy up what a desktop pet cat remembers about its owner. \
+    Given numbered facts with when each was learned, list the ones that are no longer worth \
+    remembering: events whose date has passed, one-off plans, things later facts contradict, \
+    and duplicates. Keep names, relationships, lasting preferences and recurring things. \
+    Reply with one line per fact to drop, as the number, a colon and a short reason, \
+    or exactly NONE.";
+
+/// Ask the model which facts look stale. Nothing is changed; pass the ones
+/// to drop to `archive_facts`.
+#[tauri::command]
+pub async fn review_facts(app: tauri::AppHandle) -> PetResult<Vec<FactProposal>> {
+    let api_key = crate::config::anthropic_api_key()
+        .ok_or_else(|| PetError::Auth("Reviewing facts needs an Anthropic API key".to_string()))?;
+    if app.state::<crate::usage::UsageStore>().budget_exceeded() {
+        return Err(PetError::InvalidInput("This month's API budget is used up".to_string()));
+    }
+    let store = app.state::<MemoryStore>();
+    if store.is_locked() {
+        return Err(PetError::InvalidInput("Unlock memory first".to_string()));
+    }
+    let facts = store.snapshot().facts;
+    if facts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let today = chrono::Local::now().format("%Y-%m-%d");
+    let learned = |fact: &Fact| {
+        fact.added_at
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "a while ago".to_string())
+    };
+    let numbered: Vec<String> = facts
+        .iter()
+        .enumerate()
+        .map(|(i, f)| format!("{}. {} (learned {})", i + 1, f.describe(), learned(f)))
+        .collect();
+    let answer = crate::dialogue::run_background_prompt(
+        &app,
+        &api_key,
+        REVIEW_INSTRUCTIONS,
+        format!("Today is {}.\n\n{}", today, numbered.join("\n")),
+        400,
+    )
+    .await?;
+    let mut proposals: Vec<FactProposal> = Vec::new();
+    for line in answer.lines() {
+        let Some((number, reason)) = line.split_once(':') else {
+            continue;
+        };
+        let index = number.trim().trim_end_matches('.').parse::<usize>().ok();
+        let Some(fact) = index.and_then(|n| facts.get(n.checked_sub(1)?)) else {
+            continue;
+        };
+        if !proposals.iter().any(|p| p.text == fact.text) {
+            proposals.push(FactProposal {
+                text: fact.text.clone(),
+                reason: reason.trim().to_string(),
+            });
+        }
+    }
+    Ok(proposals)
+}
+
+/// Stop giving the cat these facts. They're kept in the archive, where
+/// `search_memory` still finds them and `restore_fact` brings one back.
+#[tauri::command(async)]
+pub fn archive_facts(store: tauri::State<'_, MemoryStore>, texts: Vec<String>) -> PetResult<usize> {
+    if store.is_locked() {
+        return Err(PetError::InvalidInput("Unlock memory first".to_string()));
+    }
+    Ok(store.update(|memory| memory.archive_facts_where(|f| texts.contains(&f.text))))
+}
+
+#[tauri::command(async)]
+pub fn restore_fact(store: tauri::State<'_, MemoryStore>, text: String) -> PetResult<()> {
+    if store.is_locked() {
+        return Err(PetError::InvalidInput("Unlock memory first".to_string()));
+    }
+    store.update(|memory| {
+        let index = memory
+            .archived_facts
+            .iter()
+            .position(|f| f.text == text)
+            .ok_or_else(|| PetError::InvalidInput("That fact isn't in the archive".to_string()))?;
+        let mut fact = memory.archived_facts.remove(index);
+        // Back on purpose, so not straight out again
+        fact.expires_at = None;
+        fact.last_seen_at = Some(chrono::Utc::now().timestamp_millis());
+        memory.facts.push(fact);
+        if memory.facts.len() > MAX_FACTS {
+            let fact = memory.facts.remove(0);
+            memory.archive_fact(fact);
+        }
+        Ok(())
+    })
+}
+
+#[derive(Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub locked: bool,
+}
+
+#[tauri::command(async)]
+pub fn get_memory_encryption_status(store: tauri::State<'_, MemoryStore>) -> EncryptionStatus {
+    EncryptionStatus {
+        enabled: store.key.lock().unwrap().is_some() || store.locked.load(Ordering::SeqCst),
+        locked: store.locked.load(Ordering::SeqCst),
+    }
+}
+
+/// Encrypt memory at rest with a key derived from `passphrase`. Existing
+/// plaintext memory is rewritten encrypted and its plaintext backup removed.
+#[tauri::command]
+pub async fn enable_memory_encryption(app: tauri::AppHandle, passphrase: String) -> PetResult<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(PetError::InvalidInput(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+    crate::run_blocking(move || {
+        let store = app.state::<MemoryStore>();
+        if store.locked.load(Ordering::SeqCst) {
+            return Err(PetError::InvalidInput("Unlock memory first".to_string()));
+        }
+        let key = MemoryKey::new(&passphrase);
+        key.store_in_keychain()?;
+        *store.key.lock().unwrap() = Some(key.clone());
+
+        save_memory(&app, &store.snapshot(), Some(&key))?;
+        // The save rotated the old plaintext file into the backups
+        persist::remove_backups(&memory_path(&app)?);
+        Ok(())
+    })
+    .await?
+}
+
+/// Turn encryption off after confirming the passphrase; memory is rewritten
+/// in plaintext and the Keychain entry removed.
+#[tauri::command]
+pub async fn disable_memory_encryption(app: tauri::AppHandle, passphrase: String) -> PetResult<()> {
+    crate::run_blocking(move || {
+        let store = app.state::<MemoryStore>();
+        if let Some(StoredMemory::Encrypted(blob)) = read_stored(&app) {
+            MemoryKey::for_blob(&passphrase, &blob)?.decrypt(&blob)?;
+        }
+        if store.locked.load(Ordering::SeqCst) {
+            return Err(PetError::InvalidInput("Unlock memory first".to_string()));
+        }
+        MemoryKey::remove_from_keychain()?;
+        *store.key.lock().unwrap() = None;
+        save_memory(&app, &store.snapshot(), None)
+    })
+    .await?
+}
+
+/// Open an encrypted memory file whose key isn't in this machine's Keychain
+/// (e.g. after moving to a new Mac). Anything said while locked is discarded.
+#[tauri::command]
+pub async fn unlock_memory(app: tauri::AppHandle, passphrase: String) -> PetResult<()> {
+    crate::run_blocking(move || {
+        let store = app.state::<MemoryStore>();
+        let Some(StoredMemory::Encrypted(blob)) = read_stored(&app) else {
+            return Err(PetError::InvalidInput("Memory isn't encrypted".to_string()));
+        };
+        let key = MemoryKey::for_blob(&passphrase, &blob)?;
+        let memory: ChatMemory = serde_json::from_slice(&key.decrypt(&blob)?)?;
+        key.store_in_keychain()?;
+
+        *store.memory.lock().unwrap() = memory.with_session_id();
+        *store.key.lock().unwrap() = Some(key);
+        store.dirty.store(false, Ordering::SeqCst);
+        store.locked.store(false, Ordering::SeqCst);
+        Ok(())
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn list_memory_backups(app: tauri::AppHandle) -> PetResult<Vec<persist::BackupInfo>> {
+    crate::run_blocking(move || Ok(persist::list_backups(&memory_path(&app)?))).await?
+}
+
+/// Replace current memory with a rotated backup (1 = newest). The current
+/// memory is itself rotated into the backups, so a restore can be undone.
+#[tauri::command]
+pub async fn restore_memory_backup(app: tauri::AppHandle, generation: usize) -> PetResult<()> {
+    crate::run_blocking(move || {
+        let store = app.state::<MemoryStore>();
+        let path = memory_path(&app)?;
+        let key = store.key.lock().unwrap().clone();
+
+        let memory = match persist::load_backup::<StoredMemory>(&path, generation) {
+            Some(StoredMemory::Plain(memory)) => memory,
+            Some(StoredMemory::Encrypted(blob)) => {
+                let key = key.as_ref().ok_or_else(|| {
+                    PetError::InvalidInput("Unlock memory to restore an encrypted backup".to_string())
+                })?;
+                serde_json::from_slice(&key.decrypt(&blob)?)?
+            }
+            None => {
+                return Err(PetError::InvalidInput(format!(
+                    "Backup {} is missing or corrupt",
+                    generation
+                )))
+            }
+        };
+
+        let memory = memory.with_session_id();
+        save_memory(&app, &memory, key.as_ref())?;
+        *store.memory.lock().unwrap() = memory;
+        store.dirty.store(false, Ordering::SeqCst);
+        store.locked.store(false, Ordering::SeqCst);
+        Ok(())
+    })
+    .await?
+}
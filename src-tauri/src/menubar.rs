@@ -0,0 +1,91 @@
+use tauri::{Manager, PhysicalPosition, Rect, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::PetResult;
+use crate::settings::SettingsStore;
+use crate::tray;
+
+const POPOVER_LABEL: &str = "popover";
+const POPOVER_WIDTH: f64 = 320.0;
+const POPOVER_HEIGHT: f64 = 360.0;
+
+pub fn is_enabled(app: &tauri::AppHandle) -> bool {
+    app.state::<SettingsStore>().get().display.menu_bar_only
+}
+
+/// Show or hide the overlay to match the menu-bar-only setting. While it is
+/// on, a left click on the tray icon opens the chat popover instead of the menu.
+pub fn apply(app: &tauri::AppHandle) {
+    let enabled = is_enabled(app);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = if enabled { window.hide() } else { window.show() };
+    }
+    if !enabled {
+        if let Some(popover) = app.get_webview_window(POPOVER_LABEL) {
+            let _ = popover.hide();
+        }
+    }
+    if let Some(icon) = app.tray_by_id(tray::TRAY_ID) {
+        let _ = icon.set_show_menu_on_left_click(!enabled);
+    }
+    tray::refresh(app);
+}
+
+fn popover(app: &tauri::AppHandle) -> tauri::Result<tauri::WebviewWindow> {
+    if let Some(window) = app.get_webview_window(POPOVER_LABEL) {
+        return Ok(window);
+    }
+    let window = WebviewWindowBuilder::new(app, POPOVER_LABEL, WebviewUrl::default())
+        .title("Desktop Pet")
+        .inner_size(POPOVER_WIDTH, POPOVER_HEIGHT)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()?;
+    // Behave like a native popover: dismiss when the user clicks elsewhere
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(false) = event {
+            let _ = handle.hide();
+        }
+    });
+    Ok(window)
+}
+
+/// Toggle the chat popover, centered under the tray icon at `anchor`.
+pub fn toggle_popover(app: &tauri::AppHandle, anchor: Rect) {
+    let window = match popover(app) {
+        Ok(window) => window,
+        Err(e) => {
+            eprintln!("Failed to open chat popover: {}", e);
+            return;
+        }
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+        return;
+    }
+
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let position = anchor.position.to_physical::<f64>(scale);
+    let size = anchor.size.to_physical::<f64>(scale);
+    let x = position.x + size.width / 2.0 - POPOVER_WIDTH * scale / 2.0;
+    let y = position.y + size.height;
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+#[tauri::command]
+pub async fn set_menu_bar_only(app: tauri::AppHandle, enabled: bool) -> PetResult<()> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.display.menu_bar_only = enabled;
+        store.replace(settings)?;
+        apply(&app);
+        Ok(())
+    })
+    .await?
+}
@@ -0,0 +1,381 @@
+use chrono::{Local, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::PetResult;
+use crate::{dialogue, notifications, persist, scheduler, settings, tray};
+
+pub const MILESTONES_FILE: &str = "milestones.json";
+/// Being up at these hours counts as an all-nighter.
+const ALL_NIGHTER_HOURS: std::ops::Range<u32> = 3..5;
+/// Recaps wait for a decent hour rather than going off at midnight.
+const RECAP_HOURS: std::ops::Range<u32> = 9..21;
+
+/// Something worth remembering the first time of.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Milestone {
+    Chat,
+    Search,
+    Journal,
+    Perch,
+    /// The owner was still at it in the small hours
+    AllNighter,
+}
+
+impl Milestone {
+    fn describe(self) -> &'static str {
+        match self {
+            Milestone::Chat => "the first time we talked",
+            Milestone::Search => "the first thing I looked up for you",
+            Milestone::Journal => "my first diary entry",
+            Milestone::Perch => "the first time I climbed onto the menu bar",
+            Milestone::AllNighter => "the first all-nighter I watched you pull",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Reached {
+    /// Unix seconds
+    at: i64,
+    /// A few words on what it was, e.g. the first thing said
+    detail: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MilestoneData {
+    /// Unix seconds; 0 until first set
+    installed_at: i64,
+    firsts: BTreeMap<Milestone, Reached>,
+    /// Anniversaries already celebrated, by days together
+    celebrated: Vec<u32>,
+}
+
+/// Anniversaries, in days together, and how to say them.
+fn anniversary_name(days: u32) -> Option<String> {
+    match days {
+        30 => Some("a month".to_string()),
+        100 => Some("100 days".to_string()),
+        365 => Some("a year".to_string()),
+        d if d > 365 && d % 365 == 0 => Some(format!("{} years", d / 365)),
+        _ => None,
+    }
+}
+
+/// The install date and the firsts since, which anniversary recaps are
+/// written from.
+pub struct MilestoneStore {
+    data: Mutex<MilestoneData>,
+    path: Option<PathBuf>,
+}
+
+impl MilestoneStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, MILESTONES_FILE).ok();
+        let mut data: MilestoneData = path.as_deref().and_then(persist::load).unwrap_or_default();
+        if data.installed_at == 0 {
+            // Installs from before milestones were kept date from the
+            // settings file
+            data.installed_at = persist::app_data_file(app, settings::SETTINGS_FILE)
+                .ok()
+                .and_then(|p| std::fs::metadata(p).ok())
+                .and_then(|m| m.created().or_else(|_| m.modified()).ok())
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp())
+                .unwrap_or_else(|| chrono::Utc::now().timestamp());
+            if let Some(path) = &path {
+                let _ = persist::save(path, &data);
+            }
+        }
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    fn save(&self, data: &MilestoneData) -> PetResult<()> {
+        match &self.path {
+            Some(path) => persist::save(path, data),
+            None => Ok(()),
+        }
+    }
+
+    /// Forget every first and anniversary and start counting from today.
+    /// The old file and its backups go; the fresh one is written so a
+    /// restart doesn't date the install from the settings file again.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        *data = MilestoneData {
+            installed_at: chrono::Utc::now().timestamp(),
+            ..Default::default()
+        };
+        if let Some(path) = &self.path {
+            persist::remove(path)?;
+        }
+        self.save(&data)
+    }
+
+    /// Everything, for syncing to another machine.
+    pub fn export(&self) -> serde_json::Value {
+        serde_json::to_value(&*self.data.lock().unwrap()).unwrap_or_default()
+    }
+
+    /// Fold in another machine's milestones: the older install date, each
+    /// first from whichever got there earlier, and every anniversary either
+    /// one celebrated. Returns whether anything changed here.
+    pub fn merge(&self, other: serde_json::Value) -> PetResult<bool> {
+        let other: MilestoneData = serde_json::from_value(other)?;
+        let mut data = self.data.lock().unwrap();
+        let before = serde_json::to_value(&*data)?;
+        if other.installed_at > 0 && other.installed_at < data.installed_at {
+            data.installed_at = other.installed_at;
+        }
+        for (milestone, reached) in other.firsts {
+            if data
+                .firsts
+                .get(&milestone)
+                .is_none_or(|mine| reached.at < mine.at)
+            {
+                data.firsts.insert(milestone, reached);
+            }
+        }
+        for days in other.celebrated {
+            if !data.celebrated.contains(&days) {
+                data.celebrated.push(days);
+            }
+        }
+        let changed = serde_json::to_value(&*data)? != before;
+        if changed {
+            self.save(&data)?;
+        }
+        Ok(changed)
+    }
+
+    /// Note a first, unless it was already reached.
+    fn reach(&self, milestone: Milestone, detail: Option<String>) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        if data.firsts.contains_key(&milestone) {
+            return Ok(());
+        }
+        data.firsts.insert(
+            milestone,
+            Reached {
+                at: chrono::Utc::now().timestamp(),
+                detail,
+            },
+        );
+        self.save(&data)
+    }
+
+    fn days_together(&self) -> u32 {
+        let installed_at = self.data.lock().unwrap().installed_at;
+        let installed = Local
+            .timestamp_opt(installed_at, 0)
+            .single()
+            .map(|t| t.date_naive())
+            .unwrap_or_else(|| Local::now().date_naive());
+        (Local::now().date_naive() - installed).num_days().max(0) as u32
+    }
+}
+
+pub fn is_reached(app: &tauri::AppHandle, milestone: Milestone) -> bool {
+    app.state::<MilestoneStore>()
+        .data
+        .lock()
+        .unwrap()
+        .firsts
+        .contains_key(&milestone)
+}
+
+/// Note a first, with a few words on it, without failing the caller.
+pub fn note(app: &tauri::AppHandle, milestone: Milestone, detail: Option<&str>) {
+    if is_reached(app, milestone) {
+        return;
+    }
+    let detail = detail.map(|d| d.chars().take(120).collect());
+    if let Err(e) = app.state::<MilestoneStore>().reach(milestone, detail) {
+        eprintln!("Milestone not saved: {}", e);
+    }
+}
+
+/// Note an all-nighter if it's the small hours and the owner is at it.
+pub fn note_if_all_nighter(app: &tauri::AppHandle) {
+    if ALL_NIGHTER_HOURS.contains(&Local::now().hour()) {
+        note(app, Milestone::AllNighter, None);
+    }
+}
+
+fn format_date(at: i64) -> String {
+    Local
+        .timestamp_opt(at, 0)
+        .single()
+        .map(|t| t.format("%B %-d, %Y").to_string())
+        .unwrap_or_default()
+}
+
+/// The stored milestones as lines for the recap prompt, oldest first.
+fn history(data: &MilestoneData) -> String {
+    let mut firsts: Vec<(&Milestone, &Reached)> = data.firsts.iter().collect();
+    firsts.sort_by_key(|(_, reached)| reached.at);
+    let mut lines = vec![format!(
+        "- {}: you installed me",
+        format_date(data.installed_at)
+    )];
+    lines.extend(firsts.iter().map(|(milestone, reached)| {
+        let detail = reached
+            .detail
+            .as_ref()
+            .map(|d| format!(" (\"{}\")", d))
+            .unwrap_or_default();
+        format!(
+            "- {}: {}{}",
+            format_date(reached.at),
+            milestone.describe(),
+            detail
+        )
+    }));
+    lines.join("\n")
+}
+
+/// Said without the model: the count and the first few milestones.
+fn offline_recap(name: &str, data: &MilestoneData) -> String {
+    let mut firsts: Vec<(&Milestone, &Reached)> = data.firsts.iter().collect();
+    firsts.sort_by_key(|(_, reached)| reached.at);
+    let remembered: Vec<&str> = firsts.iter().take(2).map(|(m, _)| m.describe()).collect();
+    if remembered.is_empty() {
+        format!(
+            "We've lived together for {}. Not bad for a cat and a human.",
+            name
+        )
+    } else {
+        format!(
+            "We've lived together for {}. I still remember {}.",
+            name,
+            remembered.join(" and ")
+        )
+    }
+}
+
+/// Write the recap for `name` together ("a year") from the milestones.
+async fn recap(app: &tauri::AppHandle, name: &str) -> PetResult<String> {
+    let (trigger, offline) = {
+        let store = app.state::<MilestoneStore>();
+        let data = store.data.lock().unwrap();
+        (
+            format!(
+                "We've lived together for {}. Our milestones:\n{}",
+                name,
+                history(&data)
+            ),
+            offline_recap(name, &data),
+        )
+    };
+    let response = dialogue::generate(
+        app.clone(),
+        String::new(),
+        String::new(),
+        trigger,
+        Some("anniversary".to_string()),
+        None,
+        None,
+        None,
+    )
+    .await?;
+    Ok(if response.offline {
+        offline
+    } else {
+        response.text
+    })
+}
+
+async fn check(app: tauri::AppHandle) {
+    let days = app.state::<MilestoneStore>().days_together();
+    let Some(name) = anniversary_name(days) else {
+        return;
+    };
+    let celebrated = app
+        .state::<MilestoneStore>()
+        .data
+        .lock()
+        .unwrap()
+        .celebrated
+        .contains(&days);
+    let paused = app.state::<tray::TrayState>().get().dialogue_paused;
+    if celebrated || paused || !RECAP_HOURS.contains(&Local::now().hour()) {
+        return;
+    }
+    match recap(&app, &name).await {
+        Ok(text) => {
+            let store = app.state::<MilestoneStore>();
+            let mut data = store.data.lock().unwrap();
+            data.celebrated.push(days);
+            if let Err(e) = store.save(&data) {
+                eprintln!("Anniversary not saved: {}", e);
+            }
+            drop(data);
+            notifications::announce(&app, &text);
+        }
+        // Tried again next tick
+        Err(e) => eprintln!("Anniversary recap failed: {}", e),
+    }
+}
+
+/// Look for an anniversary every scheduler tick.
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, check);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneEntry {
+    pub milestone: Milestone,
+    pub description: &'static str,
+    pub at: i64,
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneSummary {
+    pub installed_at: i64,
+    pub days_together: u32,
+    pub firsts: Vec<MilestoneEntry>,
+    /// Days together at the next anniversary
+    pub next_anniversary: u32,
+}
+
+#[tauri::command(async)]
+pub fn get_milestones(store: tauri::State<'_, MilestoneStore>) -> MilestoneSummary {
+    let days_together = store.days_together();
+    let data = store.data.lock().unwrap();
+    let mut firsts: Vec<MilestoneEntry> = data
+        .firsts
+        .iter()
+        .map(|(milestone, reached)| MilestoneEntry {
+            milestone: *milestone,
+            description: milestone.describe(),
+            at: reached.at,
+            detail: reached.detail.clone(),
+        })
+        .collect();
+    firsts.sort_by_key(|entry| entry.at);
+    MilestoneSummary {
+        installed_at: data.installed_at,
+        days_together,
+        firsts,
+        next_anniversary: (days_together + 1..)
+            .find(|d| anniversary_name(*d).is_some())
+            .unwrap_or(365),
+    }
+}
+
+/// Note a first that happens in the webview, like the first perch.
+#[tauri::command]
+pub async fn record_milestone(app: tauri::AppHandle, milestone: Milestone) -> PetResult<()> {
+    crate::run_blocking(move || note(&app, milestone, None)).await
+}
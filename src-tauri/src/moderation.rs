@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::persist;
+use crate::settings::SettingsStore;
+use crate::usage::UsageStore;
+
+pub const MODERATION_FILE: &str = "moderation.json";
+/// Filtered messages from one sender before they're blocked automatically.
+const STRIKES_TO_BLOCK: u32 = 3;
+
+/// Always filtered, on top of the user's own words.
+const BUILTIN_WORDS: &[&str] = &[
+    "fuck",
+    "fucking",
+    "shit",
+    "bitch",
+    "cunt",
+    "asshole",
+    "dick",
+    "whore",
+    "slut",
+    "retard",
+    "kys",
+    "kill yourself",
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedSender {
+    pub pet_id: String,
+    pub name: String,
+    /// Unix millis
+    pub blocked_at: i64,
+    /// Blocked by the strike rule rather than by hand
+    pub automatic: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationData {
+    pub blocked: Vec<BlockedSender>,
+    /// Filtered-message count per sender pet id
+    pub strikes: HashMap<String, u32>,
+}
+
+pub struct ModerationStore {
+    data: Mutex<ModerationData>,
+    path: Option<PathBuf>,
+}
+
+impl ModerationStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, MODERATION_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    fn save(&self, data: &ModerationData) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, data);
+        }
+    }
+
+    pub fn get(&self) -> ModerationData {
+        self.data.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, new_data: ModerationData) {
+        let mut data = self.data.lock().unwrap();
+        *data = new_data;
+        self.save(&data);
+    }
+
+    fn is_blocked(&self, pet_id: &str) -> bool {
+        self.data
+            .lock()
+            .unwrap()
+            .blocked
+            .iter()
+            .any(|b| b.pet_id == pet_id)
+    }
+
+    fn block(&self, pet_id: &str, name: &str, automatic: bool) {
+        let mut data = self.data.lock().unwrap();
+        if data.blocked.iter().any(|b| b.pet_id == pet_id) {
+            return;
+        }
+        data.blocked.push(BlockedSender {
+            pet_id: pet_id.to_string(),
+            name: name.to_string(),
+            blocked_at: chrono::Utc::now().timestamp_millis(),
+            automatic,
+        });
+        self.save(&data);
+    }
+
+    fn unblock(&self, pet_id: &str) {
+        let mut data = self.data.lock().unwrap();
+        data.blocked.retain(|b| b.pet_id != pet_id);
+        data.strikes.remove(pet_id);
+        self.save(&data);
+    }
+
+    /// Count a filtered message against `pet_id`, blocking them once they
+    /// reach the limit. Returns whether they're now blocked.
+    fn strike(&self, pet_id: &str, name: &str) -> bool {
+        let strikes = {
+            let mut data = self.data.lock().unwrap();
+            let strikes = data.strikes.entry(pet_id.to_string()).or_default();
+            *strikes += 1;
+            let strikes = *strikes;
+            self.save(&data);
+            strikes
+        };
+        if strikes >= STRIKES_TO_BLOCK {
+            self.block(pet_id, name, true);
+            return true;
+        }
+        false
+    }
+}
+
+/// Lowercase, undo common letter swaps (sh1t, @ss) and reduce everything
+/// else to single spaces, so words can be matched on their boundaries.
+fn normalize(text: &str) -> String {
+    let swapped: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            '0' => 'o',
+            '1' | '!' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect();
+    format!(
+        " {} ",
+        swapped.split_whitespace().collect::<Vec<_>>().join(" ")
+    )
+}
+
+/// The first filtered word or phrase in `text`, if any.
+pub fn find_blocked_word(app: &tauri::AppHandle, text: &str) -> Option<String> {
+    let custom = app.state::<SettingsStore>().get().moderation.blocked_words;
+    let text = normalize(text);
+    BUILTIN_WORDS
+        .iter()
+        .map(|w| w.to_string())
+        .chain(custom)
+        .find(|word| {
+            let word = normalize(word);
+            word.trim() != "" && text.contains(&word)
+        })
+}
+
+/// Ask the model whether a message is harassment or touches one of the
+/// user's off-limits topics. Fails open: the word list already ran.
+async fn llm_allows(app: &tauri::AppHandle, message: &str, topics: &[String]) -> bool {
+    let api_key = match crate::config::anthropic_api_key() {
+        Some(key) => key,
+        None => return true,
+    };
+    if app.state::<UsageStore>().budget_exceeded() {
+        return true;
+    }
+    let mut instructions = "You moderate short messages sent between friends' desktop pets. \
+        Reply BLOCK if the message is harassment, a threat, sexual, or hateful"
+        .to_string();
+    if !topics.is_empty() {
+        instructions.push_str(&format!(
+            ", or if it's about any of these topics: {}",
+            topics.join(", ")
+        ));
+    }
+    instructions.push_str(". Otherwise reply ALLOW. Reply with the one word only.");
+    crate::dialogue::run_background_prompt(app, &api_key, &instructions, message.to_string(), 5)
+        .await
+        .map_or(true, |answer| !answer.to_uppercase().contains("BLOCK"))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Verdict {
+    /// Whether the visit should be shown at all
+    pub show_visit: bool,
+    /// Whether its message can be shown
+    pub show_message: bool,
+    pub reason: Option<String>,
+}
+
+/// Check a message carried by a visiting friend's pet before it's shown.
+/// Blocked senders' visits are dropped entirely; filtered messages count
+/// as strikes against the sender.
+#[tauri::command]
+pub async fn moderate_visit_message(
+    app: tauri::AppHandle,
+    from_pet_id: String,
+    from_name: String,
+    message: String,
+) -> PetResult<Verdict> {
+    let store = app.state::<ModerationStore>();
+    if store.is_blocked(&from_pet_id) {
+        return Ok(Verdict {
+            show_visit: false,
+            show_message: false,
+            reason: Some("Sender is blocked".to_string()),
+        });
+    }
+    if message.trim().is_empty() {
+        return Ok(Verdict {
+            show_visit: true,
+            show_message: true,
+            reason: None,
+        });
+    }
+
+    let settings = app.state::<SettingsStore>().get().moderation;
+    let reason = if let Some(word) = find_blocked_word(&app, &message) {
+        Some(format!("Contains \"{}\"", word))
+    } else if settings.llm_check && !llm_allows(&app, &message, &settings.blocked_topics).await {
+        Some("Flagged by the model".to_string())
+    } else {
+        None
+    };
+    let Some(reason) = reason else {
+        return Ok(Verdict {
+            show_visit: true,
+            show_message: true,
+            reason: None,
+        });
+    };
+    let blocked = store.strike(&from_pet_id, &from_name);
+    Ok(Verdict {
+        show_visit: !blocked,
+        show_message: false,
+        reason: Some(reason),
+    })
+}
+
+#[tauri::command(async)]
+pub fn list_blocked_senders(store: tauri::State<'_, ModerationStore>) -> Vec<BlockedSender> {
+    store.get().blocked
+}
+
+#[tauri::command(async)]
+pub fn block_sender(
+    store: tauri::State<'_, ModerationStore>,
+    pet_id: String,
+    name: String,
+) -> PetResult<Vec<BlockedSender>> {
+    if pet_id.trim().is_empty() {
+        return Err(PetError::InvalidInput("No pet to block".to_string()));
+    }
+    store.block(&pet_id, &name, false);
+    Ok(store.get().blocked)
+}
+
+/// Unblock a sender and forgive their strikes.
+#[tauri::command(async)]
+pub fn unblock_sender(
+    store: tauri::State<'_, ModerationStore>,
+    pet_id: String,
+) -> Vec<BlockedSender> {
+    store.unblock(&pet_id);
+    store.get().blocked
+}
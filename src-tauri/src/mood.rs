@@ -0,0 +1,353 @@
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::error::PetResult;
+use crate::persist;
+use crate::scheduler;
+
+pub const MOOD_FILE: &str = "mood.json";
+/// Mood moves halfway to where it's heading in this long.
+const SETTLE_HALF_LIFE_MINUTES: f64 = 30.0;
+/// Being left alone starts to sting after this long...
+const IGNORED_AFTER_MINUTES: f64 = 60.0;
+/// ...and stings as much as it's going to after this long.
+const IGNORED_FULL_MINUTES: f64 = 360.0;
+const MAX_IGNORED_PENALTY: f64 = 0.5;
+/// Hours for a fully met need to run out.
+const ATTENTION_HOURS: f64 = 4.0;
+const PLAY_HOURS: f64 = 8.0;
+const REST_HOURS: f64 = 14.0;
+/// Below this a need is what the cat is thinking about.
+const NEED_LOW: f64 = 0.3;
+
+/// Something the owner did that the cat has feelings about.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum Interaction {
+    /// Talked to it: chat, search, or asking about some text
+    Chat,
+    /// Clicked on it
+    Petted,
+    /// Chased the cursor with it
+    Played,
+    /// Let it nap
+    Napped,
+    /// Made it see-through and unclickable
+    Shooed,
+}
+
+impl Interaction {
+    /// (mood nudge, attention, play, rest)
+    fn effects(self) -> (f64, f64, f64, f64) {
+        match self {
+            Interaction::Chat => (0.08, 0.3, 0.0, 0.0),
+            Interaction::Petted => (0.12, 0.4, 0.05, 0.0),
+            Interaction::Played => (0.15, 0.2, 0.5, -0.1),
+            Interaction::Napped => (0.05, 0.0, 0.0, 0.6),
+            Interaction::Shooed => (-0.1, -0.1, 0.0, 0.0),
+        }
+    }
+}
+
+/// Mood from -1 (miserable) to 1 (delighted) and needs from 0 (unmet) to 1
+/// (met), as of `updated` (unix seconds).
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+struct MoodData {
+    value: f64,
+    attention: f64,
+    play: f64,
+    rest: f64,
+    last_interaction: i64,
+    updated: i64,
+}
+
+impl Default for MoodData {
+    fn default() -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            value: 0.2,
+            attention: 0.7,
+            play: 0.7,
+            rest: 0.7,
+            last_interaction: now,
+            updated: now,
+        }
+    }
+}
+
+/// A little brighter in the morning and evening, flat in the small hours.
+fn time_of_day_baseline(hour: u32) -> f64 {
+    match hour {
+        0..=5 => -0.1,
+        6..=11 => 0.15,
+        12..=16 => 0.05,
+        17..=20 => 0.15,
+        _ => 0.0,
+    }
+}
+
+fn ignored_penalty(ignored_minutes: f64) -> f64 {
+    let t = ((ignored_minutes - IGNORED_AFTER_MINUTES)
+        / (IGNORED_FULL_MINUTES - IGNORED_AFTER_MINUTES))
+        .clamp(0.0, 1.0);
+    t * MAX_IGNORED_PENALTY
+}
+
+impl MoodData {
+    /// Run time forward to `now`: needs run down and the mood drifts toward
+    /// what the needs, the hour and being ignored add up to.
+    fn advance(&mut self, now: i64) {
+        let minutes = (now - self.updated).max(0) as f64 / 60.0;
+        if minutes <= 0.0 {
+            return;
+        }
+        let hours = minutes / 60.0;
+        self.attention = (self.attention - hours / ATTENTION_HOURS).clamp(0.0, 1.0);
+        self.play = (self.play - hours / PLAY_HOURS).clamp(0.0, 1.0);
+        self.rest = (self.rest - hours / REST_HOURS).clamp(0.0, 1.0);
+
+        let needs = (self.attention + self.play + self.rest) / 3.0 - 0.5;
+        let hour = chrono::Local::now().hour();
+        let target = (time_of_day_baseline(hour) + needs
+            - ignored_penalty(self.ignored_minutes(now) as f64))
+        .clamp(-1.0, 1.0);
+        let keep = 0.5f64.powf(minutes / SETTLE_HALF_LIFE_MINUTES);
+        self.value = target + (self.value - target) * keep;
+        self.updated = now;
+    }
+
+    fn ignored_minutes(&self, now: i64) -> i64 {
+        (now - self.last_interaction).max(0) / 60
+    }
+
+    fn apply(&mut self, interaction: Interaction, now: i64) {
+        let (nudge, attention, play, rest) = interaction.effects();
+        self.value = (self.value + nudge).clamp(-1.0, 1.0);
+        self.attention = (self.attention + attention).clamp(0.0, 1.0);
+        self.play = (self.play + play).clamp(0.0, 1.0);
+        self.rest = (self.rest + rest).clamp(0.0, 1.0);
+        if !matches!(interaction, Interaction::Shooed) {
+            self.last_interaction = now;
+        }
+    }
+
+    fn mood(&self, now: i64) -> Mood {
+        let label = match self.value {
+            v if v < -0.6 => "miserable",
+            v if v < -0.2 => "grumpy",
+            v if v < 0.2 => "content",
+            v if v < 0.6 => "happy",
+            _ => "delighted",
+        };
+        let ignored_minutes = self.ignored_minutes(now);
+        let lowest = [
+            (self.attention, "lonely"),
+            (self.play, "bored"),
+            (self.rest, "tired"),
+        ]
+        .into_iter()
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .filter(|(level, _)| *level < NEED_LOW)
+        .map(|(_, feeling)| feeling);
+
+        let reason = if ignored_minutes as f64 >= IGNORED_AFTER_MINUTES {
+            Some(format!(
+                "your owner hasn't paid you any attention for {}",
+                if ignored_minutes < 120 {
+                    "an hour".to_string()
+                } else {
+                    format!("{} hours", ignored_minutes / 60)
+                }
+            ))
+        } else if let Some(feeling) = lowest {
+            Some(format!("you're feeling {}", feeling))
+        } else if self.value >= 0.2 {
+            Some("your owner has been good company".to_string())
+        } else {
+            None
+        };
+
+        // The face to wear between lines
+        let face = match (label, lowest) {
+            (_, Some("tired")) => Some("sleepy"),
+            ("miserable" | "grumpy", _) => Some("annoyed"),
+            ("delighted", _) => Some("happy"),
+            (_, Some("bored")) => Some("curious"),
+            _ => None,
+        };
+
+        Mood {
+            value: (self.value * 100.0).round() / 100.0,
+            label,
+            reason,
+            face,
+            attention: self.attention,
+            play: self.play,
+            rest: self.rest,
+            ignored_minutes,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Mood {
+    pub value: f64,
+    pub label: &'static str,
+    /// What's behind it most, in words for the prompt
+    pub reason: Option<String>,
+    /// An emotion the frontend can show when the cat isn't talking
+    pub face: Option<&'static str>,
+    pub attention: f64,
+    pub play: f64,
+    pub rest: f64,
+    pub ignored_minutes: i64,
+}
+
+impl Mood {
+    /// The mood as a system prompt instruction.
+    pub fn describe(&self) -> String {
+        let reason = self
+            .reason
+            .as_ref()
+            .map(|r| format!(" because {}", r))
+            .unwrap_or_default();
+        format!(
+            "Right now you're {}{}. Let that colour how you talk, without announcing it.",
+            self.label, reason
+        )
+    }
+}
+
+/// The cat's mood, carried across restarts. Moves with what the owner does,
+/// with needs running down, with the hour and with being ignored.
+pub struct MoodEngine {
+    data: Mutex<MoodData>,
+    /// The loaded pet's mood file
+    path: Mutex<Option<PathBuf>>,
+    /// Label and face last emitted, so `mood-changed` only fires on a change
+    last: Mutex<Option<(&'static str, Option<&'static str>)>>,
+}
+
+impl MoodEngine {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::pets::data_file(app, MOOD_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path: Mutex::new(path),
+            last: Mutex::new(None),
+        }
+    }
+
+    pub fn current(&self) -> Mood {
+        let now = chrono::Utc::now().timestamp();
+        let mut data = self.data.lock().unwrap();
+        data.advance(now);
+        data.mood(now)
+    }
+
+    fn save(&self, data: &MoodData) -> PetResult<()> {
+        match &*self.path.lock().unwrap() {
+            Some(path) => persist::save(path, data),
+            None => Ok(()),
+        }
+    }
+
+    /// Save this pet's mood, let `switch` change which pet is loaded, and
+    /// load that one's.
+    pub fn switch(
+        &self,
+        app: &tauri::AppHandle,
+        switch: impl FnOnce() -> PetResult<()>,
+    ) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        data.advance(chrono::Utc::now().timestamp());
+        self.save(&data)?;
+        switch()?;
+        let path = crate::pets::data_file(app, MOOD_FILE).ok();
+        *data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        *self.path.lock().unwrap() = path;
+        Ok(())
+    }
+}
+
+/// Tell the webview when the label or face changes.
+fn announce(app: &tauri::AppHandle, mood: &Mood, force: bool) {
+    let engine = app.state::<MoodEngine>();
+    let mut last = engine.last.lock().unwrap();
+    let key = (mood.label, mood.face);
+    if force || *last != Some(key) {
+        *last = Some(key);
+        let _ = app.emit("mood-changed", mood.clone());
+    }
+}
+
+/// Count something the owner did. Emits `mood-changed` every time, since
+/// the numbers always move.
+pub fn record(app: &tauri::AppHandle, interaction: Interaction) -> PetResult<Mood> {
+    let engine = app.state::<MoodEngine>();
+    let now = chrono::Utc::now().timestamp();
+    let mood = {
+        let mut data = engine.data.lock().unwrap();
+        data.advance(now);
+        data.apply(interaction, now);
+        engine.save(&data)?;
+        data.mood(now)
+    };
+    announce(app, &mood, true);
+    Ok(mood)
+}
+
+/// Record without failing the caller; the mood is a nice-to-have.
+pub fn note(app: &tauri::AppHandle, interaction: Interaction) {
+    if let Err(e) = record(app, interaction) {
+        eprintln!("Mood not updated: {}", e);
+    }
+}
+
+async fn tick(app: tauri::AppHandle) {
+    let _ = crate::run_blocking(move || {
+        let engine = app.state::<MoodEngine>();
+        let now = chrono::Utc::now().timestamp();
+        let mood = {
+            let mut data = engine.data.lock().unwrap();
+            data.advance(now);
+            let _ = engine.save(&data);
+            data.mood(now)
+        };
+        announce(&app, &mood, false);
+    })
+    .await;
+}
+
+/// Send the current mood to the webview whether or not it changed, e.g.
+/// after switching pets.
+pub fn refresh(app: &tauri::AppHandle) {
+    let mood = app.state::<MoodEngine>().current();
+    announce(app, &mood, true);
+}
+
+/// Let time pass for the mood every scheduler tick, saving it and emitting
+/// `mood-changed` when it turns.
+pub fn spawn_ticker(app: tauri::AppHandle) {
+    scheduler::every_tick(app, tick);
+}
+
+#[tauri::command(async)]
+pub fn get_mood(engine: tauri::State<'_, MoodEngine>) -> Mood {
+    engine.current()
+}
+
+/// Count something the owner did in the webview, like a click on the cat.
+#[tauri::command]
+pub async fn record_interaction(
+    app: tauri::AppHandle,
+    interaction: Interaction,
+) -> PetResult<Mood> {
+    crate::run_blocking(move || record(&app, interaction)).await?
+}
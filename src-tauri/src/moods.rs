@@ -0,0 +1,111 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::persist;
+
+pub const MOODS_FILE: &str = "moods.json";
+pub const MIN_MOOD: u8 = 1;
+pub const MAX_MOOD: u8 = 5;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoodDay {
+    /// Local date (YYYY-MM-DD)
+    pub date: String,
+    /// 1 (rough day) to 5 (great day), as judged when the journal entry was written
+    pub score: u8,
+}
+
+/// Mood score per journal date. Entries themselves live in the webview's
+/// storage; only the scores are kept here so the calendar can be queried.
+pub struct MoodStore {
+    moods: Mutex<BTreeMap<String, u8>>,
+    path: Option<PathBuf>,
+}
+
+impl MoodStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, MOODS_FILE).ok();
+        let moods = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            moods: Mutex::new(moods),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut moods = self.moods.lock().unwrap();
+        *moods = BTreeMap::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn all(&self) -> BTreeMap<String, u8> {
+        self.moods.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, new_moods: BTreeMap<String, u8>) {
+        let mut moods = self.moods.lock().unwrap();
+        *moods = new_moods;
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*moods);
+        }
+    }
+
+    pub fn record(&self, date: &str, score: u8) {
+        let mut moods = self.moods.lock().unwrap();
+        moods.insert(date.to_string(), score.clamp(MIN_MOOD, MAX_MOOD));
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*moods);
+        }
+    }
+
+    /// Drop scores for days before `cutoff` (YYYY-MM-DD).
+    pub fn prune_before(&self, cutoff: &str) {
+        let mut moods = self.moods.lock().unwrap();
+        if moods
+            .first_key_value()
+            .is_some_and(|(date, _)| date.as_str() < cutoff)
+        {
+            *moods = moods.split_off(cutoff);
+            if let Some(path) = &self.path {
+                let _ = persist::save(path, &*moods);
+            }
+        }
+    }
+
+    fn month(&self, month: &str) -> Vec<MoodDay> {
+        let prefix = format!("{}-", month);
+        self.moods
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(date, _)| date.starts_with(&prefix))
+            .map(|(date, score)| MoodDay {
+                date: date.clone(),
+                score: *score,
+            })
+            .collect()
+    }
+}
+
+/// Mood scores for every journaled day in `month` (YYYY-MM), defaulting to
+/// the current month.
+#[tauri::command]
+pub async fn get_mood_calendar(
+    app: tauri::AppHandle,
+    month: Option<String>,
+) -> PetResult<Vec<MoodDay>> {
+    let month = month.unwrap_or_else(|| chrono::Local::now().format("%Y-%m").to_string());
+    chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map_err(|_| PetError::InvalidInput("Months look like 2025-07".to_string()))?;
+    crate::run_blocking(move || app.state::<MoodStore>().month(&month)).await
+}
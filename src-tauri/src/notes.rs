@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::{notifications, occlusion, persist, scheduler, stickies};
+
+pub const NOTES_FILE: &str = "notes.json";
+/// How often due todos are looked for; petctl's `remind --in` relies on it.
+const DUE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: String,
+    pub text: String,
+    /// Unix millis, matching the frontend's Date.now()
+    pub created_at: i64,
+    /// Unix millis; the cat speaks up once it passes
+    #[serde(default)]
+    pub due: Option<i64>,
+    #[serde(default)]
+    pub done: bool,
+    /// Whether the due reminder was already said
+    #[serde(default)]
+    pub reminded: bool,
+}
+
+/// Reminders and todos captured from [NOTE: ...] tags in the cat's replies.
+pub struct NotesStore {
+    notes: Mutex<Vec<Note>>,
+    path: Option<PathBuf>,
+}
+
+impl NotesStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, NOTES_FILE).ok();
+        let notes = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            notes: Mutex::new(notes),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut notes = self.notes.lock().unwrap();
+        *notes = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, notes: &[Note]) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &notes);
+        }
+    }
+
+    pub fn all(&self) -> Vec<Note> {
+        self.notes.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, new_notes: Vec<Note>) {
+        let mut notes = self.notes.lock().unwrap();
+        *notes = new_notes;
+        self.save(&notes);
+    }
+
+    pub fn add(&self, text: &str, due: Option<i64>) -> Note {
+        let note = Note {
+            id: uuid::Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            due,
+            done: false,
+            reminded: false,
+        };
+        let mut notes = self.notes.lock().unwrap();
+        notes.push(note.clone());
+        self.save(&notes);
+        note
+    }
+
+    /// Add the note a `[NOTE: ...]` tag describes.
+    pub fn add_from_tag(&self, tag: &str) -> Note {
+        let (text, due) = parse_tag(tag);
+        self.add(&text, due)
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut Vec<Note>, usize)) -> PetResult<Vec<Note>> {
+        let mut notes = self.notes.lock().unwrap();
+        let index = notes
+            .iter()
+            .position(|n| n.id == id)
+            .ok_or_else(|| PetError::InvalidInput(format!("No note with id {}", id)))?;
+        f(&mut notes, index);
+        self.save(&notes);
+        Ok(notes.clone())
+    }
+
+    /// Open notes whose due time has passed and that haven't been said yet,
+    /// marked as said.
+    fn take_due(&self, now: i64) -> Vec<Note> {
+        let mut notes = self.notes.lock().unwrap();
+        let due: Vec<Note> = notes
+            .iter_mut()
+            .filter(|n| !n.done && !n.reminded && n.due.is_some_and(|due| due <= now))
+            .map(|n| {
+                n.reminded = true;
+                n.clone()
+            })
+            .collect();
+        if !due.is_empty() {
+            self.save(&notes);
+        }
+        due
+    }
+}
+
+/// Split `Call the dentist | due: 2025-03-14 15:00` into the text and a due
+/// time (local). A time that doesn't parse stays part of the text rather
+/// than being lost.
+fn parse_tag(tag: &str) -> (String, Option<i64>) {
+    let Some((text, due)) = tag.rsplit_once('|') else {
+        return (tag.trim().to_string(), None);
+    };
+    let Some(due) = due.trim().strip_prefix("due:") else {
+        return (tag.trim().to_string(), None);
+    };
+    let due = due.trim();
+    let parsed = chrono::NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            // A day alone is due first thing that morning
+            chrono::NaiveDate::parse_from_str(due, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(9, 0, 0))
+        })
+        .and_then(|t| t.and_local_timezone(chrono::Local).earliest());
+    match parsed {
+        Some(t) => (text.trim().to_string(), Some(t.timestamp_millis())),
+        None => (tag.trim().to_string(), None),
+    }
+}
+
+/// Save a reminder note; with a delay the cat also speaks up when it's due.
+/// Shared by petctl and `desktoppet://remind` links.
+pub fn remind(app: &tauri::AppHandle, text: &str, in_seconds: Option<u64>) {
+    let due = in_seconds.map(|s| {
+        let millis = i64::try_from(s.saturating_mul(1000)).unwrap_or(i64::MAX);
+        chrono::Utc::now().timestamp_millis().saturating_add(millis)
+    });
+    app.state::<NotesStore>().add(text, due);
+    crate::tray::refresh(app);
+}
+
+async fn check_due(app: tauri::AppHandle) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let due = app.state::<NotesStore>().take_due(now);
+    for note in due {
+        notifications::announce(&app, &format!("Reminder: {}", note.text));
+        // And tape it up beside the cat, where it'll still be after the bubble
+        if let Some((x, y)) = occlusion::pet_spot(&app) {
+            let _ = stickies::create(&app, &note.text, x + 48.0, y - 140.0);
+        }
+    }
+}
+
+/// Say each todo when it comes due. Due times are kept in notes.json, so
+/// a reminder set before a restart still goes off (late, if the app was
+/// closed at the time).
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, DUE_CHECK_INTERVAL, check_due);
+}
+
+/// Every note, oldest first; `include_done: false` leaves out finished ones.
+#[tauri::command(async)]
+pub fn list_notes(store: tauri::State<'_, NotesStore>, include_done: Option<bool>) -> Vec<Note> {
+    let include_done = include_done.unwrap_or(true);
+    store
+        .all()
+        .into_iter()
+        .filter(|n| include_done || !n.done)
+        .collect()
+}
+
+/// Tick a note off, or back on with `done: false`. Returns all notes.
+#[tauri::command]
+pub async fn complete_note(
+    app: tauri::AppHandle,
+    id: String,
+    done: Option<bool>,
+) -> PetResult<Vec<Note>> {
+    crate::run_blocking(move || {
+        let notes = app.state::<NotesStore>().update(&id, |notes, i| {
+            notes[i].done = done.unwrap_or(true);
+        })?;
+        crate::tray::refresh(&app);
+        Ok(notes)
+    })
+    .await?
+}
+
+/// Returns the notes left.
+#[tauri::command]
+pub async fn delete_note(app: tauri::AppHandle, id: String) -> PetResult<Vec<Note>> {
+    crate::run_blocking(move || {
+        let notes = app.state::<NotesStore>().update(&id, |notes, i| {
+            notes.remove(i);
+        })?;
+        crate::tray::refresh(&app);
+        Ok(notes)
+    })
+    .await?
+}
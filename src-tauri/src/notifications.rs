@@ -0,0 +1,26 @@
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+const TITLE: &str = "Desktop Pet";
+
+/// Show a native notification from the cat. Failures (e.g. notifications
+/// disabled in System Settings) are logged, never surfaced.
+pub fn notify(app: &tauri::AppHandle, body: &str) {
+    if let Err(e) = app.notification().builder().title(TITLE).body(body).show() {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
+
+/// Have the cat speak up unprompted: a speech bubble on the overlay, or a
+/// native notification while the overlay is hidden in menu-bar-only mode.
+pub fn announce(app: &tauri::AppHandle, text: &str) {
+    // Saved for after the call rather than popping up on a shared screen
+    if crate::meetings::hold(app, text) {
+        return;
+    }
+    if crate::menubar::is_enabled(app) {
+        notify(app, text);
+    } else {
+        let _ = app.emit("pet-announce", text);
+    }
+}
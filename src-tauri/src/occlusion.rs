@@ -0,0 +1,347 @@
+// Only macOS can list other apps' windows so far; elsewhere nothing is
+// ever occluded and the watcher never emits
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::scheduler;
+use crate::settings::SettingsStore;
+
+/// How often the watcher checks whether the pet is in the way.
+const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+/// How long a read of the window list is reused. A chase settles the pet
+/// many times a second, and each settle asks what's under it.
+const LAYOUT_TTL: Duration = Duration::from_millis(500);
+const MAX_EXCLUSIONS: usize = 50;
+const MAX_PATTERN_CHARS: usize = 100;
+
+/// A rectangle in the pet window's coordinates (CSS pixels from its
+/// top-left), the same space the frontend positions the pet in.
+#[derive(Serialize, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    pub fn offset(self, dx: f64, dy: f64) -> Self {
+        Self {
+            x: self.x + dx,
+            y: self.y + dy,
+            ..self
+        }
+    }
+}
+
+/// Another app's window, in global screen points.
+#[derive(Clone)]
+pub struct AppWindow {
+    pub app_name: String,
+    pub bounds: Rect,
+}
+
+#[derive(Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Occlusion {
+    pub occluded: bool,
+    /// The app whose window the pet is standing on
+    pub app_name: Option<String>,
+    /// That window, so the pet knows which way is out
+    pub bounds: Option<Rect>,
+}
+
+impl Occlusion {
+    fn clear() -> Self {
+        Self {
+            occluded: false,
+            app_name: None,
+            bounds: None,
+        }
+    }
+}
+
+/// Where the frontend last put the pet, and what the watcher last reported.
+#[derive(Default)]
+pub struct OcclusionState {
+    spot: Mutex<Option<(f64, f64)>>,
+    last: Mutex<Option<Occlusion>>,
+    layout: Mutex<Option<(Instant, Vec<AppWindow>)>>,
+}
+
+/// Ordinary app windows on screen, front to back, leaving out our own
+/// (by process, so a renamed build still finds itself).
+fn all_app_windows() -> Vec<AppWindow> {
+    #[cfg(target_os = "macos")]
+    return macos::app_windows();
+    #[cfg(not(target_os = "macos"))]
+    Vec::new()
+}
+
+/// A `windows.exclude` pattern as a regex over the whole app name.
+fn exclusion_regex(pattern: &str) -> Result<regex::Regex, regex::Error> {
+    let parts: Vec<String> = pattern.split('*').map(regex::escape).collect();
+    regex::Regex::new(&format!("(?i)^{}$", parts.join(".*")))
+}
+
+/// The apps the user asked the pet to look through.
+pub struct Exclusions(Vec<regex::Regex>);
+
+impl Exclusions {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let patterns = app.state::<SettingsStore>().get().windows.exclude;
+        // Checked when set; a hand-edited bad one is skipped
+        Self(
+            patterns
+                .iter()
+                .filter_map(|p| exclusion_regex(p).ok())
+                .collect(),
+        )
+    }
+
+    pub fn excludes(&self, app_name: &str) -> bool {
+        self.0.iter().any(|re| re.is_match(app_name))
+    }
+}
+
+/// `all_app_windows` without the excluded apps.
+pub fn app_windows(app: &tauri::AppHandle) -> Vec<AppWindow> {
+    let exclusions = Exclusions::load(app);
+    all_app_windows()
+        .into_iter()
+        .filter(|w| !exclusions.excludes(&w.app_name))
+        .collect()
+}
+
+/// `app_windows`, reusing a read from the last `LAYOUT_TTL`.
+fn cached_app_windows(app: &tauri::AppHandle) -> Vec<AppWindow> {
+    let state = app.state::<OcclusionState>();
+    let mut layout = state.layout.lock().unwrap();
+    match &*layout {
+        Some((read_at, windows)) if read_at.elapsed() < LAYOUT_TTL => windows.clone(),
+        _ => {
+            let windows = app_windows(app);
+            *layout = Some((Instant::now(), windows.clone()));
+            windows
+        }
+    }
+}
+
+/// Read the window list now, so the next lookup doesn't wait for the
+/// window server.
+pub fn warm_layout(app: &tauri::AppHandle) {
+    let windows = app_windows(app);
+    *app.state::<OcclusionState>().layout.lock().unwrap() = Some((Instant::now(), windows));
+}
+
+/// Top-left of the pet window in screen points, to convert between the
+/// frontend's coordinates and the window server's.
+pub fn window_origin(app: &tauri::AppHandle) -> PetResult<(f64, f64)> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| PetError::Internal("Pet window not found".to_string()))?;
+    let position = window
+        .outer_position()?
+        .to_logical::<f64>(window.scale_factor()?);
+    Ok((position.x, position.y))
+}
+
+/// What covers the point (window coordinates): the frontmost window, if the
+/// point falls inside it. With `cursor` given, it also has to be under the
+/// cursor, i.e. the window the user is actually working in. Finding the
+/// text caret needs accessibility access, so the cursor stands in for it.
+fn occlusion_at(
+    app: &tauri::AppHandle,
+    x: f64,
+    y: f64,
+    cursor: Option<(f64, f64)>,
+) -> PetResult<Occlusion> {
+    let (origin_x, origin_y) = window_origin(app)?;
+    let Some(front) = cached_app_windows(app).into_iter().next() else {
+        return Ok(Occlusion::clear());
+    };
+    let bounds = front.bounds.offset(-origin_x, -origin_y);
+    let in_use = cursor.is_none_or(|(cx, cy)| front.bounds.contains(cx, cy));
+    if !bounds.contains(x, y) || !in_use {
+        return Ok(Occlusion::clear());
+    }
+    Ok(Occlusion {
+        occluded: true,
+        app_name: Some(front.app_name),
+        bounds: Some(bounds),
+    })
+}
+
+async fn check(app: tauri::AppHandle) {
+    let Some((x, y)) = *app.state::<OcclusionState>().spot.lock().unwrap() else {
+        return;
+    };
+    let occlusion = crate::run_blocking({
+        let app = app.clone();
+        move || {
+            let cursor = crate::get_mouse_position().ok().map(|p| (p.x, p.y));
+            occlusion_at(&app, x, y, cursor)
+        }
+    })
+    .await;
+    let Ok(Ok(occlusion)) = occlusion else {
+        return;
+    };
+    let state = app.state::<OcclusionState>();
+    let mut last = state.last.lock().unwrap();
+    // Only tell the webview when the pet starts or stops being in the way,
+    // or ends up on a different window
+    let changed = last
+        .as_ref()
+        .is_none_or(|l| l.occluded != occlusion.occluded || l.app_name != occlusion.app_name);
+    if changed {
+        let _ = app.emit("pet-occluding", occlusion.clone());
+        *last = Some(occlusion);
+    }
+}
+
+/// Watch whether the pet is standing on the window the user is working in,
+/// emitting `pet-occluding` when that changes.
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    scheduler::every(app, WATCH_INTERVAL, check);
+}
+
+/// Whether the point (pet window coordinates) is covered by the frontmost
+/// app window.
+#[tauri::command]
+pub async fn is_point_occluded(app: tauri::AppHandle, x: f64, y: f64) -> PetResult<Occlusion> {
+    crate::run_blocking(move || occlusion_at(&app, x, y, None)).await?
+}
+
+/// Where the pet has settled (window coordinates), for the watcher. None
+/// stops watching until the next spot.
+#[tauri::command(async)]
+pub fn set_pet_spot(state: tauri::State<'_, OcclusionState>, spot: Option<(f64, f64)>) {
+    *state.spot.lock().unwrap() = spot;
+    if spot.is_none() {
+        *state.last.lock().unwrap() = None;
+    }
+}
+
+/// Where the pet last settled (window coordinates), if it has.
+pub fn pet_spot(app: &tauri::AppHandle) -> Option<(f64, f64)> {
+    *app.state::<OcclusionState>().spot.lock().unwrap()
+}
+
+/// Replace the apps whose windows the pet ignores. Each pattern is an app
+/// name, case-insensitive, with `*` for any run of characters.
+#[tauri::command]
+pub async fn set_window_exclusions(
+    app: tauri::AppHandle,
+    patterns: Vec<String>,
+) -> PetResult<Vec<String>> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().to_string();
+        if pattern.is_empty() || cleaned.contains(&pattern) {
+            continue;
+        }
+        if pattern.chars().count() > MAX_PATTERN_CHARS || pattern.chars().all(|c| c == '*') {
+            return Err(PetError::InvalidInput(format!(
+                "\"{}\" isn't an app name pattern",
+                pattern
+            )));
+        }
+        cleaned.push(pattern);
+    }
+    if cleaned.len() > MAX_EXCLUSIONS {
+        return Err(PetError::InvalidInput(format!(
+            "At most {} excluded apps",
+            MAX_EXCLUSIONS
+        )));
+    }
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.windows.exclude = cleaned;
+        store.replace(settings)?;
+        // The next lookup reads the window list again
+        *app.state::<OcclusionState>().layout.lock().unwrap() = None;
+        Ok(store.get().windows.exclude)
+    })
+    .await?
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+    use core_foundation_sys::dictionary::CFDictionaryRef;
+    use core_graphics::window::{
+        copy_window_info, kCGNullWindowID, kCGWindowListExcludeDesktopElements,
+        kCGWindowListOptionOnScreenOnly,
+    };
+
+    use super::{AppWindow, Rect};
+
+    /// The window list is front to back. Layer 0 is where normal app windows
+    /// live; menus, the Dock and our own always-on-top overlay sit higher.
+    pub fn app_windows() -> Vec<AppWindow> {
+        let Some(list) = copy_window_info(
+            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+            kCGNullWindowID,
+        ) else {
+            return Vec::new();
+        };
+        let own_pid = std::process::id() as i64;
+        list.iter()
+            .filter_map(|item| {
+                // Owned by the list, so don't release it
+                let info: CFDictionary<CFString, CFType> =
+                    unsafe { CFDictionary::wrap_under_get_rule(*item as CFDictionaryRef) };
+                let number = |key: &str| {
+                    info.find(CFString::new(key))?
+                        .downcast::<CFNumber>()?
+                        .to_f64()
+                };
+                if number("kCGWindowLayer")? != 0.0
+                    || number("kCGWindowOwnerPID")? as i64 == own_pid
+                    || number("kCGWindowAlpha").unwrap_or(1.0) == 0.0
+                {
+                    return None;
+                }
+                let app_name = info
+                    .find(CFString::new("kCGWindowOwnerName"))
+                    .and_then(|v| v.downcast::<CFString>())
+                    .map(|s| s.to_string())
+                    .unwrap_or_default();
+                let bounds = info
+                    .find(CFString::new("kCGWindowBounds"))?
+                    .downcast::<CFDictionary>()?;
+                let bounds: CFDictionary<CFString, CFType> =
+                    unsafe { CFDictionary::wrap_under_get_rule(bounds.as_concrete_TypeRef()) };
+                let edge = |key: &str| {
+                    bounds
+                        .find(CFString::new(key))?
+                        .downcast::<CFNumber>()?
+                        .to_f64()
+                };
+                Some(AppWindow {
+                    app_name,
+                    bounds: Rect {
+                        x: edge("X")?,
+                        y: edge("Y")?,
+                        width: edge("Width")?,
+                        height: edge("Height")?,
+                    },
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,225 @@
+// Only macOS has Vision; elsewhere there's never any text to read
+#![cfg_attr(not(target_os = "macos"), allow(dead_code, unused_variables))]
+
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::permissions::{self, PermissionKind, PermissionStatus};
+use crate::settings::SettingsStore;
+
+/// Enough to get the joke; a whole document would drown the rest of the prompt.
+const MAX_PROMPT_CHARS: usize = 800;
+const MAX_TEXT_CHARS: usize = 10_000;
+/// Never read, whatever the settings say.
+const PRIVATE_APPS: &[&str] = &[
+    "1Password",
+    "Bitwarden",
+    "Dashlane",
+    "Keychain Access",
+    "LastPass",
+    "Passwords",
+    "KeePassXC",
+];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowText {
+    pub app_name: String,
+    pub window_title: String,
+    /// Recognized lines in reading order, one per line
+    pub text: String,
+}
+
+/// Read the text visible in the front window. None when it's our own
+/// window or a password manager's.
+fn active_window_text() -> PetResult<Option<WindowText>> {
+    let window = active_win_pos_rs::get_active_window()
+        .map_err(|()| PetError::Platform("Failed to get active window info".to_string()))?;
+    if window.process_id == std::process::id() as u64
+        || PRIVATE_APPS
+            .iter()
+            .any(|private| private.eq_ignore_ascii_case(&window.app_name))
+    {
+        return Ok(None);
+    }
+    let window_id: u32 = window
+        .window_id
+        .parse()
+        .map_err(|_| PetError::Platform("The front window has no id".to_string()))?;
+    #[cfg(target_os = "macos")]
+    let lines = macos::recognize_window(window_id)?;
+    #[cfg(not(target_os = "macos"))]
+    let lines: Vec<String> = Vec::new();
+    let text: String = lines.join("\n").chars().take(MAX_TEXT_CHARS).collect();
+    Ok((!text.trim().is_empty()).then_some(WindowText {
+        app_name: window.app_name,
+        window_title: window.title,
+        text,
+    }))
+}
+
+/// Whether a prompt for `mode` would carry the front window's text: the
+/// owner opted in and Screen Recording is already granted. Never prompts.
+pub fn applies(app: &tauri::AppHandle, mode: &str) -> bool {
+    mode == "judge"
+        && app.state::<SettingsStore>().get().context.send_screen_text
+        && permissions::check(PermissionKind::ScreenRecording, None) == PermissionStatus::Granted
+}
+
+/// The front window's text for a judge prompt, when it `applies`. Call off
+/// the async runtime; recognition takes a moment.
+pub fn text_for_prompt(app: &tauri::AppHandle, mode: &str) -> Option<String> {
+    if !applies(app, mode) {
+        return None;
+    }
+    let window = active_window_text().ok().flatten()?;
+    let flat = window.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut text: String = flat.chars().take(MAX_PROMPT_CHARS).collect();
+    if text.len() < flat.len() {
+        text.push('…');
+    }
+    Some(format!("Text visible in their window: \"{}\"", text))
+}
+
+/// Recognize the text in the front window with Apple's Vision framework.
+/// Fails with a Platform error (and shows the system prompt) until Screen
+/// Recording access is granted.
+#[tauri::command]
+pub async fn ocr_active_window(app: tauri::AppHandle) -> PetResult<Option<WindowText>> {
+    if !cfg!(target_os = "macos") {
+        return Err(PetError::Platform(
+            "Reading window text uses Vision, so it only works on macOS".to_string(),
+        ));
+    }
+    crate::run_blocking(move || {
+        if permissions::check(PermissionKind::ScreenRecording, None) != PermissionStatus::Granted {
+            permissions::request(&app, PermissionKind::ScreenRecording, None);
+            return Err(PetError::Platform(
+                "Desktop Pet needs Screen Recording access (System Settings → Privacy & Security → Screen Recording)"
+                    .to_string(),
+            ));
+        }
+        active_window_text()
+    })
+    .await?
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2::encode::{Encoding, RefEncode};
+    use objc2::msg_send;
+    use objc2::rc::{Allocated, Retained};
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+    use objc2_foundation::{NSArray, NSDictionary, NSError, NSPoint, NSRect, NSSize, NSString};
+
+    use crate::error::{PetError, PetResult};
+
+    // CGWindowListOption and CGWindowImageOption
+    const LIST_INCLUDING_WINDOW: u32 = 1 << 3;
+    const IMAGE_BOUNDS_IGNORE_FRAMING: u32 = 1 << 0;
+    const IMAGE_BEST_RESOLUTION: u32 = 1 << 3;
+    /// VNRequestTextRecognitionLevelAccurate
+    const RECOGNITION_ACCURATE: isize = 0;
+    /// Guesses below this are more often noise than words
+    const MIN_CONFIDENCE: f32 = 0.3;
+
+    #[repr(C)]
+    struct CGImage {
+        _private: [u8; 0],
+    }
+
+    unsafe impl RefEncode for CGImage {
+        const ENCODING_REF: Encoding = Encoding::Pointer(&Encoding::Struct("CGImage", &[]));
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGWindowListCreateImage(
+            bounds: NSRect,
+            list_option: u32,
+            window_id: u32,
+            image_option: u32,
+        ) -> *const CGImage;
+        fn CGImageRelease(image: *const CGImage);
+    }
+
+    // Loads the framework so its classes can be looked up by name
+    #[link(name = "Vision", kind = "framework")]
+    extern "C" {}
+
+    /// A window's contents as an image, released on drop.
+    struct WindowImage(*const CGImage);
+
+    impl Drop for WindowImage {
+        fn drop(&mut self) {
+            unsafe { CGImageRelease(self.0) };
+        }
+    }
+
+    fn capture(window_id: u32) -> Option<WindowImage> {
+        // CGRectNull: the window's own bounds
+        let null = NSRect::new(
+            NSPoint::new(f64::INFINITY, f64::INFINITY),
+            NSSize::new(0.0, 0.0),
+        );
+        let image = unsafe {
+            CGWindowListCreateImage(
+                null,
+                LIST_INCLUDING_WINDOW,
+                window_id,
+                IMAGE_BOUNDS_IGNORE_FRAMING | IMAGE_BEST_RESOLUTION,
+            )
+        };
+        (!image.is_null()).then_some(WindowImage(image))
+    }
+
+    fn class(name: &std::ffi::CStr) -> PetResult<&'static AnyClass> {
+        AnyClass::get(name)
+            .ok_or_else(|| PetError::Platform("Vision isn't available on this Mac".to_string()))
+    }
+
+    /// The lines of text Vision finds in the window, in its reading order.
+    pub fn recognize_window(window_id: u32) -> PetResult<Vec<String>> {
+        let image = capture(window_id)
+            .ok_or_else(|| PetError::Platform("Couldn't capture the front window".to_string()))?;
+        let (request_class, handler_class) = (
+            class(c"VNRecognizeTextRequest")?,
+            class(c"VNImageRequestHandler")?,
+        );
+        unsafe {
+            let request: Retained<AnyObject> = msg_send![request_class, new];
+            let _: () = msg_send![&*request, setRecognitionLevel: RECOGNITION_ACCURATE];
+            let _: () = msg_send![&*request, setUsesLanguageCorrection: Bool::YES];
+            let handler: Allocated<AnyObject> = msg_send![handler_class, alloc];
+            let options = NSDictionary::<NSString, AnyObject>::new();
+            let handler: Option<Retained<AnyObject>> =
+                msg_send![handler, initWithCGImage: image.0, options: &*options];
+            let handler = handler.ok_or_else(|| {
+                PetError::Platform("Vision couldn't read the capture".to_string())
+            })?;
+            let requests = NSArray::from_retained_slice(&[request.clone()]);
+            let performed: Result<(), Retained<NSError>> =
+                msg_send![&*handler, performRequests: &*requests, error: _];
+            performed.map_err(|e| PetError::Platform(e.localizedDescription().to_string()))?;
+            let results: Option<Retained<NSArray<AnyObject>>> = msg_send![&*request, results];
+            let Some(results) = results else {
+                return Ok(Vec::new());
+            };
+            Ok(results
+                .iter()
+                .filter_map(|observation| {
+                    let candidates: Retained<NSArray<AnyObject>> =
+                        msg_send![&*observation, topCandidates: 1usize];
+                    let best = candidates.firstObject()?;
+                    let confidence: f32 = msg_send![&*best, confidence];
+                    if confidence < MIN_CONFIDENCE {
+                        return None;
+                    }
+                    let text: Retained<NSString> = msg_send![&*best, string];
+                    Some(text.to_string())
+                })
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use std::path::Path;
+
+use crate::error::{PetError, PetResult};
+
+/// Open `path` the way the system would: pages in the browser, files in
+/// their usual app. On Windows this goes through ShellExecute, not
+/// `cmd /C start`, which would run whatever follows an `&` in the name.
+pub fn open_path(path: &Path) -> PetResult<()> {
+    tauri_plugin_opener::open_path(path, None::<&str>)
+        .map_err(|e| PetError::Platform(format!("Couldn't open {}: {}", path.display(), e)))
+}
+
+/// Open a web address in the browser.
+pub fn open_url(url: &str) -> PetResult<()> {
+    tauri_plugin_opener::open_url(url, None::<&str>)
+        .map_err(|e| PetError::Platform(format!("Couldn't open {}: {}", url, e)))
+}
@@ -0,0 +1,470 @@
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::net::{IpAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::error::{PetError, PetResult};
+use crate::mood::MoodEngine;
+use crate::notes::NotesStore;
+use crate::pets::PetRegistry;
+use crate::{persist, tray};
+
+pub const COMPANIONS_FILE: &str = "companions.json";
+/// How long a pairing code can be scanned before it has to be regenerated.
+const PAIRING_TTL_MS: i64 = 5 * 60 * 1000;
+/// LAN port the mobile companion connects to.
+pub const COMPANION_PORT: u16 = 47_216;
+const MAX_COMPANIONS: usize = 4;
+const MAX_NAME: usize = 40;
+/// Pairing and status requests are tiny; anything bigger is a mistake.
+const MAX_BODY_BYTES: u64 = 4 * 1024;
+/// Requests served at once, since a status request can wait a while.
+const MAX_CONNECTIONS: usize = 8;
+/// How long a status request waits for something to change before
+/// answering with what there is.
+const LONG_POLL: Duration = Duration::from_secs(25);
+const LONG_POLL_STEP: Duration = Duration::from_millis(500);
+const MAX_REMINDERS: usize = 10;
+
+struct PendingPairing {
+    token_hash: String,
+    expires_at: i64,
+}
+
+/// A paired phone. Only a hash of its token is kept, so the file alone
+/// can't be used to connect.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StoredCompanion {
+    id: String,
+    name: String,
+    /// Unix millis
+    paired_at: i64,
+    token_hash: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Companion {
+    pub id: String,
+    pub name: String,
+    /// Unix millis
+    pub paired_at: i64,
+}
+
+impl From<&StoredCompanion> for Companion {
+    fn from(stored: &StoredCompanion) -> Self {
+        Self {
+            id: stored.id.clone(),
+            name: stored.name.clone(),
+            paired_at: stored.paired_at,
+        }
+    }
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The pairing offer on screen, if any, the phones already paired and the
+/// LAN server they talk to. Generating a new code replaces the old one so
+/// only the latest QR code works.
+pub struct PairingState {
+    pending: Mutex<Option<PendingPairing>>,
+    companions: Mutex<Vec<StoredCompanion>>,
+    path: Option<PathBuf>,
+    server: Mutex<Option<Arc<Server>>>,
+    connections: AtomicUsize,
+}
+
+impl PairingState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, COMPANIONS_FILE).ok();
+        let companions = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            pending: Mutex::new(None),
+            companions: Mutex::new(companions),
+            path,
+            server: Mutex::new(None),
+            connections: AtomicUsize::new(0),
+        }
+    }
+
+    fn save(&self, companions: &[StoredCompanion]) -> PetResult<()> {
+        match &self.path {
+            Some(path) => persist::save(path, &companions),
+            None => Ok(()),
+        }
+    }
+
+    fn list(&self) -> Vec<Companion> {
+        self.companions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(Companion::from)
+            .collect()
+    }
+
+    /// Trade the token from the current unexpired code, once, for a
+    /// companion token of its own.
+    fn redeem(&self, token: &str, name: &str) -> PetResult<(Companion, String)> {
+        let mut pending = self.pending.lock().unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+        let valid = pending
+            .as_ref()
+            .is_some_and(|p| p.token_hash == hash(token) && p.expires_at > now);
+        if !valid {
+            return Err(PetError::Auth(
+                "That pairing code isn't current".to_string(),
+            ));
+        }
+        *pending = None;
+        let mut companions = self.companions.lock().unwrap();
+        if companions.len() >= MAX_COMPANIONS {
+            return Err(PetError::InvalidInput(format!(
+                "There's room for {} companions",
+                MAX_COMPANIONS
+            )));
+        }
+        let device_token = random_hex(32);
+        let stored = StoredCompanion {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            paired_at: now,
+            token_hash: hash(&device_token),
+        };
+        companions.push(stored.clone());
+        self.save(&companions)?;
+        Ok((Companion::from(&stored), device_token))
+    }
+
+    fn is_paired(&self, token: &str) -> bool {
+        let token_hash = hash(token);
+        self.companions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|c| c.token_hash == token_hash)
+    }
+
+    /// Whether anything could connect: a code on screen or a paired phone.
+    fn is_needed(&self) -> bool {
+        let now = chrono::Utc::now().timestamp_millis();
+        let pending = self
+            .pending
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|p| p.expires_at > now);
+        pending || !self.companions.lock().unwrap().is_empty()
+    }
+
+    fn seconds_left(&self) -> Option<i64> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.pending
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|p| (p.expires_at - now) / 1000)
+            .filter(|s| *s > 0)
+    }
+}
+
+/// Listen for companions while a code is on screen or a phone is paired,
+/// and not otherwise.
+pub fn apply(app: &tauri::AppHandle) -> PetResult<()> {
+    let state = app.state::<PairingState>();
+    let wanted = state.is_needed();
+    let mut server = state.server.lock().unwrap();
+    if server.is_some() == wanted {
+        return Ok(());
+    }
+    if let Some(running) = server.take() {
+        running.unblock();
+        return Ok(());
+    }
+
+    // Phones are on the LAN, so this can't be loopback only; everything but
+    // pairing needs a companion token
+    let started = Arc::new(Server::http(("0.0.0.0", COMPANION_PORT)).map_err(|e| {
+        PetError::Platform(format!("Couldn't listen on port {}: {}", COMPANION_PORT, e))
+    })?);
+    *server = Some(started.clone());
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for request in started.incoming_requests() {
+            let connections = &app.state::<PairingState>().connections;
+            if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                connections.fetch_sub(1, Ordering::SeqCst);
+                let _ = request.respond(Response::from_string("").with_status_code(503));
+                continue;
+            }
+            // Its own thread, since a status request waits for news
+            let app = app.clone();
+            std::thread::spawn(move || {
+                serve(&app, request);
+                app.state::<PairingState>()
+                    .connections
+                    .fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct PairBody {
+    token: String,
+    name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Reminder {
+    text: String,
+    /// Unix millis
+    due: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetStatus {
+    pet_name: String,
+    /// What the cat is doing on screen: idle, walking, napping...
+    animation: String,
+    mood: &'static str,
+    reminders: Vec<Reminder>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompanionStatus {
+    /// Changes whenever anything else here does; send it back as `since` to
+    /// wait for the next change
+    version: String,
+    #[serde(flatten)]
+    status: PetStatus,
+}
+
+fn companion_status(app: &tauri::AppHandle) -> PetResult<CompanionStatus> {
+    let registry = app.state::<PetRegistry>().get();
+    let pet_name = registry
+        .pets
+        .iter()
+        .find(|p| p.id == registry.active)
+        .map(|p| p.name.clone())
+        .unwrap_or_default();
+    let mut notes = app.state::<NotesStore>().all();
+    notes.retain(|note| !note.done);
+    // Due ones first, soonest first, then the newest todos
+    notes.sort_by_key(|note| (note.due.is_none(), note.due, -note.created_at));
+    let status = PetStatus {
+        pet_name,
+        animation: app.state::<tray::TrayState>().get().animation,
+        mood: app.state::<MoodEngine>().current().label,
+        reminders: notes
+            .into_iter()
+            .take(MAX_REMINDERS)
+            .map(|note| Reminder {
+                text: note.text,
+                due: note.due,
+            })
+            .collect(),
+    };
+    Ok(CompanionStatus {
+        version: hash(&serde_json::to_string(&status)?),
+        status,
+    })
+}
+
+/// The status once it differs from `since`, or as it is after LONG_POLL.
+fn wait_for_change(app: &tauri::AppHandle, since: Option<&str>) -> PetResult<CompanionStatus> {
+    let started = Instant::now();
+    loop {
+        let status = companion_status(app)?;
+        if since != Some(status.version.as_str()) || started.elapsed() >= LONG_POLL {
+            return Ok(status);
+        }
+        std::thread::sleep(LONG_POLL_STEP);
+    }
+}
+
+fn bearer(request: &Request) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .map(str::to_string)
+}
+
+fn route(app: &tauri::AppHandle, request: &mut Request) -> PetResult<serde_json::Value> {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let state = app.state::<PairingState>();
+    match (request.method(), path) {
+        (Method::Post, "/pair") => {
+            let mut body = String::new();
+            request
+                .as_reader()
+                .take(MAX_BODY_BYTES)
+                .read_to_string(&mut body)?;
+            let body: PairBody = serde_json::from_str(&body)
+                .map_err(|e| PetError::InvalidInput(format!("Bad request body: {}", e)))?;
+            let name = body.name.trim();
+            if name.is_empty() || name.chars().count() > MAX_NAME {
+                return Err(PetError::InvalidInput(format!(
+                    "A name is 1 to {} characters",
+                    MAX_NAME
+                )));
+            }
+            let (companion, token) = state.redeem(&body.token, name)?;
+            let _ = app.emit("companion-paired", &companion);
+            Ok(serde_json::json!({
+                "id": companion.id,
+                "token": token,
+                "status": companion_status(app)?,
+            }))
+        }
+        (Method::Get, "/status") => {
+            if !bearer(request).is_some_and(|token| state.is_paired(&token)) {
+                return Err(PetError::Auth("Not paired".to_string()));
+            }
+            let since = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("since="));
+            Ok(serde_json::to_value(wait_for_change(app, since)?)?)
+        }
+        _ => Err(PetError::InvalidInput("Unknown route".to_string())),
+    }
+}
+
+fn serve(app: &tauri::AppHandle, mut request: Request) {
+    let (status, body) = match route(app, &mut request) {
+        Ok(body) => (200, body),
+        Err(PetError::Auth(message)) => (401, serde_json::json!({ "error": message })),
+        Err(PetError::InvalidInput(message)) => (400, serde_json::json!({ "error": message })),
+        Err(e) => (500, serde_json::json!({ "error": e.to_string() })),
+    };
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let _ = request.respond(
+        Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(header),
+    );
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingCode {
+    /// Six digits shown on both screens so the user can check they match
+    pub code: String,
+    pub host: String,
+    pub port: u16,
+    /// Unix millis
+    pub expires_at: i64,
+    /// What the QR code encodes
+    pub qr_payload: String,
+}
+
+/// The address other devices on the LAN can reach us at. Connecting a UDP
+/// socket sends nothing; it just makes the OS pick the outbound interface.
+fn lan_address() -> PetResult<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+    socket
+        .connect(("192.168.0.1", 9))
+        .map_err(|_| PetError::Platform("Not connected to a local network".to_string()))?;
+    let ip = socket.local_addr()?.ip();
+    if ip.is_unspecified() || ip.is_loopback() {
+        return Err(PetError::Platform(
+            "Not connected to a local network".to_string(),
+        ));
+    }
+    Ok(ip)
+}
+
+/// Start pairing a mobile companion: a short-lived token plus where to find
+/// this machine, packed into a `desktoppet://pair` link for a QR code. The
+/// phone posts the token to `/pair` once to get a token of its own.
+#[tauri::command]
+pub async fn generate_pairing_code(app: tauri::AppHandle) -> PetResult<PairingCode> {
+    crate::run_blocking(move || {
+        let state = app.state::<PairingState>();
+        if state.companions.lock().unwrap().len() >= MAX_COMPANIONS {
+            return Err(PetError::InvalidInput(format!(
+                "There's room for {} companions; remove one first",
+                MAX_COMPANIONS
+            )));
+        }
+        let host = lan_address()?.to_string();
+        let token = random_hex(16);
+        let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+        let expires_at = chrono::Utc::now().timestamp_millis() + PAIRING_TTL_MS;
+        let qr_payload = format!(
+            "desktoppet://pair?host={}&port={}&token={}&code={}",
+            host, COMPANION_PORT, token, code
+        );
+        *state.pending.lock().unwrap() = Some(PendingPairing {
+            token_hash: hash(&token),
+            expires_at,
+        });
+        apply(&app)?;
+        Ok(PairingCode {
+            code,
+            host,
+            port: COMPANION_PORT,
+            expires_at,
+            qr_payload,
+        })
+    })
+    .await?
+}
+
+/// Seconds until the code on screen expires, or None once it has (or was
+/// used), so the dialog can offer a fresh one.
+#[tauri::command(async)]
+pub fn get_pairing_status(state: tauri::State<'_, PairingState>) -> Option<i64> {
+    state.seconds_left()
+}
+
+/// Withdraw the code on screen, e.g. when the pairing dialog is closed.
+#[tauri::command(async)]
+pub fn cancel_pairing_code(app: tauri::AppHandle) -> PetResult<()> {
+    *app.state::<PairingState>().pending.lock().unwrap() = None;
+    apply(&app)
+}
+
+#[tauri::command(async)]
+pub fn list_companions(state: tauri::State<'_, PairingState>) -> Vec<Companion> {
+    state.list()
+}
+
+/// Unpair a phone; its token stops working at once.
+#[tauri::command(async)]
+pub fn remove_companion(app: tauri::AppHandle, id: String) -> PetResult<Vec<Companion>> {
+    let state = app.state::<PairingState>();
+    {
+        let mut companions = state.companions.lock().unwrap();
+        companions.retain(|c| c.id != id);
+        state.save(&companions)?;
+    }
+    apply(&app)?;
+    Ok(state.list())
+}
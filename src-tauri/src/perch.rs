@@ -0,0 +1,149 @@
+// The menu bar is only readable through macOS's Accessibility API
+#![cfg_attr(not(target_os = "macos"), allow(dead_code, unused_variables))]
+
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::accessibility;
+use crate::error::{PetError, PetResult};
+use crate::occlusion::Rect;
+use crate::settings::{PerchSide, SettingsStore};
+
+/// Room the cat needs on the bar, in points.
+const PERCH_WIDTH: f64 = 64.0;
+/// Kept clear next to the menus and the status items.
+const PADDING: f64 = 8.0;
+
+/// The menu bar in the pet window's coordinates.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuBarLayout {
+    pub bar: Rect,
+    /// The front app's menus
+    pub menus: Option<Rect>,
+    /// Every app's status items, the clock and Control Center included
+    pub status_items: Option<Rect>,
+    /// Center of the spot on the favoured side, None when the bar is full
+    pub perch_x: Option<f64>,
+    pub side: PerchSide,
+}
+
+fn union(rects: impl Iterator<Item = Rect>) -> Option<Rect> {
+    rects.reduce(|a, b| {
+        let x = a.x.min(b.x);
+        let y = a.y.min(b.y);
+        Rect {
+            x,
+            y,
+            width: (a.x + a.width).max(b.x + b.width) - x,
+            height: (a.y + a.height).max(b.y + b.height) - y,
+        }
+    })
+}
+
+/// The free stretch lies between the menus and the status items.
+fn perch_x(
+    bar: &Rect,
+    menus: Option<&Rect>,
+    status_items: Option<&Rect>,
+    side: PerchSide,
+) -> Option<f64> {
+    let start = menus.map_or(bar.x, |menus| menus.x + menus.width) + PADDING;
+    let end = status_items.map_or(bar.x + bar.width, |items| items.x) - PADDING;
+    if end - start < PERCH_WIDTH {
+        return None;
+    }
+    Some(match side {
+        PerchSide::Left => start + PERCH_WIDTH / 2.0,
+        PerchSide::Right => end - PERCH_WIDTH / 2.0,
+    })
+}
+
+fn window_width(app: &tauri::AppHandle) -> PetResult<f64> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| PetError::Internal("Pet window not found".to_string()))?;
+    Ok(window
+        .outer_size()?
+        .to_logical::<f64>(window.scale_factor()?)
+        .width)
+}
+
+/// Where the menus and status items are, and where the pet can sit between
+/// them. None when the menu bar isn't on the pet's display.
+pub fn layout(app: &tauri::AppHandle) -> PetResult<Option<MenuBarLayout>> {
+    accessibility::require_trust()?;
+    #[cfg(target_os = "macos")]
+    {
+        let Some((bar, menus)) = macos::menu_bar() else {
+            return Ok(None);
+        };
+        let status_items = macos::status_items();
+        let (origin_x, origin_y) = crate::occlusion::window_origin(app)?;
+        let to_window = |rect: Rect| rect.offset(-origin_x, -origin_y);
+        let bar = to_window(bar);
+        if bar.x + bar.width <= 0.0 || bar.x >= window_width(app)? {
+            return Ok(None);
+        }
+        let menus = menus.map(to_window);
+        let status_items = status_items.map(to_window);
+        let side = app.state::<SettingsStore>().get().display.perch_side;
+        Ok(Some(MenuBarLayout {
+            perch_x: perch_x(&bar, menus.as_ref(), status_items.as_ref(), side),
+            bar,
+            menus,
+            status_items,
+            side,
+        }))
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(None)
+}
+
+/// The menu bar's layout for perching on it. Needs Accessibility access.
+#[tauri::command]
+pub async fn get_menu_bar_layout(app: tauri::AppHandle) -> PetResult<Option<MenuBarLayout>> {
+    crate::run_blocking(move || layout(&app)).await?
+}
+
+/// Favour the end of the free stretch next to the menus, or the one next to
+/// the status items.
+#[tauri::command]
+pub async fn set_perch_side(app: tauri::AppHandle, side: PerchSide) -> PetResult<()> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.display.perch_side = side;
+        store.replace(settings)
+    })
+    .await?
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2_app_kit::NSWorkspace;
+
+    use crate::accessibility::macos::Element;
+    use crate::occlusion::Rect;
+
+    /// The front app's menu bar, and the span its menus take up.
+    pub fn menu_bar() -> Option<(Rect, Option<Rect>)> {
+        let front = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+        let bar = Element::application(front.processIdentifier())?.element("AXMenuBar")?;
+        let menus = super::union(bar.children().iter().filter_map(Element::frame));
+        Some((bar.frame()?, menus))
+    }
+
+    /// The span taken by every app's menu bar extras. Apps that don't answer
+    /// within the messaging timeout are left out.
+    pub fn status_items() -> Option<Rect> {
+        let apps = NSWorkspace::sharedWorkspace().runningApplications();
+        super::union(
+            apps.iter()
+                .filter_map(|app| Element::application(app.processIdentifier()))
+                .filter_map(|app| app.element("AXExtrasMenuBar"))
+                .flat_map(|extras| extras.children())
+                .filter_map(|item| item.frame()),
+        )
+    }
+}
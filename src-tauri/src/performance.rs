@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::error::PetResult;
+use crate::mood::{self, Interaction};
+use crate::occlusion;
+
+/// Cursor updates during a chase, about 120 a second. Outside game mode the
+/// cursor is only polled for click-through.
+const GAME_TICK: Duration = Duration::from_millis(8);
+/// The chase is over once the cursor has been still this long.
+const CHASE_IDLE: Duration = Duration::from_secs(4);
+/// Game mode never outlives this, even if the cursor keeps moving.
+const MAX_GAME: Duration = Duration::from_secs(120);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PerformanceMode {
+    /// Idle-friendly: nothing runs faster than it has to
+    #[default]
+    Normal,
+    /// Cursor chasing: fast cursor updates and a fresh window layout
+    Game,
+}
+
+#[derive(Default)]
+pub struct PerformanceState {
+    mode: Mutex<PerformanceMode>,
+    /// Bumped on every switch, so an old chase loop knows to stop
+    generation: AtomicU64,
+}
+
+pub fn mode(app: &tauri::AppHandle) -> PerformanceMode {
+    *app.state::<PerformanceState>().mode.lock().unwrap()
+}
+
+/// Switch to `mode`, unless `only_from` is given and another switch has
+/// happened since that generation. Returns the new generation.
+fn switch(app: &tauri::AppHandle, mode: PerformanceMode, only_from: Option<u64>) -> Option<u64> {
+    let state = app.state::<PerformanceState>();
+    let mut current = state.mode.lock().unwrap();
+    if only_from.is_some_and(|g| g != state.generation.load(Ordering::SeqCst)) {
+        return None;
+    }
+    *current = mode;
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    drop(current);
+    let _ = app.emit("performance-mode-changed", mode);
+    Some(generation)
+}
+
+/// Publish the cursor as `cursor-moved` until the chase ends, then drop
+/// back to normal. Ticks are scheduled against fixed deadlines rather than
+/// sleeping a fixed time after each one, so the rate doesn't drift with
+/// the work done per tick.
+fn run_chase(app: tauri::AppHandle, generation: u64) {
+    let started = Instant::now();
+    let mut last_moved = started;
+    let mut last_position = None;
+    let mut deadline = started;
+    loop {
+        let state = app.state::<PerformanceState>();
+        if state.generation.load(Ordering::SeqCst) != generation {
+            // Switched again since; that switch owns the mode now
+            return;
+        }
+        if last_moved.elapsed() >= CHASE_IDLE || started.elapsed() >= MAX_GAME {
+            switch(&app, PerformanceMode::Normal, Some(generation));
+            return;
+        }
+
+        if let Ok(position) = crate::get_mouse_position() {
+            if last_position != Some(position) {
+                last_position = Some(position);
+                last_moved = Instant::now();
+                let _ = app.emit("cursor-moved", position);
+            }
+        }
+
+        deadline += GAME_TICK;
+        let now = Instant::now();
+        if deadline > now {
+            std::thread::sleep(deadline - now);
+        } else {
+            // Fell behind (the Mac was busy); don't try to catch up in a burst
+            deadline = now;
+        }
+    }
+}
+
+/// Switch modes. Game mode reads the window layout up front and starts the
+/// cursor updates; it reverts to normal on its own once the cursor rests.
+pub fn set_mode(app: &tauri::AppHandle, mode: PerformanceMode) {
+    let Some(generation) = switch(app, mode, None) else {
+        return;
+    };
+    if mode == PerformanceMode::Game {
+        occlusion::warm_layout(app);
+        mood::note(app, Interaction::Played);
+        let app = app.clone();
+        std::thread::spawn(move || run_chase(app, generation));
+    }
+}
+
+#[tauri::command(async)]
+pub fn get_performance_mode(app: tauri::AppHandle) -> PerformanceMode {
+    mode(&app)
+}
+
+/// `game` for a cursor chase, `normal` to end it early.
+#[tauri::command]
+pub async fn set_performance_mode(
+    app: tauri::AppHandle,
+    mode: PerformanceMode,
+) -> PetResult<PerformanceMode> {
+    crate::run_blocking(move || {
+        set_mode(&app, mode);
+        self::mode(&app)
+    })
+    .await
+}
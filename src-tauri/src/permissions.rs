@@ -0,0 +1,295 @@
+// Only macOS gates these behind privacy prompts; elsewhere every check
+// reports Unsupported
+#![cfg_attr(not(target_os = "macos"), allow(dead_code, unused_variables))]
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::error::{PetError, PetResult};
+
+/// App asked about when checking Automation without a target. Scripting it
+/// is covered by the same prompt the browsers get.
+const DEFAULT_AUTOMATION_TARGET: &str = "com.apple.systemevents";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionKind {
+    /// Reading other apps' UI: selections, the menu bar, the Dock
+    Accessibility,
+    /// Reading other apps' window titles and pixels
+    ScreenRecording,
+    Microphone,
+    /// Scripting another app, like asking a browser for its tab
+    Automation,
+}
+
+impl PermissionKind {
+    /// The pane in System Settings → Privacy & Security.
+    fn settings_anchor(self) -> &'static str {
+        match self {
+            PermissionKind::Accessibility => "Privacy_Accessibility",
+            PermissionKind::ScreenRecording => "Privacy_ScreenCapture",
+            PermissionKind::Microphone => "Privacy_Microphone",
+            PermissionKind::Automation => "Privacy_Automation",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// Never asked, so requesting shows the system prompt
+    NotDetermined,
+    /// macOS won't say, e.g. the app to automate isn't running
+    Unknown,
+    /// Not something this platform asks about
+    Unsupported,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionState {
+    pub kind: PermissionKind,
+    pub status: PermissionStatus,
+}
+
+/// Where a permission stands. Accessibility and Screen Recording only
+/// report granted or not, so they never come back NotDetermined.
+pub fn check(kind: PermissionKind, target: Option<&str>) -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    {
+        let granted_or_denied = |granted: bool| {
+            if granted {
+                PermissionStatus::Granted
+            } else {
+                PermissionStatus::Denied
+            }
+        };
+        match kind {
+            PermissionKind::Accessibility => granted_or_denied(crate::accessibility::is_trusted()),
+            PermissionKind::ScreenRecording => granted_or_denied(macos::screen_recording()),
+            PermissionKind::Microphone => macos::microphone(),
+            PermissionKind::Automation => {
+                macos::automation(target.unwrap_or(DEFAULT_AUTOMATION_TARGET), false)
+            }
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    PermissionStatus::Unsupported
+}
+
+/// Show the system prompt for a permission where macOS still offers one.
+/// Returns the status afterwards; the microphone answer arrives later as
+/// a `permission-changed` event.
+pub fn request(
+    app: &tauri::AppHandle,
+    kind: PermissionKind,
+    target: Option<&str>,
+) -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    {
+        match kind {
+            PermissionKind::Accessibility => crate::accessibility::prompt_for_trust(),
+            PermissionKind::ScreenRecording => macos::request_screen_recording(),
+            PermissionKind::Microphone => {
+                let app = app.clone();
+                macos::request_microphone(move |_| announce(&app, kind, None));
+            }
+            // Blocks until the user answers the prompt
+            PermissionKind::Automation => {
+                macos::automation(target.unwrap_or(DEFAULT_AUTOMATION_TARGET), true);
+            }
+        }
+    }
+    announce(app, kind, target)
+}
+
+fn announce(
+    app: &tauri::AppHandle,
+    kind: PermissionKind,
+    target: Option<&str>,
+) -> PermissionStatus {
+    let status = check(kind, target);
+    let _ = app.emit("permission-changed", PermissionState { kind, status });
+    status
+}
+
+/// Open the Privacy & Security pane for a permission, the only way back
+/// once it has been denied.
+pub fn open_settings(kind: PermissionKind) -> PetResult<()> {
+    if !cfg!(target_os = "macos") {
+        return Err(PetError::Platform(
+            "Permissions are only managed on macOS".to_string(),
+        ));
+    }
+    let url = format!(
+        "x-apple.systempreferences:com.apple.preference.security?{}",
+        kind.settings_anchor()
+    );
+    std::process::Command::new("open")
+        .arg(url)
+        .spawn()
+        .map_err(|e| PetError::Platform(format!("Couldn't open System Settings: {}", e)))?;
+    Ok(())
+}
+
+/// `target` is the bundle id of the app to automate, System Events if left
+/// out; the other kinds ignore it.
+#[tauri::command]
+pub async fn check_permission(
+    kind: PermissionKind,
+    target: Option<String>,
+) -> PetResult<PermissionStatus> {
+    crate::run_blocking(move || check(kind, target.as_deref())).await
+}
+
+#[tauri::command]
+pub async fn request_permission(
+    app: tauri::AppHandle,
+    kind: PermissionKind,
+    target: Option<String>,
+) -> PetResult<PermissionStatus> {
+    crate::run_blocking(move || request(&app, kind, target.as_deref())).await
+}
+
+#[tauri::command]
+pub async fn open_permission_settings(kind: PermissionKind) -> PetResult<()> {
+    crate::run_blocking(move || open_settings(kind)).await?
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::runtime::{AnyClass, Bool};
+    use objc2_foundation::NSString;
+    use std::ffi::c_void;
+
+    use super::PermissionStatus;
+
+    /// typeApplicationBundleID ('bund')
+    const TYPE_BUNDLE_ID: u32 = u32::from_be_bytes(*b"bund");
+    /// typeWildCard ('****'): any event class or id
+    const TYPE_WILD_CARD: u32 = u32::from_be_bytes(*b"****");
+    const ERR_AE_EVENT_NOT_PERMITTED: i32 = -1743;
+    const ERR_AE_EVENT_WOULD_REQUIRE_USER_CONSENT: i32 = -1744;
+
+    // AVAuthorizationStatus
+    const AV_NOT_DETERMINED: isize = 0;
+    const AV_AUTHORIZED: isize = 3;
+
+    #[repr(C)]
+    struct AEDesc {
+        descriptor_type: u32,
+        data_handle: *mut c_void,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+        fn CGRequestScreenCaptureAccess() -> bool;
+    }
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        fn AECreateDesc(
+            type_code: u32,
+            data: *const c_void,
+            size: isize,
+            result: *mut AEDesc,
+        ) -> i16;
+        fn AEDisposeDesc(desc: *mut AEDesc) -> i16;
+        fn AEDeterminePermissionToAutomateTarget(
+            target: *const AEDesc,
+            event_class: u32,
+            event_id: u32,
+            ask_user_if_needed: u8,
+        ) -> i32;
+    }
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: &'static NSString;
+    }
+
+    pub fn screen_recording() -> bool {
+        unsafe { CGPreflightScreenCaptureAccess() }
+    }
+
+    /// Only prompts the first time; after that the answer stands until it's
+    /// changed in System Settings.
+    pub fn request_screen_recording() {
+        unsafe {
+            CGRequestScreenCaptureAccess();
+        }
+    }
+
+    fn capture_device() -> Option<&'static AnyClass> {
+        AnyClass::get(c"AVCaptureDevice")
+    }
+
+    pub fn microphone() -> PermissionStatus {
+        let Some(class) = capture_device() else {
+            return PermissionStatus::Unknown;
+        };
+        let status: isize =
+            unsafe { msg_send![class, authorizationStatusForMediaType: AVMediaTypeAudio] };
+        match status {
+            AV_NOT_DETERMINED => PermissionStatus::NotDetermined,
+            AV_AUTHORIZED => PermissionStatus::Granted,
+            // Denied, or restricted by a profile
+            _ => PermissionStatus::Denied,
+        }
+    }
+
+    pub fn request_microphone(answered: impl Fn(bool) + 'static) {
+        let Some(class) = capture_device() else {
+            return;
+        };
+        let block = RcBlock::new(move |granted: Bool| answered(granted.as_bool()));
+        unsafe {
+            let _: () = msg_send![
+                class,
+                requestAccessForMediaType: AVMediaTypeAudio,
+                completionHandler: &*block
+            ];
+        }
+    }
+
+    /// Whether we may send Apple events to the app with `bundle_id`, asking
+    /// (and blocking until answered) when `ask` is set.
+    pub fn automation(bundle_id: &str, ask: bool) -> PermissionStatus {
+        let mut target = AEDesc {
+            descriptor_type: 0,
+            data_handle: std::ptr::null_mut(),
+        };
+        let status = unsafe {
+            if AECreateDesc(
+                TYPE_BUNDLE_ID,
+                bundle_id.as_ptr().cast(),
+                bundle_id.len() as isize,
+                &mut target,
+            ) != 0
+            {
+                return PermissionStatus::Unknown;
+            }
+            let status = AEDeterminePermissionToAutomateTarget(
+                &target,
+                TYPE_WILD_CARD,
+                TYPE_WILD_CARD,
+                ask as u8,
+            );
+            AEDisposeDesc(&mut target);
+            status
+        };
+        match status {
+            0 => PermissionStatus::Granted,
+            ERR_AE_EVENT_NOT_PERMITTED => PermissionStatus::Denied,
+            ERR_AE_EVENT_WOULD_REQUIRE_USER_CONSENT => PermissionStatus::NotDetermined,
+            // procNotFound: the app has to be running to be asked about
+            _ => PermissionStatus::Unknown,
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+
+/// Rotated known-good copies kept next to each persisted file.
+pub const BACKUP_GENERATIONS: usize = 3;
+
+/// On-disk wrapper so a truncated or hand-mangled file is detected on load
+/// instead of silently deserializing into defaults.
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    checksum: String,
+    data: serde_json::Value,
+}
+
+fn checksum(data: &serde_json::Value) -> String {
+    let bytes = serde_json::to_vec(data).unwrap_or_default();
+    Sha256::digest(&bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Path of a rotated backup; generation 1 is the most recent.
+pub fn backup_path(path: &Path, generation: usize) -> PathBuf {
+    with_suffix(path, &format!(".bak.{}", generation))
+}
+
+fn backup_paths(path: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    (1..=BACKUP_GENERATIONS).map(move |g| backup_path(path, g))
+}
+
+/// Resolve a file inside the app data dir, creating the dir if needed.
+pub fn app_data_file(app: &tauri::AppHandle, name: &str) -> PetResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| PetError::Storage(format!("Failed to get app data dir: {}", e)))?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(name))
+}
+
+/// Write `value` to `path` crash-safely: serialize into a temp file, fsync it,
+/// rotate the current file into the backups, then atomically rename into place.
+pub fn save<T: Serialize>(path: &Path, value: &T) -> PetResult<()> {
+    let data = serde_json::to_value(value)?;
+    let envelope = Envelope {
+        checksum: checksum(&data),
+        data,
+    };
+    let json = serde_json::to_string_pretty(&envelope)?;
+
+    let tmp = with_suffix(path, ".tmp");
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+    }
+
+    // Only rotate the current file into the backups if it is itself valid,
+    // so a corrupt file never pushes a good copy out of the rotation.
+    if read_valid::<serde_json::Value>(path).is_some() {
+        for generation in (1..BACKUP_GENERATIONS).rev() {
+            let from = backup_path(path, generation);
+            if from.exists() {
+                let _ = fs::rename(&from, backup_path(path, generation + 1));
+            }
+        }
+        let _ = fs::rename(path, backup_path(path, 1));
+    }
+    fs::rename(&tmp, path)?;
+
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Read and validate a single file. Accepts both checksummed envelopes and
+/// legacy plain JSON written before the envelope existed.
+fn read_valid<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let raw = fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&raw).ok()?;
+
+    if let Ok(envelope) = serde_json::from_value::<Envelope>(value.clone()) {
+        if envelope.checksum != checksum(&envelope.data) {
+            return None;
+        }
+        return serde_json::from_value(envelope.data).ok();
+    }
+    serde_json::from_value(value).ok()
+}
+
+/// Whether `path` holds plain JSON from before the envelope existed. Such
+/// files still load, but damage to them can't be detected.
+pub fn is_legacy(path: &Path) -> bool {
+    let Some(value) = fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+    else {
+        return false;
+    };
+    serde_json::from_value::<Envelope>(value).is_err()
+}
+
+/// Load `path`, falling back to the newest valid backup if the primary file
+/// is missing, truncated, or fails its checksum.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    read_valid(path).or_else(|| backup_paths(path).find_map(|p| read_valid(&p)))
+}
+
+/// Load a specific backup generation, if it exists and is valid.
+pub fn load_backup<T: DeserializeOwned>(path: &Path, generation: usize) -> Option<T> {
+    if generation == 0 || generation > BACKUP_GENERATIONS {
+        return None;
+    }
+    read_valid(&backup_path(path, generation))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub generation: usize,
+    /// Unix millis of the backup file's last modification
+    pub modified_at: Option<i64>,
+    pub size_bytes: u64,
+    pub valid: bool,
+}
+
+/// Describe the backups that exist for `path`, newest first.
+pub fn list_backups(path: &Path) -> Vec<BackupInfo> {
+    (1..=BACKUP_GENERATIONS)
+        .filter_map(|generation| {
+            let p = backup_path(path, generation);
+            let meta = fs::metadata(&p).ok()?;
+            let modified_at = meta
+                .modified()
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp_millis());
+            Some(BackupInfo {
+                generation,
+                modified_at,
+                size_bytes: meta.len(),
+                valid: read_valid::<serde_json::Value>(&p).is_some(),
+            })
+        })
+        .collect()
+}
+
+/// Delete every backup of `path`, e.g. after rewriting it in a form the old
+/// copies must not outlive (plaintext after enabling encryption).
+pub fn remove_backups(path: &Path) {
+    for p in backup_paths(path) {
+        let _ = fs::remove_file(p);
+    }
+}
+
+/// Remove a persisted file along with its backups and any stray temp file.
+pub fn remove(path: &Path) -> PetResult<()> {
+    let files = std::iter::once(path.to_path_buf())
+        .chain(backup_paths(path))
+        .chain(std::iter::once(with_suffix(path, ".tmp")));
+    for p in files {
+        if p.exists() {
+            fs::remove_file(&p)
+                .map_err(|e| PetError::Storage(format!("Failed to delete {}: {}", p.display(), e)))?;
+        }
+    }
+    Ok(())
+}
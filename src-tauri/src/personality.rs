@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::settings::SettingsStore;
+
+/// Folder in the app data dir holding one `<id>.toml` per personality.
+pub const PERSONALITY_DIR: &str = "personalities";
+/// Keeps a runaway pack from eating the prompt.
+const MAX_ITEMS: usize = 8;
+const MAX_FIELD_CHARS: usize = 300;
+
+/// Written into the folder the first time it's created, as something to
+/// pick straight away and as examples to copy.
+const PRESETS: &[(&str, &str)] = &[
+    (
+        "grumpy-old-cat",
+        r#"name = "Grumpy Old Cat"
+description = "Has seen it all, approves of none of it."
+traits = ["world-weary", "secretly fond of the owner", "suspicious of anything new"]
+speaking_style = "Dry, clipped complaints. Grumbles about how things were better in the old days."
+catchphrases = ["Back in my day...", "Hmph.", "Kids these days."]
+forbidden_topics = []
+"#,
+    ),
+    (
+        "overly-dramatic",
+        r#"name = "Overly Dramatic"
+description = "Every minor event is a tragedy or a triumph of epic proportions."
+traits = ["theatrical", "easily scandalized", "craves attention"]
+speaking_style = "Grand, breathless declarations, as if performing on stage."
+catchphrases = ["The betrayal!", "I shall never recover.", "Truly, a day for the history books."]
+forbidden_topics = []
+"#,
+    ),
+    (
+        "zen-monk-cat",
+        r#"name = "Zen Monk Cat"
+description = "Calm, unhurried and quietly wise."
+traits = ["serene", "patient", "gently encouraging"]
+speaking_style = "Short, peaceful sentences, like koans. Never rushes, never scolds."
+catchphrases = ["Breathe.", "The nap is the path.", "This too shall pass."]
+forbidden_topics = []
+"#,
+    ),
+];
+
+/// A personality pack as written in its TOML file. Every field is optional.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Personality {
+    pub name: String,
+    pub description: String,
+    pub traits: Vec<String>,
+    pub speaking_style: String,
+    pub catchphrases: Vec<String>,
+    /// Subjects the cat never brings up, even when asked
+    pub forbidden_topics: Vec<String>,
+}
+
+fn clip(text: &str) -> String {
+    text.trim().chars().take(MAX_FIELD_CHARS).collect()
+}
+
+fn clip_all(items: &[String]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| clip(item))
+        .filter(|item| !item.is_empty())
+        .take(MAX_ITEMS)
+        .collect()
+}
+
+impl Personality {
+    /// The pack as system prompt instructions. Empty fields are left out.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        let name = clip(&self.name);
+        if !name.is_empty() {
+            parts.push(format!("Your personality: {}.", name));
+        }
+        let description = clip(&self.description);
+        if !description.is_empty() {
+            parts.push(description);
+        }
+        let traits = clip_all(&self.traits);
+        if !traits.is_empty() {
+            parts.push(format!("You are {}.", traits.join(", ")));
+        }
+        let style = clip(&self.speaking_style);
+        if !style.is_empty() {
+            parts.push(format!("Speaking style: {}", style));
+        }
+        let catchphrases = clip_all(&self.catchphrases);
+        if !catchphrases.is_empty() {
+            let quoted: Vec<String> = catchphrases.iter().map(|c| format!("\"{}\"", c)).collect();
+            parts.push(format!(
+                "Now and then, not every time, use one of your catchphrases: {}.",
+                quoted.join(", ")
+            ));
+        }
+        let forbidden = clip_all(&self.forbidden_topics);
+        if !forbidden.is_empty() {
+            parts.push(format!(
+                "Never bring up or discuss these topics, even if asked: {}.",
+                forbidden.join(", ")
+            ));
+        }
+        parts.join(" ")
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalityInfo {
+    /// The file name without `.toml`; what settings refer to it by
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub active: bool,
+    /// Why the file didn't load, so it can be fixed
+    pub error: Option<String>,
+}
+
+/// The personalities folder, with the presets written into it the first
+/// time. Deleting a preset afterwards keeps it deleted.
+fn dir(app: &tauri::AppHandle) -> PetResult<PathBuf> {
+    let dir = crate::persist::app_data_file(app, PERSONALITY_DIR)?;
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+        for (id, preset) in PRESETS {
+            fs::write(dir.join(format!("{}.toml", id)), preset)?;
+        }
+    }
+    Ok(dir)
+}
+
+fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn read(path: &Path) -> PetResult<Personality> {
+    let text = fs::read_to_string(path)?;
+    toml::from_str(&text).map_err(|e| PetError::Parse(e.message().to_string()))
+}
+
+fn load(app: &tauri::AppHandle, id: &str) -> PetResult<Personality> {
+    if !valid_id(id) {
+        return Err(PetError::InvalidInput(format!(
+            "\"{}\" isn't a personality",
+            id
+        )));
+    }
+    let path = dir(app)?.join(format!("{}.toml", id));
+    if !path.exists() {
+        return Err(PetError::InvalidInput(format!(
+            "No personality called \"{}\"",
+            id
+        )));
+    }
+    read(&path)
+}
+
+/// The selected personality, read fresh so edits to its file apply to the
+/// next line. None when there isn't one or its file is broken.
+pub fn active(app: &tauri::AppHandle) -> Option<Personality> {
+    let id = app.state::<SettingsStore>().get().personality.active?;
+    load(app, &id).ok()
+}
+
+/// Every personality in the folder, broken files included with the reason.
+pub fn list(app: &tauri::AppHandle) -> PetResult<Vec<PersonalityInfo>> {
+    let active = app.state::<SettingsStore>().get().personality.active;
+    let mut personalities: Vec<PersonalityInfo> = fs::read_dir(dir(app)?)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_str()?.to_string();
+            if !valid_id(&id) {
+                return None;
+            }
+            let (personality, error) = match read(&path) {
+                Ok(personality) => (personality, None),
+                Err(e) => (Personality::default(), Some(e.to_string())),
+            };
+            Some(PersonalityInfo {
+                name: if personality.name.trim().is_empty() {
+                    id.clone()
+                } else {
+                    personality.name
+                },
+                description: personality.description,
+                active: active.as_deref() == Some(id.as_str()),
+                error,
+                id,
+            })
+        })
+        .collect();
+    personalities.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(personalities)
+}
+
+#[tauri::command]
+pub async fn get_personalities(app: tauri::AppHandle) -> PetResult<Vec<PersonalityInfo>> {
+    crate::run_blocking(move || list(&app)).await?
+}
+
+/// Switch personality, or go back to the plain cat with None. The file has
+/// to load before it's selected.
+#[tauri::command]
+pub async fn set_personality(
+    app: tauri::AppHandle,
+    id: Option<String>,
+) -> PetResult<Vec<PersonalityInfo>> {
+    crate::run_blocking(move || {
+        if let Some(id) = &id {
+            load(&app, id)?;
+        }
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.personality.active = id;
+        store.replace(settings)?;
+        list(&app)
+    })
+    .await?
+}
+
+/// Where the TOML files live, for adding or editing one.
+#[tauri::command]
+pub async fn get_personalities_dir(app: tauri::AppHandle) -> PetResult<String> {
+    crate::run_blocking(move || Ok(dir(&app)?.to_string_lossy().into_owned())).await?
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::memory::MemoryStore;
+use crate::mood::MoodEngine;
+use crate::persist;
+
+pub const PETS_FILE: &str = "pets.json";
+/// Each pet but the first keeps its memory and mood in here, under its id.
+pub const PETS_DIR: &str = "pets";
+/// The pet every install starts with, whose files stay where they always
+/// were in the app data dir.
+const FIRST_PET: &str = "default";
+const MAX_PETS: usize = 8;
+const MAX_NAME: usize = 30;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PetProfile {
+    pub id: String,
+    pub name: String,
+    /// Unix millis
+    pub created_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PetsData {
+    pub pets: Vec<PetProfile>,
+    pub active: String,
+}
+
+impl Default for PetsData {
+    fn default() -> Self {
+        Self {
+            pets: vec![PetProfile {
+                id: FIRST_PET.to_string(),
+                name: "Cat".to_string(),
+                created_at: chrono::Utc::now().timestamp_millis(),
+            }],
+            active: FIRST_PET.to_string(),
+        }
+    }
+}
+
+/// Which pets there are and whose memory and mood are loaded. Settings,
+/// stats and everything else are shared by the household.
+pub struct PetRegistry {
+    data: Mutex<PetsData>,
+    path: Option<PathBuf>,
+}
+
+impl PetRegistry {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, PETS_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    fn save(&self, data: &PetsData) -> PetResult<()> {
+        match &self.path {
+            Some(path) => persist::save(path, data),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self) -> PetsData {
+        self.data.lock().unwrap().clone()
+    }
+
+    pub fn active(&self) -> String {
+        self.data.lock().unwrap().active.clone()
+    }
+
+    fn add(&self, name: &str) -> PetResult<PetProfile> {
+        let mut data = self.data.lock().unwrap();
+        if data.pets.len() >= MAX_PETS {
+            return Err(PetError::InvalidInput(format!(
+                "There's room for {} cats",
+                MAX_PETS
+            )));
+        }
+        let pet = PetProfile {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        data.pets.push(pet.clone());
+        self.save(&data)?;
+        Ok(pet)
+    }
+
+    /// Add the cats another machine has that this one doesn't, as room
+    /// allows. Returns whether any were added.
+    pub fn merge(&self, others: Vec<PetProfile>) -> PetResult<bool> {
+        let mut data = self.data.lock().unwrap();
+        let before = data.pets.len();
+        for pet in others {
+            if data.pets.len() < MAX_PETS && !data.pets.iter().any(|p| p.id == pet.id) {
+                data.pets.push(pet);
+            }
+        }
+        if data.pets.len() == before {
+            return Ok(false);
+        }
+        self.save(&data)?;
+        Ok(true)
+    }
+
+    /// Take on another machine's household whole, as from an archive.
+    pub fn replace(&self, data: PetsData) -> PetResult<()> {
+        let mut current = self.data.lock().unwrap();
+        *current = data;
+        self.save(&current)
+    }
+
+    fn set_active(&self, id: &str) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        data.active = id.to_string();
+        self.save(&data)
+    }
+}
+
+/// Check a household from elsewhere before it replaces this one: ids go
+/// into file paths, and the cat that's out has to be one of them.
+pub fn check(data: &PetsData) -> PetResult<()> {
+    let invalid = |reason: &str| Err(PetError::InvalidInput(format!("Those cats {}", reason)));
+    if data.pets.is_empty() || data.pets.len() > MAX_PETS {
+        return invalid(&format!("aren't 1 to {} cats", MAX_PETS));
+    }
+    if data
+        .pets
+        .iter()
+        .any(|p| p.id != FIRST_PET && uuid::Uuid::parse_str(&p.id).is_err())
+    {
+        return invalid("have an id that isn't one");
+    }
+    if !data.pets.iter().any(|p| p.id == data.active) {
+        return invalid("don't include the one that's out");
+    }
+    Ok(())
+}
+
+/// `name` (e.g. chat_memory.json) for the pet with `id`.
+pub fn pet_file(app: &tauri::AppHandle, id: &str, name: &str) -> PetResult<PathBuf> {
+    if id == FIRST_PET {
+        return persist::app_data_file(app, name);
+    }
+    let dir = persist::app_data_file(app, PETS_DIR)?.join(id);
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(name))
+}
+
+/// `name` (e.g. chat_memory.json) for the pet whose data is loaded.
+pub fn data_file(app: &tauri::AppHandle, name: &str) -> PetResult<PathBuf> {
+    pet_file(app, &app.state::<PetRegistry>().active(), name)
+}
+
+#[tauri::command(async)]
+pub fn list_pets(registry: tauri::State<'_, PetRegistry>) -> PetsData {
+    registry.get()
+}
+
+/// Add a cat with its own memory, facts, mood and needs. It starts out
+/// knowing nothing; `switch_pet` makes it the one on screen.
+#[tauri::command(async)]
+pub fn create_pet(registry: tauri::State<'_, PetRegistry>, name: String) -> PetResult<PetProfile> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME {
+        return Err(PetError::InvalidInput(format!(
+            "A name is 1 to {} characters",
+            MAX_NAME
+        )));
+    }
+    registry.add(name)
+}
+
+/// Load another cat's memory and mood in place of this one's, which are
+/// saved first. Emits `pet-switched` with the new cat, and its mood.
+#[tauri::command]
+pub async fn switch_pet(app: tauri::AppHandle, id: String) -> PetResult<PetProfile> {
+    let pet = crate::run_blocking({
+        let app = app.clone();
+        move || {
+            let registry = app.state::<PetRegistry>();
+            let pet = registry
+                .get()
+                .pets
+                .into_iter()
+                .find(|p| p.id == id)
+                .ok_or_else(|| PetError::InvalidInput(format!("No pet with id {}", id)))?;
+            if registry.active() == pet.id {
+                return Ok(pet);
+            }
+            let (memory, mood) = (app.state::<MemoryStore>(), app.state::<MoodEngine>());
+            memory.switch(&app, || mood.switch(&app, || registry.set_active(&pet.id)))?;
+            Ok::<_, PetError>(pet)
+        }
+    })
+    .await??;
+    crate::mood::refresh(&app);
+    app.emit("pet-switched", &pet)?;
+    Ok(pet)
+}
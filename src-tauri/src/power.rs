@@ -0,0 +1,283 @@
+// Only macOS reports sleep and lock so far; elsewhere nothing calls `handle`
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager};
+
+use crate::error::PetResult;
+use crate::scheduler;
+use crate::settings::SettingsStore;
+
+/// Whether the Mac is asleep or sitting behind the lock screen. Nobody is
+/// around to hear the cat, so background jobs skip their ticks meanwhile.
+#[derive(Default)]
+pub struct PowerState {
+    asleep: AtomicBool,
+    locked: AtomicBool,
+    /// Low on battery: poll and animate less often
+    saving: AtomicBool,
+}
+
+impl PowerState {
+    fn is_suspended(&self) -> bool {
+        self.asleep.load(Ordering::SeqCst) || self.locked.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum PowerEvent {
+    /// System sleep or the displays going dark
+    Sleep,
+    Wake,
+    Lock,
+    Unlock,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PowerPayload {
+    event: PowerEvent,
+    suspended: bool,
+}
+
+pub fn is_suspended(app: &tauri::AppHandle) -> bool {
+    app.try_state::<PowerState>()
+        .is_some_and(|state| state.is_suspended())
+}
+
+fn handle(app: &tauri::AppHandle, event: PowerEvent) {
+    let state = app.state::<PowerState>();
+    let was_suspended = state.is_suspended();
+    match event {
+        PowerEvent::Sleep => state.asleep.store(true, Ordering::SeqCst),
+        PowerEvent::Wake => state.asleep.store(false, Ordering::SeqCst),
+        PowerEvent::Lock => state.locked.store(true, Ordering::SeqCst),
+        PowerEvent::Unlock => state.locked.store(false, Ordering::SeqCst),
+    }
+    let suspended = state.is_suspended();
+    // Sleep and lock usually arrive together; the webview only cares when
+    // the overall state flips
+    if suspended != was_suspended {
+        let _ = app.emit("power-state", PowerPayload { event, suspended });
+    }
+}
+
+pub fn is_saving(app: &tauri::AppHandle) -> bool {
+    app.try_state::<PowerState>()
+        .is_some_and(|state| state.saving.load(Ordering::SeqCst))
+}
+
+pub struct Battery {
+    pub percent: u8,
+    pub charging: bool,
+    /// Running off the battery rather than the charger
+    pub on_battery: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+    /// None on machines without a battery
+    pub battery_percent: Option<u8>,
+    pub charging: bool,
+    pub on_battery: bool,
+    pub low_power_mode: bool,
+    /// Whether the pet is currently polling and animating less
+    pub saving: bool,
+}
+
+pub fn battery() -> Option<Battery> {
+    #[cfg(target_os = "macos")]
+    return macos::battery();
+    #[cfg(not(target_os = "macos"))]
+    None
+}
+
+fn low_power_mode() -> bool {
+    #[cfg(target_os = "macos")]
+    return objc2_foundation::NSProcessInfo::processInfo().isLowPowerModeEnabled();
+    #[cfg(not(target_os = "macos"))]
+    false
+}
+
+fn read_status(app: &tauri::AppHandle) -> PowerStatus {
+    let threshold = app
+        .state::<SettingsStore>()
+        .get()
+        .battery
+        .saver_threshold_percent;
+    let battery = battery();
+    let low_power_mode = low_power_mode();
+    let on_battery = battery.as_ref().is_some_and(|b| b.on_battery);
+    let running_low = battery
+        .as_ref()
+        .is_some_and(|b| b.on_battery && b.percent <= threshold);
+    PowerStatus {
+        battery_percent: battery.as_ref().map(|b| b.percent),
+        charging: battery.as_ref().is_some_and(|b| b.charging),
+        on_battery,
+        low_power_mode,
+        saving: running_low || (on_battery && low_power_mode),
+    }
+}
+
+/// One line for the dialogue context once the battery is running low, so
+/// the cat can nag about it.
+pub fn battery_context(app: &tauri::AppHandle) -> Option<String> {
+    let threshold = app
+        .state::<SettingsStore>()
+        .get()
+        .battery
+        .saver_threshold_percent;
+    let battery = battery().filter(|b| b.on_battery && b.percent <= threshold)?;
+    Some(format!(
+        "User's laptop battery is at {}% and not plugged in.",
+        battery.percent
+    ))
+}
+
+async fn check_battery(app: tauri::AppHandle) {
+    let status = crate::run_blocking({
+        let app = app.clone();
+        move || read_status(&app)
+    })
+    .await;
+    let Ok(status) = status else {
+        return;
+    };
+    let state = app.state::<PowerState>();
+    if state.saving.swap(status.saving, Ordering::SeqCst) != status.saving {
+        let _ = app.emit("power-saving", status.saving);
+    }
+}
+
+pub fn init(app: &tauri::App) {
+    app.manage(PowerState::default());
+    #[cfg(target_os = "macos")]
+    macos::observe(app.handle());
+    scheduler::every_tick(app.handle().clone(), check_battery);
+}
+
+#[tauri::command]
+pub async fn get_power_status(app: tauri::AppHandle) -> PetResult<PowerStatus> {
+    crate::run_blocking(move || read_status(&app)).await
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use block2::RcBlock;
+    use objc2_app_kit::{
+        NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceScreensDidSleepNotification,
+        NSWorkspaceScreensDidWakeNotification, NSWorkspaceWillSleepNotification,
+    };
+    use objc2_foundation::{
+        NSDistributedNotificationCenter, NSNotification, NSNotificationCenter, NSNotificationName,
+        NSString,
+    };
+    use std::ptr::NonNull;
+
+    use core_foundation::array::CFArray;
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+    use core_foundation_sys::array::CFArrayRef;
+    use core_foundation_sys::base::CFTypeRef;
+    use core_foundation_sys::dictionary::CFDictionaryRef;
+
+    use super::{handle, Battery, PowerEvent};
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+        fn IOPSCopyPowerSourcesList(blob: CFTypeRef) -> CFArrayRef;
+        fn IOPSGetPowerSourceDescription(blob: CFTypeRef, source: CFTypeRef) -> CFDictionaryRef;
+    }
+
+    /// The internal battery, read from IOKit's power source info.
+    pub fn battery() -> Option<Battery> {
+        unsafe {
+            let blob = IOPSCopyPowerSourcesInfo();
+            if blob.is_null() {
+                return None;
+            }
+            let blob = CFType::wrap_under_create_rule(blob);
+            let list = IOPSCopyPowerSourcesList(blob.as_CFTypeRef());
+            if list.is_null() {
+                return None;
+            }
+            let list: CFArray<CFType> = CFArray::wrap_under_create_rule(list);
+            list.iter().find_map(|source| {
+                let description =
+                    IOPSGetPowerSourceDescription(blob.as_CFTypeRef(), source.as_CFTypeRef());
+                if description.is_null() {
+                    return None;
+                }
+                // Owned by the blob, so don't release it
+                let description: CFDictionary<CFString, CFType> =
+                    CFDictionary::wrap_under_get_rule(description);
+                let value = |key: &str| description.find(CFString::new(key));
+                let number = |key: &str| value(key)?.downcast::<CFNumber>()?.to_i64();
+
+                let kind = value("Type")?.downcast::<CFString>()?.to_string();
+                if kind != "InternalBattery" {
+                    return None;
+                }
+                let current = number("Current Capacity")?;
+                let max = number("Max Capacity")?.max(1);
+                let state = value("Power Source State")
+                    .and_then(|v| v.downcast::<CFString>())
+                    .map(|s| s.to_string());
+                let charging = value("Is Charging")
+                    .and_then(|v| v.downcast::<CFBoolean>())
+                    .is_some_and(bool::from);
+                Some(Battery {
+                    percent: (current * 100 / max).clamp(0, 100) as u8,
+                    charging,
+                    on_battery: state.as_deref() == Some("Battery Power"),
+                })
+            })
+        }
+    }
+
+    fn add_observer(
+        center: &NSNotificationCenter,
+        name: &NSNotificationName,
+        app: &tauri::AppHandle,
+        event: PowerEvent,
+    ) {
+        let app = app.clone();
+        let block = RcBlock::new(move |_: NonNull<NSNotification>| handle(&app, event));
+        // The center keeps the observer alive for the life of the app
+        let _ = unsafe {
+            center.addObserverForName_object_queue_usingBlock(Some(name), None, None, &block)
+        };
+    }
+
+    pub fn observe(app: &tauri::AppHandle) {
+        let workspace = NSWorkspace::sharedWorkspace().notificationCenter();
+        let system = unsafe {
+            [
+                (NSWorkspaceWillSleepNotification, PowerEvent::Sleep),
+                (NSWorkspaceDidWakeNotification, PowerEvent::Wake),
+                (NSWorkspaceScreensDidSleepNotification, PowerEvent::Sleep),
+                (NSWorkspaceScreensDidWakeNotification, PowerEvent::Wake),
+            ]
+        };
+        for (name, event) in system {
+            add_observer(&workspace, name, app, event);
+        }
+
+        // Lock and unlock are only posted as distributed notifications
+        let distributed = NSDistributedNotificationCenter::defaultCenter();
+        for (name, event) in [
+            ("com.apple.screenIsLocked", PowerEvent::Lock),
+            ("com.apple.screenIsUnlocked", PowerEvent::Unlock),
+        ] {
+            add_observer(&distributed, &NSString::from_str(name), app, event);
+        }
+    }
+}
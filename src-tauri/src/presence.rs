@@ -0,0 +1,423 @@
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+use tiny_http::{Method, Request, Response, Server};
+
+use crate::crypto::{EncryptedBlob, MemoryKey};
+use crate::error::{PetError, PetResult};
+use crate::memory::{ChatMemory, MemoryStore};
+use crate::settings::SettingsStore;
+use crate::{persist, tray};
+
+pub const PRESENCE_FILE: &str = "presence.json";
+const SERVICE_TYPE: &str = "_desktoppet._tcp.local.";
+/// Arrivals sent longer ago than this are turned away; newer ones are
+/// remembered by id so the same one can't be let in twice.
+const MAX_CLOCK_SKEW_MS: i64 = 30_000;
+/// A chat memory is a few dozen messages; anything bigger is a mistake.
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+/// What the shared key is expanded for, so the same key could serve
+/// something else without the two being interchangeable.
+const KEY_PURPOSE: &str = "desktop-pet presence hand-off";
+
+/// Identity of this install plus the secret shared by every machine the cat
+/// may walk between. Kept out of settings.json so the key isn't exported.
+#[derive(Serialize, Deserialize, Clone)]
+struct Identity {
+    device_id: String,
+    key: String,
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn device_name() -> String {
+    std::process::Command::new("hostname")
+        .arg("-s")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Desktop Pet".to_string())
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Peer {
+    pub id: String,
+    pub name: String,
+    #[serde(skip)]
+    address: String,
+    #[serde(skip)]
+    port: u16,
+}
+
+/// What travels with the cat, as JSON encrypted with the shared key so the
+/// memory isn't readable on the way and only a machine holding the key can
+/// send one.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Arrival {
+    /// Random, so a captured arrival can't be replayed
+    id: String,
+    from_id: String,
+    from_name: String,
+    /// Unix millis
+    sent_at: i64,
+    /// None when the sender's memory is locked
+    memory: Option<ChatMemory>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ArrivedPayload {
+    from: String,
+}
+
+/// LAN discovery and hand-off between installs that share a presence key.
+pub struct PresenceState {
+    identity: Mutex<Identity>,
+    /// The cipher key from the shared key, made once rather than per request
+    key: Mutex<MemoryKey>,
+    identity_path: Option<PathBuf>,
+    peers: Arc<Mutex<HashMap<String, Peer>>>,
+    server: Mutex<Option<Arc<Server>>>,
+    daemon: Mutex<Option<ServiceDaemon>>,
+    /// Arrivals let in within the last MAX_CLOCK_SKEW_MS, by id, with when
+    /// they were sent
+    seen: Mutex<HashMap<String, i64>>,
+}
+
+impl PresenceState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let identity_path = persist::app_data_file(app, PRESENCE_FILE).ok();
+        let identity = match identity_path.as_deref().and_then(persist::load::<Identity>) {
+            Some(identity) => identity,
+            None => {
+                let identity = Identity {
+                    device_id: random_hex(8),
+                    key: random_hex(32),
+                };
+                if let Some(path) = &identity_path {
+                    let _ = persist::save(path, &identity);
+                }
+                identity
+            }
+        };
+        Self {
+            key: Mutex::new(MemoryKey::from_secret(&identity.key, KEY_PURPOSE)),
+            identity: Mutex::new(identity),
+            identity_path,
+            peers: Arc::new(Mutex::new(HashMap::new())),
+            server: Mutex::new(None),
+            daemon: Mutex::new(None),
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn identity(&self) -> Identity {
+        self.identity.lock().unwrap().clone()
+    }
+
+    fn key(&self) -> MemoryKey {
+        self.key.lock().unwrap().clone()
+    }
+
+    fn set_key(&self, key: String) -> PetResult<()> {
+        let mut identity = self.identity.lock().unwrap();
+        let updated = Identity {
+            key,
+            ..identity.clone()
+        };
+        if let Some(path) = &self.identity_path {
+            persist::save(path, &updated)?;
+        }
+        *self.key.lock().unwrap() = MemoryKey::from_secret(&updated.key, KEY_PURPOSE);
+        *identity = updated;
+        Ok(())
+    }
+
+    fn stop(&self) {
+        if let Some(server) = self.server.lock().unwrap().take() {
+            server.unblock();
+        }
+        if let Some(daemon) = self.daemon.lock().unwrap().take() {
+            let _ = daemon.shutdown();
+        }
+        self.peers.lock().unwrap().clear();
+    }
+}
+
+/// Start or stop discovery and the hand-off listener to match the settings.
+pub fn apply(app: &tauri::AppHandle) -> PetResult<()> {
+    let settings = app.state::<SettingsStore>().get().presence;
+    let state = app.state::<PresenceState>();
+    state.stop();
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    // Unlike the automation API this one has to be reachable from the LAN;
+    // every request has to decrypt with the shared key instead
+    let server = Arc::new(Server::http(("0.0.0.0", settings.port)).map_err(|e| {
+        PetError::Platform(format!("Couldn't listen on port {}: {}", settings.port, e))
+    })?);
+    *state.server.lock().unwrap() = Some(server.clone());
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            receive(&handle, request);
+        }
+    });
+
+    let daemon = ServiceDaemon::new().map_err(|e| PetError::Platform(e.to_string()))?;
+    let identity = state.identity();
+    let name = device_name();
+    let host = format!("{}.local.", identity.device_id);
+    let service = ServiceInfo::new(
+        SERVICE_TYPE,
+        &identity.device_id,
+        &host,
+        "",
+        settings.port,
+        [("id", identity.device_id.as_str()), ("name", name.as_str())],
+    )
+    .map_err(|e| PetError::Platform(e.to_string()))?
+    .enable_addr_auto();
+    daemon
+        .register(service)
+        .map_err(|e| PetError::Platform(e.to_string()))?;
+
+    let events = daemon
+        .browse(SERVICE_TYPE)
+        .map_err(|e| PetError::Platform(e.to_string()))?;
+    let peers = state.peers.clone();
+    let own_id = identity.device_id;
+    std::thread::spawn(move || {
+        // Ends when the daemon shuts down and drops the sender
+        while let Ok(event) = events.recv() {
+            match event {
+                ServiceEvent::ServiceResolved(info) => {
+                    let Some(id) = info.get_property_val_str("id").map(str::to_string) else {
+                        continue;
+                    };
+                    let Some(address) = info.get_addresses().iter().next() else {
+                        continue;
+                    };
+                    if id == own_id {
+                        continue;
+                    }
+                    let peer = Peer {
+                        name: info.get_property_val_str("name").unwrap_or(&id).to_string(),
+                        address: address.to_string(),
+                        port: info.get_port(),
+                        id: id.clone(),
+                    };
+                    peers.lock().unwrap().insert(id, peer);
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    peers
+                        .lock()
+                        .unwrap()
+                        .retain(|id, _| !fullname.starts_with(id.as_str()));
+                }
+                _ => {}
+            }
+        }
+    });
+    *state.daemon.lock().unwrap() = Some(daemon);
+    Ok(())
+}
+
+fn accept(app: &tauri::AppHandle, request: &mut Request) -> PetResult<()> {
+    if request.method() != &Method::Post || request.url() != "/arrive" {
+        return Err(PetError::InvalidInput("Unknown route".to_string()));
+    }
+    // Turned away before any key work: too big, or not even a blob
+    if request.body_length().is_some_and(|length| length as u64 > MAX_BODY_BYTES) {
+        return Err(PetError::InvalidInput("Too big".to_string()));
+    }
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_string(&mut body)?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(PetError::InvalidInput("Too big".to_string()));
+    }
+    let blob: EncryptedBlob = serde_json::from_str(&body)
+        .map_err(|e| PetError::InvalidInput(format!("Bad request body: {}", e)))?;
+
+    let state = app.state::<PresenceState>();
+    let plaintext = state
+        .key()
+        .decrypt(&blob)
+        .map_err(|_| PetError::InvalidInput("Not sent with our key".to_string()))?;
+    let arrival: Arrival = serde_json::from_slice(&plaintext)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    if (now - arrival.sent_at).abs() > MAX_CLOCK_SKEW_MS {
+        return Err(PetError::InvalidInput("Stale hand-off".to_string()));
+    }
+    {
+        let mut seen = state.seen.lock().unwrap();
+        seen.retain(|_, sent_at| (now - *sent_at).abs() <= MAX_CLOCK_SKEW_MS);
+        if seen.insert(arrival.id, arrival.sent_at).is_some() {
+            return Err(PetError::InvalidInput("Already arrived".to_string()));
+        }
+    }
+
+    // Without memory (the sender's is locked) the cat arrives without the
+    // chat history. Otherwise both sides' are merged; if that fails the
+    // cat stays where it was rather than arriving with nothing
+    if let Some(memory) = arrival.memory {
+        app.state::<MemoryStore>()
+            .absorb(memory)
+            .map_err(|e| PetError::Storage(e.to_string()))?;
+    }
+    tray::set_pet_visible(app, true);
+    let _ = app.emit(
+        "pet-arrived",
+        ArrivedPayload {
+            from: arrival.from_name,
+        },
+    );
+    Ok(())
+}
+
+fn receive(app: &tauri::AppHandle, mut request: Request) {
+    // Only a sender holding the key gets told what went wrong
+    let (status, message) = match accept(app, &mut request) {
+        Ok(()) => (200, String::new()),
+        Err(PetError::InvalidInput(_)) => (403, String::new()),
+        Err(e) => (500, e.to_string()),
+    };
+    let _ = request.respond(Response::from_string(message).with_status_code(status));
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresenceStatus {
+    pub enabled: bool,
+    pub device_id: String,
+    pub device_name: String,
+    /// Paste this into the other machines so they trust each other
+    pub key: String,
+    pub peers: Vec<Peer>,
+}
+
+fn status(app: &tauri::AppHandle) -> PresenceStatus {
+    let state = app.state::<PresenceState>();
+    let identity = state.identity();
+    let mut peers: Vec<Peer> = state.peers.lock().unwrap().values().cloned().collect();
+    peers.sort_by(|a, b| a.name.cmp(&b.name));
+    PresenceStatus {
+        enabled: app.state::<SettingsStore>().get().presence.enabled,
+        device_id: identity.device_id,
+        device_name: device_name(),
+        key: identity.key,
+        peers,
+    }
+}
+
+#[tauri::command(async)]
+pub fn get_presence_status(app: tauri::AppHandle) -> PresenceStatus {
+    status(&app)
+}
+
+#[tauri::command]
+pub async fn set_presence_enabled(
+    app: tauri::AppHandle,
+    enabled: bool,
+) -> PetResult<PresenceStatus> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.presence.enabled = enabled;
+        store.replace(settings)?;
+        apply(&app)?;
+        Ok(status(&app))
+    })
+    .await?
+}
+
+/// Adopt the key from another machine so the cat can travel between them.
+#[tauri::command]
+pub async fn set_presence_key(app: tauri::AppHandle, key: String) -> PetResult<PresenceStatus> {
+    let key = key.trim().to_string();
+    if key.len() < 32 {
+        return Err(PetError::InvalidInput(
+            "That doesn't look like a presence key".to_string(),
+        ));
+    }
+    crate::run_blocking(move || {
+        app.state::<PresenceState>().set_key(key)?;
+        Ok(status(&app))
+    })
+    .await?
+}
+
+/// Walk the cat over to `peer_id`: hand over its memory, then hide it here
+/// once the other machine has let it in.
+#[tauri::command]
+pub async fn send_pet_to(app: tauri::AppHandle, peer_id: String) -> PetResult<()> {
+    let state = app.state::<PresenceState>();
+    let peer = state
+        .peers
+        .lock()
+        .unwrap()
+        .get(&peer_id)
+        .cloned()
+        .ok_or_else(|| PetError::InvalidInput("That machine isn't around".to_string()))?;
+    let (identity, key) = (state.identity(), state.key());
+    let handle = app.clone();
+    let blob = crate::run_blocking(move || {
+        let memory = handle.state::<MemoryStore>();
+        let arrival = Arrival {
+            id: random_hex(16),
+            from_id: identity.device_id,
+            from_name: device_name(),
+            sent_at: chrono::Utc::now().timestamp_millis(),
+            memory: (!memory.is_locked()).then(|| memory.snapshot()),
+        };
+        key.encrypt(&serde_json::to_vec(&arrival)?)
+    })
+    .await??;
+
+    let host = if peer.address.contains(':') {
+        format!("[{}]", peer.address)
+    } else {
+        peer.address.clone()
+    };
+    let response = reqwest::Client::new()
+        .post(format!("http://{}:{}/arrive", host, peer.port))
+        .json(&blob)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| PetError::Network(e.to_string()))?;
+    if response.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err(PetError::InvalidInput(format!(
+            "{} wouldn't let the cat in (do both machines have the same key?)",
+            peer.name
+        )));
+    }
+    if !response.status().is_success() {
+        let message = response.text().await.unwrap_or_default();
+        return Err(PetError::InvalidInput(format!(
+            "{} couldn't take the cat in: {}",
+            peer.name, message
+        )));
+    }
+    tray::set_pet_visible(&app, false);
+    let _ = app.emit("pet-left", peer.name);
+    Ok(())
+}
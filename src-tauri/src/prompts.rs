@@ -0,0 +1,351 @@
+use handlebars::Handlebars;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+
+/// Folder in the app data dir holding one `<name>.hbs` per prompt.
+pub const PROMPTS_DIR: &str = "prompts";
+const EXTENSION: &str = "hbs";
+
+/// Starts a template written out by `customize_prompt_template`. A
+/// Handlebars comment, so it renders as nothing.
+const CUSTOM_HEADER: &str =
+    "{{!-- Edit away. Delete this file to go back to the built-in prompt. --}}\n";
+/// SHA-256 of every built-in as earlier versions copied them into the
+/// folder on first run. A file still matching one was never edited, and
+/// is removed so the current built-in is used instead.
+const SEEDED: &[&str] = &[
+    "bf4dc02c56fcf237616f527d5e2dfcb9a0eb1b0ffbef1d915b58fecd73440e29", // achievement.hbs
+    "ee7148e658317af8ce26c1f1f3eadf9ef7efb55325137fb29b98581be8725f53", // achievement.hbs
+    "e8ce2695e954cce4d408b14af8e5530625222a5214e0f7a3ebf8d5535fbf1937", // anniversary.hbs
+    "0e06278cc9a3ee06c37aeae90772aaa64fc960ee998c4c1bda2132fe34baaeef", // chat.hbs
+    "3a8698673781c0745bedca9ab4deda3d4e4735f58c34f186ad9df407966f468a", // chat.hbs
+    "3e512ee5b9ebf153f56c375588fea70a25d9469e13541f11955f2c3b4adfec94", // chat.hbs
+    "c255953a4a60adf465af0cb3d3d590ab9b60988a00a300679c361eef6cbf525e", // chat.hbs
+    "069812c5d3b129680f5124c80274b8920f3a621269b8fde0da865ebe91889fae", // digest.hbs
+    "a524fec1a1dd89aa6499574a3cfc588f9432f99cca236e5d31cfafd9b75b9570", // journal.hbs
+    "d40dffd2d35bfc5989f3af5085e34a98a724d6637ad091d4d183c280b4f23c7f", // journal.hbs
+    "42f533f87b9cbaf3ade24290a55d2c93eb3feaa3497fd0cda2eaed22ef8825d0", // judge.hbs
+    "4310838315ef11822d3e7d1183f9485991cd1111138b89ebdea5fc9fa71079e2", // judge.hbs
+    "cb30de796b209ffc5d90366778ccefe30ce992f135217eb5f8f687b477f65c73", // report.hbs
+    "aa7d5e8c28e7be1aaf56f72119c531c56aa863633a3a0846b76b09bdad09cdc4", // screenshot.hbs
+    "66b5b251685bd753b01721dda34038eede49f3f95b9b411cb117952fa960f06d", // search.hbs
+    "d65419595d5d54aa39f74bb67513246c48d8b6731eb64077db7d7b9302beb575", // search.hbs
+    "2f2df8f308dbaa471d6300f2e2ee7d5272a0433f8d6723ff9a33548088ed0747", // selection.hbs
+    "a2ca0ca07e4ae0d521264505af6c497ec74786b047f52cd8e3ef6c81d0e5b741", // selection.hbs
+    "d04889eb66f9c7c2146ab8d17dd2d01942b6d50cad6f53679cc76e7bbee7ae24", // selection.hbs
+    "0c95cf38e2abd0e2f2280d1c4f9ca8512705fdf5f601d1c88f1fd104067c2587", // spontaneous.hbs
+    "c9747b0ac01ae3687b7ae0520e546504068a81bd11196319e08e1f41d4fef8e3", // spontaneous.hbs
+    "f08bfceeb1a11595d7bef9d111de616baecbe453779da5a7f5d29fd7725b5105", // standup.hbs
+    "0afae8e612ef9f2b9317dcdf9b5336cdbca1dba5f40d0a11630c514076821eab", // translate.hbs
+];
+
+/// The built-in prompts, by template name. Only written into the folder
+/// when the owner asks to customize one.
+const DEFAULTS: &[(&str, &str)] = &[
+    ("spontaneous", include_str!("../prompts/spontaneous.hbs")),
+    ("chat", include_str!("../prompts/chat.hbs")),
+    ("judge", include_str!("../prompts/judge.hbs")),
+    ("search", include_str!("../prompts/search.hbs")),
+    ("journal", include_str!("../prompts/journal.hbs")),
+    ("selection", include_str!("../prompts/selection.hbs")),
+    ("translate", include_str!("../prompts/translate.hbs")),
+    ("achievement", include_str!("../prompts/achievement.hbs")),
+    ("standup", include_str!("../prompts/standup.hbs")),
+    ("anniversary", include_str!("../prompts/anniversary.hbs")),
+    ("digest", include_str!("../prompts/digest.hbs")),
+    ("screenshot", include_str!("../prompts/screenshot.hbs")),
+    ("report", include_str!("../prompts/report.hbs")),
+];
+
+/// The built-in template a mode falls back to. A `<mode>.hbs` file takes
+/// precedence, so e.g. `react.hbs` can split react off from spontaneous.
+fn shared_name(mode: &str) -> &'static str {
+    match mode {
+        "chat" => "chat",
+        "judge" => "judge",
+        "search" => "search",
+        "journal" => "journal",
+        "summarize" | "explain" => "selection",
+        "translate" => "translate",
+        "achievement" => "achievement",
+        "standup" => "standup",
+        "anniversary" => "anniversary",
+        "digest" => "digest",
+        "screenshot" | "extract" => "screenshot",
+        "report" => "report",
+        _ => "spontaneous",
+    }
+}
+
+fn default_source(name: &str) -> &'static str {
+    DEFAULTS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, source)| *source)
+        .unwrap_or(DEFAULTS[0].1)
+}
+
+/// What a template can refer to. Empty strings render as nothing and are
+/// false in `{{#if}}`.
+#[derive(Serialize, Default)]
+pub struct PromptVars {
+    pub mode: String,
+    /// The language to speak, by name
+    pub language: String,
+    /// How the cat feels, as an instruction; also added after the prompt
+    pub mood: String,
+    /// Remembered facts, numbered
+    pub facts: String,
+    /// Summary of older conversations
+    pub summary: String,
+    /// The active personality pack as instructions
+    pub personality: String,
+    /// What the user is doing; also sent after the prompt on its own
+    pub context: String,
+    /// Today's date, only when the time may be shared
+    pub date: String,
+    pub time_of_day: String,
+    /// What summarize/explain/translate/screenshot/extract was asked to do,
+    /// translate's target language included
+    pub task: String,
+    pub journal_length: String,
+    pub journal_tone: String,
+    pub min_mood: u8,
+    pub max_mood: u8,
+}
+
+/// A template as last read, so it's only parsed again once its file changes.
+struct Loaded {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    error: Option<String>,
+}
+
+/// Prompt templates, read from disk and re-read whenever a file changes so
+/// edits apply to the next line without restarting.
+pub struct PromptTemplates {
+    dir: Option<PathBuf>,
+    registry: Mutex<(Handlebars<'static>, HashMap<String, Loaded>)>,
+}
+
+fn registry() -> Handlebars<'static> {
+    let mut handlebars = Handlebars::new();
+    // Prompts are plain text; nothing needs HTML escaping
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Templates are written over several lines for reading; the prompt is one.
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl PromptTemplates {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let dir = crate::persist::app_data_file(app, PROMPTS_DIR).ok();
+        if let Some(dir) = &dir {
+            remove_seeded(dir);
+        }
+        Self {
+            dir,
+            registry: Mutex::new((registry(), HashMap::new())),
+        }
+    }
+
+    fn file(&self, name: &str) -> Option<PathBuf> {
+        let path = self.dir.as_ref()?.join(format!("{}.{}", name, EXTENSION));
+        path.exists().then_some(path)
+    }
+
+    /// The mode's own file, else its shared one, else the built-in.
+    fn source_for(&self, mode: &str) -> (String, Option<PathBuf>) {
+        let name = shared_name(mode);
+        (
+            name.to_string(),
+            self.file(mode).or_else(|| self.file(name)),
+        )
+    }
+
+    /// Render the prompt for `mode`. A template that doesn't parse or render
+    /// falls back to the built-in one, with the error kept for
+    /// `get_prompt_templates`.
+    pub fn render(&self, mode: &str, vars: &PromptVars) -> String {
+        let (name, path) = self.source_for(mode);
+        let key = mode.to_string();
+        let mut guard = self.registry.lock().unwrap();
+        let (handlebars, loaded) = &mut *guard;
+
+        let mtime = path.as_deref().and_then(modified);
+        let stale = loaded
+            .get(&key)
+            .is_none_or(|l| l.path != path || l.modified != mtime);
+        if stale {
+            let custom = path
+                .as_deref()
+                .map(|p| fs::read_to_string(p).map_err(|e| e.to_string()));
+            let error = match custom {
+                Some(Ok(source)) => handlebars
+                    .register_template_string(&key, source)
+                    .err()
+                    .map(|e| e.to_string()),
+                Some(Err(e)) => Some(e),
+                None => None,
+            };
+            if path.is_none() || error.is_some() {
+                if let Some(e) = &error {
+                    eprintln!("Prompt template for {} not used: {}", mode, e);
+                }
+                let _ = handlebars.register_template_string(&key, default_source(&name));
+            }
+            loaded.insert(
+                key.clone(),
+                Loaded {
+                    path,
+                    modified: mtime,
+                    error,
+                },
+            );
+        }
+
+        match handlebars.render(&key, vars) {
+            Ok(text) => collapse(&text),
+            Err(e) => {
+                if let Some(l) = loaded.get_mut(&key) {
+                    l.error = Some(e.to_string());
+                }
+                let text = handlebars
+                    .render_template(default_source(&name), vars)
+                    .unwrap_or_default();
+                collapse(&text)
+            }
+        }
+    }
+
+    fn last_error(&self, mode: &str) -> Option<String> {
+        let guard = self.registry.lock().unwrap();
+        guard.1.get(mode).and_then(|l| l.error.clone())
+    }
+}
+
+/// Delete copies of built-ins that earlier versions wrote out and nobody
+/// edited, which would otherwise hold back every later built-in change.
+fn remove_seeded(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some(EXTENSION) {
+            continue;
+        }
+        let Ok(source) = fs::read(&path) else {
+            continue;
+        };
+        let hash: String = Sha256::digest(&source)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        if SEEDED.contains(&hash.as_str()) {
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+fn check_name(name: &str) -> PetResult<()> {
+    if !DEFAULTS.iter().any(|(n, _)| *n == name) {
+        return Err(PetError::InvalidInput(format!(
+            "\"{}\" isn't a prompt template",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateInfo {
+    pub mode: String,
+    /// The file it's read from, None when the built-in is used
+    pub path: Option<String>,
+    /// The file says something other than the built-in
+    pub customized: bool,
+    /// Why the file was last passed over for the built-in
+    pub error: Option<String>,
+}
+
+/// Which template each mode uses and whether it loaded.
+#[tauri::command]
+pub async fn get_prompt_templates(app: tauri::AppHandle) -> PetResult<Vec<PromptTemplateInfo>> {
+    crate::run_blocking(move || {
+        let templates = app.state::<PromptTemplates>();
+        crate::dialogue::modes()
+            .map(|mode| {
+                let (name, path) = templates.source_for(mode);
+                let customized = path
+                    .as_deref()
+                    .and_then(|p| fs::read_to_string(p).ok())
+                    .is_some_and(|source| {
+                        source.strip_prefix(CUSTOM_HEADER).unwrap_or(&source)
+                            != default_source(&name)
+                    });
+                PromptTemplateInfo {
+                    mode: mode.to_string(),
+                    path: path.map(|p| p.to_string_lossy().into_owned()),
+                    customized,
+                    error: templates.last_error(mode),
+                }
+            })
+            .collect()
+    })
+    .await
+}
+
+/// Write the built-in template `name` into the folder to be edited, unless
+/// there's already a file for it. Returns the file's path.
+#[tauri::command]
+pub async fn customize_prompt_template(app: tauri::AppHandle, name: String) -> PetResult<String> {
+    check_name(&name)?;
+    crate::run_blocking(move || {
+        let dir = crate::persist::app_data_file(&app, PROMPTS_DIR)?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.{}", name, EXTENSION));
+        if !path.exists() {
+            fs::write(&path, format!("{}{}", CUSTOM_HEADER, default_source(&name)))?;
+        }
+        Ok(path.to_string_lossy().into_owned())
+    })
+    .await?
+}
+
+/// Go back to the built-in template by deleting the file.
+#[tauri::command]
+pub async fn reset_prompt_template(app: tauri::AppHandle, name: String) -> PetResult<()> {
+    check_name(&name)?;
+    crate::run_blocking(move || {
+        let dir = crate::persist::app_data_file(&app, PROMPTS_DIR)?;
+        crate::persist::remove(&dir.join(format!("{}.{}", name, EXTENSION)))
+    })
+    .await?
+}
+
+/// Where the template files live, for editing them.
+#[tauri::command]
+pub async fn get_prompts_dir(app: tauri::AppHandle) -> PetResult<String> {
+    crate::run_blocking(move || {
+        let dir = crate::persist::app_data_file(&app, PROMPTS_DIR)?;
+        fs::create_dir_all(&dir)?;
+        Ok(dir.to_string_lossy().into_owned())
+    })
+    .await?
+}
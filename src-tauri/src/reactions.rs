@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::persist;
+
+pub const REACTIONS_FILE: &str = "reactions.json";
+/// A reaction's weight halves every two weeks it goes unused, so old
+/// favourites make way for new ones.
+const HALF_LIFE_DAYS: f64 = 14.0;
+/// Weights below this are forgotten altogether.
+const FORGET_BELOW: f64 = 0.05;
+/// Use in the same context counts this much more than use anywhere.
+const CONTEXT_WEIGHT: f64 = 2.0;
+const SUGGESTIONS: usize = 6;
+const MAX_REACTION_CHARS: usize = 24;
+const MAX_CONTEXT_CHARS: usize = 40;
+const MAX_CONTEXTS: usize = 50;
+
+/// Offered until the user has favourites of their own, and to fill the row
+/// after that.
+const DEFAULT_REACTIONS: &[&str] = &[
+    "😂",
+    "❤️",
+    "👍",
+    "😮",
+    "(=^･ω･^=)",
+    "¯\\_(ツ)_/¯",
+    "(╯°□°)╯︵ ┻━┻",
+    "😿",
+];
+
+/// A reaction's decaying weight, as of `updated` (unix seconds).
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct Weight {
+    score: f64,
+    updated: i64,
+}
+
+impl Weight {
+    fn at(&self, now: i64) -> f64 {
+        let days = (now - self.updated).max(0) as f64 / 86_400.0;
+        self.score * 0.5f64.powf(days / HALF_LIFE_DAYS)
+    }
+}
+
+type Weights = HashMap<String, Weight>;
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct ReactionData {
+    overall: Weights,
+    /// Per context, e.g. the mode of the line being reacted to
+    by_context: HashMap<String, Weights>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionSuggestion {
+    pub reaction: String,
+    /// 0 for defaults that haven't been used yet
+    pub score: f64,
+}
+
+fn bump(weights: &mut Weights, reaction: &str, now: i64) {
+    let score = weights.get(reaction).map_or(0.0, |w| w.at(now)) + 1.0;
+    weights.insert(
+        reaction.to_string(),
+        Weight {
+            score,
+            updated: now,
+        },
+    );
+    weights.retain(|_, w| w.at(now) >= FORGET_BELOW);
+}
+
+fn context_key(context: &str) -> Option<String> {
+    let key: String = context
+        .trim()
+        .to_lowercase()
+        .chars()
+        .take(MAX_CONTEXT_CHARS)
+        .collect();
+    (!key.is_empty()).then_some(key)
+}
+
+/// Reactions the user picks most, learned from their picks with older
+/// picks fading, overall and per context.
+pub struct ReactionStore {
+    data: Mutex<ReactionData>,
+    path: Option<PathBuf>,
+}
+
+impl ReactionStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, REACTIONS_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        *data = ReactionData::default();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn record(&self, reaction: &str, context: Option<&str>) -> PetResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        let mut data = self.data.lock().unwrap();
+        bump(&mut data.overall, reaction, now);
+        if let Some(key) = context.and_then(context_key) {
+            bump(data.by_context.entry(key).or_default(), reaction, now);
+            data.by_context.retain(|_, weights| !weights.is_empty());
+            // Keep the contexts used most recently
+            if data.by_context.len() > MAX_CONTEXTS {
+                let mut latest: Vec<(String, i64)> = data
+                    .by_context
+                    .iter()
+                    .map(|(key, weights)| {
+                        let last = weights.values().map(|w| w.updated).max().unwrap_or(0);
+                        (key.clone(), last)
+                    })
+                    .collect();
+                latest.sort_by_key(|(_, last)| std::cmp::Reverse(*last));
+                for (key, _) in latest.into_iter().skip(MAX_CONTEXTS) {
+                    data.by_context.remove(&key);
+                }
+            }
+        }
+        if let Some(path) = &self.path {
+            persist::save(path, &*data)?;
+        }
+        Ok(())
+    }
+
+    pub fn suggestions(&self, context: Option<&str>) -> Vec<ReactionSuggestion> {
+        let now = chrono::Utc::now().timestamp();
+        let data = self.data.lock().unwrap();
+        let mut scores: HashMap<&str, f64> = data
+            .overall
+            .iter()
+            .map(|(reaction, w)| (reaction.as_str(), w.at(now)))
+            .collect();
+        let in_context = context
+            .and_then(context_key)
+            .and_then(|key| data.by_context.get(&key));
+        for (reaction, w) in in_context.into_iter().flatten() {
+            *scores.entry(reaction.as_str()).or_default() += CONTEXT_WEIGHT * w.at(now);
+        }
+
+        let mut ranked: Vec<(&str, f64)> = scores
+            .into_iter()
+            .filter(|(_, score)| *score >= FORGET_BELOW)
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let mut suggestions: Vec<ReactionSuggestion> = ranked
+            .into_iter()
+            .take(SUGGESTIONS)
+            .map(|(reaction, score)| ReactionSuggestion {
+                reaction: reaction.to_string(),
+                score,
+            })
+            .collect();
+        for reaction in DEFAULT_REACTIONS {
+            if suggestions.len() >= SUGGESTIONS {
+                break;
+            }
+            if !suggestions.iter().any(|s| s.reaction == *reaction) {
+                suggestions.push(ReactionSuggestion {
+                    reaction: reaction.to_string(),
+                    score: 0.0,
+                });
+            }
+        }
+        suggestions
+    }
+}
+
+/// The reactions to offer, favourites first. `context` (e.g. the mode of
+/// the line being reacted to) favours what was picked there before.
+#[tauri::command]
+pub async fn get_reaction_suggestions(
+    app: tauri::AppHandle,
+    context: Option<String>,
+) -> PetResult<Vec<ReactionSuggestion>> {
+    crate::run_blocking(move || app.state::<ReactionStore>().suggestions(context.as_deref())).await
+}
+
+/// Count a pick towards future suggestions.
+#[tauri::command]
+pub async fn record_reaction(
+    app: tauri::AppHandle,
+    reaction: String,
+    context: Option<String>,
+) -> PetResult<()> {
+    let reaction = reaction.trim().to_string();
+    if reaction.is_empty() || reaction.chars().count() > MAX_REACTION_CHARS {
+        return Err(PetError::InvalidInput(format!(
+            "Reactions are 1 to {} characters",
+            MAX_REACTION_CHARS
+        )));
+    }
+    crate::run_blocking(move || {
+        app.state::<ReactionStore>()
+            .record(&reaction, context.as_deref())
+    })
+    .await?
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use crate::config::{self, Key};
+use crate::error::{PetError, PetResult};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The REST url for `table` in the friends system's Supabase project, and
+/// the anon key to call it with.
+pub fn table(table: &str) -> PetResult<(String, String)> {
+    let url = config::get(Key::SupabaseUrl).unwrap_or_default();
+    let key = config::get(Key::SupabaseAnonKey).unwrap_or_default();
+    if url.trim().is_empty() || key.trim().is_empty() {
+        return Err(PetError::Auth(
+            "Friends features need VITE_SUPABASE_URL and VITE_SUPABASE_ANON_KEY".to_string(),
+        ));
+    }
+    Ok((
+        format!("{}/rest/v1/{}", url.trim_end_matches('/'), table),
+        key,
+    ))
+}
+
+pub fn request(method: reqwest::Method, url: &str, key: &str) -> reqwest::RequestBuilder {
+    reqwest::Client::new()
+        .request(method, url)
+        .header("apikey", key)
+        .bearer_auth(key)
+        .timeout(REQUEST_TIMEOUT)
+}
+
+/// Send `request`, turning a failed status into `PetError::Api`.
+pub async fn send(request: reqwest::RequestBuilder) -> PetResult<reqwest::Response> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| PetError::Network(e.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    Err(PetError::Api {
+        status: status.as_u16(),
+        message: response.text().await.unwrap_or_default(),
+    })
+}
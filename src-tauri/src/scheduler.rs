@@ -0,0 +1,49 @@
+use std::future::Future;
+use std::time::Duration;
+
+/// How often periodic jobs wake up to look for due work.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Run `job` every TICK_INTERVAL for the life of the app.
+pub fn every_tick<F, Fut>(app: tauri::AppHandle, job: F)
+where
+    F: Fn(tauri::AppHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    every(app, TICK_INTERVAL, job);
+}
+
+/// Run `job` every `period` for the life of the app, skipping ticks while
+/// the machine is asleep or locked, and every other tick while saving
+/// battery.
+pub fn every<F, Fut>(app: tauri::AppHandle, period: Duration, job: F)
+where
+    F: Fn(tauri::AppHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        let mut skipped = false;
+        loop {
+            interval.tick().await;
+            if crate::power::is_suspended(&app) {
+                continue;
+            }
+            if crate::power::is_saving(&app) && !skipped {
+                skipped = true;
+                continue;
+            }
+            skipped = false;
+            job(app.clone()).await;
+        }
+    });
+}
+
+/// Whether something last checked at `last_checked_at` (unix millis) is due
+/// again after `interval_minutes`. Never-checked items are always due.
+pub fn is_due(last_checked_at: Option<i64>, interval_minutes: u32, now: i64) -> bool {
+    match last_checked_at {
+        Some(last) => now - last >= interval_minutes as i64 * 60_000,
+        None => true,
+    }
+}
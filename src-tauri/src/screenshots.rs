@@ -0,0 +1,247 @@
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{Emitter, Manager};
+
+use crate::dialogue::{self, DialogueResponse, Image};
+use crate::error::{PetError, PetResult};
+use crate::scheduler;
+use crate::settings::SettingsStore;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// An offer the user ignored is forgotten after this long.
+const OFFER_TTL: Duration = Duration::from_secs(120);
+/// Longest edge sent to the model; bigger only costs more tokens.
+const MAX_EDGE: u32 = 1568;
+const JPEG_QUALITY: u8 = 80;
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+/// Set by macOS on every screenshot it saves, whatever the file is called.
+const SCREEN_CAPTURE_ATTRIBUTE: &str = "com.apple.metadata:kMDItemIsScreenCapture";
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ScreenshotAction {
+    /// A one-line remark about it
+    Remark,
+    /// The text in it, copied out
+    Text,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotOffer {
+    pub id: String,
+    pub name: String,
+    /// Ask for a remark straight away instead of offering one
+    pub remark: bool,
+}
+
+struct Shot {
+    id: String,
+    path: PathBuf,
+    taken_at: Instant,
+}
+
+#[derive(Default)]
+pub struct ScreenshotState {
+    /// Newest file seen in the screenshot folder; older ones are never offered
+    seen: Mutex<Option<SystemTime>>,
+    shot: Mutex<Option<Shot>>,
+}
+
+impl ScreenshotState {
+    fn forget(&self) {
+        *self.seen.lock().unwrap() = None;
+        *self.shot.lock().unwrap() = None;
+    }
+}
+
+fn defaults_read(key: &str) -> Option<String> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "com.apple.screencapture", key])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+/// Where screenshots are saved: the folder set in the Screenshot app's
+/// options, or the Desktop.
+fn folder() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    let location = defaults_read("location").map(|location| match location.strip_prefix('~') {
+        Some(rest) => format!("{}{}", home, rest),
+        None => location,
+    });
+    location
+        .map(PathBuf::from)
+        .filter(|path| path.is_dir())
+        .or_else(|| Some(Path::new(&home).join("Desktop")))
+}
+
+fn is_screen_capture(path: &Path) -> bool {
+    std::process::Command::new("xattr")
+        .args(["-p", SCREEN_CAPTURE_ATTRIBUTE])
+        .arg(path)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Images in the screenshot folder added after `since`, newest first, and
+/// the newest time seen.
+fn new_images(folder: &Path, since: Option<SystemTime>) -> (Vec<PathBuf>, Option<SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return (Vec::new(), since);
+    };
+    let mut newest = since;
+    let mut images: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let extension = path.extension()?.to_string_lossy().to_lowercase();
+            if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+                return None;
+            }
+            let meta = entry.metadata().ok()?;
+            let added = meta.created().or_else(|_| meta.modified()).ok()?;
+            newest = newest.max(Some(added));
+            since
+                .is_some_and(|since| added > since)
+                .then_some((added, path))
+        })
+        .collect();
+    images.sort_by_key(|(added, _)| std::cmp::Reverse(*added));
+    (images.into_iter().map(|(_, path)| path).collect(), newest)
+}
+
+/// Shrink and re-encode as JPEG for the model.
+fn prepare(path: &Path) -> PetResult<Image> {
+    let image = image::open(path)
+        .map_err(|e| PetError::Parse(format!("Couldn't read the screenshot: {}", e)))?;
+    let image = if image.width().max(image.height()) > MAX_EDGE {
+        image.resize(MAX_EDGE, MAX_EDGE, image::imageops::FilterType::Triangle)
+    } else {
+        image
+    };
+    let mut bytes = Vec::new();
+    JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)
+        .encode_image(&image.to_rgb8())
+        .map_err(|e| PetError::Internal(format!("Couldn't encode the screenshot: {}", e)))?;
+    Ok(Image {
+        media_type: "image/jpeg",
+        data: base64::engine::general_purpose::STANDARD.encode(bytes),
+    })
+}
+
+async fn poll(app: tauri::AppHandle) {
+    let settings = app.state::<SettingsStore>().get().screenshots;
+    if !settings.enabled || !cfg!(target_os = "macos") {
+        return;
+    }
+    let since = *app.state::<ScreenshotState>().seen.lock().unwrap();
+    let Ok(Some((found, newest))) = crate::run_blocking(move || {
+        let folder = folder()?;
+        let (images, newest) = new_images(&folder, since);
+        let found = images.into_iter().find(|path| is_screen_capture(path));
+        Some((found, newest))
+    })
+    .await
+    else {
+        return;
+    };
+    let state = app.state::<ScreenshotState>();
+    // The first look is the baseline; what's already there isn't offered
+    *state.seen.lock().unwrap() = newest;
+    let Some(path) = found else {
+        return;
+    };
+    // Nothing unprompted while the owner is on a call
+    if crate::meetings::is_in_meeting(&app) {
+        return;
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let offer = ScreenshotOffer {
+        id: id.clone(),
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        remark: settings.remark,
+    };
+    *state.shot.lock().unwrap() = Some(Shot {
+        id,
+        path,
+        taken_at: Instant::now(),
+    });
+    let _ = app.emit("screenshot-taken", offer);
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, POLL_INTERVAL, poll);
+}
+
+/// Turn noticing screenshots on or off, and whether the cat comments on
+/// each one unasked. Turning it off drops anything already offered.
+#[tauri::command]
+pub async fn set_screenshot_watch(
+    app: tauri::AppHandle,
+    enabled: bool,
+    remark: bool,
+) -> PetResult<()> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.screenshots.enabled = enabled;
+        settings.screenshots.remark = remark;
+        store.replace(settings)?;
+        app.state::<ScreenshotState>().forget();
+        Ok(())
+    })
+    .await?
+}
+
+/// Act on an offer: look at the screenshot and remark on it, or read the
+/// text out of it. The image is only sent to the model here. The offer
+/// stays open, so both can be asked for.
+#[tauri::command]
+pub async fn ask_about_screenshot(
+    app: tauri::AppHandle,
+    id: String,
+    action: ScreenshotAction,
+) -> PetResult<DialogueResponse> {
+    let path = app
+        .state::<ScreenshotState>()
+        .shot
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|shot| shot.id == id && shot.taken_at.elapsed() < OFFER_TTL)
+        .map(|shot| shot.path.clone())
+        .ok_or_else(|| PetError::InvalidInput("That screenshot offer has expired".to_string()))?;
+    let image = crate::run_blocking(move || prepare(&path)).await??;
+    let mode = match action {
+        ScreenshotAction::Remark => "screenshot",
+        ScreenshotAction::Text => "extract",
+    };
+    dialogue::generate(
+        app,
+        String::new(),
+        String::new(),
+        "screenshot".to_string(),
+        Some(mode.to_string()),
+        None,
+        None,
+        Some(image),
+    )
+    .await
+}
+
+/// Decline an offer without using it.
+#[tauri::command(async)]
+pub fn dismiss_screenshot_offer(state: tauri::State<'_, ScreenshotState>) {
+    *state.shot.lock().unwrap() = None;
+}
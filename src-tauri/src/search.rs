@@ -0,0 +1,280 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::journal::{DigestStore, JournalEntry};
+use crate::memory::{MemoryMessage, MemoryStore};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+/// Words either side of the best match kept in a snippet.
+const SNIPPET_RADIUS: usize = 12;
+// BM25's usual tuning
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+/// Question words and filler that would match everything: "what did I tell
+/// the cat about ..." should search for whatever comes after.
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "an", "and", "any", "are", "at", "be", "cat", "did", "do", "does", "for", "from",
+    "had", "has", "have", "he", "her", "his", "how", "i", "in", "is", "it", "me", "my", "of", "on",
+    "or", "said", "say", "she", "tell", "that", "the", "their", "they", "this", "to", "told",
+    "was", "we", "were", "what", "when", "where", "which", "who", "why", "with", "you", "your",
+];
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum HitSource {
+    /// Something you said in chat
+    You,
+    /// Something the cat said in chat
+    Cat,
+    /// A fact the cat remembered about you
+    Fact,
+    /// The summary of older conversations
+    Summary,
+    Journal,
+    /// A weekly journal digest
+    Digest,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub source: HitSource,
+    /// Around the best match, with "…" where it was cut
+    pub snippet: String,
+    /// Unix millis for chat messages
+    pub at: Option<i64>,
+    /// YYYY-MM-DD for journal entries and digests
+    pub date: Option<String>,
+    pub score: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemorySearch {
+    pub hits: Vec<SearchHit>,
+    /// Chat and facts were skipped because encrypted memory is locked
+    pub memory_locked: bool,
+}
+
+struct Document {
+    source: HitSource,
+    text: String,
+    at: Option<i64>,
+    date: Option<String>,
+}
+
+/// Lowercase, strip possessives and plurals, so "sister's" finds "sister"
+/// and "birthdays" finds "birthday".
+fn normalize(word: &str) -> String {
+    let word = word.to_lowercase();
+    let word = word
+        .strip_suffix("'s")
+        .or_else(|| word.strip_suffix("’s"))
+        .unwrap_or(&word);
+    match word.strip_suffix('s') {
+        Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// The words of `text` with their byte ranges, for snippets.
+fn words(text: &str) -> Vec<(usize, usize, String)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        let in_word = c.is_alphanumeric() || c == '\'' || c == '’';
+        match (in_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push((s, i, normalize(&text[s..i])));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, text.len(), normalize(&text[s..])));
+    }
+    words
+}
+
+fn query_terms(query: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    words(query)
+        .into_iter()
+        // Checked before stemming, or "does" would slip through as "doe"
+        .filter(|(start, end, _)| {
+            !STOP_WORDS.contains(&query[*start..*end].to_lowercase().as_str())
+        })
+        .map(|(_, _, word)| word)
+        .filter(|word| seen.insert(word.clone()))
+        .collect()
+}
+
+/// `text` cut down to the stretch around its first match.
+fn snippet(text: &str, words: &[(usize, usize, String)], terms: &[String]) -> String {
+    let Some(hit) = words.iter().position(|(_, _, w)| terms.contains(w)) else {
+        return text.to_string();
+    };
+    let first = hit.saturating_sub(SNIPPET_RADIUS);
+    let last = (hit + SNIPPET_RADIUS).min(words.len() - 1);
+    let start = if first == 0 { 0 } else { words[first].0 };
+    let end = if last == words.len() - 1 {
+        text.len()
+    } else {
+        words[last].1
+    };
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        text[start..end].trim(),
+        if end < text.len() { "…" } else { "" }
+    )
+}
+
+/// BM25 over `documents`, best first.
+fn rank(documents: Vec<Document>, terms: &[String], limit: usize) -> Vec<SearchHit> {
+    let tokenized: Vec<Vec<(usize, usize, String)>> =
+        documents.iter().map(|d| words(&d.text)).collect();
+    let count = documents.len() as f64;
+    let average = tokenized.iter().map(Vec::len).sum::<usize>() as f64 / count.max(1.0);
+    let idf: HashMap<&String, f64> = terms
+        .iter()
+        .map(|term| {
+            let having = tokenized
+                .iter()
+                .filter(|words| words.iter().any(|(_, _, w)| w == term))
+                .count() as f64;
+            (term, ((count - having + 0.5) / (having + 0.5) + 1.0).ln())
+        })
+        .collect();
+
+    let mut hits: Vec<SearchHit> = documents
+        .into_iter()
+        .zip(&tokenized)
+        .filter_map(|(document, words)| {
+            let length = words.len() as f64;
+            let score: f64 = terms
+                .iter()
+                .map(|term| {
+                    let tf = words.iter().filter(|(_, _, w)| w == term).count() as f64;
+                    idf[term] * tf * (K1 + 1.0)
+                        / (tf + K1 * (1.0 - B + B * length / average.max(1.0)))
+                })
+                .sum();
+            (score > 0.0).then(|| SearchHit {
+                source: document.source,
+                snippet: snippet(&document.text, words, terms),
+                at: document.at,
+                date: document.date,
+                score,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    hits
+}
+
+fn chat_document(message: &MemoryMessage) -> Document {
+    Document {
+        source: if message.role == "user" {
+            HitSource::You
+        } else {
+            HitSource::Cat
+        },
+        text: message.content.clone(),
+        at: message.at,
+        date: None,
+    }
+}
+
+fn documents(
+    app: &tauri::AppHandle,
+    entries: Vec<JournalEntry>,
+    memory_locked: bool,
+) -> Vec<Document> {
+    let mut documents = Vec::new();
+    if !memory_locked {
+        let memory = app.state::<MemoryStore>().snapshot();
+        // Messages waiting to be summarized are still the user's words
+        for message in memory.pending_compaction.iter().chain(&memory.messages) {
+            documents.push(chat_document(message));
+        }
+        // And the earlier conversations
+        for message in memory.sessions.iter().flat_map(|s| &s.messages) {
+            documents.push(chat_document(message));
+        }
+        // Archived facts too; they're just not given to the cat any more
+        documents.extend(
+            memory
+                .facts
+                .into_iter()
+                .chain(memory.archived_facts)
+                .map(|fact| Document {
+                    source: HitSource::Fact,
+                    text: fact.text,
+                    at: None,
+                    date: None,
+                }),
+        );
+        if !memory.summary.trim().is_empty() {
+            documents.push(Document {
+                source: HitSource::Summary,
+                text: memory.summary,
+                at: None,
+                date: None,
+            });
+        }
+    }
+    documents.extend(entries.into_iter().map(|entry| Document {
+        source: HitSource::Journal,
+        text: entry.text,
+        at: None,
+        date: Some(entry.date),
+    }));
+    documents.extend(
+        app.state::<DigestStore>()
+            .all()
+            .into_iter()
+            .map(|digest| Document {
+                source: HitSource::Digest,
+                text: digest.text,
+                at: None,
+                date: Some(digest.week_start),
+            }),
+    );
+    documents
+}
+
+/// Search chat history, remembered facts and the diary for `query`, best
+/// matches first. Journal `entries` live in the webview, so it passes them
+/// in. Words like "what did I tell the cat about" are ignored, so a question
+/// works as well as keywords.
+#[tauri::command]
+pub async fn search_memory(
+    app: tauri::AppHandle,
+    query: String,
+    entries: Option<Vec<JournalEntry>>,
+    limit: Option<usize>,
+) -> PetResult<MemorySearch> {
+    let terms = query_terms(&query);
+    if terms.is_empty() {
+        return Err(PetError::InvalidInput(
+            "Search for a word or two, like \"birthday\"".to_string(),
+        ));
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    crate::run_blocking(move || {
+        let memory_locked = app.state::<MemoryStore>().is_locked();
+        let documents = documents(&app, entries.unwrap_or_default(), memory_locked);
+        MemorySearch {
+            hits: rank(documents, &terms, limit),
+            memory_locked,
+        }
+    })
+    .await
+}
@@ -0,0 +1,653 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::clipboard::ClipKind;
+use crate::error::PetResult;
+use crate::persist;
+use crate::tools::ToolPermission;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// What situational context is allowed to leave the machine in prompts.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContextSettings {
+    pub send_app_name: bool,
+    pub send_window_title: bool,
+    pub send_time: bool,
+    /// Remembered facts and the long-term conversation summary
+    pub send_facts: bool,
+    /// Battery level, once it's running low
+    pub send_battery: bool,
+    /// The front browser tab's title and address (without query string)
+    pub send_browser_tab: bool,
+    /// Current conditions where the owner set their weather location
+    pub send_weather: bool,
+    /// Reviews and CI results on the owner's pull requests in the last hour
+    pub send_github: bool,
+    /// Branch, uncommitted changes and last commit of the repo open in the
+    /// front editor or terminal
+    pub send_git: bool,
+    /// Text read off the front window when judging. Off unless turned on
+    pub send_screen_text: bool,
+}
+
+impl Default for ContextSettings {
+    fn default() -> Self {
+        Self {
+            send_app_name: true,
+            send_window_title: true,
+            send_time: true,
+            send_facts: true,
+            send_battery: true,
+            send_browser_tab: true,
+            send_weather: true,
+            send_github: true,
+            send_git: true,
+            send_screen_text: false,
+        }
+    }
+}
+
+/// Accelerator strings (e.g. "CommandOrControl+Shift+Space") per global
+/// shortcut action. `None` leaves the action unbound.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShortcutSettings {
+    pub open_palette: Option<String>,
+    pub toggle_hide: Option<String>,
+    pub trigger_judge: Option<String>,
+    pub start_focus_session: Option<String>,
+    pub ask_about_selection: Option<String>,
+    pub dictate: Option<String>,
+}
+
+impl Default for ShortcutSettings {
+    fn default() -> Self {
+        Self {
+            open_palette: Some("CommandOrControl+Shift+Space".to_string()),
+            toggle_hide: None,
+            trigger_judge: None,
+            start_focus_session: None,
+            ask_about_selection: None,
+            dictate: None,
+        }
+    }
+}
+
+/// What the pet does while another app is fullscreen (movies, slides).
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FullscreenBehavior {
+    /// Stay put
+    Ignore,
+    /// Curl up small in the corner
+    #[default]
+    Corner,
+    /// Hide the overlay until the app leaves fullscreen
+    Hide,
+}
+
+/// Which end of the free stretch of menu bar the pet perches on.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PerchSide {
+    /// Just after the front app's menus
+    Left,
+    /// Just before the status items, clear of the clock
+    #[default]
+    Right,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DisplaySettings {
+    /// Hide the overlay and live in the tray: dialogue arrives as native
+    /// notifications and chat happens in a popover under the tray icon.
+    pub menu_bar_only: bool,
+    pub fullscreen: FullscreenBehavior,
+    /// Display the pet lives on when it's connected, by name. Change it with
+    /// set_home_display, which also moves the pet.
+    pub home_display: Option<String>,
+    /// Displays the pet never goes on, like the one used for screen sharing
+    pub blocked_displays: Vec<String>,
+    pub perch_side: PerchSide,
+    /// Watch the Dock for bouncing icons so the cat can bat at them
+    pub dock_reactions: bool,
+    /// How opaque the pet window is, from 0.2 to 1
+    pub opacity: f64,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            menu_bar_only: false,
+            fullscreen: FullscreenBehavior::default(),
+            home_display: None,
+            blocked_displays: Vec::new(),
+            perch_side: PerchSide::default(),
+            dock_reactions: true,
+            opacity: 1.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteProvider {
+    /// Yahoo Finance's public chart endpoint; no key needed
+    #[default]
+    Yahoo,
+    /// Finnhub, keyed by FINNHUB_API_KEY
+    Finnhub,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuoteSettings {
+    pub provider: QuoteProvider,
+    /// Mention a watched ticker once a day when it moves past the threshold
+    pub daily_mention: bool,
+    pub mention_threshold_percent: f64,
+}
+
+impl Default for QuoteSettings {
+    fn default() -> Self {
+        Self {
+            provider: QuoteProvider::default(),
+            daily_mention: false,
+            mention_threshold_percent: 3.0,
+        }
+    }
+}
+
+/// Opt-in localhost HTTP API for Raycast, Alfred and shell scripts.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutomationSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for AutomationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47_215,
+        }
+    }
+}
+
+/// Filters for messages carried by visiting friends' pets (and stream guests).
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModerationSettings {
+    /// Words and phrases to filter on top of the built-in list
+    pub blocked_words: Vec<String>,
+    /// Also ask the model about each message; costs a request per message
+    pub llm_check: bool,
+    /// Subjects the model should filter too ("work", "politics")
+    pub blocked_topics: Vec<String>,
+}
+
+/// Stream mode: viewers and friends can pet the cat, toss it treats and
+/// send short messages through the relay.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GuestSettings {
+    pub enabled: bool,
+    pub per_guest_per_minute: u32,
+    /// Across all guests together
+    pub per_minute: u32,
+    pub max_message_chars: usize,
+}
+
+impl Default for GuestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            per_guest_per_minute: 2,
+            per_minute: 12,
+            max_message_chars: 100,
+        }
+    }
+}
+
+/// Let the cat walk between installs on the same LAN that share a key.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PresenceSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for PresenceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47_217,
+        }
+    }
+}
+
+/// Offer to summarize links and articles or explain code right after
+/// they're copied. Off by default; the clipboard is never read while off.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipboardSettings {
+    pub enabled: bool,
+    /// Only these kinds of copy are ever offered
+    pub allowed_kinds: Vec<ClipKind>,
+    /// Longer copies are ignored rather than cut down
+    pub max_chars: usize,
+    /// Copies made in these apps are never looked at
+    pub ignored_apps: Vec<String>,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_kinds: vec![ClipKind::Url, ClipKind::Article, ClipKind::Code],
+            max_chars: 8_000,
+            ignored_apps: [
+                "1Password",
+                "Bitwarden",
+                "Dashlane",
+                "Keychain Access",
+                "LastPass",
+                "Passwords",
+                "KeePassXC",
+            ]
+            .iter()
+            .map(|app| app.to_string())
+            .collect(),
+        }
+    }
+}
+
+/// Share playtime and the achievement count, anonymously, for the
+/// leaderboard. Off until the user opts in.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LeaderboardSettings {
+    pub enabled: bool,
+}
+
+/// The personality pack the cat speaks with, by id (its file name in the
+/// personalities folder). None is the plain cat.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PersonalitySettings {
+    pub active: Option<String>,
+}
+
+/// Let the cat type what the user tells it into the focused text field,
+/// each time only after they confirm the transcription. Off until opted in.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DictationSettings {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalLength {
+    /// Three lines, five-seven-five if the cat can count
+    Haiku,
+    #[default]
+    Short,
+    /// Roughly half a page
+    Long,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalTone {
+    #[default]
+    Introspective,
+    Dramatic,
+    Grumpy,
+    Sweet,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JournalSettings {
+    pub length: JournalLength,
+    pub tone: JournalTone,
+    /// Mention which apps got the most time today
+    pub include_app_usage: bool,
+    /// Quote a few things the owner said in chat
+    pub include_chat_quotes: bool,
+    /// Where `export_journal` last wrote to
+    pub export_folder: Option<String>,
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        Self {
+            length: JournalLength::default(),
+            tone: JournalTone::default(),
+            include_app_usage: true,
+            include_chat_quotes: false,
+            export_folder: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Pre-release builds published under the `beta` release tag
+    Beta,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateSettings {
+    pub channel: UpdateChannel,
+    /// Check for updates in the background every few hours
+    pub auto_check: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::default(),
+            auto_check: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BatterySettings {
+    /// On battery at or below this level, poll and animate less often
+    pub saver_threshold_percent: u8,
+}
+
+impl Default for BatterySettings {
+    fn default() -> Self {
+        Self {
+            saver_threshold_percent: 20,
+        }
+    }
+}
+
+/// How long the maintenance job keeps activity data on disk. `None` keeps it
+/// until something else (e.g. a count limit) drops it.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionSettings {
+    /// Chat messages and judge-mode lines
+    pub chat_history_days: Option<u32>,
+    /// Daily API usage samples
+    pub usage_weeks: Option<u32>,
+    /// Journal mood scores
+    pub journal_days: Option<u32>,
+    /// Remembered facts not mentioned again in this long are archived
+    pub fact_days: Option<u32>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            chat_history_days: None,
+            // About the 90 days usage.json always kept
+            usage_weeks: Some(13),
+            journal_days: None,
+            fact_days: None,
+        }
+    }
+}
+
+/// Other apps' windows the pet treats as not there: it won't stand on them
+/// and their time isn't logged as work. Meant for menu bar and screenshot
+/// overlays that sit over everything.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowSettings {
+    /// App names, case-insensitive, where `*` matches any run of characters
+    pub exclude: Vec<String>,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            exclude: vec![
+                "Bartender*".to_string(),
+                "CleanShot*".to_string(),
+                "screencaptureui".to_string(),
+                "Shottr".to_string(),
+            ],
+        }
+    }
+}
+
+/// Per-tool overrides of whether the cat may use it without asking, keyed
+/// by tool name. Tools not listed use their default.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolSettings {
+    pub permissions: HashMap<String, ToolPermission>,
+    /// Apps that open without asking, matched case-insensitively
+    pub apps: Vec<String>,
+    /// Sites that open without asking, subdomains included, e.g. github.com
+    pub sites: Vec<String>,
+}
+
+/// A place the weather is fetched for, looked up by name.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherLocation {
+    /// As the geocoder spells it, e.g. "Lisbon, Portugal"
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Where the weather comes from; nothing is fetched until a location is set.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WeatherSettings {
+    pub location: Option<WeatherLocation>,
+    pub fahrenheit: bool,
+}
+
+/// A build or test command the cat keeps an eye on. Nothing runs until a
+/// command and folder are set.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DevWatcherSettings {
+    /// Run through the shell, e.g. "cargo build" or "npm test"
+    pub command: Option<String>,
+    /// The project folder it runs in, and the one watched for changes
+    pub directory: Option<String>,
+    /// Run it again whenever files in the folder change
+    pub watch: bool,
+}
+
+/// Noticing new screenshots. Off until turned on; a screenshot is only sent
+/// to the model when asked about, or straight away with `remark`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScreenshotSettings {
+    pub enabled: bool,
+    /// Comment on each one without asking first
+    pub remark: bool,
+}
+
+/// What the ambient music plays.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Soundscape {
+    /// A warm synthesized hum
+    #[default]
+    Drone,
+    Rain,
+    /// Murmuring voices and the odd cup
+    Cafe,
+    /// The audio files in the music folder
+    Lofi,
+}
+
+/// What the pet plays. Volume and mute cover everything, music included.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SoundSettings {
+    /// From 0 to 1
+    pub volume: f32,
+    pub muted: bool,
+    pub soundscape: Soundscape,
+    /// Audio files here are the lo-fi soundscape
+    pub music_folder: Option<String>,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            volume: 0.6,
+            muted: false,
+            soundscape: Soundscape::default(),
+            music_folder: None,
+        }
+    }
+}
+
+/// Generation parameters for one dialogue mode. Anything left unset uses
+/// the built-in default for that mode.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModeConfig {
+    pub model: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+}
+
+/// Per-mode overrides for power users trading cost for verbosity, keyed by
+/// mode ("chat", "search", "journal", ...).
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DialogueConfig {
+    pub modes: HashMap<String, ModeConfig>,
+}
+
+/// Backend-owned preferences. Every section defaults independently so older
+/// settings files keep loading as new sections are added.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub context: ContextSettings,
+    pub shortcuts: ShortcutSettings,
+    pub display: DisplaySettings,
+    pub quotes: QuoteSettings,
+    pub updates: UpdateSettings,
+    pub journal: JournalSettings,
+    pub automation: AutomationSettings,
+    pub retention: RetentionSettings,
+    pub battery: BatterySettings,
+    pub presence: PresenceSettings,
+    pub guests: GuestSettings,
+    pub moderation: ModerationSettings,
+    pub leaderboard: LeaderboardSettings,
+    pub clipboard: ClipboardSettings,
+    pub dictation: DictationSettings,
+    pub dialogue_config: DialogueConfig,
+    pub personality: PersonalitySettings,
+    pub windows: WindowSettings,
+    pub tools: ToolSettings,
+    pub weather: WeatherSettings,
+    pub dev_watcher: DevWatcherSettings,
+    pub screenshots: ScreenshotSettings,
+    pub sound: SoundSettings,
+    /// Language tag the pet speaks, e.g. "fr" or "pt-BR". None follows the
+    /// OS language.
+    pub language: Option<String>,
+}
+
+pub struct SettingsStore {
+    settings: Mutex<Settings>,
+    path: Option<PathBuf>,
+}
+
+impl SettingsStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, SETTINGS_FILE).ok();
+        let settings = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            settings: Mutex::new(settings),
+            path,
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, settings: Settings) -> PetResult<()> {
+        let mut current = self.settings.lock().unwrap();
+        if let Some(path) = &self.path {
+            persist::save(path, &settings)?;
+        }
+        *current = settings;
+        Ok(())
+    }
+}
+
+#[tauri::command(async)]
+pub fn get_settings(store: tauri::State<'_, SettingsStore>) -> Settings {
+    store.get()
+}
+
+#[tauri::command]
+pub async fn set_settings(app: tauri::AppHandle, settings: Settings) -> PetResult<Settings> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        // Bindings only change through set_shortcut, which also re-registers
+        // them; likewise the automation server through set_automation_enabled
+        // LAN presence through set_presence_enabled, and the leaderboard
+        // through set_leaderboard_enabled, which withdraws from it too, and
+        // dictation through set_dictation_enabled, which drops any draft.
+        // Generation overrides are validated by set_dialogue_config, and the
+        // language tag by set_language. The update channel goes through
+        // set_update_channel, which checks the build can verify it, and the
+        // journal's export folder is whatever export_journal last wrote to.
+        // Window exclusions are checked by set_window_exclusions, tool
+        // permissions by set_tool_permission, the apps and sites that open
+        // without asking by set_launch_allowlist, the weather location by
+        // set_weather, which looks it up, the build command by
+        // set_dev_watcher, which checks its folder exists, screenshot
+        // watching by set_screenshot_watch, which drops any pending offer,
+        // and sound by set_volume, set_sound_muted, set_soundscape and
+        // set_music_folder, which tell the audio thread.
+        let current = store.get();
+        let mut settings = settings;
+        settings.updates.channel = current.updates.channel;
+        settings.journal.export_folder = current.journal.export_folder;
+        let settings = Settings {
+            shortcuts: current.shortcuts,
+            automation: current.automation,
+            presence: current.presence,
+            leaderboard: current.leaderboard,
+            dictation: current.dictation,
+            dialogue_config: current.dialogue_config,
+            language: current.language,
+            windows: current.windows,
+            tools: current.tools,
+            weather: current.weather,
+            dev_watcher: current.dev_watcher,
+            screenshots: current.screenshots,
+            sound: current.sound,
+            ..settings
+        };
+        store.replace(settings)?;
+        Ok(store.get())
+    })
+    .await?
+}
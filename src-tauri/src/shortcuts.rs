@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::error::{PetError, PetResult};
+use crate::settings::{SettingsStore, ShortcutSettings};
+use crate::{accessibility, dictation, tray};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutAction {
+    OpenPalette,
+    ToggleHide,
+    TriggerJudge,
+    StartFocusSession,
+    AskAboutSelection,
+    Dictate,
+}
+
+impl ShortcutAction {
+    const ALL: [ShortcutAction; 6] = [
+        ShortcutAction::OpenPalette,
+        ShortcutAction::ToggleHide,
+        ShortcutAction::TriggerJudge,
+        ShortcutAction::StartFocusSession,
+        ShortcutAction::AskAboutSelection,
+        ShortcutAction::Dictate,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            ShortcutAction::OpenPalette => "Open the command palette",
+            ShortcutAction::ToggleHide => "Hide or show the pet",
+            ShortcutAction::TriggerJudge => "Have the cat judge what you're doing",
+            ShortcutAction::StartFocusSession => "Start a focus session",
+            ShortcutAction::AskAboutSelection => "Ask the cat about highlighted text",
+            ShortcutAction::Dictate => "Tell the cat what to type",
+        }
+    }
+}
+
+impl ShortcutSettings {
+    pub fn binding(&self, action: ShortcutAction) -> Option<&str> {
+        match action {
+            ShortcutAction::OpenPalette => self.open_palette.as_deref(),
+            ShortcutAction::ToggleHide => self.toggle_hide.as_deref(),
+            ShortcutAction::TriggerJudge => self.trigger_judge.as_deref(),
+            ShortcutAction::StartFocusSession => self.start_focus_session.as_deref(),
+            ShortcutAction::AskAboutSelection => self.ask_about_selection.as_deref(),
+            ShortcutAction::Dictate => self.dictate.as_deref(),
+        }
+    }
+
+    fn binding_mut(&mut self, action: ShortcutAction) -> &mut Option<String> {
+        match action {
+            ShortcutAction::OpenPalette => &mut self.open_palette,
+            ShortcutAction::ToggleHide => &mut self.toggle_hide,
+            ShortcutAction::TriggerJudge => &mut self.trigger_judge,
+            ShortcutAction::StartFocusSession => &mut self.start_focus_session,
+            ShortcutAction::AskAboutSelection => &mut self.ask_about_selection,
+            ShortcutAction::Dictate => &mut self.dictate,
+        }
+    }
+}
+
+fn parse(accelerator: &str) -> PetResult<Shortcut> {
+    Shortcut::from_str(accelerator)
+        .map_err(|e| PetError::InvalidInput(format!("Invalid shortcut \"{}\": {}", accelerator, e)))
+}
+
+/// Read the highlighted text and hand it to the webview as
+/// `selection-captured`, or have the cat say why it couldn't.
+fn capture_selection(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Ok(selection) = crate::run_blocking(accessibility::selected_text).await else {
+            return;
+        };
+        let _ = match selection {
+            Ok(Some(selection)) => app.emit("selection-captured", selection),
+            Ok(None) => app.emit("pet-announce", "Highlight something first, then ask me!"),
+            Err(_) => app.emit(
+                "pet-announce",
+                "I can't see what you highlighted until I'm allowed under Accessibility.",
+            ),
+        };
+    });
+}
+
+/// Hide/show and focus sessions are backend state shared with the tray, and
+/// the selection can only be read here; everything else is forwarded to the
+/// frontend as a `shortcut-triggered` event. Dictation only gets there once
+/// it's been turned on.
+pub fn handle(app: &tauri::AppHandle, action: ShortcutAction) {
+    match action {
+        ShortcutAction::ToggleHide => tray::toggle_pet_visible(app),
+        ShortcutAction::StartFocusSession => tray::set_focus_session(app, true),
+        ShortcutAction::AskAboutSelection => capture_selection(app),
+        ShortcutAction::Dictate if !dictation::is_enabled(app) => {
+            let _ = app.emit(
+                "pet-announce",
+                "Turn on dictation in Settings and I'll type for you.",
+            );
+        }
+        _ => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.emit("shortcut-triggered", action);
+            }
+        }
+    }
+}
+
+fn register(app: &tauri::AppHandle, action: ShortcutAction, shortcut: Shortcut) -> PetResult<()> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _, event| {
+            if event.state == ShortcutState::Pressed {
+                handle(app, action);
+            }
+        })
+        .map_err(|e| PetError::Platform(format!("Failed to register shortcut: {}", e)))
+}
+
+/// Register every persisted binding, replacing whatever is currently
+/// registered. A binding that fails (e.g. taken by another app) is skipped so
+/// the rest still work.
+pub fn register_all(app: &tauri::AppHandle) {
+    let _ = app.global_shortcut().unregister_all();
+    let bindings = app.state::<SettingsStore>().get().shortcuts;
+    for action in ShortcutAction::ALL {
+        let Some(accelerator) = bindings.binding(action) else {
+            continue;
+        };
+        if let Err(e) = parse(accelerator).and_then(|s| register(app, action, s)) {
+            eprintln!("Skipping shortcut for {:?}: {}", action, e);
+        }
+    }
+    announce(app);
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Keybinding {
+    pub action: ShortcutAction,
+    pub accelerator: Option<String>,
+    pub description: &'static str,
+    /// Whether the OS actually has it; false when another app holds the keys
+    pub registered: bool,
+}
+
+fn keybindings(app: &tauri::AppHandle) -> Vec<Keybinding> {
+    let bindings = app.state::<SettingsStore>().get().shortcuts;
+    ShortcutAction::ALL
+        .into_iter()
+        .map(|action| {
+            let accelerator = bindings.binding(action).map(str::to_string);
+            let registered = accelerator
+                .as_deref()
+                .and_then(|a| parse(a).ok())
+                .is_some_and(|s| app.global_shortcut().is_registered(s));
+            Keybinding {
+                action,
+                accelerator,
+                description: action.description(),
+                registered,
+            }
+        })
+        .collect()
+}
+
+/// Tell the webview the bindings changed so shortcut overlays stay accurate.
+fn announce(app: &tauri::AppHandle) {
+    let _ = app.emit("keybindings-changed", keybindings(app));
+}
+
+/// Every shortcut action with its binding and whether it's live right now,
+/// for the shortcuts overlay.
+#[tauri::command(async)]
+pub fn get_keybindings(app: tauri::AppHandle) -> Vec<Keybinding> {
+    keybindings(&app)
+}
+
+#[tauri::command(async)]
+pub fn get_shortcuts(store: tauri::State<'_, SettingsStore>) -> ShortcutSettings {
+    store.get().shortcuts
+}
+
+/// Bind `action` to `accelerator`, or unbind it when `accelerator` is empty.
+/// The new binding is validated and registered before it is persisted.
+#[tauri::command]
+pub async fn set_shortcut(
+    app: tauri::AppHandle,
+    action: ShortcutAction,
+    accelerator: Option<String>,
+) -> PetResult<ShortcutSettings> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        let accelerator = accelerator
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty());
+
+        let new = accelerator.as_deref().map(parse).transpose()?;
+        if let Some(new) = new {
+            let conflict = ShortcutAction::ALL.into_iter().find(|&other| {
+                other != action
+                    && settings
+                        .shortcuts
+                        .binding(other)
+                        .and_then(|a| parse(a).ok())
+                        .is_some_and(|s| s.id() == new.id())
+            });
+            if let Some(other) = conflict {
+                return Err(PetError::InvalidInput(format!(
+                    "That shortcut is already bound to {:?}",
+                    other
+                )));
+            }
+        }
+
+        let old = settings
+            .shortcuts
+            .binding(action)
+            .and_then(|a| parse(a).ok());
+        if let Some(old) = old {
+            let _ = app.global_shortcut().unregister(old);
+        }
+        if let Some(new) = new {
+            if let Err(e) = register(&app, action, new) {
+                if let Some(old) = old {
+                    let _ = register(&app, action, old);
+                }
+                return Err(e);
+            }
+        }
+
+        *settings.shortcuts.binding_mut(action) = accelerator;
+        store.replace(settings)?;
+        announce(&app);
+        Ok(store.get().shortcuts)
+    })
+    .await?
+}
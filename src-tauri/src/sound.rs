@@ -0,0 +1,765 @@
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::persist;
+use crate::settings::{SettingsStore, Soundscape};
+use crate::tray;
+
+const SAMPLE_RATE: u32 = 44_100;
+/// How often the audio thread looks at volume, meetings and the playlist
+/// when nothing is asked of it.
+const TICK: Duration = Duration::from_millis(100);
+/// Each pet keeps the purr going this much longer.
+const PURR_FOR: Duration = Duration::from_secs(4);
+const PURR_FADE: Duration = Duration::from_millis(600);
+const TRACK_FADE_IN: Duration = Duration::from_secs(1);
+/// Tracks and soundscapes blend into each other over this long.
+const CROSSFADE: Duration = Duration::from_secs(4);
+/// The sleep timer turns the music down over its last minute.
+const SLEEP_FADE: Duration = Duration::from_secs(60);
+const MAX_SLEEP_MINUTES: u32 = 12 * 60;
+/// Offered in the tray, in minutes.
+pub const SLEEP_TIMERS: &[u32] = &[15, 30, 60];
+/// Every soundscape with its tray label.
+pub const SOUNDSCAPES: &[(Soundscape, &str)] = &[
+    (Soundscape::Drone, "Warm Hum"),
+    (Soundscape::Rain, "Rain"),
+    (Soundscape::Cafe, "Café"),
+    (Soundscape::Lofi, "Lo-fi"),
+];
+pub const SOUNDS_DIR: &str = "sounds";
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "flac", "ogg"];
+
+type Sound = Box<dyn Source<Item = f32> + Send>;
+
+enum Command {
+    Play(Sound),
+    Purr,
+    /// Settings or the music toggle changed
+    Refresh,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SoundStatus {
+    pub volume: f32,
+    pub muted: bool,
+    pub soundscape: Soundscape,
+    pub music_folder: Option<String>,
+    pub music_playing: bool,
+    /// File name of the track playing; None for the synthesized soundscapes
+    pub track: Option<String>,
+    /// When the sleep timer stops the music. Unix millis
+    pub sleep_at: Option<i64>,
+    /// What the sleep timer was set to
+    pub sleep_minutes: Option<u32>,
+    /// False when there's no output device to play on
+    pub available: bool,
+}
+
+struct SleepTimer {
+    at: Instant,
+    /// The same moment, for the webview
+    at_millis: i64,
+    minutes: u32,
+}
+
+pub struct SoundState {
+    commands: Mutex<Sender<Command>>,
+    music: AtomicBool,
+    track: Mutex<Option<String>>,
+    sleep: Mutex<Option<SleepTimer>>,
+    available: AtomicBool,
+}
+
+/// Manage the state and start the audio thread. The thread owns the output
+/// stream, which can't leave the thread that opened it.
+pub fn start(app: &tauri::AppHandle) {
+    let (commands, receiver) = mpsc::channel();
+    app.manage(SoundState {
+        commands: Mutex::new(commands),
+        music: AtomicBool::new(false),
+        track: Mutex::new(None),
+        sleep: Mutex::new(None),
+        available: AtomicBool::new(true),
+    });
+    let handle = app.clone();
+    std::thread::spawn(move || run(handle, receiver));
+}
+
+impl SoundState {
+    fn send(&self, command: Command) -> PetResult<()> {
+        self.commands
+            .lock()
+            .unwrap()
+            .send(command)
+            .map_err(|_| PetError::Platform("There's no audio output to play on".to_string()))
+    }
+}
+
+/// A mono voice worked out sample by sample from the time in seconds.
+struct Synth<F> {
+    voice: F,
+    sample: u64,
+    /// In samples; None plays forever
+    length: Option<u64>,
+}
+
+fn synth<F: FnMut(f64) -> f32>(length: Option<Duration>, voice: F) -> Synth<F> {
+    Synth {
+        voice,
+        sample: 0,
+        length: length.map(|length| (length.as_secs_f64() * SAMPLE_RATE as f64) as u64),
+    }
+}
+
+impl<F: FnMut(f64) -> f32> Iterator for Synth<F> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.length.is_some_and(|length| self.sample >= length) {
+            return None;
+        }
+        let t = self.sample as f64 / SAMPLE_RATE as f64;
+        self.sample += 1;
+        Some((self.voice)(t))
+    }
+}
+
+impl<F: FnMut(f64) -> f32> Source for Synth<F> {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.length
+            .map(|length| Duration::from_secs_f64(length as f64 / SAMPLE_RATE as f64))
+    }
+}
+
+/// xorshift: plenty random for noise.
+struct Noise(u32);
+
+impl Noise {
+    /// Uniform between -1 and 1.
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f64 / u32::MAX as f64 * 2.0 - 1.0
+    }
+}
+
+fn sine(frequency: f64, t: f64) -> f64 {
+    (std::f64::consts::TAU * frequency * t).sin()
+}
+
+fn triangle(frequency: f64, t: f64) -> f64 {
+    let phase = (frequency * t).fract();
+    4.0 * (phase - 0.5).abs() - 1.0
+}
+
+/// The sounds that ship with the app, made up on the spot.
+fn builtin(name: &str) -> Option<Sound> {
+    let sound: Sound = match name {
+        // Two bells, for unlocks and good news
+        "chime" => Box::new(synth(Some(Duration::from_millis(1200)), |t| {
+            ((sine(880.0, t) + 0.6 * sine(1320.0, t)) * (-4.0 * t).exp() * 0.35) as f32
+        })),
+        // A quick falling blip, for speech bubbles
+        "pop" => Box::new(synth(Some(Duration::from_millis(80)), |t| {
+            let phase = 900.0 * t - 3750.0 * t * t;
+            ((std::f64::consts::TAU * phase).sin() * (1.0 - t / 0.08) * 0.4) as f32
+        })),
+        // Something soft hitting the floor
+        "thud" => Box::new(synth(Some(Duration::from_millis(500)), |t| {
+            (sine(70.0, t) * (-12.0 * t).exp() * 0.6) as f32
+        })),
+        _ => return None,
+    };
+    Some(sound)
+}
+
+/// Rumbling noise, pulsing 26 times a second and swelling with each breath.
+fn purring() -> Sound {
+    let mut noise = Noise(0x9e37_79b9);
+    let mut rumble = 0.0;
+    Box::new(synth(None, move |t| {
+        rumble += 0.05 * (noise.next() - rumble);
+        let pulse = (0.5 + 0.5 * sine(26.0, t)).powi(2);
+        let breath = 0.55 + 0.45 * sine(1.0 / 2.4, t);
+        ((rumble * pulse * breath * 3.0).clamp(-1.0, 1.0)) as f32
+    }))
+}
+
+/// The warm hum: three detuned low notes and a slowly pulsing high one.
+fn drone() -> Sound {
+    Box::new(synth(None, |t| {
+        let pad = 0.4 * (sine(130.3, t) + triangle(164.2, t) + sine(195.8, t));
+        let shimmer = (0.12 + 0.08 * sine(0.15, t)) * sine(523.5, t);
+        ((pad + shimmer) * 0.25) as f32
+    }))
+}
+
+/// A steady hiss that swells and ebbs, with drops pattering through it.
+fn rain() -> Sound {
+    let mut noise = Noise(0x2545_f491);
+    let (mut low, mut high) = (0.0, 0.0);
+    // Loudness, pitch and start of the latest drop
+    let mut drop = (0.0, 0.0, 0.0);
+    Box::new(synth(None, move |t| {
+        // Noise with the harshest highs and the rumble taken off
+        low += 0.3 * (noise.next() - low);
+        high += 0.01 * (low - high);
+        let swell = 0.8 + 0.2 * sine(0.07, t);
+        // About a dozen drops a second
+        if noise.next() > 0.9994 {
+            drop = (
+                0.1 + 0.2 * noise.next().abs(),
+                1800.0 + 2500.0 * noise.next().abs(),
+                t,
+            );
+        }
+        let since = t - drop.2;
+        let patter = drop.0 * (-300.0 * since).exp() * sine(drop.1, since);
+        (((low - high) * 0.5 * swell + patter) * 0.6) as f32
+    }))
+}
+
+/// A room of murmuring voices that gets louder and quieter, and a cup set
+/// down every few seconds.
+fn cafe() -> Sound {
+    let mut noise = Noise(0x68e3_1da4);
+    let (mut low, mut high) = (0.0, 0.0);
+    let mut chatter: f64 = 0.6;
+    // Loudness, pitch and start of the latest clink
+    let mut clink = (0.0, 0.0, 0.0);
+    Box::new(synth(None, move |t| {
+        // Roughly the band voices sit in, 300 Hz to 3 kHz
+        low += 0.35 * (noise.next() - low);
+        high += 0.04 * (low - high);
+        chatter = (chatter + 0.0004 * noise.next()).clamp(0.3, 1.0);
+        let syllables = 0.7 + 0.3 * sine(3.1, t) * sine(0.23, t);
+        // One every six seconds or so
+        if noise.next() > 0.999_992_4 {
+            clink = (
+                0.08 + 0.1 * noise.next().abs(),
+                2400.0 + 1200.0 * noise.next().abs(),
+                t,
+            );
+        }
+        let since = t - clink.2;
+        let cup = clink.0
+            * (-18.0 * since).exp()
+            * (sine(clink.1, since) + 0.5 * sine(clink.1 * 1.51, since));
+        (((low - high) * chatter * syllables * 0.9 + cup) * 0.5) as f32
+    }))
+}
+
+fn decode(path: &Path) -> PetResult<Sound> {
+    let file = File::open(path)?;
+    let decoder = Decoder::new(BufReader::new(file))
+        .map_err(|e| PetError::Parse(format!("Couldn't play {}: {}", path.display(), e)))?;
+    Ok(Box::new(decoder.convert_samples()))
+}
+
+fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| AUDIO_EXTENSIONS.contains(&extension.as_str()))
+}
+
+/// Audio files directly in `folder`, by name.
+fn playlist(folder: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut tracks: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_audio(path))
+        .collect();
+    tracks.sort();
+    tracks
+}
+
+/// The soundscape or track playing now.
+struct Track {
+    sink: Sink,
+    started: Instant,
+    /// None when it plays forever or the file doesn't say
+    length: Option<Duration>,
+}
+
+/// Everything playing, owned by the audio thread.
+struct Player {
+    output: OutputStreamHandle,
+    effects: Vec<Sink>,
+    purr: Option<(Sink, Instant)>,
+    music: Option<Track>,
+    /// Music on its way out, and when it started fading
+    fading: Vec<(Sink, Instant)>,
+    volume: f32,
+    muted: bool,
+    music_on: bool,
+    soundscape: Soundscape,
+    folder: Option<String>,
+    tracks: Vec<PathBuf>,
+    next_track: usize,
+}
+
+impl Player {
+    fn sink(&self) -> Option<Sink> {
+        Sink::try_new(&self.output).ok()
+    }
+
+    fn level(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+
+    fn play(&mut self, sound: Sound) {
+        if let Some(sink) = self.sink() {
+            sink.set_volume(self.level());
+            sink.append(sound);
+            self.effects.push(sink);
+        }
+    }
+
+    fn purr(&mut self) {
+        let until = Instant::now() + PURR_FOR;
+        match &mut self.purr {
+            Some((_, end)) => *end = until,
+            None => {
+                if let Some(sink) = self.sink() {
+                    sink.set_volume(self.level());
+                    sink.append(purring().fade_in(Duration::from_millis(300)));
+                    self.purr = Some((sink, until));
+                }
+            }
+        }
+    }
+
+    fn fade_out_music(&mut self) {
+        if let Some(track) = self.music.take() {
+            self.fading.push((track.sink, Instant::now()));
+        }
+    }
+
+    fn refresh(&mut self, app: &tauri::AppHandle) {
+        let settings = app.state::<SettingsStore>().get().sound;
+        self.volume = settings.volume.clamp(0.0, 1.0);
+        self.muted = settings.muted;
+        self.music_on = app.state::<SoundState>().music.load(Ordering::SeqCst);
+        let mut restart = settings.soundscape != self.soundscape;
+        self.soundscape = settings.soundscape;
+        if settings.music_folder != self.folder {
+            self.tracks = settings
+                .music_folder
+                .as_deref()
+                .map(|folder| playlist(Path::new(folder)))
+                .unwrap_or_default();
+            self.next_track = 0;
+            self.folder = settings.music_folder;
+            // Start over from the new folder's first track
+            restart |= self.soundscape == Soundscape::Lofi;
+        }
+        if restart || !self.music_on {
+            self.fade_out_music();
+        }
+        if !self.music_on {
+            set_track(app, None);
+        }
+    }
+
+    /// What the soundscape plays next: for lo-fi the next track that
+    /// decodes, or the hum when none does.
+    fn next(&mut self) -> (Sound, Option<String>) {
+        match self.soundscape {
+            Soundscape::Drone => return (drone(), None),
+            Soundscape::Rain => return (rain(), None),
+            Soundscape::Cafe => return (cafe(), None),
+            Soundscape::Lofi => {}
+        }
+        for _ in 0..self.tracks.len() {
+            let path = self.tracks[self.next_track % self.tracks.len()].clone();
+            self.next_track = (self.next_track + 1) % self.tracks.len();
+            match decode(&path) {
+                Ok(sound) => {
+                    let name = path
+                        .file_stem()
+                        .map(|name| name.to_string_lossy().into_owned());
+                    return (sound, name);
+                }
+                Err(e) => eprintln!("Skipping track: {}", e),
+            }
+        }
+        (drone(), None)
+    }
+
+    /// The music's share of the volume, turned down over the sleep timer's
+    /// last minute. Stops the music when it runs out.
+    fn sleep_fade(&mut self, app: &tauri::AppHandle) -> f32 {
+        let state = app.state::<SoundState>();
+        let mut sleep = state.sleep.lock().unwrap();
+        let Some(timer) = sleep.as_ref() else {
+            return 1.0;
+        };
+        let left = timer.at.saturating_duration_since(Instant::now());
+        if !left.is_zero() {
+            return (left.as_secs_f32() / SLEEP_FADE.as_secs_f32()).min(1.0);
+        }
+        *sleep = None;
+        drop(sleep);
+        state.music.store(false, Ordering::SeqCst);
+        self.music_on = false;
+        self.fade_out_music();
+        set_track(app, None);
+        let _ = app.emit("sound-changed", status(app));
+        tray::refresh(app);
+        0.0
+    }
+
+    fn tick(&mut self, app: &tauri::AppHandle) {
+        let level = self.level();
+        // Music waits out calls rather than playing to nobody
+        let paused = crate::meetings::is_in_meeting(app);
+        if paused {
+            // Dropping a sink stops it
+            self.effects.clear();
+            self.purr = None;
+        }
+        self.effects.retain(|sink| !sink.empty());
+        for sink in &self.effects {
+            sink.set_volume(level);
+        }
+        if let Some((sink, until)) = &self.purr {
+            let left = until.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                self.purr = None;
+            } else {
+                let fade = (left.as_secs_f32() / PURR_FADE.as_secs_f32()).min(1.0);
+                sink.set_volume(level * fade);
+            }
+        }
+        let music_level = level * self.sleep_fade(app);
+        self.fading
+            .retain(|(sink, since)| since.elapsed() < CROSSFADE && !sink.empty());
+        for (sink, since) in &self.fading {
+            let left = 1.0 - since.elapsed().as_secs_f32() / CROSSFADE.as_secs_f32();
+            sink.set_volume(music_level * left);
+            if paused {
+                sink.pause();
+            } else {
+                sink.play();
+            }
+        }
+        if !self.music_on {
+            return;
+        }
+        let due = self.music.as_ref().is_none_or(|track| {
+            track.sink.empty()
+                || track.length.is_some_and(|length| {
+                    // Very short files just play out
+                    length > CROSSFADE * 2 && track.started.elapsed() + CROSSFADE >= length
+                })
+        });
+        if due && !paused {
+            // Fade in gently from silence, or blend over what was playing
+            let fade = if self.music.is_some() || !self.fading.is_empty() {
+                CROSSFADE
+            } else {
+                TRACK_FADE_IN
+            };
+            self.fade_out_music();
+            let (sound, name) = self.next();
+            let length = sound.total_duration();
+            self.music = self.sink().map(|sink| {
+                sink.append(sound.fade_in(fade));
+                Track {
+                    sink,
+                    started: Instant::now(),
+                    length,
+                }
+            });
+            set_track(app, name);
+        }
+        if let Some(track) = &self.music {
+            track.sink.set_volume(music_level);
+            if paused {
+                track.sink.pause();
+            } else {
+                track.sink.play();
+            }
+        }
+    }
+}
+
+fn set_track(app: &tauri::AppHandle, track: Option<String>) {
+    let state = app.state::<SoundState>();
+    let mut current = state.track.lock().unwrap();
+    if *current != track {
+        *current = track;
+        drop(current);
+        let _ = app.emit("sound-changed", status(app));
+    }
+}
+
+fn run(app: tauri::AppHandle, commands: Receiver<Command>) {
+    let Ok((_stream, output)) = OutputStream::try_default() else {
+        eprintln!("No audio output device; sounds are off");
+        app.state::<SoundState>()
+            .available
+            .store(false, Ordering::SeqCst);
+        return;
+    };
+    let mut player = Player {
+        output,
+        effects: Vec::new(),
+        purr: None,
+        music: None,
+        fading: Vec::new(),
+        volume: 0.0,
+        muted: false,
+        music_on: false,
+        soundscape: Soundscape::default(),
+        folder: None,
+        tracks: Vec::new(),
+        next_track: 0,
+    };
+    player.refresh(&app);
+    loop {
+        match commands.recv_timeout(TICK) {
+            // Effects and purring are dropped during calls, not saved for
+            // after; a chime late is a chime for nothing
+            Ok(Command::Play(_) | Command::Purr) if crate::meetings::is_in_meeting(&app) => {}
+            Ok(Command::Play(sound)) => player.play(sound),
+            Ok(Command::Purr) => player.purr(),
+            Ok(Command::Refresh) => player.refresh(&app),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        player.tick(&app);
+    }
+}
+
+pub fn status(app: &tauri::AppHandle) -> SoundStatus {
+    let settings = app.state::<SettingsStore>().get().sound;
+    let state = app.state::<SoundState>();
+    let track = state.track.lock().unwrap().clone();
+    let (sleep_at, sleep_minutes) = state
+        .sleep
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|timer| (timer.at_millis, timer.minutes))
+        .unzip();
+    SoundStatus {
+        volume: settings.volume.clamp(0.0, 1.0),
+        muted: settings.muted,
+        soundscape: settings.soundscape,
+        music_folder: settings.music_folder,
+        music_playing: state.music.load(Ordering::SeqCst),
+        track,
+        sleep_at,
+        sleep_minutes,
+        available: state.available.load(Ordering::SeqCst),
+    }
+}
+
+/// Tell the audio thread, the webview and the tray that something changed.
+fn changed(app: &tauri::AppHandle) -> PetResult<SoundStatus> {
+    app.state::<SoundState>().send(Command::Refresh)?;
+    let status = status(app);
+    let _ = app.emit("sound-changed", status.clone());
+    tray::refresh(app);
+    Ok(status)
+}
+
+/// Start or stop the ambient music. Stopping it clears the sleep timer.
+pub fn set_playing(app: &tauri::AppHandle, playing: bool) -> PetResult<SoundStatus> {
+    let state = app.state::<SoundState>();
+    state.music.store(playing, Ordering::SeqCst);
+    if !playing {
+        *state.sleep.lock().unwrap() = None;
+    }
+    changed(app)
+}
+
+/// Switch to `soundscape`, crossfading if music is playing, and start it
+/// if not. Saved.
+pub fn set_scene(app: &tauri::AppHandle, soundscape: Soundscape) -> PetResult<SoundStatus> {
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.sound.soundscape = soundscape;
+    store.replace(settings)?;
+    set_playing(app, true)
+}
+
+/// Stop the music `minutes` from now, or never with None. Not saved.
+pub fn set_sleep(app: &tauri::AppHandle, minutes: Option<u32>) -> PetResult<SoundStatus> {
+    if minutes.is_some_and(|minutes| minutes == 0 || minutes > MAX_SLEEP_MINUTES) {
+        return Err(PetError::InvalidInput(format!(
+            "The sleep timer goes from 1 to {} minutes",
+            MAX_SLEEP_MINUTES
+        )));
+    }
+    *app.state::<SoundState>().sleep.lock().unwrap() = minutes.map(|minutes| {
+        let length = Duration::from_secs(minutes as u64 * 60);
+        SleepTimer {
+            at: Instant::now() + length,
+            at_millis: chrono::Utc::now().timestamp_millis() + length.as_millis() as i64,
+            minutes,
+        }
+    });
+    changed(app)
+}
+
+/// Play a sound once: `<name>.wav` (or mp3, flac, ogg) from the sounds
+/// folder in the app data, or one of the built-in "chime", "pop" and
+/// "thud". Files there replace the built-in sound of the same name.
+pub fn play(app: &tauri::AppHandle, name: &str) -> PetResult<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(PetError::InvalidInput(format!(
+            "\"{}\" isn't a sound name",
+            name
+        )));
+    }
+    let folder = persist::app_data_file(app, SOUNDS_DIR)?;
+    let file = AUDIO_EXTENSIONS
+        .iter()
+        .map(|extension| folder.join(format!("{}.{}", name, extension)))
+        .find(|path| path.is_file());
+    let sound = match file {
+        Some(path) => decode(&path)?,
+        None => builtin(name)
+            .ok_or_else(|| PetError::InvalidInput(format!("There's no sound called {}", name)))?,
+    };
+    app.state::<SoundState>().send(Command::Play(sound))
+}
+
+#[tauri::command(async)]
+pub fn get_sound_status(app: tauri::AppHandle) -> SoundStatus {
+    status(&app)
+}
+
+#[tauri::command]
+pub async fn play_sound(app: tauri::AppHandle, name: String) -> PetResult<()> {
+    crate::run_blocking(move || play(&app, &name)).await?
+}
+
+/// Start purring, or keep it going a few seconds more.
+#[tauri::command(async)]
+pub fn purr(state: tauri::State<'_, SoundState>) -> PetResult<()> {
+    state.send(Command::Purr)
+}
+
+/// Volume for everything the pet plays, from 0 to 1. Saved.
+#[tauri::command]
+pub async fn set_volume(app: tauri::AppHandle, volume: f32) -> PetResult<SoundStatus> {
+    if !(0.0..=1.0).contains(&volume) {
+        return Err(PetError::InvalidInput(
+            "Volume goes from 0 to 1".to_string(),
+        ));
+    }
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.sound.volume = volume;
+        store.replace(settings)?;
+        changed(&app)
+    })
+    .await?
+}
+
+/// Silence everything the pet plays, music included, without losing the
+/// volume. Saved.
+#[tauri::command]
+pub async fn set_sound_muted(app: tauri::AppHandle, muted: bool) -> PetResult<SoundStatus> {
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.sound.muted = muted;
+        store.replace(settings)?;
+        changed(&app)
+    })
+    .await?
+}
+
+/// Play the audio files in `folder` as the lo-fi soundscape, in name order
+/// and round again, crossfading between them.
+#[tauri::command]
+pub async fn set_music_folder(
+    app: tauri::AppHandle,
+    folder: Option<String>,
+) -> PetResult<SoundStatus> {
+    let folder = folder
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty());
+    if let Some(folder) = &folder {
+        if !Path::new(folder).is_dir() {
+            return Err(PetError::InvalidInput(format!(
+                "There's no folder at {}",
+                folder
+            )));
+        }
+    }
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.sound.music_folder = folder;
+        store.replace(settings)?;
+        changed(&app)
+    })
+    .await?
+}
+
+/// Start or stop the ambient music. Not saved: the pet starts quiet.
+#[tauri::command(async)]
+pub fn set_music_playing(app: tauri::AppHandle, playing: bool) -> PetResult<SoundStatus> {
+    set_playing(&app, playing)
+}
+
+/// Pick the ambient soundscape and play it, or stop the music with None.
+#[tauri::command]
+pub async fn set_soundscape(
+    app: tauri::AppHandle,
+    soundscape: Option<Soundscape>,
+) -> PetResult<SoundStatus> {
+    crate::run_blocking(move || match soundscape {
+        Some(soundscape) => set_scene(&app, soundscape),
+        None => set_playing(&app, false),
+    })
+    .await?
+}
+
+/// Stop the music after `minutes`, fading it out over the last one. None
+/// clears the timer.
+#[tauri::command(async)]
+pub fn set_sleep_timer(app: tauri::AppHandle, minutes: Option<u32>) -> PetResult<SoundStatus> {
+    set_sleep(&app, minutes)
+}
@@ -0,0 +1,327 @@
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::usage::UsageStore;
+use crate::{notifications, persist, scheduler};
+
+pub const TEAMS_FILE: &str = "teams.json";
+/// Local hour after which yesterday's games are looked up, so results land
+/// the morning after rather than at midnight.
+const MORNING_HOUR: u32 = 8;
+/// Consecutive losses before a muted team stops being mentioned.
+const LOSING_STREAK_MUTE: u32 = 3;
+const NO_GAME_MARKER: &str = "NO_GAME";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    pub id: String,
+    pub name: String,
+    /// Disambiguates teams that share a name across sports ("NBA", "Premier League")
+    pub league: Option<String>,
+    /// Unix millis
+    pub created_at: i64,
+    /// Local date (YYYY-MM-DD) whose games were last looked up
+    pub last_checked_date: Option<String>,
+    /// Score line of the most recent game, e.g. "Won 3-1 against Rivals"
+    pub last_result: Option<String>,
+    pub losing_streak: u32,
+    /// Stop bringing the team up after LOSING_STREAK_MUTE straight losses,
+    /// until they win again. Purely for the owner's emotional wellbeing.
+    pub mute_during_losing_streak: bool,
+}
+
+impl Team {
+    fn display_name(&self) -> String {
+        match &self.league {
+            Some(league) => format!("{} ({})", self.name, league),
+            None => self.name.clone(),
+        }
+    }
+
+    fn is_sulking(&self) -> bool {
+        self.mute_during_losing_streak && self.losing_streak >= LOSING_STREAK_MUTE
+    }
+}
+
+pub struct TeamStore {
+    teams: Mutex<Vec<Team>>,
+    path: Option<PathBuf>,
+}
+
+impl TeamStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, TEAMS_FILE).ok();
+        let teams = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            teams: Mutex::new(teams),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut teams = self.teams.lock().unwrap();
+        *teams = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, teams: &[Team]) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &teams);
+        }
+    }
+
+    pub fn all(&self) -> Vec<Team> {
+        self.teams.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, new_teams: Vec<Team>) {
+        let mut teams = self.teams.lock().unwrap();
+        *teams = new_teams;
+        self.save(&teams);
+    }
+
+    fn add(&self, name: String, league: Option<String>) -> Team {
+        let team = Team {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            league,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            // Start with today's games so the first report is about tomorrow morning
+            last_checked_date: Some(yesterday()),
+            last_result: None,
+            losing_streak: 0,
+            mute_during_losing_streak: false,
+        };
+        let mut teams = self.teams.lock().unwrap();
+        teams.push(team.clone());
+        self.save(&teams);
+        team
+    }
+
+    fn remove(&self, id: &str) -> bool {
+        let mut teams = self.teams.lock().unwrap();
+        let before = teams.len();
+        teams.retain(|t| t.id != id);
+        let removed = teams.len() != before;
+        if removed {
+            self.save(&teams);
+        }
+        removed
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut Team)) -> Option<Team> {
+        let mut teams = self.teams.lock().unwrap();
+        let team = teams.iter_mut().find(|t| t.id == id)?;
+        f(team);
+        let updated = team.clone();
+        self.save(&teams);
+        Some(updated)
+    }
+}
+
+fn yesterday() -> String {
+    (chrono::Local::now() - chrono::Duration::days(1))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Parse the "WIN 3-1 vs Rivals" style answer the game check asks for.
+fn parse_result(answer: &str) -> Option<(Outcome, String)> {
+    let line = answer.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let outcome = match word
+        .trim_matches(|c: char| !c.is_alphabetic())
+        .to_uppercase()
+        .as_str()
+    {
+        "WIN" | "WON" => Outcome::Win,
+        "LOSS" | "LOST" => Outcome::Loss,
+        "DRAW" | "TIE" | "TIED" => Outcome::Draw,
+        _ => return None,
+    };
+    Some((outcome, rest.trim().to_string()))
+}
+
+/// Ask search mode whether the team played on `date` and how it went.
+/// Returns None when there was no game.
+async fn look_up_game(
+    app: &tauri::AppHandle,
+    api_key: &str,
+    team: &Team,
+    date: &str,
+) -> PetResult<Option<(Outcome, String)>> {
+    let query = format!("{} game result on {}", team.display_name(), date);
+    let instructions = format!(
+        "This is an automated game-day check. If {} did not finish a game on {}, reply with \
+        exactly {} and nothing else. Otherwise reply with one line: WIN, LOSS or DRAW, then \
+        the final score and opponent, e.g. \"WIN 3-1 vs Rivals\".",
+        team.display_name(),
+        date,
+        NO_GAME_MARKER
+    );
+    let answer = crate::dialogue::run_background_search(
+        app,
+        api_key,
+        "game-day check",
+        &query,
+        Some(instructions),
+    )
+    .await?;
+    if answer.contains(NO_GAME_MARKER) {
+        return Ok(None);
+    }
+    parse_result(&answer)
+        .map(Some)
+        .ok_or_else(|| PetError::Parse(format!("Unreadable game result: {}", answer)))
+}
+
+fn announcement(team: &Team, outcome: Outcome, score: &str) -> Option<String> {
+    let was_sulking = team.is_sulking();
+    let streak = match outcome {
+        Outcome::Loss => team.losing_streak + 1,
+        _ => 0,
+    };
+    let sulking = team.mute_during_losing_streak && streak >= LOSING_STREAK_MUTE;
+
+    match outcome {
+        _ if sulking && was_sulking => None,
+        _ if sulking => Some(format!(
+            "The {} lost again ({}). That's {} straight. We don't talk about the {} anymore.",
+            team.name, score, streak, team.name
+        )),
+        Outcome::Win if was_sulking => Some(format!(
+            "The {} won ({})! Fine. We can talk about them again.",
+            team.name, score
+        )),
+        Outcome::Win => Some(format!("The {} won last night! {}", team.name, score)),
+        Outcome::Loss => Some(format!(
+            "The {} lost last night. {}. Sorry.",
+            team.name, score
+        )),
+        Outcome::Draw => Some(format!("The {} drew last night. {}", team.name, score)),
+    }
+}
+
+/// Each morning, look up yesterday's game for every followed team and report
+/// the results.
+async fn morning_results(app: tauri::AppHandle) {
+    if chrono::Local::now().hour() < MORNING_HOUR {
+        return;
+    }
+    let date = yesterday();
+    let due: Vec<Team> = app
+        .state::<TeamStore>()
+        .all()
+        .into_iter()
+        .filter(|t| t.last_checked_date.as_deref() < Some(date.as_str()))
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+    let api_key = match crate::config::anthropic_api_key() {
+        Some(key) => key,
+        None => return,
+    };
+    if app.state::<UsageStore>().budget_exceeded() {
+        return;
+    }
+
+    for team in due {
+        let result = match look_up_game(&app, &api_key, &team, &date).await {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("Game check for {} failed: {}", team.name, e);
+                // Try again next tick rather than skipping the day
+                continue;
+            }
+        };
+        if let Some((outcome, score)) = &result {
+            if let Some(message) = announcement(&team, *outcome, score) {
+                notifications::announce(&app, &message);
+            }
+        }
+
+        let handle = app.clone();
+        let date = date.clone();
+        let _ = crate::run_blocking(move || {
+            handle.state::<TeamStore>().update(&team.id, |t| {
+                t.last_checked_date = Some(date);
+                if let Some((outcome, score)) = result {
+                    t.losing_streak = match outcome {
+                        Outcome::Loss => t.losing_streak + 1,
+                        _ => 0,
+                    };
+                    t.last_result = Some(score);
+                }
+            })
+        })
+        .await;
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, morning_results);
+}
+
+#[tauri::command(async)]
+pub fn list_teams(store: tauri::State<'_, TeamStore>) -> Vec<Team> {
+    store.all()
+}
+
+#[tauri::command]
+pub async fn add_team(
+    app: tauri::AppHandle,
+    name: String,
+    league: Option<String>,
+) -> PetResult<Team> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(PetError::InvalidInput("Which team?".to_string()));
+    }
+    let league = league
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    crate::run_blocking(move || app.state::<TeamStore>().add(name, league)).await
+}
+
+#[tauri::command]
+pub async fn remove_team(app: tauri::AppHandle, id: String) -> PetResult<()> {
+    let removed = crate::run_blocking(move || app.state::<TeamStore>().remove(&id)).await?;
+    if removed {
+        Ok(())
+    } else {
+        Err(PetError::InvalidInput("No such team".to_string()))
+    }
+}
+
+#[tauri::command]
+pub async fn set_team_losing_streak_mute(
+    app: tauri::AppHandle,
+    id: String,
+    enabled: bool,
+) -> PetResult<Team> {
+    crate::run_blocking(move || {
+        app.state::<TeamStore>()
+            .update(&id, |t| t.mute_during_losing_streak = enabled)
+    })
+    .await?
+    .ok_or_else(|| PetError::InvalidInput("No such team".to_string()))
+}
@@ -0,0 +1,432 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::memory::MemoryStore;
+use crate::permissions::{self, PermissionKind, PermissionStatus};
+use crate::settings::SettingsStore;
+use crate::{automation, config, inventory, persist};
+
+/// Below this much free space saves start failing soon; warn early.
+const LOW_DISK_BYTES: u64 = 500 * 1024 * 1024;
+/// Below this a save can fail outright.
+const CRITICAL_DISK_BYTES: u64 = 50 * 1024 * 1024;
+const API_KEY_URL: &str = "https://console.anthropic.com/settings/keys";
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Ok,
+    /// Something works worse than it could
+    Warning,
+    /// Something doesn't work at all
+    Error,
+}
+
+/// What a fix-it button does. The webview handles `OpenUrl` itself; the
+/// rest go back through `run_fix_it`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FixAction {
+    OpenUrl {
+        url: String,
+    },
+    RequestPermission {
+        kind: PermissionKind,
+    },
+    OpenPermissionSettings {
+        kind: PermissionKind,
+    },
+    OpenDataFolder,
+    /// Rewrite files from before checksums into the current format
+    MigrateData,
+    /// Move the automation server to a free port
+    UseFreePort,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FixIt {
+    pub label: &'static str,
+    pub action: FixAction,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Check {
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub title: String,
+    pub detail: Option<String>,
+    pub fixes: Vec<FixIt>,
+}
+
+impl Check {
+    fn ok(id: &'static str, title: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Ok,
+            title: title.into(),
+            detail: None,
+            fixes: Vec::new(),
+        }
+    }
+
+    fn problem(
+        id: &'static str,
+        status: CheckStatus,
+        title: impl Into<String>,
+        detail: impl Into<String>,
+        fixes: Vec<FixIt>,
+    ) -> Self {
+        Self {
+            id,
+            status,
+            title: title.into(),
+            detail: Some(detail.into()),
+            fixes,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub checks: Vec<Check>,
+    /// Every check passed
+    pub ok: bool,
+    pub checked_at: i64,
+}
+
+/// The last report, kept for a webview that wasn't listening yet when it
+/// was emitted.
+#[derive(Default)]
+pub struct StartupState {
+    report: Mutex<Option<StartupReport>>,
+}
+
+fn check_api_key() -> Check {
+    let key = config::anthropic_api_key().unwrap_or_default();
+    let fix = || {
+        vec![FixIt {
+            label: "Get an API key",
+            action: FixAction::OpenUrl {
+                url: API_KEY_URL.to_string(),
+            },
+        }]
+    };
+    if key.trim().is_empty() {
+        return Check::problem(
+            "apiKey",
+            CheckStatus::Warning,
+            "No Anthropic API key",
+            "The cat can only use its pre-written lines. Put anthropic_api_key in config.toml in the data folder (or set ANTHROPIC_API_KEY) and restart.",
+            fix(),
+        );
+    }
+    if !key.trim().starts_with("sk-ant-") {
+        return Check::problem(
+            "apiKey",
+            CheckStatus::Warning,
+            "The API key looks wrong",
+            "Anthropic keys start with sk-ant-. get_config_sources shows where the key in use came from.",
+            fix(),
+        );
+    }
+    Check::ok("apiKey", "API key found")
+}
+
+/// Permissions the features that are turned on depend on. Features that
+/// are off don't need anything, so nothing is asked about them.
+fn check_permissions(app: &tauri::AppHandle) -> Vec<Check> {
+    if !cfg!(target_os = "macos") {
+        return Vec::new();
+    }
+    let settings = app.state::<SettingsStore>().get();
+    let mut needed = Vec::new();
+    let wants_accessibility = settings.shortcuts.ask_about_selection.is_some()
+        || settings.dictation.enabled
+        || settings.display.dock_reactions;
+    if wants_accessibility {
+        needed.push((
+            PermissionKind::Accessibility,
+            "Accessibility",
+            "Reading selections, typing dictation and watching the Dock",
+        ));
+    }
+    if settings.dictation.enabled {
+        needed.push((
+            PermissionKind::Microphone,
+            "Microphone",
+            "Hearing what to type when dictating",
+        ));
+    }
+
+    needed
+        .into_iter()
+        .map(|(kind, name, used_for)| {
+            let id = "permission";
+            match permissions::check(kind, None) {
+                PermissionStatus::Granted | PermissionStatus::Unsupported => {
+                    Check::ok(id, format!("{} access granted", name))
+                }
+                PermissionStatus::NotDetermined => Check::problem(
+                    id,
+                    CheckStatus::Warning,
+                    format!("{} access not asked for yet", name),
+                    format!("Needed for: {}.", used_for),
+                    vec![FixIt {
+                        label: "Ask now",
+                        action: FixAction::RequestPermission { kind },
+                    }],
+                ),
+                PermissionStatus::Denied | PermissionStatus::Unknown => Check::problem(
+                    id,
+                    CheckStatus::Warning,
+                    format!("{} access is off", name),
+                    format!("Needed for: {}.", used_for),
+                    vec![
+                        FixIt {
+                            label: "Ask again",
+                            action: FixAction::RequestPermission { kind },
+                        },
+                        FixIt {
+                            label: "Open System Settings",
+                            action: FixAction::OpenPermissionSettings { kind },
+                        },
+                    ],
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Free bytes on the volume holding `path`, from `df`.
+fn free_bytes(path: &Path) -> Option<u64> {
+    let output = std::process::Command::new("df")
+        .arg("-k")
+        .arg(path)
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let available_kb: u64 = text
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()?;
+    Some(available_kb * 1024)
+}
+
+fn check_disk(app: &tauri::AppHandle) -> Check {
+    let id = "diskSpace";
+    let Some(free) = persist::app_data_file(app, "")
+        .ok()
+        .and_then(|dir| free_bytes(&dir))
+    else {
+        return Check::ok(id, "Disk space not checked");
+    };
+    let free_mb = free / (1024 * 1024);
+    let fixes = || {
+        vec![FixIt {
+            label: "Show pet data",
+            action: FixAction::OpenDataFolder,
+        }]
+    };
+    if free < CRITICAL_DISK_BYTES {
+        return Check::problem(
+            id,
+            CheckStatus::Error,
+            "The disk is full",
+            format!(
+                "Only {} MB free. Chats, notes and settings may fail to save.",
+                free_mb
+            ),
+            fixes(),
+        );
+    }
+    if free < LOW_DISK_BYTES {
+        return Check::problem(
+            id,
+            CheckStatus::Warning,
+            "The disk is nearly full",
+            format!("{} MB free.", free_mb),
+            fixes(),
+        );
+    }
+    Check::ok(id, format!("{} MB free", free_mb))
+}
+
+/// Data files still in the plain JSON written before checksums existed.
+fn legacy_files(app: &tauri::AppHandle) -> Vec<String> {
+    inventory::data_files()
+        .filter(|name| {
+            persist::app_data_file(app, name)
+                .map(|path| persist::is_legacy(&path))
+                .unwrap_or(false)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+fn check_data(app: &tauri::AppHandle) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let legacy = legacy_files(app);
+    checks.push(if legacy.is_empty() {
+        Check::ok("migration", "Pet data is up to date")
+    } else {
+        Check::problem(
+            "migration",
+            CheckStatus::Warning,
+            "Some pet data is in an old format",
+            format!(
+                "{} can't be recovered from a backup if it gets damaged until it's rewritten.",
+                legacy.join(", ")
+            ),
+            vec![FixIt {
+                label: "Update now",
+                action: FixAction::MigrateData,
+            }],
+        )
+    });
+    if app.state::<MemoryStore>().is_locked() {
+        checks.push(Check::problem(
+            "memoryLocked",
+            CheckStatus::Error,
+            "Chat memory is locked",
+            "It's encrypted with a key this Mac doesn't have. Unlock it with your passphrase \
+            from Settings, or the cat won't remember anything new.",
+            Vec::new(),
+        ));
+    }
+    checks
+}
+
+fn port_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+fn check_automation(app: &tauri::AppHandle) -> Option<Check> {
+    let status = automation::status(app);
+    if !status.enabled {
+        return None;
+    }
+    if status.running {
+        return Some(Check::ok(
+            "automationPort",
+            format!("Automation API on port {}", status.port),
+        ));
+    }
+    let detail = if port_free(status.port) {
+        "It didn't start; try turning it off and on again.".to_string()
+    } else {
+        format!(
+            "Another app is using port {}, so scripts can't reach the cat.",
+            status.port
+        )
+    };
+    Some(Check::problem(
+        "automationPort",
+        CheckStatus::Error,
+        "The automation API isn't running",
+        detail,
+        vec![FixIt {
+            label: "Use a free port",
+            action: FixAction::UseFreePort,
+        }],
+    ))
+}
+
+fn build_report(app: &tauri::AppHandle) -> StartupReport {
+    let mut checks = vec![check_api_key()];
+    checks.extend(check_permissions(app));
+    checks.push(check_disk(app));
+    checks.extend(check_data(app));
+    checks.extend(check_automation(app));
+    StartupReport {
+        ok: checks.iter().all(|check| check.status == CheckStatus::Ok),
+        checks,
+        checked_at: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+/// Run every check, keep the result and emit it as `startup-report`.
+pub fn run_checks(app: &tauri::AppHandle) -> StartupReport {
+    let report = build_report(app);
+    *app.state::<StartupState>().report.lock().unwrap() = Some(report.clone());
+    let _ = app.emit("startup-report", report.clone());
+    report
+}
+
+/// Check once the rest of setup has run, off the main thread.
+pub fn spawn_check(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let _ = crate::run_blocking(move || run_checks(&app)).await;
+    });
+}
+
+fn migrate(app: &tauri::AppHandle) -> PetResult<()> {
+    for name in legacy_files(app) {
+        let path = persist::app_data_file(app, &name)?;
+        if let Some(value) = persist::load::<serde_json::Value>(&path) {
+            persist::save(&path, &value)?;
+        }
+    }
+    Ok(())
+}
+
+fn use_free_port(app: &tauri::AppHandle) -> PetResult<()> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+    let port = listener.local_addr()?.port();
+    drop(listener);
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.automation.port = port;
+    store.replace(settings)?;
+    automation::apply(app)
+}
+
+fn open_data_folder(app: &tauri::AppHandle) -> PetResult<()> {
+    let dir = persist::app_data_file(app, "")?;
+    std::process::Command::new("open")
+        .arg(dir)
+        .spawn()
+        .map_err(|e| PetError::Platform(format!("Couldn't open the data folder: {}", e)))?;
+    Ok(())
+}
+
+/// The last report, or a fresh one if the launch check hasn't finished.
+#[tauri::command]
+pub async fn get_startup_report(app: tauri::AppHandle) -> PetResult<StartupReport> {
+    let last = app.state::<StartupState>().report.lock().unwrap().clone();
+    match last {
+        Some(report) => Ok(report),
+        None => crate::run_blocking(move || run_checks(&app)).await,
+    }
+}
+
+/// Carry out a fix-it and check again.
+#[tauri::command]
+pub async fn run_fix_it(app: tauri::AppHandle, action: FixAction) -> PetResult<StartupReport> {
+    crate::run_blocking(move || {
+        match action {
+            FixAction::OpenUrl { .. } => {
+                return Err(PetError::InvalidInput(
+                    "Links are opened by the webview".to_string(),
+                ))
+            }
+            FixAction::RequestPermission { kind } => {
+                permissions::request(&app, kind, None);
+            }
+            FixAction::OpenPermissionSettings { kind } => permissions::open_settings(kind)?,
+            FixAction::OpenDataFolder => open_data_folder(&app)?,
+            FixAction::MigrateData => migrate(&app)?,
+            FixAction::UseFreePort => use_free_port(&app)?,
+        }
+        Ok(run_checks(&app))
+    })
+    .await?
+}
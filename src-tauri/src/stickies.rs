@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::{PetError, PetResult};
+use crate::{occlusion, persist};
+
+pub const STICKIES_FILE: &str = "stickies.json";
+/// Window labels are this plus the note's id; the webview renders a note
+/// for any window labelled like this.
+const LABEL_PREFIX: &str = "sticky-";
+const WIDTH: f64 = 180.0;
+const HEIGHT: f64 = 120.0;
+const MAX_STICKIES: usize = 10;
+const MAX_TEXT: usize = 280;
+/// How long a note has to sit still after a drag before its spot is saved.
+const MOVE_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Sticky {
+    pub id: String,
+    pub text: String,
+    /// Screen position of the top-left corner, in logical pixels
+    pub x: f64,
+    pub y: f64,
+    /// Unix millis
+    pub created_at: i64,
+}
+
+/// Notes the cat taped to the screen, each in its own small window.
+pub struct StickyStore {
+    stickies: Mutex<Vec<Sticky>>,
+    path: Option<PathBuf>,
+    /// Bumped on every move, so only the last one of a drag saves
+    moves: AtomicU64,
+}
+
+impl StickyStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, STICKIES_FILE).ok();
+        let stickies = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            stickies: Mutex::new(stickies),
+            path,
+            moves: AtomicU64::new(0),
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut stickies = self.stickies.lock().unwrap();
+        *stickies = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, stickies: &[Sticky]) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &stickies);
+        }
+    }
+
+    pub fn all(&self) -> Vec<Sticky> {
+        self.stickies.lock().unwrap().clone()
+    }
+
+    fn add(&self, text: &str, x: f64, y: f64) -> PetResult<Sticky> {
+        let mut stickies = self.stickies.lock().unwrap();
+        if stickies.len() >= MAX_STICKIES {
+            return Err(PetError::InvalidInput(format!(
+                "There's only room for {} notes; take one down first",
+                MAX_STICKIES
+            )));
+        }
+        let sticky = Sticky {
+            id: uuid::Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            x,
+            y,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        stickies.push(sticky.clone());
+        self.save(&stickies);
+        Ok(sticky)
+    }
+
+    fn remove(&self, id: &str) -> bool {
+        let mut stickies = self.stickies.lock().unwrap();
+        let before = stickies.len();
+        stickies.retain(|s| s.id != id);
+        let removed = stickies.len() != before;
+        if removed {
+            self.save(&stickies);
+        }
+        removed
+    }
+
+    /// Note the new spot in memory; returns the move's number for `settle`.
+    fn moved(&self, id: &str, x: f64, y: f64) -> u64 {
+        if let Some(sticky) = self
+            .stickies
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|s| s.id == id)
+        {
+            sticky.x = x;
+            sticky.y = y;
+        }
+        self.moves.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Save once nothing has moved since move `n`.
+    fn settle(&self, n: u64) {
+        if self.moves.load(Ordering::SeqCst) == n {
+            self.save(&self.stickies.lock().unwrap());
+        }
+    }
+}
+
+fn label(id: &str) -> String {
+    format!("{}{}", LABEL_PREFIX, id)
+}
+
+fn open(app: &tauri::AppHandle, sticky: &Sticky) -> tauri::Result<()> {
+    let label = label(&sticky.id);
+    if app.get_webview_window(&label).is_some() {
+        return Ok(());
+    }
+    let window = WebviewWindowBuilder::new(app, &label, WebviewUrl::default())
+        .title("Sticky Note")
+        .inner_size(WIDTH, HEIGHT)
+        .position(sticky.x, sticky.y)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible_on_all_workspaces(true)
+        .build()?;
+    let id = sticky.id.clone();
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        let WindowEvent::Moved(position) = event else {
+            return;
+        };
+        let scale = handle.scale_factor().unwrap_or(1.0);
+        let position = position.to_logical::<f64>(scale);
+        let app = handle.app_handle().clone();
+        let n = app
+            .state::<StickyStore>()
+            .moved(&id, position.x, position.y);
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(MOVE_SAVE_DELAY).await;
+            app.state::<StickyStore>().settle(n);
+        });
+    });
+    Ok(())
+}
+
+/// Put the saved notes back on screen after a restart.
+pub fn restore(app: &tauri::AppHandle) {
+    for sticky in app.state::<StickyStore>().all() {
+        if let Err(e) = open(app, &sticky) {
+            eprintln!("Failed to restore sticky note: {}", e);
+        }
+    }
+}
+
+/// Close every note window, after a wipe.
+pub fn close_all(app: &tauri::AppHandle) {
+    for (label, window) in app.webview_windows() {
+        if label.starts_with(LABEL_PREFIX) {
+            let _ = window.close();
+        }
+    }
+}
+
+/// Tape `text` to the screen with its top-left corner at `(x, y)` in the
+/// overlay's coordinates, e.g. just beside the pet. It stays until closed,
+/// across restarts.
+pub fn create(app: &tauri::AppHandle, text: &str, x: f64, y: f64) -> PetResult<Sticky> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(PetError::InvalidInput("The note is empty".to_string()));
+    }
+    if text.chars().count() > MAX_TEXT {
+        return Err(PetError::InvalidInput(format!(
+            "A sticky note fits {} characters",
+            MAX_TEXT
+        )));
+    }
+    let (left, top) = occlusion::window_origin(app)?;
+    let sticky = app.state::<StickyStore>().add(text, left + x, top + y)?;
+    open(app, &sticky)?;
+    Ok(sticky)
+}
+
+#[tauri::command]
+pub async fn create_sticky_note(
+    app: tauri::AppHandle,
+    text: String,
+    x: f64,
+    y: f64,
+) -> PetResult<Sticky> {
+    crate::run_blocking(move || create(&app, &text, x, y)).await?
+}
+
+#[tauri::command(async)]
+pub fn get_sticky_notes(store: tauri::State<'_, StickyStore>) -> Vec<Sticky> {
+    store.all()
+}
+
+/// Take a note down and close its window.
+#[tauri::command(async)]
+pub fn remove_sticky_note(app: tauri::AppHandle, id: String) -> PetResult<()> {
+    if !app.state::<StickyStore>().remove(&id) {
+        return Err(PetError::InvalidInput(format!(
+            "No sticky note with id {}",
+            id
+        )));
+    }
+    if let Some(window) = app.get_webview_window(&label(&id)) {
+        window.close()?;
+    }
+    Ok(())
+}
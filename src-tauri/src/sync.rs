@@ -0,0 +1,1066 @@
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::crypto::{self, EncryptedBlob, MemoryKey};
+use crate::error::{PetError, PetResult};
+use crate::memory::{self, ChatMemory, MemoryStore};
+use crate::milestones::MilestoneStore;
+use crate::pets::{PetProfile, PetRegistry};
+use crate::settings::{self, Settings, SettingsStore};
+use crate::{persist, relay};
+
+pub const SYNC_FILE: &str = "sync.json";
+/// The one object every machine reads and writes, whichever the target.
+const REMOTE_NAME: &str = "desktop-pet-sync.json";
+const RELAY_TABLE: &str = "pet_sync";
+pub const KEY_ACCOUNT: &str = "sync-key";
+/// The WebDAV password or S3 secret key.
+const CREDENTIAL_ACCOUNT: &str = "sync-credential";
+const MIN_PASSPHRASE_LEN: usize = 8;
+/// Uploads are the whole bundle; give a slow link time to send it.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Bump when the bundle layout changes incompatibly.
+const BUNDLE_VERSION: u32 = 1;
+/// Rounds to try when another machine keeps uploading in between.
+const MAX_ATTEMPTS: usize = 3;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Where the encrypted bundle is kept.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SyncTarget {
+    /// A row in the friends relay, under a random code every machine shares
+    Relay { code: String },
+    /// A folder on a WebDAV server; the password is in the Keychain
+    WebDav { url: String, username: String },
+    /// Any S3-compatible bucket, addressed path-style; the secret key is in
+    /// the Keychain
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        access_key_id: String,
+    },
+}
+
+impl SyncTarget {
+    fn needs_credential(&self) -> bool {
+        !matches!(self, SyncTarget::Relay { .. })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ItemState {
+    /// Of the data here as of the last sync
+    hash: String,
+    /// The remote copy's `updated_at` as of the last sync
+    remote_updated_at: i64,
+}
+
+/// Where this machine syncs to and what it last saw there. Kept out of
+/// settings.json, which is itself synced.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SyncData {
+    target: Option<SyncTarget>,
+    /// Unix millis
+    last_synced_at: Option<i64>,
+    last_error: Option<String>,
+    items: BTreeMap<String, ItemState>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RemoteItem {
+    /// Unix millis of the change it carries
+    updated_at: i64,
+    hash: String,
+    data: Value,
+}
+
+/// What's encrypted into the remote object.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Bundle {
+    version: u32,
+    items: BTreeMap<String, RemoteItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RelayRow {
+    code: String,
+    blob: EncryptedBlob,
+    #[serde(default)]
+    updated_at: Option<String>,
+    /// Bumped on every upload
+    #[serde(default)]
+    version: i64,
+}
+
+/// The remote copy as this machine fetched it, so an upload only lands if
+/// no other machine's did in between.
+enum Precondition {
+    /// Nothing had synced there yet
+    Absent,
+    /// The WebDAV or S3 object's ETag
+    ETag(String),
+    /// The relay row's version
+    Version(i64),
+    /// The server sent no ETag, so there's nothing to check against
+    Any,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub target: Option<SyncTarget>,
+    pub last_synced_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub syncing: bool,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// Items sent up, e.g. "settings" or "memory:<pet id>"
+    pub pushed: Vec<String>,
+    /// Items changed here by what another machine sent
+    pub pulled: Vec<String>,
+    /// The merged achievements, when they changed here
+    pub achievements: Option<Value>,
+}
+
+/// Opt-in sync of memory, facts, milestones, achievements and settings
+/// between your machines, end-to-end encrypted with a passphrase.
+pub struct SyncStore {
+    data: Mutex<SyncData>,
+    path: Option<PathBuf>,
+    running: AtomicBool,
+}
+
+impl SyncStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, SYNC_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+            running: AtomicBool::new(false),
+        }
+    }
+
+    fn save(&self, data: &SyncData) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, data);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.data.lock().unwrap().target.is_some()
+    }
+
+    fn target(&self) -> Option<SyncTarget> {
+        self.data.lock().unwrap().target.clone()
+    }
+
+    fn items(&self) -> BTreeMap<String, ItemState> {
+        self.data.lock().unwrap().items.clone()
+    }
+
+    /// Point sync somewhere new, or nowhere. What was seen at the old
+    /// target says nothing about the new one, so that's forgotten.
+    fn configure(&self, target: Option<SyncTarget>) {
+        let mut data = self.data.lock().unwrap();
+        *data = SyncData {
+            target,
+            ..Default::default()
+        };
+        self.save(&data);
+    }
+
+    fn finish(&self, states: BTreeMap<String, ItemState>, error: Option<String>) {
+        let mut data = self.data.lock().unwrap();
+        data.items.extend(states);
+        if error.is_none() {
+            data.last_synced_at = Some(chrono::Utc::now().timestamp_millis());
+        }
+        data.last_error = error;
+        self.save(&data);
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        let data = self.data.lock().unwrap();
+        SyncStatus {
+            target: data.target.clone(),
+            last_synced_at: data.last_synced_at,
+            last_error: data.last_error.clone(),
+            syncing: self.running.load(Ordering::SeqCst),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hash(value: &Value) -> String {
+    sha256_hex(value.to_string().as_bytes())
+}
+
+/// When `path` was last written, in unix millis.
+fn modified_at(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(chrono::DateTime::<chrono::Utc>::from(modified).timestamp_millis())
+}
+
+/// A target and the secret to reach it with.
+struct Remote {
+    target: SyncTarget,
+    credential: Option<String>,
+}
+
+impl Remote {
+    fn load(target: SyncTarget) -> PetResult<Self> {
+        let credential = if target.needs_credential() {
+            Some(crypto::load_secret(CREDENTIAL_ACCOUNT).ok_or_else(|| {
+                PetError::Auth(
+                    "The sync password isn't in the Keychain; set sync up again".to_string(),
+                )
+            })?)
+        } else {
+            None
+        };
+        Ok(Self { target, credential })
+    }
+
+    fn credential(&self) -> &str {
+        self.credential.as_deref().unwrap_or_default()
+    }
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// A request for the sync object in an S3 bucket, signed with Signature
+/// Version 4.
+fn s3_request(
+    method: reqwest::Method,
+    remote: &Remote,
+    body: Vec<u8>,
+) -> PetResult<reqwest::RequestBuilder> {
+    let SyncTarget::S3 {
+        endpoint,
+        region,
+        bucket,
+        access_key_id,
+    } = &remote.target
+    else {
+        return Err(PetError::Internal("Not an S3 target".to_string()));
+    };
+    let url = reqwest::Url::parse(&format!(
+        "{}/{}/{}",
+        endpoint.trim_end_matches('/'),
+        bucket,
+        REMOTE_NAME
+    ))
+    .map_err(|e| PetError::InvalidInput(format!("Invalid S3 endpoint: {}", e)))?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        _ => {
+            return Err(PetError::InvalidInput(
+                "The S3 endpoint has no host".to_string(),
+            ))
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = sha256_hex(&body);
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method,
+        url.path(),
+        host,
+        payload_hash,
+        amz_date,
+        signed_headers,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        sha256_hex(canonical.as_bytes())
+    );
+    let key = [date.as_str(), region, "s3", "aws4_request"].iter().fold(
+        format!("AWS4{}", remote.credential()).into_bytes(),
+        |key, part| hmac(&key, part),
+    );
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id,
+        scope,
+        signed_headers,
+        hex(&hmac(&key, &to_sign))
+    );
+    Ok(reqwest::Client::new()
+        .request(method, url)
+        .timeout(REQUEST_TIMEOUT)
+        .header("x-amz-date", amz_date)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("Authorization", authorization)
+        .body(body))
+}
+
+fn webdav_request(method: reqwest::Method, remote: &Remote, url: &str) -> reqwest::RequestBuilder {
+    let SyncTarget::WebDav { username, .. } = &remote.target else {
+        unreachable!("only called for WebDAV targets");
+    };
+    reqwest::Client::new()
+        .request(
+            method,
+            format!("{}/{}", url.trim_end_matches('/'), REMOTE_NAME),
+        )
+        .timeout(REQUEST_TIMEOUT)
+        .basic_auth(username, Some(remote.credential()))
+}
+
+/// The encrypted bundle, or None if nothing has synced there yet, and
+/// what an upload has to find there to replace it.
+async fn fetch(remote: &Remote) -> PetResult<(Option<EncryptedBlob>, Precondition)> {
+    let response = match &remote.target {
+        SyncTarget::Relay { code } => {
+            let (url, key) = relay::table(RELAY_TABLE)?;
+            let filter = format!("eq.{}", code);
+            let rows: Vec<RelayRow> = relay::send(
+                relay::request(reqwest::Method::GET, &url, &key)
+                    .query(&[("code", filter.as_str()), ("select", "code,blob,version")]),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(|e| PetError::Parse(e.to_string()))?;
+            return Ok(match rows.into_iter().next() {
+                Some(row) => (Some(row.blob), Precondition::Version(row.version)),
+                None => (None, Precondition::Absent),
+            });
+        }
+        SyncTarget::WebDav { url, .. } => {
+            relay::send(webdav_request(reqwest::Method::GET, remote, url)).await
+        }
+        SyncTarget::S3 { .. } => {
+            relay::send(s3_request(reqwest::Method::GET, remote, Vec::new())?).await
+        }
+    };
+    match response {
+        Ok(response) => {
+            let precondition = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map_or(Precondition::Any, |etag| Precondition::ETag(etag.to_string()));
+            let blob = response
+                .json()
+                .await
+                .map_err(|e| PetError::Parse(e.to_string()))?;
+            Ok((Some(blob), precondition))
+        }
+        Err(PetError::Api { status: 404, .. }) => Ok((None, Precondition::Absent)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace the bundle, if it's still what `precondition` says. Returns
+/// false when another machine uploaded first.
+async fn upload(
+    remote: &Remote,
+    blob: &EncryptedBlob,
+    precondition: &Precondition,
+) -> PetResult<bool> {
+    let request = match &remote.target {
+        SyncTarget::Relay { code } => {
+            let (url, key) = relay::table(RELAY_TABLE)?;
+            let row = RelayRow {
+                code: code.clone(),
+                blob: blob.clone(),
+                updated_at: Some(chrono::Utc::now().to_rfc3339()),
+                version: match precondition {
+                    Precondition::Version(version) => version + 1,
+                    _ => 1,
+                },
+            };
+            let Precondition::Version(version) = precondition else {
+                // A plain insert, which fails if another machine's row got
+                // there first
+                let request = relay::request(reqwest::Method::POST, &url, &key).json(&row);
+                return conditional(relay::send(request).await);
+            };
+            let (code, version) = (format!("eq.{}", code), format!("eq.{}", version));
+            let updated: Vec<RelayRow> = relay::send(
+                relay::request(reqwest::Method::PATCH, &url, &key)
+                    .query(&[("code", code.as_str()), ("version", version.as_str())])
+                    .header("Prefer", "return=representation")
+                    .json(&row),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(|e| PetError::Parse(e.to_string()))?;
+            return Ok(!updated.is_empty());
+        }
+        SyncTarget::WebDav { url, .. } => {
+            webdav_request(reqwest::Method::PUT, remote, url).json(blob)
+        }
+        SyncTarget::S3 { .. } => s3_request(reqwest::Method::PUT, remote, serde_json::to_vec(blob)?)?
+            .header("Content-Type", "application/json"),
+    };
+    let request = match precondition {
+        Precondition::Absent => request.header(reqwest::header::IF_NONE_MATCH, "*"),
+        Precondition::ETag(etag) => request.header(reqwest::header::IF_MATCH, etag),
+        Precondition::Version(_) | Precondition::Any => request,
+    };
+    conditional(relay::send(request).await)
+}
+
+/// A conditional write's result: whether it landed, with "someone else's
+/// landed first" not counting as an error.
+fn conditional(result: PetResult<reqwest::Response>) -> PetResult<bool> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(PetError::Api {
+            status: 409 | 412, ..
+        }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Works out, item by item, which copy to keep: this machine's or the
+/// bundle's.
+struct Reconciler {
+    bundle: Bundle,
+    previous: BTreeMap<String, ItemState>,
+    states: BTreeMap<String, ItemState>,
+    report: SyncReport,
+    now: i64,
+}
+
+impl Reconciler {
+    fn push(&mut self, name: &str, data: Value, updated_at: i64) -> ItemState {
+        let hash = hash(&data);
+        self.bundle.items.insert(
+            name.to_string(),
+            RemoteItem {
+                updated_at,
+                hash: hash.clone(),
+                data,
+            },
+        );
+        self.report.pushed.push(name.to_string());
+        ItemState {
+            hash,
+            remote_updated_at: updated_at,
+        }
+    }
+
+    /// Data replaced whole, like settings. Whichever side changed since the
+    /// last sync wins; if both did, the later change. A machine that has
+    /// never synced takes what's already there. `apply` puts the remote
+    /// copy in place and returns the data as it now stands here.
+    fn whole(
+        &mut self,
+        name: &str,
+        local: Value,
+        modified_at: i64,
+        apply: impl FnOnce(Value) -> PetResult<Value>,
+    ) -> PetResult<()> {
+        let local_hash = hash(&local);
+        let previous = self.previous.get(name).cloned();
+        let remote = self
+            .bundle
+            .items
+            .get(name)
+            .map(|r| (r.hash.clone(), r.updated_at, r.data.clone()));
+        let state = match remote {
+            Some((remote_hash, remote_updated_at, data)) if remote_hash != local_hash => {
+                let pull = match &previous {
+                    None => Some(true),
+                    Some(previous) => {
+                        let local_changed = previous.hash != local_hash;
+                        let remote_changed = previous.remote_updated_at != remote_updated_at;
+                        match (local_changed, remote_changed) {
+                            (false, false) => None,
+                            (true, true) => Some(remote_updated_at > modified_at),
+                            (local_changed, _) => Some(!local_changed),
+                        }
+                    }
+                };
+                match pull {
+                    None => ItemState {
+                        hash: local_hash,
+                        remote_updated_at,
+                    },
+                    Some(true) => {
+                        let applied = apply(data)?;
+                        self.report.pulled.push(name.to_string());
+                        ItemState {
+                            hash: hash(&applied),
+                            remote_updated_at,
+                        }
+                    }
+                    Some(false) => self.push(name, local, modified_at),
+                }
+            }
+            Some((_, remote_updated_at, _)) => ItemState {
+                hash: local_hash,
+                remote_updated_at,
+            },
+            None => self.push(name, local, modified_at),
+        };
+        self.states.insert(name.to_string(), state);
+        Ok(())
+    }
+
+    /// Data merged entry by entry, like milestones, where each entry's own
+    /// date settles conflicts. `merge` folds the remote copy in here and
+    /// returns the result and whether that changed anything here; the
+    /// result goes back up if the remote copy was missing any of it.
+    fn merged(
+        &mut self,
+        name: &str,
+        merge: impl FnOnce(Option<Value>) -> PetResult<(Value, bool)>,
+    ) -> PetResult<Value> {
+        let remote = self.bundle.items.get(name).map(|r| r.data.clone());
+        let (merged, changed) = merge(remote.clone())?;
+        if changed {
+            self.report.pulled.push(name.to_string());
+        }
+        if remote.as_ref() != Some(&merged) {
+            self.push(name, merged.clone(), self.now);
+        }
+        Ok(merged)
+    }
+}
+
+fn tier_rank(entry: &Value) -> u8 {
+    match entry.get("tier").and_then(Value::as_str) {
+        Some("gold") => 3,
+        Some("silver") => 2,
+        Some("bronze") => 1,
+        _ => 0,
+    }
+}
+
+/// Achievements from both sides, keeping the higher tier of each and, for
+/// the same tier, the earlier unlock.
+fn merge_achievements(local: &Value, remote: Option<&Value>) -> Value {
+    let rank = |entry: &Value| {
+        let unlocked_at = entry
+            .get("unlockedAt")
+            .and_then(Value::as_i64)
+            .unwrap_or(i64::MAX);
+        (tier_rank(entry), Reverse(unlocked_at))
+    };
+    let mut merged = local.as_object().cloned().unwrap_or_default();
+    for (id, theirs) in remote.and_then(Value::as_object).into_iter().flatten() {
+        if merged.get(id).is_none_or(|mine| rank(theirs) > rank(mine)) {
+            merged.insert(id.clone(), theirs.clone());
+        }
+    }
+    Value::Object(merged)
+}
+
+/// Settings as they go up: without the dev watcher's shell command and
+/// folder, or the music folder, which are paths on this machine and
+/// nobody else's business.
+fn shared_settings(settings: Settings) -> PetResult<Value> {
+    let mut settings = Settings {
+        dev_watcher: Default::default(),
+        ..settings
+    };
+    settings.sound.music_folder = None;
+    Ok(serde_json::to_value(settings)?)
+}
+
+/// Settings from another machine, less the parts that only make sense on
+/// this one: its displays, the dev watcher and the music folder, and the
+/// automation server and LAN presence, which are started and stopped by
+/// their own commands.
+fn apply_settings(app: &tauri::AppHandle, remote: Value) -> PetResult<Value> {
+    let store = app.state::<SettingsStore>();
+    let current = store.get();
+    let mut settings = Settings {
+        display: current.display,
+        automation: current.automation,
+        presence: current.presence,
+        dev_watcher: current.dev_watcher,
+        ..serde_json::from_value(remote)?
+    };
+    settings.sound.music_folder = current.sound.music_folder;
+    store.replace(settings)?;
+    crate::shortcuts::register_all(app);
+    shared_settings(store.get())
+}
+
+fn reconcile(
+    app: &tauri::AppHandle,
+    bundle: Bundle,
+    previous: BTreeMap<String, ItemState>,
+    achievements: Option<Value>,
+) -> PetResult<Reconciler> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut reconciler = Reconciler {
+        bundle,
+        previous,
+        states: BTreeMap::new(),
+        report: SyncReport::default(),
+        now,
+    };
+    let file_modified = |name: &str| {
+        persist::app_data_file(app, name)
+            .ok()
+            .and_then(|p| modified_at(&p))
+            .unwrap_or(now)
+    };
+
+    let settings = shared_settings(app.state::<SettingsStore>().get())?;
+    reconciler.whole(
+        "settings",
+        settings,
+        file_modified(settings::SETTINGS_FILE),
+        |remote| apply_settings(app, remote),
+    )?;
+
+    // Only the cat that's out; the others catch up once they are
+    let store = app.state::<MemoryStore>();
+    if !store.is_locked() {
+        store.flush(app);
+        let pet = app.state::<PetRegistry>().active();
+        let modified = crate::pets::data_file(app, memory::MEMORY_FILE)
+            .ok()
+            .and_then(|p| modified_at(&p))
+            .unwrap_or(now);
+        let local = serde_json::to_value(store.snapshot())?;
+        reconciler.whole(&format!("memory:{}", pet), local, modified, |remote| {
+            store.replace(serde_json::from_value::<ChatMemory>(remote)?)?;
+            store.flush(app);
+            Ok(serde_json::to_value(store.snapshot())?)
+        })?;
+    }
+
+    reconciler.merged("milestones", |remote| {
+        let milestones = app.state::<MilestoneStore>();
+        let changed = match remote {
+            Some(remote) => milestones.merge(remote)?,
+            None => false,
+        };
+        Ok((milestones.export(), changed))
+    })?;
+
+    reconciler.merged("pets", |remote| {
+        let registry = app.state::<PetRegistry>();
+        let changed = match remote {
+            Some(remote) => registry.merge(serde_json::from_value::<Vec<PetProfile>>(remote)?)?,
+            None => false,
+        };
+        Ok((serde_json::to_value(registry.get().pets)?, changed))
+    })?;
+
+    // The webview keeps these, so they only sync when it hands them in
+    if let Some(local) = achievements {
+        let merged = reconciler.merged("achievements", |remote| {
+            let merged = merge_achievements(&local, remote.as_ref());
+            let changed = merged != local;
+            Ok((merged, changed))
+        })?;
+        if merged != local {
+            reconciler.report.achievements = Some(merged);
+        }
+    }
+    Ok(reconciler)
+}
+
+/// Fetch, reconcile, and upload if this machine had anything new. If
+/// another machine uploaded in between, go again with what it sent, so
+/// neither side's changes are lost. Item states land in `states` even when
+/// the upload fails, less the items that were meant to go up.
+async fn run(
+    app: &tauri::AppHandle,
+    mut achievements: Option<Value>,
+    states: &mut BTreeMap<String, ItemState>,
+) -> PetResult<SyncReport> {
+    let store = app.state::<SyncStore>();
+    let target = store
+        .target()
+        .ok_or_else(|| PetError::InvalidInput("Sync isn't set up".to_string()))?;
+    let remote = Remote::load(target)?;
+    let key = MemoryKey::from_keychain_as(KEY_ACCOUNT).ok_or_else(|| {
+        PetError::Auth("The sync key isn't in the Keychain; set sync up again".to_string())
+    })?;
+    let mut report = SyncReport::default();
+    for _ in 0..MAX_ATTEMPTS {
+        let (blob, precondition) = fetch(&remote).await?;
+        let bundle: Bundle = match blob {
+            Some(blob) => serde_json::from_slice(&key.decrypt(&blob)?)?,
+            None => Bundle {
+                version: BUNDLE_VERSION,
+                ..Default::default()
+            },
+        };
+        if bundle.version > BUNDLE_VERSION {
+            return Err(PetError::InvalidInput(
+                "Another machine syncs with a newer version of Desktop Pet; update this one first"
+                    .to_string(),
+            ));
+        }
+
+        let mut previous = store.items();
+        previous.extend(states.clone());
+        let reconciler = crate::run_blocking({
+            let (app, achievements) = (app.clone(), achievements.clone());
+            move || reconcile(&app, bundle, previous, achievements)
+        })
+        .await??;
+        let Reconciler {
+            bundle,
+            states: mut reconciled,
+            report: round,
+            ..
+        } = reconciler;
+        // What an earlier round pulled in is already here
+        for name in round.pulled {
+            if !report.pulled.contains(&name) {
+                report.pulled.push(name);
+            }
+        }
+        if let Some(merged) = round.achievements {
+            achievements = Some(merged.clone());
+            report.achievements = Some(merged);
+        }
+        if round.pushed.is_empty() {
+            states.extend(reconciled);
+            return Ok(report);
+        }
+
+        let uploaded = match serde_json::to_vec(&bundle)
+            .map_err(PetError::from)
+            .and_then(|b| key.encrypt(&b))
+        {
+            Ok(blob) => upload(&remote, &blob, &precondition).await,
+            Err(e) => Err(e),
+        };
+        if let Ok(true) = uploaded {
+            states.extend(reconciled);
+            report.pushed = round.pushed;
+            return Ok(report);
+        }
+        reconciled.retain(|name, _| !round.pushed.contains(name));
+        states.extend(reconciled);
+        uploaded?;
+    }
+    Err(PetError::InvalidInput(
+        "Another machine kept syncing at the same time; try again in a moment".to_string(),
+    ))
+}
+
+fn required(value: String, what: &str) -> PetResult<String> {
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        return Err(PetError::InvalidInput(format!("{} is required", what)));
+    }
+    Ok(value)
+}
+
+fn https(url: String, what: &str) -> PetResult<String> {
+    let url = required(url, what)?;
+    if !url.starts_with("https://") {
+        return Err(PetError::InvalidInput(format!(
+            "{} must start with https://, so the password isn't sent in the clear",
+            what
+        )));
+    }
+    Ok(url)
+}
+
+fn validated(target: SyncTarget) -> PetResult<SyncTarget> {
+    Ok(match target {
+        SyncTarget::Relay { code } => SyncTarget::Relay {
+            code: match code.trim() {
+                "" => uuid::Uuid::new_v4().to_string(),
+                code => code.to_string(),
+            },
+        },
+        SyncTarget::WebDav { url, username } => SyncTarget::WebDav {
+            url: https(url, "The WebDAV address")?,
+            username: required(username, "A username")?,
+        },
+        SyncTarget::S3 {
+            endpoint,
+            region,
+            bucket,
+            access_key_id,
+        } => SyncTarget::S3 {
+            endpoint: https(endpoint, "The S3 endpoint")?,
+            region: required(region, "A region")?,
+            bucket: required(bucket, "A bucket")?,
+            access_key_id: required(access_key_id, "An access key id")?,
+        },
+    })
+}
+
+#[tauri::command(async)]
+pub fn get_sync_status(store: tauri::State<'_, SyncStore>) -> SyncStatus {
+    store.status()
+}
+
+/// Start syncing to `target`, end-to-end encrypted with a key derived from
+/// `passphrase`. Every machine uses the same target and passphrase; for the
+/// relay, leave the code empty on the first and paste its code into the
+/// rest. `secret` is the WebDAV password or S3 secret key, kept in the
+/// Keychain. Call `sync_now` after for the first round.
+#[tauri::command]
+pub async fn enable_sync(
+    app: tauri::AppHandle,
+    target: SyncTarget,
+    passphrase: String,
+    secret: Option<String>,
+) -> PetResult<SyncStatus> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(PetError::InvalidInput(format!(
+            "The sync passphrase needs at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+    let target = validated(target)?;
+    let credential = match secret.map(|s| s.trim().to_string()) {
+        Some(secret) if !secret.is_empty() => Some(secret),
+        _ if target.needs_credential() => {
+            return Err(PetError::InvalidInput(
+                "A password or secret key is required".to_string(),
+            ))
+        }
+        _ => None,
+    };
+    let remote = Remote {
+        target: target.clone(),
+        credential,
+    };
+    let (existing, _) = fetch(&remote).await?;
+    let key = crate::run_blocking(move || match existing {
+        // Another machine got here first; its salt makes the key
+        Some(blob) => {
+            let key = MemoryKey::for_blob(&passphrase, &blob)?;
+            key.decrypt(&blob).map_err(|_| {
+                PetError::InvalidInput(
+                    "That isn't the passphrase your other machines sync with".to_string(),
+                )
+            })?;
+            Ok::<_, PetError>(key)
+        }
+        None => Ok(MemoryKey::new(&passphrase)),
+    })
+    .await??;
+
+    key.store_in_keychain_as(KEY_ACCOUNT)?;
+    match &remote.credential {
+        Some(credential) => crypto::store_secret(CREDENTIAL_ACCOUNT, credential)?,
+        None => crypto::remove_secret(CREDENTIAL_ACCOUNT)?,
+    }
+    let store = app.state::<SyncStore>();
+    store.configure(Some(target));
+    let status = store.status();
+    app.emit("sync-changed", &status)?;
+    Ok(status)
+}
+
+/// Stop syncing and forget the key and password. What's already uploaded
+/// stays where it is for the other machines.
+#[tauri::command(async)]
+pub fn disable_sync(app: tauri::AppHandle) -> PetResult<SyncStatus> {
+    crypto::remove_secret(KEY_ACCOUNT)?;
+    crypto::remove_secret(CREDENTIAL_ACCOUNT)?;
+    let store = app.state::<SyncStore>();
+    store.configure(None);
+    let status = store.status();
+    app.emit("sync-changed", &status)?;
+    Ok(status)
+}
+
+/// Sync now. The webview hands in its achievements (`pet-achievements`) and
+/// gets `achievements-synced` with the merged set if another machine had
+/// more. Emits `sync-changed` with the new status either way.
+#[tauri::command]
+pub async fn sync_now(app: tauri::AppHandle, achievements: Option<Value>) -> PetResult<SyncReport> {
+    let store = app.state::<SyncStore>();
+    if store.running.swap(true, Ordering::SeqCst) {
+        return Err(PetError::InvalidInput("Already syncing".to_string()));
+    }
+    let mut states = BTreeMap::new();
+    let result = run(&app, achievements, &mut states).await;
+    store.running.store(false, Ordering::SeqCst);
+    store.finish(states, result.as_ref().err().map(|e| e.to_string()));
+    let _ = app.emit("sync-changed", store.status());
+    let report = result?;
+    if let Some(achievements) = &report.achievements {
+        app.emit("achievements-synced", achievements)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A reconciler whose bundle holds `remote` as "settings", updated at
+    /// `remote_at`, after a last sync that saw `previous`.
+    fn reconciler(remote: Option<(Value, i64)>, previous: Option<(&Value, i64)>) -> Reconciler {
+        let mut bundle = Bundle::default();
+        if let Some((data, updated_at)) = remote {
+            bundle.items.insert(
+                "settings".to_string(),
+                RemoteItem {
+                    updated_at,
+                    hash: hash(&data),
+                    data,
+                },
+            );
+        }
+        let previous = previous
+            .map(|(data, remote_updated_at)| {
+                let state = ItemState {
+                    hash: hash(data),
+                    remote_updated_at,
+                };
+                ("settings".to_string(), state)
+            })
+            .into_iter()
+            .collect();
+        Reconciler {
+            bundle,
+            previous,
+            states: BTreeMap::new(),
+            report: SyncReport::default(),
+            now: 1_000,
+        }
+    }
+
+    /// Run `whole` on "settings"; returns what was applied here, if anything.
+    fn whole(reconciler: &mut Reconciler, local: &Value, modified_at: i64) -> Option<Value> {
+        let mut applied = None;
+        reconciler
+            .whole("settings", local.clone(), modified_at, |remote| {
+                applied = Some(remote.clone());
+                Ok(remote)
+            })
+            .unwrap();
+        applied
+    }
+
+    #[test]
+    fn whole_pushes_when_nothing_is_there() {
+        let local = json!({ "a": 1 });
+        let mut r = reconciler(None, None);
+        assert_eq!(whole(&mut r, &local, 10), None);
+        assert_eq!(r.report.pushed, ["settings"]);
+        assert_eq!(r.bundle.items["settings"].data, local);
+    }
+
+    #[test]
+    fn whole_takes_what_is_there_on_first_sync() {
+        let (local, remote) = (json!({ "a": 1 }), json!({ "a": 2 }));
+        let mut r = reconciler(Some((remote.clone(), 5)), None);
+        assert_eq!(whole(&mut r, &local, 10), Some(remote));
+        assert_eq!(r.report.pulled, ["settings"]);
+        assert!(r.report.pushed.is_empty());
+    }
+
+    #[test]
+    fn whole_pushes_a_local_change() {
+        let (old, local) = (json!({ "a": 1 }), json!({ "a": 2 }));
+        let mut r = reconciler(Some((old.clone(), 5)), Some((&old, 5)));
+        assert_eq!(whole(&mut r, &local, 10), None);
+        assert_eq!(r.report.pushed, ["settings"]);
+        assert_eq!(r.bundle.items["settings"].data, local);
+    }
+
+    #[test]
+    fn whole_pulls_a_remote_change() {
+        let (old, remote) = (json!({ "a": 1 }), json!({ "a": 2 }));
+        let mut r = reconciler(Some((remote.clone(), 8)), Some((&old, 5)));
+        assert_eq!(whole(&mut r, &old, 3), Some(remote));
+        assert!(r.report.pushed.is_empty());
+    }
+
+    #[test]
+    fn whole_keeps_the_later_change_when_both_changed() {
+        let old = json!({ "a": 1 });
+        let (local, remote) = (json!({ "a": 2 }), json!({ "a": 3 }));
+
+        let mut r = reconciler(Some((remote.clone(), 20)), Some((&old, 5)));
+        assert_eq!(whole(&mut r, &local, 10), Some(remote.clone()));
+
+        let mut r = reconciler(Some((remote, 20)), Some((&old, 5)));
+        assert_eq!(whole(&mut r, &local, 30), None);
+        assert_eq!(r.bundle.items["settings"].data, local);
+    }
+
+    #[test]
+    fn whole_leaves_matching_copies_alone() {
+        let same = json!({ "a": 1 });
+        let mut r = reconciler(Some((same.clone(), 5)), None);
+        assert_eq!(whole(&mut r, &same, 10), None);
+        assert!(r.report.pushed.is_empty() && r.report.pulled.is_empty());
+    }
+
+    #[test]
+    fn achievements_keep_the_higher_tier() {
+        let local = json!({ "a": { "tier": "bronze", "unlockedAt": 1 } });
+        let remote = json!({ "a": { "tier": "gold", "unlockedAt": 9 } });
+        assert_eq!(merge_achievements(&local, Some(&remote)), remote);
+        assert_eq!(merge_achievements(&remote, Some(&local)), remote);
+    }
+
+    #[test]
+    fn achievements_keep_the_earlier_unlock_of_a_tier() {
+        let early = json!({ "a": { "tier": "silver", "unlockedAt": 1 } });
+        let late = json!({ "a": { "tier": "silver", "unlockedAt": 9 } });
+        assert_eq!(merge_achievements(&late, Some(&early)), early);
+        assert_eq!(merge_achievements(&early, Some(&late)), early);
+    }
+
+    #[test]
+    fn achievements_from_either_side_are_kept() {
+        let local = json!({ "a": { "unlockedAt": 1 } });
+        let remote = json!({ "b": { "unlockedAt": 2 } });
+        let merged = merge_achievements(&local, Some(&remote));
+        assert_eq!(merged, json!({ "a": { "unlockedAt": 1 }, "b": { "unlockedAt": 2 } }));
+        assert_eq!(merge_achievements(&local, None), local);
+    }
+}
@@ -0,0 +1,304 @@
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::config::{self, Key};
+use crate::error::{PetError, PetResult};
+use crate::settings::{QuoteProvider, SettingsStore};
+use crate::{notifications, persist, scheduler};
+
+pub const WATCHLIST_FILE: &str = "watchlist.json";
+/// Local hour after which the daily mover check runs, so it lands mid-morning.
+const DAILY_MENTION_HOUR: u32 = 10;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    pub symbol: String,
+    pub price: f64,
+    pub previous_close: f64,
+    pub change_percent: f64,
+    pub currency: Option<String>,
+}
+
+impl Quote {
+    fn new(symbol: &str, price: f64, previous_close: f64, currency: Option<String>) -> Self {
+        let change_percent = if previous_close > 0.0 {
+            (price - previous_close) / previous_close * 100.0
+        } else {
+            0.0
+        };
+        Self {
+            symbol: symbol.to_string(),
+            price,
+            previous_close,
+            change_percent,
+            currency,
+        }
+    }
+
+    /// One-line summary handed to the model as a tool result.
+    pub fn describe(&self) -> String {
+        format!(
+            "{}: {:.2}{} ({:+.2}% today)",
+            self.symbol,
+            self.price,
+            self.currency
+                .as_deref()
+                .map(|c| format!(" {}", c))
+                .unwrap_or_default(),
+            self.change_percent
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Watchlist {
+    pub symbols: Vec<String>,
+    /// Local date (YYYY-MM-DD) of the last daily mover check
+    pub last_mention_date: Option<String>,
+}
+
+pub struct WatchlistStore {
+    data: Mutex<Watchlist>,
+    path: Option<PathBuf>,
+}
+
+impl WatchlistStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, WATCHLIST_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        *data = Watchlist::default();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self) -> Watchlist {
+        self.data.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, watchlist: Watchlist) {
+        let mut data = self.data.lock().unwrap();
+        *data = watchlist;
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*data);
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Watchlist)) -> Watchlist {
+        let mut data = self.data.lock().unwrap();
+        f(&mut data);
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*data);
+        }
+        data.clone()
+    }
+}
+
+fn normalize_symbol(symbol: &str) -> PetResult<String> {
+    let symbol = symbol.trim().trim_start_matches('$').to_uppercase();
+    let valid = !symbol.is_empty()
+        && symbol.len() <= 15
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '^' | '='));
+    if valid {
+        Ok(symbol)
+    } else {
+        Err(PetError::InvalidInput(format!("\"{}\" isn't a ticker symbol", symbol)))
+    }
+}
+
+#[derive(Deserialize)]
+struct YahooChart {
+    chart: YahooResult,
+}
+
+#[derive(Deserialize)]
+struct YahooResult {
+    result: Option<Vec<YahooEntry>>,
+}
+
+#[derive(Deserialize)]
+struct YahooEntry {
+    meta: YahooMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YahooMeta {
+    regular_market_price: f64,
+    chart_previous_close: Option<f64>,
+    currency: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FinnhubQuote {
+    /// Current price
+    c: f64,
+    /// Previous close
+    pc: f64,
+}
+
+/// Fetch a quote from the configured provider. Crypto works with Yahoo-style
+/// pair symbols such as BTC-USD.
+pub async fn fetch_quote(app: &tauri::AppHandle, symbol: &str) -> PetResult<Quote> {
+    let symbol = normalize_symbol(symbol)?;
+    let provider = app.state::<SettingsStore>().get().quotes.provider;
+    let client = reqwest::Client::new();
+    let network = |e: reqwest::Error| PetError::Network(e.to_string());
+
+    match provider {
+        QuoteProvider::Yahoo => {
+            let chart: YahooChart = client
+                .get(format!(
+                    "https://query1.finance.yahoo.com/v8/finance/chart/{}?range=1d&interval=1d",
+                    symbol
+                ))
+                .header("user-agent", "Mozilla/5.0")
+                .send()
+                .await
+                .map_err(network)?
+                .json()
+                .await
+                .map_err(network)?;
+            let meta = chart
+                .chart
+                .result
+                .and_then(|r| r.into_iter().next())
+                .map(|entry| entry.meta)
+                .ok_or_else(|| PetError::InvalidInput(format!("No quote for {}", symbol)))?;
+            Ok(Quote::new(
+                &symbol,
+                meta.regular_market_price,
+                meta.chart_previous_close.unwrap_or(meta.regular_market_price),
+                meta.currency,
+            ))
+        }
+        QuoteProvider::Finnhub => {
+            let token = config::get(Key::FinnhubApiKey)
+                .ok_or_else(|| PetError::Auth("FINNHUB_API_KEY is not set".to_string()))?;
+            let quote: FinnhubQuote = client
+                .get("https://finnhub.io/api/v1/quote")
+                .query(&[("symbol", symbol.as_str()), ("token", token.as_str())])
+                .send()
+                .await
+                .map_err(network)?
+                .json()
+                .await
+                .map_err(network)?;
+            // Finnhub answers unknown symbols with all zeros rather than an error
+            if quote.c == 0.0 {
+                return Err(PetError::InvalidInput(format!("No quote for {}", symbol)));
+            }
+            Ok(Quote::new(&symbol, quote.c, quote.pc, None))
+        }
+    }
+}
+
+/// Once a day, mention any watched ticker that moved more than the threshold.
+async fn daily_mention(app: tauri::AppHandle) {
+    let settings = app.state::<SettingsStore>().get().quotes;
+    if !settings.daily_mention {
+        return;
+    }
+    let now = chrono::Local::now();
+    let today = now.format("%Y-%m-%d").to_string();
+    let watchlist = app.state::<WatchlistStore>().get();
+    if now.hour() < DAILY_MENTION_HOUR
+        || watchlist.symbols.is_empty()
+        || watchlist.last_mention_date.as_deref() == Some(today.as_str())
+    {
+        return;
+    }
+
+    let mut movers = Vec::new();
+    for symbol in &watchlist.symbols {
+        match fetch_quote(&app, symbol).await {
+            Ok(quote) if quote.change_percent.abs() >= settings.mention_threshold_percent => {
+                movers.push(quote)
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Quote for {} failed: {}", symbol, e),
+        }
+    }
+
+    let handle = app.clone();
+    let _ = crate::run_blocking(move || {
+        handle
+            .state::<WatchlistStore>()
+            .update(|w| w.last_mention_date = Some(today))
+    })
+    .await;
+
+    if let Some(biggest) = movers
+        .iter()
+        .max_by(|a, b| a.change_percent.abs().total_cmp(&b.change_percent.abs()))
+    {
+        let direction = if biggest.change_percent >= 0.0 { "up" } else { "down" };
+        notifications::announce(
+            &app,
+            &format!(
+                "{} is {} {:.1}% today. Not that I'm watching. I'm watching.",
+                biggest.symbol,
+                direction,
+                biggest.change_percent.abs()
+            ),
+        );
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, daily_mention);
+}
+
+#[tauri::command(async)]
+pub fn get_watchlist(store: tauri::State<'_, WatchlistStore>) -> Vec<String> {
+    store.get().symbols
+}
+
+#[tauri::command]
+pub async fn add_ticker(app: tauri::AppHandle, symbol: String) -> PetResult<Vec<String>> {
+    let symbol = normalize_symbol(&symbol)?;
+    crate::run_blocking(move || {
+        app.state::<WatchlistStore>()
+            .update(|w| {
+                if !w.symbols.contains(&symbol) {
+                    w.symbols.push(symbol);
+                }
+            })
+            .symbols
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_ticker(app: tauri::AppHandle, symbol: String) -> PetResult<Vec<String>> {
+    let symbol = normalize_symbol(&symbol)?;
+    crate::run_blocking(move || {
+        app.state::<WatchlistStore>()
+            .update(|w| w.symbols.retain(|s| *s != symbol))
+            .symbols
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_quote(app: tauri::AppHandle, symbol: String) -> PetResult<Quote> {
+    fetch_quote(&app, &symbol).await
+}
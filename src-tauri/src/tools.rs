@@ -0,0 +1,631 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tokio::sync::oneshot;
+
+use crate::error::{PetError, PetResult};
+use crate::files;
+use crate::launch::{self, Target};
+use crate::notes::{self, NotesStore};
+use crate::persist;
+use crate::settings::SettingsStore;
+
+pub const TOOL_AUDIT_FILE: &str = "tool_audit.json";
+const MAX_AUDIT: usize = 200;
+/// A permission prompt nobody answers in this long counts as a no.
+const PERMISSION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Calendar answers slowly, and not at all until Automation access is given.
+const CALENDAR_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_CALENDAR_DAYS: u64 = 7;
+/// Reminders further out than a year are clamped to a year.
+const MAX_REMINDER_MINUTES: u64 = 365 * 24 * 60;
+/// How much of a tool's output the audit log keeps.
+const MAX_AUDIT_DETAIL: usize = 200;
+
+/// Whether the cat may use a tool on its own.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ToolPermission {
+    Allow,
+    /// Ask every time, in a speech bubble
+    Ask,
+    /// Not offered to the model at all
+    Deny,
+}
+
+/// A tool the model can ask to run on this machine.
+struct ToolSpec {
+    name: &'static str,
+    /// For the permission settings
+    label: &'static str,
+    /// For the model
+    description: &'static str,
+    input_schema: &'static str,
+    default: ToolPermission,
+}
+
+const TOOLS: &[ToolSpec] = &[
+    ToolSpec {
+        name: "search_notes",
+        label: "Search your notes and reminders",
+        description: "Search the owner's saved notes and reminders. Returns matching notes, one per line.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": { "query": { "type": "string", "description": "Words to look for" } },
+            "required": ["query"]
+        }"#,
+        default: ToolPermission::Allow,
+    },
+    ToolSpec {
+        name: "get_quote",
+        label: "Look up stock and crypto prices",
+        description: "Get the latest price and today's change for a stock or crypto ticker.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": {
+                "symbol": {
+                    "type": "string",
+                    "description": "Ticker symbol, e.g. AAPL, or a crypto pair like BTC-USD"
+                }
+            },
+            "required": ["symbol"]
+        }"#,
+        default: ToolPermission::Allow,
+    },
+    ToolSpec {
+        name: "open_url",
+        label: "Open web pages",
+        description: "Open a web page in the owner's browser.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": { "url": { "type": "string", "description": "An http or https address" } },
+            "required": ["url"]
+        }"#,
+        default: ToolPermission::Ask,
+    },
+    ToolSpec {
+        name: "open_app",
+        label: "Open apps",
+        description: "Open or bring forward an app on the owner's Mac, by name.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": { "name": { "type": "string", "description": "The app's name, e.g. Calendar" } },
+            "required": ["name"]
+        }"#,
+        default: ToolPermission::Ask,
+    },
+    ToolSpec {
+        name: "open_file",
+        label: "Open files",
+        description: "Open a file in its usual app. Use search_files first to find the path.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": { "path": { "type": "string", "description": "The file's full path" } },
+            "required": ["path"]
+        }"#,
+        default: ToolPermission::Ask,
+    },
+    ToolSpec {
+        name: "create_reminder",
+        label: "Add reminders",
+        description: "Add a note to the owner's reminders, optionally due in some minutes.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": {
+                "text": { "type": "string", "description": "What to remind them of" },
+                "in_minutes": { "type": "integer", "description": "Minutes from now; leave out for no due time" }
+            },
+            "required": ["text"]
+        }"#,
+        default: ToolPermission::Allow,
+    },
+    ToolSpec {
+        name: "search_files",
+        label: "Search your files",
+        description: "Find files in the owner's home folder with Spotlight, by name, kind or content, best matches first. Returns full paths with when each was last changed, one per line.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": { "query": { "type": "string", "description": "What to look for, as you'd type it into Spotlight, e.g. timesheet or tax pdf" } },
+            "required": ["query"]
+        }"#,
+        default: ToolPermission::Ask,
+    },
+    ToolSpec {
+        name: "read_calendar",
+        label: "Read your calendar",
+        description: "List the owner's calendar events from now through the next few days.",
+        input_schema: r#"{
+            "type": "object",
+            "properties": { "days": { "type": "integer", "description": "How many days ahead, 1 to 7" } }
+        }"#,
+        default: ToolPermission::Ask,
+    },
+];
+
+fn spec(name: &str) -> Option<&'static ToolSpec> {
+    TOOLS.iter().find(|tool| tool.name == name)
+}
+
+fn permission(app: &tauri::AppHandle, tool: &ToolSpec) -> ToolPermission {
+    app.state::<SettingsStore>()
+        .get()
+        .tools
+        .permissions
+        .get(tool.name)
+        .copied()
+        .unwrap_or(tool.default)
+}
+
+/// Definitions of every tool the owner hasn't turned off, for the request.
+pub fn definitions(app: &tauri::AppHandle) -> Vec<Value> {
+    TOOLS
+        .iter()
+        .filter(|tool| permission(app, tool) != ToolPermission::Deny)
+        .map(|tool| {
+            serde_json::json!({
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": serde_json::from_str::<Value>(tool.input_schema)
+                    .expect("tool schemas are valid JSON"),
+            })
+        })
+        .collect()
+}
+
+fn string_input<'a>(input: &'a Value, key: &str) -> Result<&'a str, String> {
+    input
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("Missing {}", key))
+}
+
+fn calendar_days(input: &Value) -> u64 {
+    input
+        .get("days")
+        .and_then(Value::as_u64)
+        .unwrap_or(1)
+        .clamp(1, MAX_CALENDAR_DAYS)
+}
+
+/// What a call would do, in words, for the prompt and the audit log.
+fn summary(name: &str, input: &Value) -> String {
+    let text = |key| string_input(input, key).unwrap_or("?");
+    match name {
+        "search_notes" => format!("Search your notes for \"{}\"", text("query")),
+        "get_quote" => format!("Look up {}", text("symbol")),
+        "open_url" => format!("Open {}", text("url")),
+        "open_app" => format!("Open {}", text("name")),
+        "open_file" => format!("Open {}", text("path")),
+        "create_reminder" => format!("Remind you: {}", text("text")),
+        "search_files" => format!("Search your files for \"{}\"", text("query")),
+        "read_calendar" => match calendar_days(input) {
+            1 => "Read today's calendar".to_string(),
+            days => format!("Read your calendar for the next {} days", days),
+        },
+        _ => name.to_string(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOutcome {
+    Ran,
+    Failed,
+    /// The owner said no, or didn't answer, or the tool is turned off
+    Denied,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Unix millis
+    pub at: i64,
+    pub tool: String,
+    pub summary: String,
+    pub outcome: AuditOutcome,
+    /// The start of the output, or the error
+    pub detail: Option<String>,
+}
+
+/// Every tool call the model asked for and what came of it, newest last.
+pub struct ToolAuditStore {
+    entries: Mutex<Vec<AuditEntry>>,
+    path: Option<PathBuf>,
+}
+
+impl ToolAuditStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, TOOL_AUDIT_FILE).ok();
+        let entries = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            entries: Mutex::new(entries),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut entries = self.entries.lock().unwrap();
+        *entries = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn all(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    fn record(&self, entry: AuditEntry) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.push(entry);
+        let excess = entries.len().saturating_sub(MAX_AUDIT);
+        entries.drain(..excess);
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*entries);
+        }
+    }
+}
+
+/// A call waiting on the owner's yes or no.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolRequest {
+    pub id: String,
+    pub tool: String,
+    pub summary: String,
+}
+
+struct PendingPrompt {
+    tool: String,
+    /// What an "Always" answer allowlists, instead of the whole tool
+    target: Option<Target>,
+    sender: oneshot::Sender<bool>,
+}
+
+/// Permission prompts waiting for an answer, by request id.
+#[derive(Default)]
+pub struct ToolPrompts(Mutex<HashMap<String, PendingPrompt>>);
+
+/// Ask the owner in a speech bubble. No answer in time is a no.
+pub async fn ask(
+    app: &tauri::AppHandle,
+    tool: &str,
+    summary: &str,
+    target: Option<Target>,
+) -> bool {
+    let request = ToolRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        tool: tool.to_string(),
+        summary: summary.to_string(),
+    };
+    let (sender, receiver) = oneshot::channel();
+    app.state::<ToolPrompts>().0.lock().unwrap().insert(
+        request.id.clone(),
+        PendingPrompt {
+            tool: tool.to_string(),
+            target,
+            sender,
+        },
+    );
+    if app.emit("tool-permission-requested", &request).is_err() {
+        app.state::<ToolPrompts>()
+            .0
+            .lock()
+            .unwrap()
+            .remove(&request.id);
+        return false;
+    }
+    match tokio::time::timeout(PERMISSION_TIMEOUT, receiver).await {
+        Ok(Ok(allowed)) => allowed,
+        _ => {
+            app.state::<ToolPrompts>()
+                .0
+                .lock()
+                .unwrap()
+                .remove(&request.id);
+            let _ = app.emit("tool-permission-expired", &request.id);
+            false
+        }
+    }
+}
+
+/// The same `osascript` dance as the browser tab lookup, with room for
+/// Calendar to wake up.
+fn run_script(source: &str, timeout: Duration) -> Result<String, String> {
+    let mut child = std::process::Command::new("osascript")
+        .args(["-e", source])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    // Read as it comes, so a busy calendar can't fill the pipe and stall
+    let mut stdout = child.stdout.take().ok_or("Couldn't read the calendar")?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if started.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Calendar didn't answer".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    let output = reader
+        .join()
+        .map_err(|_| "Couldn't read the calendar".to_string())?
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err("Couldn't read the calendar; is Automation access allowed?".to_string());
+    }
+    Ok(String::from_utf8_lossy(&output).trim().to_string())
+}
+
+fn calendar_script(days: u64) -> String {
+    format!(
+        r#"set startDate to current date
+set endDate to startDate + ({} * days)
+set output to ""
+tell application "Calendar"
+    repeat with cal in calendars
+        repeat with ev in (every event of cal whose start date ≥ startDate and start date < endDate)
+            set output to output & (summary of ev) & " | " & ((start date of ev) as string) & linefeed
+        end repeat
+    end repeat
+end tell
+return output"#,
+        days
+    )
+}
+
+fn search_notes(app: &tauri::AppHandle, query: &str) -> String {
+    let words: Vec<String> = query
+        .to_lowercase()
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+    let matches: Vec<String> = app
+        .state::<NotesStore>()
+        .all()
+        .into_iter()
+        .map(|note| note.text)
+        .filter(|text| {
+            let text = text.to_lowercase();
+            words.iter().any(|word| text.contains(word))
+        })
+        .collect();
+    if matches.is_empty() {
+        "No matching notes.".to_string()
+    } else {
+        matches.join("\n")
+    }
+}
+
+fn search_files(app: &tauri::AppHandle, query: &str) -> Result<String, String> {
+    let hits = files::search(query, files::MAX_TOOL_RESULTS).map_err(|e| e.to_string())?;
+    app.state::<files::FoundFiles>().remember(&hits);
+    if hits.is_empty() {
+        return Ok("No matching files.".to_string());
+    }
+    Ok(hits
+        .iter()
+        .map(|hit| {
+            match hit
+                .modified
+                .and_then(chrono::DateTime::from_timestamp_millis)
+            {
+                Some(at) => format!("{} (changed {})", hit.path, at.format("%Y-%m-%d")),
+                None => hit.path.clone(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+async fn blocking(
+    f: impl FnOnce() -> Result<String, String> + Send + 'static,
+) -> Result<String, String> {
+    crate::run_blocking(f).await.map_err(|e| e.to_string())?
+}
+
+/// Run a tool that's been allowed. Blocking work goes off the runtime.
+async fn run(app: &tauri::AppHandle, name: &str, input: &Value) -> Result<String, String> {
+    match name {
+        "search_notes" => {
+            let (app, query) = (app.clone(), string_input(input, "query")?.to_string());
+            blocking(move || Ok(search_notes(&app, &query))).await
+        }
+        "get_quote" => crate::tickers::fetch_quote(app, string_input(input, "symbol")?)
+            .await
+            .map(|quote| quote.describe())
+            .map_err(|e| e.to_string()),
+        "open_url" => {
+            let target = Target::url(string_input(input, "url")?).map_err(|e| e.to_string())?;
+            blocking(move || target.open().map_err(|e| e.to_string())).await
+        }
+        "open_app" => {
+            let target = Target::app(string_input(input, "name")?).map_err(|e| e.to_string())?;
+            blocking(move || target.open().map_err(|e| e.to_string())).await
+        }
+        "open_file" => {
+            let (app, path) = (app.clone(), PathBuf::from(string_input(input, "path")?));
+            blocking(move || {
+                files::open(&app, &path).map_err(|e| e.to_string())?;
+                Ok(format!("Opened {}.", path.display()))
+            })
+            .await
+        }
+        "create_reminder" => {
+            let text = string_input(input, "text")?.to_string();
+            let minutes = input
+                .get("in_minutes")
+                .and_then(Value::as_u64)
+                .map(|m| m.min(MAX_REMINDER_MINUTES));
+            let app = app.clone();
+            blocking(move || {
+                notes::remind(&app, &text, minutes.map(|m| m.saturating_mul(60)));
+                Ok(match minutes {
+                    Some(m) => format!("Added, due in {} minutes.", m),
+                    None => "Added.".to_string(),
+                })
+            })
+            .await
+        }
+        "search_files" => {
+            let (app, query) = (app.clone(), string_input(input, "query")?.to_string());
+            blocking(move || search_files(&app, &query)).await
+        }
+        "read_calendar" => {
+            if !cfg!(target_os = "macos") {
+                return Err("The calendar can only be read on macOS".to_string());
+            }
+            let days = calendar_days(input);
+            blocking(move || {
+                let events = run_script(&calendar_script(days), CALENDAR_TIMEOUT)?;
+                Ok(if events.is_empty() {
+                    "Nothing on the calendar.".to_string()
+                } else {
+                    events
+                })
+            })
+            .await
+        }
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+fn preview(text: &str) -> String {
+    match text.char_indices().nth(MAX_AUDIT_DETAIL) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Run a tool the model asked for, asking the owner first if that's the
+/// setting, and log it. Errors and refusals are returned as text for the
+/// model to read rather than failing the whole dialogue.
+pub async fn execute(app: &tauri::AppHandle, name: &str, input: &Value) -> Result<String, String> {
+    let tool = spec(name).ok_or_else(|| format!("Unknown tool: {}", name))?;
+    let summary = summary(name, input);
+    let allowed = match permission(app, tool) {
+        ToolPermission::Allow => true,
+        ToolPermission::Ask => {
+            launch::preapproved(app, name, input)
+                || ask(app, name, &summary, Target::from_tool(name, input)).await
+        }
+        ToolPermission::Deny => false,
+    };
+    let result = if allowed {
+        run(app, name, input).await
+    } else {
+        Err("The owner didn't allow that.".to_string())
+    };
+    let (outcome, detail) = match (&result, allowed) {
+        (Ok(output), _) => (AuditOutcome::Ran, Some(preview(output))),
+        (Err(_), false) => (AuditOutcome::Denied, None),
+        (Err(error), true) => (AuditOutcome::Failed, Some(preview(error))),
+    };
+    app.state::<ToolAuditStore>().record(AuditEntry {
+        at: chrono::Utc::now().timestamp_millis(),
+        tool: name.to_string(),
+        summary,
+        outcome,
+        detail,
+    });
+    result
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolInfo {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub permission: ToolPermission,
+}
+
+#[tauri::command(async)]
+pub fn get_tools(app: tauri::AppHandle) -> Vec<ToolInfo> {
+    TOOLS
+        .iter()
+        .map(|tool| ToolInfo {
+            name: tool.name,
+            label: tool.label,
+            permission: permission(&app, tool),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn set_tool_permission(
+    app: tauri::AppHandle,
+    tool: String,
+    permission: ToolPermission,
+) -> PetResult<()> {
+    crate::run_blocking(move || set_permission(&app, &tool, permission)).await?
+}
+
+fn set_permission(app: &tauri::AppHandle, tool: &str, permission: ToolPermission) -> PetResult<()> {
+    if spec(tool).is_none() {
+        return Err(PetError::InvalidInput(format!("No tool called {}", tool)));
+    }
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings
+        .tools
+        .permissions
+        .insert(tool.to_string(), permission);
+    store.replace(settings)
+}
+
+/// Answer a `tool-permission-requested` prompt. With `remember`, the
+/// answer becomes the tool's setting, except that a yes to opening an app
+/// or site puts just that one on the allowlist.
+#[tauri::command]
+pub async fn answer_tool_permission(
+    app: tauri::AppHandle,
+    id: String,
+    allow: bool,
+    remember: Option<bool>,
+) -> PetResult<()> {
+    let Some(prompt) = app.state::<ToolPrompts>().0.lock().unwrap().remove(&id) else {
+        return Err(PetError::InvalidInput(
+            "That question already timed out".to_string(),
+        ));
+    };
+    let _ = prompt.sender.send(allow);
+    if !remember.unwrap_or(false) {
+        return Ok(());
+    }
+    crate::run_blocking(move || match (prompt.target, allow) {
+        (Some(target), true) => launch::allow(&app, &target),
+        (_, allow) => {
+            let permission = if allow {
+                ToolPermission::Allow
+            } else {
+                ToolPermission::Deny
+            };
+            set_permission(&app, &prompt.tool, permission)
+        }
+    })
+    .await?
+}
+
+#[tauri::command(async)]
+pub fn get_tool_audit(store: tauri::State<'_, ToolAuditStore>) -> Vec<AuditEntry> {
+    store.all()
+}
@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::usage::UsageStore;
+use crate::{notifications, persist, scheduler};
+
+pub const TRACKERS_FILE: &str = "trackers.json";
+const PRICE_INTERVAL_MINUTES: u32 = 360;
+const PACKAGE_INTERVAL_MINUTES: u32 = 60;
+/// Product pages often block requests without a browser-like user agent.
+const USER_AGENT: &str =
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Safari/605.1.15";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TrackerTarget {
+    /// Scraped from the product page's structured price metadata
+    Price { url: String },
+    /// Looked up through search mode, since carriers have no open API
+    #[serde(rename_all = "camelCase")]
+    Package {
+        carrier: String,
+        tracking_number: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Tracker {
+    pub id: String,
+    pub label: String,
+    pub target: TrackerTarget,
+    pub interval_minutes: u32,
+    /// Unix millis
+    pub created_at: i64,
+    pub last_checked_at: Option<i64>,
+    /// Last price ("19.99 USD") or delivery status the user was told about
+    pub last_value: Option<String>,
+}
+
+pub struct TrackerStore {
+    trackers: Mutex<Vec<Tracker>>,
+    path: Option<PathBuf>,
+}
+
+impl TrackerStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, TRACKERS_FILE).ok();
+        let trackers = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            trackers: Mutex::new(trackers),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut trackers = self.trackers.lock().unwrap();
+        *trackers = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, trackers: &[Tracker]) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &trackers);
+        }
+    }
+
+    pub fn all(&self) -> Vec<Tracker> {
+        self.trackers.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, new_trackers: Vec<Tracker>) {
+        let mut trackers = self.trackers.lock().unwrap();
+        *trackers = new_trackers;
+        self.save(&trackers);
+    }
+
+    fn add(
+        &self,
+        label: String,
+        target: TrackerTarget,
+        interval_minutes: u32,
+        initial_value: Option<String>,
+    ) -> Tracker {
+        let tracker = Tracker {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+            target,
+            interval_minutes,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            last_checked_at: initial_value
+                .as_ref()
+                .map(|_| chrono::Utc::now().timestamp_millis()),
+            last_value: initial_value,
+        };
+        let mut trackers = self.trackers.lock().unwrap();
+        trackers.push(tracker.clone());
+        self.save(&trackers);
+        tracker
+    }
+
+    fn remove(&self, id: &str) -> bool {
+        let mut trackers = self.trackers.lock().unwrap();
+        let before = trackers.len();
+        trackers.retain(|t| t.id != id);
+        let removed = trackers.len() != before;
+        if removed {
+            self.save(&trackers);
+        }
+        removed
+    }
+
+    fn due(&self) -> Vec<Tracker> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.all()
+            .into_iter()
+            .filter(|t| scheduler::is_due(t.last_checked_at, t.interval_minutes, now))
+            .collect()
+    }
+
+    fn record_check(&self, id: &str, value: Option<String>) {
+        let mut trackers = self.trackers.lock().unwrap();
+        if let Some(tracker) = trackers.iter_mut().find(|t| t.id == id) {
+            tracker.last_checked_at = Some(chrono::Utc::now().timestamp_millis());
+            if value.is_some() {
+                tracker.last_value = value;
+            }
+        }
+        self.save(&trackers);
+    }
+}
+
+/// Guess the carrier from the tracking number's shape.
+fn detect_carrier(tracking_number: &str) -> Option<&'static str> {
+    let n = tracking_number.replace(' ', "").to_uppercase();
+    let digits = n.chars().all(|c| c.is_ascii_digit());
+    if n.starts_with("1Z") && n.len() == 18 {
+        Some("UPS")
+    } else if digits && (n.len() == 20 || n.len() == 22) {
+        Some("USPS")
+    } else if digits && (n.len() == 12 || n.len() == 15) {
+        Some("FedEx")
+    } else if digits && n.len() == 10 {
+        Some("DHL")
+    } else if n.starts_with("TBA") {
+        Some("Amazon")
+    } else {
+        None
+    }
+}
+
+/// Pull "amount currency" out of a product page's Open Graph, microdata or
+/// JSON-LD price metadata.
+fn parse_price(html: &str) -> Option<String> {
+    let amount_patterns = [
+        r#"<meta[^>]+(?:property|itemprop|name)="(?:product:price:amount|og:price:amount|price)"[^>]+content="([0-9][0-9.,]*)""#,
+        r#""price"\s*:\s*"?([0-9]+(?:\.[0-9]{1,2})?)"#,
+    ];
+    let amount = amount_patterns.iter().find_map(|pattern| {
+        regex::Regex::new(pattern)
+            .ok()?
+            .captures(html)
+            .map(|c| c[1].replace(',', ""))
+    })?;
+
+    let currency = [
+        r#"<meta[^>]+(?:property|itemprop)="(?:product:price:currency|og:price:currency|priceCurrency)"[^>]+content="([A-Z]{3})""#,
+        r#""priceCurrency"\s*:\s*"([A-Z]{3})""#,
+    ]
+    .iter()
+    .find_map(|pattern| {
+        regex::Regex::new(pattern)
+            .ok()?
+            .captures(html)
+            .map(|c| c[1].to_string())
+    });
+
+    Some(match currency {
+        Some(currency) => format!("{} {}", amount, currency),
+        None => amount,
+    })
+}
+
+async fn fetch_price(url: &str) -> PetResult<String> {
+    let html = reqwest::Client::new()
+        .get(url)
+        .header("user-agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| PetError::Network(e.to_string()))?
+        .text()
+        .await
+        .map_err(|e| PetError::Network(e.to_string()))?;
+    parse_price(&html).ok_or_else(|| PetError::Parse("No price found on the page".to_string()))
+}
+
+fn price_amount(value: &str) -> Option<f64> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// Check one tracker. Returns the new value and, if it changed since the
+/// last check, the line the cat should announce.
+async fn check(
+    app: &tauri::AppHandle,
+    tracker: &Tracker,
+) -> PetResult<(Option<String>, Option<String>)> {
+    match &tracker.target {
+        TrackerTarget::Price { url } => {
+            let value = fetch_price(url).await?;
+            let message = match tracker.last_value.as_deref() {
+                Some(old) if old != value => {
+                    let direction = match (price_amount(old), price_amount(&value)) {
+                        (Some(a), Some(b)) if b < a => "dropped",
+                        (Some(a), Some(b)) if b > a => "went up",
+                        _ => "changed",
+                    };
+                    Some(format!(
+                        "Psst! {} {} from {} to {}.",
+                        tracker.label, direction, old, value
+                    ))
+                }
+                _ => None,
+            };
+            Ok((Some(value), message))
+        }
+        TrackerTarget::Package {
+            carrier,
+            tracking_number,
+        } => {
+            let api_key = match crate::config::anthropic_api_key() {
+                Some(key) => key,
+                None => return Ok((None, None)),
+            };
+            if app.state::<UsageStore>().budget_exceeded() {
+                return Ok((None, None));
+            }
+            let query = format!(
+                "Current delivery status of {} package with tracking number {}",
+                carrier, tracking_number
+            );
+            let status = crate::dialogue::run_watch_query(
+                app,
+                &api_key,
+                &query,
+                tracker.last_value.as_deref(),
+            )
+            .await?;
+            // The first lookup is the baseline, not news
+            let message = status
+                .as_ref()
+                .filter(|_| tracker.last_value.is_some())
+                .map(|status| format!("Package update for {}: {}", tracker.label, status));
+            Ok((status, message))
+        }
+    }
+}
+
+async fn check_due(app: tauri::AppHandle) {
+    for tracker in app.state::<TrackerStore>().due() {
+        let value = match check(&app, &tracker).await {
+            Ok((value, message)) => {
+                if let Some(message) = message {
+                    notifications::announce(&app, &message);
+                }
+                value
+            }
+            Err(e) => {
+                eprintln!("Tracker \"{}\" failed: {}", tracker.label, e);
+                None
+            }
+        };
+        let handle = app.clone();
+        let _ = crate::run_blocking(move || {
+            handle.state::<TrackerStore>().record_check(&tracker.id, value)
+        })
+        .await;
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, check_due);
+}
+
+#[tauri::command(async)]
+pub fn list_trackers(store: tauri::State<'_, TrackerStore>) -> Vec<Tracker> {
+    store.all()
+}
+
+/// Track a product page's price. The page is fetched once up front so a URL
+/// without readable price metadata is rejected immediately.
+#[tauri::command]
+pub async fn add_price_tracker(
+    app: tauri::AppHandle,
+    url: String,
+    label: Option<String>,
+) -> PetResult<Tracker> {
+    let url = url.trim().to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(PetError::InvalidInput("Not a web address".to_string()));
+    }
+    let price = fetch_price(&url).await?;
+    let label = label
+        .filter(|l| !l.trim().is_empty())
+        .unwrap_or_else(|| "that item".to_string());
+
+    crate::run_blocking(move || {
+        app.state::<TrackerStore>().add(
+            label,
+            TrackerTarget::Price { url },
+            PRICE_INTERVAL_MINUTES,
+            Some(price),
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn add_package_tracker(
+    app: tauri::AppHandle,
+    tracking_number: String,
+    carrier: Option<String>,
+    label: Option<String>,
+) -> PetResult<Tracker> {
+    let tracking_number = tracking_number.trim().to_string();
+    let carrier = carrier
+        .filter(|c| !c.trim().is_empty())
+        .or_else(|| detect_carrier(&tracking_number).map(str::to_string))
+        .ok_or_else(|| {
+            PetError::InvalidInput("Couldn't tell which carrier that number belongs to".to_string())
+        })?;
+    let label = label
+        .filter(|l| !l.trim().is_empty())
+        .unwrap_or_else(|| format!("your {} package", carrier));
+
+    crate::run_blocking(move || {
+        app.state::<TrackerStore>().add(
+            label,
+            TrackerTarget::Package {
+                carrier,
+                tracking_number,
+            },
+            PACKAGE_INTERVAL_MINUTES,
+            None,
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_tracker(app: tauri::AppHandle, id: String) -> PetResult<()> {
+    let removed = crate::run_blocking(move || app.state::<TrackerStore>().remove(&id)).await?;
+    if removed {
+        Ok(())
+    } else {
+        Err(PetError::InvalidInput("No such tracker".to_string()))
+    }
+}
@@ -0,0 +1,485 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{Emitter, Manager, Wry};
+
+use crate::notes::NotesStore;
+use crate::settings::Soundscape;
+
+pub const TRAY_ID: &str = "main";
+const RECENT_REMINDERS: usize = 5;
+const REMINDER_LABEL_CHARS: usize = 40;
+/// Sprite sheets are a single row of square frames this many pixels tall.
+const FRAME_SIZE: u32 = 32;
+
+pub const IDLE_SHEET: &[u8] = include_bytes!("../../src/assets/sprites/idle.png");
+const WALK_SHEET: &[u8] = include_bytes!("../../src/assets/sprites/walk.png");
+const SLEEP_SHEET: &[u8] = include_bytes!("../../src/assets/sprites/sleep.png");
+
+/// Pet state mirrored into the tray menu. The animation is reported by the
+/// frontend, which owns it; the toggles are owned here.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayStatus {
+    /// What the pet is doing on screen: idle, walking, napping...
+    pub animation: String,
+    pub dialogue_paused: bool,
+    pub focus_session: bool,
+}
+
+impl Default for TrayStatus {
+    fn default() -> Self {
+        Self {
+            animation: "idle".to_string(),
+            dialogue_paused: false,
+            focus_session: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TrayState {
+    status: Mutex<TrayStatus>,
+    /// The menu's line for the animation, which changes too often to
+    /// rebuild the whole menu for
+    animation_item: Mutex<Option<MenuItem<Wry>>>,
+}
+
+impl TrayState {
+    pub fn get(&self) -> TrayStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+fn pet_visible(app: &tauri::AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(true)
+}
+
+fn reminder_label(text: &str) -> String {
+    if text.chars().count() > REMINDER_LABEL_CHARS {
+        let cut: String = text.chars().take(REMINDER_LABEL_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        text.to_string()
+    }
+}
+
+fn animation_label(animation: &str) -> String {
+    format!("Currently: {}", animation)
+}
+
+fn build_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<Wry>> {
+    let status = app.state::<TrayState>().get();
+
+    let animation = MenuItem::with_id(
+        app,
+        "animation",
+        animation_label(&status.animation),
+        false,
+        None::<&str>,
+    )?;
+    *app.state::<TrayState>().animation_item.lock().unwrap() = Some(animation.clone());
+    let hide = CheckMenuItem::with_id(
+        app,
+        "toggle-hide",
+        "Hide Pet",
+        true,
+        !pet_visible(app),
+        None::<&str>,
+    )?;
+    let pause = CheckMenuItem::with_id(
+        app,
+        "pause-dialogue",
+        "Pause Dialogue",
+        true,
+        status.dialogue_paused,
+        None::<&str>,
+    )?;
+    let focus = CheckMenuItem::with_id(
+        app,
+        "focus-session",
+        "Focus Session",
+        true,
+        status.focus_session,
+        None::<&str>,
+    )?;
+
+    let mut notes = app.state::<NotesStore>().all();
+    notes.retain(|note| !note.done);
+    let reminder_items = notes
+        .iter()
+        .rev()
+        .take(RECENT_REMINDERS)
+        .map(|note| {
+            MenuItem::with_id(
+                app,
+                format!("note:{}", note.id),
+                reminder_label(&note.text),
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let reminder_refs: Vec<&dyn IsMenuItem<Wry>> = reminder_items
+        .iter()
+        .map(|i| i as &dyn IsMenuItem<Wry>)
+        .collect();
+    let reminders =
+        Submenu::with_items(app, "Recent Reminders", !notes.is_empty(), &reminder_refs)?;
+
+    let menu_bar_only = CheckMenuItem::with_id(
+        app,
+        "menu-bar-only",
+        "Menu Bar Only",
+        true,
+        crate::menubar::is_enabled(app),
+        None::<&str>,
+    )?;
+
+    let clipboard = CheckMenuItem::with_id(
+        app,
+        "clipboard-watch",
+        "Clipboard Help",
+        true,
+        crate::clipboard::is_enabled(app),
+        None::<&str>,
+    )?;
+
+    let ghost = CheckMenuItem::with_id(
+        app,
+        "ghost-mode",
+        "Ghost Mode",
+        true,
+        crate::ghost::is_active(app),
+        None::<&str>,
+    )?;
+
+    let launch_at_login = CheckMenuItem::with_id(
+        app,
+        "launch-at-login",
+        "Launch at Login",
+        true,
+        crate::autostart::is_enabled(app),
+        None::<&str>,
+    )?;
+
+    let standup = MenuItem::with_id(app, "standup", "Morning Standup", true, None::<&str>)?;
+
+    let sound = crate::sound::status(app);
+    let mut scene_items = vec![CheckMenuItem::with_id(
+        app,
+        "soundscape:off",
+        "Off",
+        true,
+        !sound.music_playing,
+        None::<&str>,
+    )?];
+    for (i, (scene, label)) in crate::sound::SOUNDSCAPES.iter().enumerate() {
+        scene_items.push(CheckMenuItem::with_id(
+            app,
+            format!("soundscape:{}", i),
+            *label,
+            // Lo-fi plays the music folder, so it needs one
+            *scene != Soundscape::Lofi || sound.music_folder.is_some(),
+            sound.music_playing && sound.soundscape == *scene,
+            None::<&str>,
+        )?);
+    }
+    let sleep_items = crate::sound::SLEEP_TIMERS
+        .iter()
+        .map(|minutes| {
+            let label = match minutes {
+                60 => "Stop in 1 Hour".to_string(),
+                _ => format!("Stop in {} Minutes", minutes),
+            };
+            CheckMenuItem::with_id(
+                app,
+                format!("sleep:{}", minutes),
+                label,
+                sound.music_playing,
+                sound.sleep_minutes == Some(*minutes),
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let sound_separator = PredefinedMenuItem::separator(app)?;
+    let mut sound_refs: Vec<&dyn IsMenuItem<Wry>> = scene_items
+        .iter()
+        .map(|i| i as &dyn IsMenuItem<Wry>)
+        .collect();
+    sound_refs.push(&sound_separator);
+    sound_refs.extend(sleep_items.iter().map(|i| i as &dyn IsMenuItem<Wry>));
+    let soundscape = Submenu::with_items(app, "Soundscape", sound.available, &sound_refs)?;
+
+    let separator = PredefinedMenuItem::separator(app)?;
+    let separator2 = PredefinedMenuItem::separator(app)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &animation,
+            &separator,
+            &hide,
+            &ghost,
+            &pause,
+            &focus,
+            &standup,
+            &soundscape,
+            &reminders,
+            &menu_bar_only,
+            &clipboard,
+            &launch_at_login,
+            &separator2,
+            &quit,
+        ],
+    )
+}
+
+/// Rebuild the tray menu from current state. Call after anything it shows changes.
+pub fn refresh(app: &tauri::AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+/// Show or hide the pet window and keep the tray check in sync.
+pub fn toggle_pet_visible(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let visible = window.is_visible().unwrap_or(true);
+        set_pet_visible(app, !visible);
+    }
+}
+
+pub fn set_pet_visible(app: &tauri::AppHandle, visible: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = if visible {
+            window.show()
+        } else {
+            window.hide()
+        };
+    }
+    refresh(app);
+}
+
+/// Cut a horizontal sprite sheet into its frames.
+fn slice_sheet(bytes: &[u8]) -> tauri::Result<Vec<Image<'static>>> {
+    let sheet = Image::from_bytes(bytes)?;
+    let row_bytes = sheet.width() as usize * 4;
+    let frame_bytes = FRAME_SIZE as usize * 4;
+    let frames = (0..sheet.width() / FRAME_SIZE)
+        .map(|i| {
+            let offset = i as usize * frame_bytes;
+            let rgba = sheet
+                .rgba()
+                .chunks(row_bytes)
+                .take(FRAME_SIZE as usize)
+                .flat_map(|row| &row[offset..offset + frame_bytes])
+                .copied()
+                .collect();
+            Image::new_owned(rgba, FRAME_SIZE, FRAME_SIZE)
+        })
+        .collect();
+    Ok(frames)
+}
+
+struct TrayFrames {
+    idle: Vec<Image<'static>>,
+    walk: Vec<Image<'static>>,
+    sleep: Vec<Image<'static>>,
+}
+
+impl TrayFrames {
+    fn load() -> tauri::Result<Self> {
+        Ok(Self {
+            idle: slice_sheet(IDLE_SHEET)?,
+            walk: slice_sheet(WALK_SHEET)?,
+            sleep: slice_sheet(SLEEP_SHEET)?,
+        })
+    }
+
+    /// Frames and per-frame delay matching the pet's animation.
+    fn for_animation(&self, animation: &str) -> (&[Image<'static>], Duration) {
+        match animation {
+            "walking" => (&self.walk, Duration::from_millis(120)),
+            "napping" | "home" => (&self.sleep, Duration::from_millis(600)),
+            _ => (&self.idle, Duration::from_millis(250)),
+        }
+    }
+}
+
+/// Cycle the tray icon through the current animation's sprite frames, so
+/// the cat is still visible in the menu bar while the overlay is hidden.
+pub fn spawn_animation(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let frames = match TrayFrames::load() {
+            Ok(frames) => frames,
+            Err(e) => {
+                eprintln!("Tray animation disabled: {}", e);
+                return;
+            }
+        };
+        let mut frame = 0;
+        loop {
+            let animation = app.state::<TrayState>().get().animation;
+            let (set, delay) = frames.for_animation(&animation);
+            if let (Some(tray), Some(image)) =
+                (app.tray_by_id(TRAY_ID), set.get(frame % set.len().max(1)))
+            {
+                let _ = tray.set_icon(Some(image.clone()));
+            }
+            frame = frame.wrapping_add(1);
+            // Half the frame rate is still a cat, and easier on the battery
+            let delay = if crate::power::is_saving(&app) {
+                delay * 2
+            } else {
+                delay
+            };
+            tokio::time::sleep(delay).await;
+        }
+    });
+}
+
+/// Start or stop a focus session; the frontend reacts to `focus-session`.
+pub fn set_focus_session(app: &tauri::AppHandle, active: bool) {
+    app.state::<TrayState>()
+        .status
+        .lock()
+        .unwrap()
+        .focus_session = active;
+    let _ = app.emit("focus-session", active);
+    refresh(app);
+}
+
+fn set_dialogue_paused(app: &tauri::AppHandle, paused: bool) {
+    app.state::<TrayState>()
+        .status
+        .lock()
+        .unwrap()
+        .dialogue_paused = paused;
+    let _ = app.emit("dialogue-paused", paused);
+    refresh(app);
+}
+
+pub fn init(app: &tauri::App) -> tauri::Result<()> {
+    app.manage(TrayState::default());
+    let menu = build_menu(app.handle())?;
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .icon(app.default_window_icon().unwrap().clone())
+        .on_menu_event(|app, event| {
+            let status = app.state::<TrayState>().get();
+            match event.id.as_ref() {
+                "quit" => app.exit(0),
+                "toggle-hide" => toggle_pet_visible(app),
+                "pause-dialogue" => set_dialogue_paused(app, !status.dialogue_paused),
+                "focus-session" => set_focus_session(app, !status.focus_session),
+                "standup" => crate::activity::standup_from_tray(app),
+                "menu-bar-only" => {
+                    let app = app.clone();
+                    let enabled = !crate::menubar::is_enabled(&app);
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = crate::menubar::set_menu_bar_only(app, enabled).await {
+                            eprintln!("Failed to toggle menu bar mode: {}", e);
+                        }
+                    });
+                }
+                "clipboard-watch" => {
+                    let enabled = !crate::clipboard::is_enabled(app);
+                    if let Err(e) = crate::clipboard::set_enabled(app, enabled) {
+                        eprintln!("Failed to toggle clipboard help: {}", e);
+                        refresh(app);
+                    }
+                }
+                "ghost-mode" => {
+                    let active = !crate::ghost::is_active(app);
+                    if let Err(e) = crate::ghost::set_ghost(app, active) {
+                        eprintln!("Failed to toggle ghost mode: {}", e);
+                        refresh(app);
+                    }
+                }
+                "launch-at-login" => {
+                    let enabled = !crate::autostart::is_enabled(app);
+                    if let Err(e) = crate::autostart::set_enabled(app, enabled) {
+                        eprintln!("{}", e);
+                        refresh(app);
+                    }
+                }
+                "soundscape:off" => {
+                    if let Err(e) = crate::sound::set_playing(app, false) {
+                        eprintln!("Failed to stop the music: {}", e);
+                    }
+                }
+                id if id.starts_with("soundscape:") => {
+                    let scene = id["soundscape:".len()..]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| crate::sound::SOUNDSCAPES.get(i));
+                    if let Some((scene, _)) = scene {
+                        if let Err(e) = crate::sound::set_scene(app, *scene) {
+                            eprintln!("Failed to change the soundscape: {}", e);
+                            refresh(app);
+                        }
+                    }
+                }
+                id if id.starts_with("sleep:") => {
+                    let minutes = id["sleep:".len()..].parse::<u32>().ok();
+                    // Picking the timer that's already set clears it
+                    let current = crate::sound::status(app).sleep_minutes;
+                    let minutes = minutes.filter(|minutes| current != Some(*minutes));
+                    if let Err(e) = crate::sound::set_sleep(app, minutes) {
+                        eprintln!("Failed to set the sleep timer: {}", e);
+                        refresh(app);
+                    }
+                }
+                id if id.starts_with("note:") => {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.show();
+                        let _ = window.emit("open-notes", ());
+                    }
+                    refresh(app);
+                }
+                _ => {}
+            }
+        })
+        .on_tray_icon_event(|icon, event| {
+            if let TrayIconEvent::Click {
+                rect,
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                if crate::menubar::is_enabled(icon.app_handle()) {
+                    crate::menubar::toggle_popover(icon.app_handle(), rect);
+                }
+            }
+        })
+        .build(app)?;
+    Ok(())
+}
+
+/// Frontend reports the pet's animation state, which the tray shows and
+/// animates its icon with.
+#[tauri::command(async)]
+pub fn set_pet_animation(app: tauri::AppHandle, animation: String) {
+    let state = app.state::<TrayState>();
+    let label = animation_label(&animation);
+    state.status.lock().unwrap().animation = animation;
+    let item = state.animation_item.lock().unwrap().clone();
+    if let Some(item) = item {
+        let _ = item.set_text(label);
+    }
+}
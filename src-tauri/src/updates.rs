@@ -0,0 +1,185 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::error::{PetError, PetResult};
+use crate::settings::{SettingsStore, UpdateChannel};
+use crate::{notifications, scheduler};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/jacksonspindle/desktop-pet/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/jacksonspindle/desktop-pet/releases/download/beta/latest.json";
+/// Baked in when building with `PET_UPDATER_PUBKEY` set, by the release
+/// build that also signs the updates. A build without it never installs
+/// anything, rather than trusting whatever tauri.conf.json holds.
+const STABLE_PUBKEY: Option<&str> = option_env!("PET_UPDATER_PUBKEY");
+/// Beta builds are signed with their own key, so a leaked or careless beta
+/// key can never push an update to someone on stable. Baked in when
+/// building with `PET_BETA_UPDATER_PUBKEY` set.
+const BETA_PUBKEY: Option<&str> = option_env!("PET_BETA_UPDATER_PUBKEY");
+const CHECK_INTERVAL_MINUTES: u32 = 6 * 60;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+impl From<&Update> for UpdateInfo {
+    fn from(update: &Update) -> Self {
+        Self {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            notes: update.body.clone(),
+            date: update.date.map(|d| d.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UpdateProgress {
+    downloaded: usize,
+    total: Option<u64>,
+}
+
+/// Background check bookkeeping; nothing here needs to survive a restart.
+#[derive(Default)]
+pub struct UpdateState {
+    last_checked_at: Mutex<Option<i64>>,
+    /// Version the cat already announced, so it only nags once per release
+    announced: Mutex<Option<String>>,
+}
+
+fn endpoint(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+/// The key updates on `channel` must be signed with.
+fn pubkey(channel: UpdateChannel) -> PetResult<&'static str> {
+    let (key, name) = match channel {
+        UpdateChannel::Stable => (STABLE_PUBKEY, "updates"),
+        UpdateChannel::Beta => (BETA_PUBKEY, "beta updates"),
+    };
+    key.filter(|key| !key.trim().is_empty())
+        .ok_or_else(|| PetError::InvalidInput(format!("This build can't verify {}", name)))
+}
+
+/// Ask the channel's `latest.json` whether there's something newer. Nothing
+/// is sent but the request for that file: no id, no version, no channel.
+async fn fetch_update(app: &tauri::AppHandle) -> PetResult<Option<Update>> {
+    let channel = app.state::<SettingsStore>().get().updates.channel;
+    let url = endpoint(channel)
+        .parse()
+        .map_err(|e| PetError::Internal(format!("Bad update endpoint: {}", e)))?;
+    let updater = app
+        .updater_builder()
+        .pubkey(pubkey(channel)?)
+        .endpoints(vec![url])
+        .and_then(|builder| builder.build())
+        .map_err(|e| PetError::Internal(format!("Updater unavailable: {}", e)))?;
+    updater
+        .check()
+        .await
+        .map_err(|e| PetError::Network(format!("Update check failed: {}", e)))
+}
+
+async fn background_check(app: tauri::AppHandle) {
+    if !app.state::<SettingsStore>().get().updates.auto_check {
+        return;
+    }
+    let state = app.state::<UpdateState>();
+    let now = chrono::Utc::now().timestamp_millis();
+    if !scheduler::is_due(*state.last_checked_at.lock().unwrap(), CHECK_INTERVAL_MINUTES, now) {
+        return;
+    }
+    *state.last_checked_at.lock().unwrap() = Some(now);
+
+    let update = match fetch_update(&app).await {
+        Ok(Some(update)) => update,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let mut announced = state.announced.lock().unwrap();
+    if announced.as_deref() != Some(update.version.as_str()) {
+        notifications::announce(
+            &app,
+            &format!("Psst. Version {} is out. Update me from Settings?", update.version),
+        );
+        *announced = Some(update.version.clone());
+    }
+    let _ = app.emit("update-available", UpdateInfo::from(&update));
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, background_check);
+}
+
+#[tauri::command]
+pub async fn check_for_updates(app: tauri::AppHandle) -> PetResult<Option<UpdateInfo>> {
+    *app.state::<UpdateState>().last_checked_at.lock().unwrap() =
+        Some(chrono::Utc::now().timestamp_millis());
+    Ok(fetch_update(&app).await?.as_ref().map(UpdateInfo::from))
+}
+
+#[tauri::command(async)]
+pub fn get_update_channel(store: tauri::State<'_, SettingsStore>) -> UpdateChannel {
+    store.get().updates.channel
+}
+
+/// Follow `stable` or `beta` from the next check on. The choice stays on
+/// this Mac; the next background check looks at the new channel right away.
+#[tauri::command]
+pub async fn set_update_channel(
+    app: tauri::AppHandle,
+    channel: UpdateChannel,
+) -> PetResult<UpdateChannel> {
+    pubkey(channel)?;
+    crate::run_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.updates.channel = channel;
+        store.replace(settings)?;
+        let state = app.state::<UpdateState>();
+        *state.last_checked_at.lock().unwrap() = None;
+        *state.announced.lock().unwrap() = None;
+        Ok(channel)
+    })
+    .await?
+}
+
+/// Download and install the latest update on the selected channel, then
+/// restart. Progress is reported through `update-progress` events.
+#[tauri::command]
+pub async fn install_update(app: tauri::AppHandle) -> PetResult<()> {
+    let update = fetch_update(&app)
+        .await?
+        .ok_or_else(|| PetError::InvalidInput("Already up to date".to_string()))?;
+
+    let mut downloaded = 0;
+    let handle = app.clone();
+    update
+        .download_and_install(
+            move |chunk, total| {
+                downloaded += chunk;
+                let _ = handle.emit("update-progress", UpdateProgress { downloaded, total });
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| PetError::Platform(format!("Update install failed: {}", e)))?;
+
+    app.state::<crate::memory::MemoryStore>().flush(&app);
+    app.restart();
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::persist;
+
+pub const USAGE_FILE: &str = "usage.json";
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DailyUsage {
+    pub requests: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub web_searches: u64,
+    pub cost_usd: f64,
+}
+
+impl DailyUsage {
+    fn add(&mut self, other: &DailyUsage) {
+        self.requests += other.requests;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.web_searches += other.web_searches;
+        self.cost_usd += other.cost_usd;
+    }
+
+    /// Share of prompt tokens served from the prompt cache.
+    fn cache_hit_rate(&self) -> f64 {
+        let prompt = self.input_tokens + self.cache_write_tokens + self.cache_read_tokens;
+        if prompt == 0 {
+            0.0
+        } else {
+            self.cache_read_tokens as f64 / prompt as f64
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct UsageData {
+    /// Keyed by local date (YYYY-MM-DD) so BTreeMap order is chronological
+    days: BTreeMap<String, DailyUsage>,
+    monthly_budget_usd: Option<f64>,
+}
+
+/// Token usage block returned with every Messages API response.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(default)]
+pub struct ApiUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub server_tool_use: ServerToolUse,
+}
+
+/// Server-side tools the call used, billed per use on top of tokens.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(default)]
+pub struct ServerToolUse {
+    pub web_search_requests: u64,
+}
+
+impl ApiUsage {
+    /// Accumulate usage across the calls of a multi-turn tool loop.
+    pub fn add(&mut self, other: &ApiUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+        self.server_tool_use.web_search_requests += other.server_tool_use.web_search_requests;
+    }
+}
+
+/// Cache writes cost 25% more than plain input; cache reads cost 10% of it.
+const CACHE_WRITE_MULTIPLIER: f64 = 1.25;
+const CACHE_READ_MULTIPLIER: f64 = 0.1;
+/// Web search is billed per search: $10 per thousand.
+const WEB_SEARCH_USD: f64 = 0.01;
+
+/// Estimated USD per million (input, output) tokens.
+fn price_per_mtok(model: &str) -> (f64, f64) {
+    if model.contains("opus") {
+        (15.0, 75.0)
+    } else if model.contains("sonnet") {
+        (3.0, 15.0)
+    } else {
+        (1.0, 5.0)
+    }
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn this_month() -> String {
+    chrono::Local::now().format("%Y-%m").to_string()
+}
+
+fn month_totals(data: &UsageData) -> DailyUsage {
+    let month = this_month();
+    data.days
+        .iter()
+        .filter(|(date, _)| date.starts_with(&month))
+        .fold(DailyUsage::default(), |mut acc, (_, day)| {
+            acc.add(day);
+            acc
+        })
+}
+
+pub struct UsageStore {
+    data: Mutex<UsageData>,
+    path: Option<PathBuf>,
+}
+
+impl UsageStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, USAGE_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        *data = UsageData::default();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, data: &UsageData) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, data);
+        }
+    }
+
+    pub fn record(&self, model: &str, usage: ApiUsage) {
+        let (input_price, output_price) = price_per_mtok(model);
+        let cost = (usage.input_tokens as f64 * input_price
+            + usage.cache_creation_input_tokens as f64 * input_price * CACHE_WRITE_MULTIPLIER
+            + usage.cache_read_input_tokens as f64 * input_price * CACHE_READ_MULTIPLIER
+            + usage.output_tokens as f64 * output_price)
+            / 1_000_000.0
+            + usage.server_tool_use.web_search_requests as f64 * WEB_SEARCH_USD;
+
+        let mut data = self.data.lock().unwrap();
+        data.days.entry(today()).or_default().add(&DailyUsage {
+            requests: 1,
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_write_tokens: usage.cache_creation_input_tokens,
+            cache_read_tokens: usage.cache_read_input_tokens,
+            web_searches: usage.server_tool_use.web_search_requests,
+            cost_usd: cost,
+        });
+        self.save(&data);
+    }
+
+    /// Drop days before `cutoff` (YYYY-MM-DD).
+    pub fn prune_before(&self, cutoff: &str) {
+        let mut data = self.data.lock().unwrap();
+        if data
+            .days
+            .first_key_value()
+            .is_some_and(|(date, _)| date.as_str() < cutoff)
+        {
+            data.days = data.days.split_off(cutoff);
+            self.save(&data);
+        }
+    }
+
+    pub fn export(&self) -> serde_json::Value {
+        serde_json::to_value(&*self.data.lock().unwrap()).unwrap_or_default()
+    }
+
+    pub fn import(&self, value: serde_json::Value) -> PetResult<()> {
+        let imported: UsageData = serde_json::from_value(value)?;
+        let mut data = self.data.lock().unwrap();
+        *data = imported;
+        self.save(&data);
+        Ok(())
+    }
+
+    /// True once this month's estimated spend has reached the configured cap.
+    pub fn budget_exceeded(&self) -> bool {
+        let data = self.data.lock().unwrap();
+        match data.monthly_budget_usd {
+            Some(budget) => month_totals(&data).cost_usd >= budget,
+            None => false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    pub today: DailyUsage,
+    pub month: DailyUsage,
+    pub monthly_budget_usd: Option<f64>,
+    pub budget_exceeded: bool,
+    pub month_cache_hit_rate: f64,
+    pub days: BTreeMap<String, DailyUsage>,
+}
+
+#[tauri::command(async)]
+pub fn get_usage_stats(store: tauri::State<'_, UsageStore>) -> UsageStats {
+    let data = store.data.lock().unwrap();
+    let month = month_totals(&data);
+    let budget_exceeded = data
+        .monthly_budget_usd
+        .is_some_and(|budget| month.cost_usd >= budget);
+    let month_cache_hit_rate = month.cache_hit_rate();
+
+    UsageStats {
+        today: data.days.get(&today()).cloned().unwrap_or_default(),
+        month,
+        monthly_budget_usd: data.monthly_budget_usd,
+        budget_exceeded,
+        month_cache_hit_rate,
+        days: data.days.clone(),
+    }
+}
+
+/// Set the monthly spend cap in USD; `None` removes the cap.
+#[tauri::command]
+pub async fn set_monthly_budget(app: tauri::AppHandle, budget_usd: Option<f64>) -> PetResult<()> {
+    if let Some(budget) = budget_usd {
+        if !budget.is_finite() || budget < 0.0 {
+            return Err(PetError::InvalidInput(
+                "Budget must be a non-negative amount".to_string(),
+            ));
+        }
+    }
+    crate::run_blocking(move || {
+        let store = app.state::<UsageStore>();
+        let mut data = store.data.lock().unwrap();
+        data.monthly_budget_usd = budget_usd;
+        store.save(&data);
+    })
+    .await
+}
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::error::{PetError, PetResult};
+use crate::usage::UsageStore;
+use crate::{notifications, persist, scheduler};
+
+pub const WATCHERS_FILE: &str = "watchers.json";
+pub const DEFAULT_INTERVAL_MINUTES: u32 = 60;
+const MIN_INTERVAL_MINUTES: u32 = 15;
+
+/// A search the cat re-runs on a schedule, speaking up only when the answer changes.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Watcher {
+    pub id: String,
+    pub query: String,
+    pub interval_minutes: u32,
+    /// Unix millis
+    pub created_at: i64,
+    pub last_checked_at: Option<i64>,
+    /// Most recent answer, used as the baseline for change detection
+    pub last_result: Option<String>,
+}
+
+pub struct WatcherStore {
+    watchers: Mutex<Vec<Watcher>>,
+    path: Option<PathBuf>,
+}
+
+impl WatcherStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, WATCHERS_FILE).ok();
+        let watchers = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            watchers: Mutex::new(watchers),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut watchers = self.watchers.lock().unwrap();
+        *watchers = Vec::new();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, watchers: &[Watcher]) {
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &watchers);
+        }
+    }
+
+    pub fn all(&self) -> Vec<Watcher> {
+        self.watchers.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, new_watchers: Vec<Watcher>) {
+        let mut watchers = self.watchers.lock().unwrap();
+        *watchers = new_watchers;
+        self.save(&watchers);
+    }
+
+    pub fn add(&self, query: &str, interval_minutes: u32) -> Watcher {
+        let watcher = Watcher {
+            id: uuid::Uuid::new_v4().to_string(),
+            query: query.to_string(),
+            interval_minutes: interval_minutes.max(MIN_INTERVAL_MINUTES),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            last_checked_at: None,
+            last_result: None,
+        };
+        let mut watchers = self.watchers.lock().unwrap();
+        watchers.push(watcher.clone());
+        self.save(&watchers);
+        watcher
+    }
+
+    fn remove(&self, id: &str) -> bool {
+        let mut watchers = self.watchers.lock().unwrap();
+        let before = watchers.len();
+        watchers.retain(|w| w.id != id);
+        let removed = watchers.len() != before;
+        if removed {
+            self.save(&watchers);
+        }
+        removed
+    }
+
+    fn due(&self) -> Vec<Watcher> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.all()
+            .into_iter()
+            .filter(|w| scheduler::is_due(w.last_checked_at, w.interval_minutes, now))
+            .collect()
+    }
+
+    /// Record a check. `result` is only replaced when the answer changed, so
+    /// the baseline stays the last thing the user was actually told.
+    fn record_check(&self, id: &str, result: Option<String>) {
+        let mut watchers = self.watchers.lock().unwrap();
+        if let Some(watcher) = watchers.iter_mut().find(|w| w.id == id) {
+            watcher.last_checked_at = Some(chrono::Utc::now().timestamp_millis());
+            if result.is_some() {
+                watcher.last_result = result;
+            }
+        }
+        self.save(&watchers);
+    }
+}
+
+async fn check_due(app: tauri::AppHandle) {
+    let api_key = match crate::config::anthropic_api_key() {
+        Some(key) => key,
+        None => return,
+    };
+    if app.state::<UsageStore>().budget_exceeded() {
+        return;
+    }
+
+    for watcher in app.state::<WatcherStore>().due() {
+        let changed = match crate::dialogue::run_watch_query(
+            &app,
+            &api_key,
+            &watcher.query,
+            watcher.last_result.as_deref(),
+        )
+        .await
+        {
+            Ok(changed) => changed,
+            Err(e) => {
+                eprintln!("Watcher \"{}\" failed: {}", watcher.query, e);
+                continue;
+            }
+        };
+
+        // The first run only establishes a baseline; there is nothing to diff yet
+        if let (Some(text), Some(_)) = (&changed, &watcher.last_result) {
+            notifications::announce(&app, text);
+        }
+        let handle = app.clone();
+        let _ = crate::run_blocking(move || {
+            handle.state::<WatcherStore>().record_check(&watcher.id, changed)
+        })
+        .await;
+    }
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, check_due);
+}
+
+#[tauri::command(async)]
+pub fn list_watchers(store: tauri::State<'_, WatcherStore>) -> Vec<Watcher> {
+    store.all()
+}
+
+#[tauri::command]
+pub async fn add_watcher(
+    app: tauri::AppHandle,
+    query: String,
+    interval_minutes: Option<u32>,
+) -> PetResult<Watcher> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err(PetError::InvalidInput("Nothing to watch".to_string()));
+    }
+    crate::run_blocking(move || {
+        app.state::<WatcherStore>()
+            .add(&query, interval_minutes.unwrap_or(DEFAULT_INTERVAL_MINUTES))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_watcher(app: tauri::AppHandle, id: String) -> PetResult<()> {
+    let removed = crate::run_blocking(move || app.state::<WatcherStore>().remove(&id)).await?;
+    if removed {
+        Ok(())
+    } else {
+        Err(PetError::InvalidInput("No such watcher".to_string()))
+    }
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::scheduler;
+use crate::settings::{SettingsStore, WeatherLocation};
+
+/// Conditions change slowly and Open-Meteo asks for restraint.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Condition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+impl Condition {
+    /// From a WMO weather interpretation code, as Open-Meteo reports them.
+    fn from_code(code: u32) -> Self {
+        match code {
+            0 => Self::Clear,
+            1 | 2 => Self::PartlyCloudy,
+            45 | 48 => Self::Fog,
+            51..=57 => Self::Drizzle,
+            61..=67 | 80..=82 => Self::Rain,
+            71..=77 | 85 | 86 => Self::Snow,
+            95..=99 => Self::Thunderstorm,
+            _ => Self::Cloudy,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Clear => "clear skies",
+            Self::PartlyCloudy => "partly cloudy",
+            Self::Cloudy => "overcast",
+            Self::Fog => "foggy",
+            Self::Drizzle => "drizzling",
+            Self::Rain => "raining",
+            Self::Snow => "snowing",
+            Self::Thunderstorm => "a thunderstorm",
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Weather {
+    pub location: String,
+    pub condition: Condition,
+    pub temperature: f64,
+    pub fahrenheit: bool,
+    /// Whether the sun is up there
+    pub is_day: bool,
+    /// Unix millis
+    pub fetched_at: i64,
+}
+
+impl Weather {
+    /// One line for the dialogue context.
+    fn describe(&self) -> String {
+        format!(
+            "Weather in {}: {}, {:.0}°{}{}.",
+            self.location,
+            self.condition.describe(),
+            self.temperature,
+            if self.fahrenheit { "F" } else { "C" },
+            if self.is_day { "" } else { ", after dark" }
+        )
+    }
+}
+
+/// The last conditions fetched, kept in memory only.
+#[derive(Default)]
+pub struct WeatherState(Mutex<Option<Weather>>);
+
+#[derive(Deserialize)]
+struct ForecastResponse {
+    current: CurrentConditions,
+}
+
+#[derive(Deserialize)]
+struct CurrentConditions {
+    temperature_2m: f64,
+    weather_code: u32,
+    is_day: u8,
+}
+
+#[derive(Deserialize)]
+struct GeocodingResponse {
+    results: Option<Vec<Place>>,
+}
+
+#[derive(Deserialize)]
+struct Place {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    country: Option<String>,
+}
+
+fn network(e: reqwest::Error) -> PetError {
+    PetError::Network(e.to_string())
+}
+
+/// Look a place name up with Open-Meteo's geocoder and take the best match.
+async fn geocode(query: &str) -> PetResult<WeatherLocation> {
+    let response: GeocodingResponse = reqwest::Client::new()
+        .get("https://geocoding-api.open-meteo.com/v1/search")
+        .query(&[("name", query), ("count", "1")])
+        .send()
+        .await
+        .map_err(network)?
+        .json()
+        .await
+        .map_err(network)?;
+    let place = response
+        .results
+        .and_then(|results| results.into_iter().next())
+        .ok_or_else(|| PetError::InvalidInput(format!("Couldn't find a place called {}", query)))?;
+    Ok(WeatherLocation {
+        name: match place.country {
+            Some(country) => format!("{}, {}", place.name, country),
+            None => place.name,
+        },
+        latitude: place.latitude,
+        longitude: place.longitude,
+    })
+}
+
+async fn fetch(location: &WeatherLocation, fahrenheit: bool) -> PetResult<Weather> {
+    let response: ForecastResponse = reqwest::Client::new()
+        .get("https://api.open-meteo.com/v1/forecast")
+        .query(&[
+            ("latitude", location.latitude.to_string()),
+            ("longitude", location.longitude.to_string()),
+            ("current", "temperature_2m,weather_code,is_day".to_string()),
+            (
+                "temperature_unit",
+                if fahrenheit { "fahrenheit" } else { "celsius" }.to_string(),
+            ),
+        ])
+        .send()
+        .await
+        .map_err(network)?
+        .json()
+        .await
+        .map_err(network)?;
+    Ok(Weather {
+        location: location.name.clone(),
+        condition: Condition::from_code(response.current.weather_code),
+        temperature: response.current.temperature_2m,
+        fahrenheit,
+        is_day: response.current.is_day == 1,
+        fetched_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// Fetch the configured location's conditions, remember them and tell the
+/// webview. None, and the cache cleared, when no location is set.
+async fn refresh(app: &tauri::AppHandle) -> PetResult<Option<Weather>> {
+    let settings = app.state::<SettingsStore>().get().weather;
+    let weather = match &settings.location {
+        Some(location) => Some(fetch(location, settings.fahrenheit).await?),
+        None => None,
+    };
+    *app.state::<WeatherState>().0.lock().unwrap() = weather.clone();
+    let _ = app.emit("weather-changed", &weather);
+    Ok(weather)
+}
+
+/// One line for the dialogue context from the last fetch, if there's been
+/// one. Never waits on the network.
+pub fn context(app: &tauri::AppHandle) -> Option<String> {
+    app.state::<WeatherState>()
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(Weather::describe)
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every(app, REFRESH_INTERVAL, |app| async move {
+        if let Err(e) = refresh(&app).await {
+            eprintln!("Weather refresh failed: {}", e);
+        }
+    });
+}
+
+/// The current conditions, fetched again only once the last ones are stale.
+#[tauri::command]
+pub async fn get_weather(app: tauri::AppHandle) -> PetResult<Option<Weather>> {
+    let cached = app.state::<WeatherState>().0.lock().unwrap().clone();
+    let now = chrono::Utc::now().timestamp_millis();
+    match cached {
+        Some(weather) if now - weather.fetched_at < REFRESH_INTERVAL.as_millis() as i64 => {
+            Ok(Some(weather))
+        }
+        _ => refresh(&app).await,
+    }
+}
+
+/// Set where the weather comes from, by place name; None stops fetching it.
+#[tauri::command]
+pub async fn set_weather(
+    app: tauri::AppHandle,
+    location: Option<String>,
+    fahrenheit: bool,
+) -> PetResult<Option<Weather>> {
+    let location = location
+        .map(|query| query.trim().to_string())
+        .filter(|query| !query.is_empty());
+    let current = app.state::<SettingsStore>().get().weather;
+    let location = match location {
+        // Don't look the place up again when only the units changed
+        Some(query) if current.location.as_ref().is_some_and(|l| l.name == query) => {
+            current.location
+        }
+        Some(query) => Some(geocode(&query).await?),
+        None => None,
+    };
+    let handle = app.clone();
+    crate::run_blocking(move || {
+        let store = handle.state::<SettingsStore>();
+        let mut settings = store.get();
+        settings.weather.location = location;
+        settings.weather.fahrenheit = fahrenheit;
+        store.replace(settings)
+    })
+    .await??;
+    refresh(&app).await
+}
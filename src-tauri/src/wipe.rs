@@ -0,0 +1,206 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::{Emitter, Manager};
+
+use crate::error::PetResult;
+use crate::{
+    activity, card, countdowns, github, journal, memory, milestones, moods, notes, persist, pets,
+    reactions, sports, stickies, tickers, tools, trackers, usage, watchers, wrapped,
+};
+
+/// localStorage keys the webview clears when told `data-wiped`. The pet's
+/// name, look and position stay, like the backend's settings.
+const WEBVIEW_KEYS: &[&str] = &[
+    "pet-events",
+    "pet-journal",
+    "pet-achievements",
+    "pet-app-usage",
+    "pet-app-usage-year",
+    "pet-notes",
+];
+
+/// Left alone, and why, so the report says what's still there.
+const KEPT: &[&str] = &[
+    "settings.json: preferences, not history",
+    "config.toml: your own keys; delete it by hand if the machine is leaving",
+    "prompts/ and personalities/: your own edits",
+    "Leaderboard, presence and automation ids: turn those off to remove them",
+    "sync.json and the sync key: turn sync off to remove them; another machine can sync it all back",
+    "The GitHub token: disconnect GitHub to remove it",
+];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipedItem {
+    pub name: String,
+    /// On disk before the wipe, backups included
+    pub bytes: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipeFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipeReport {
+    pub removed: Vec<WipedItem>,
+    pub failed: Vec<WipeFailure>,
+    /// Cleared by the webview on `data-wiped`
+    pub webview_keys: Vec<&'static str>,
+    pub kept: Vec<&'static str>,
+}
+
+fn size_on_disk(path: &Path) -> u64 {
+    let file = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let backups: u64 = (1..=persist::BACKUP_GENERATIONS)
+        .filter_map(|g| fs::metadata(persist::backup_path(path, g)).ok())
+        .map(|m| m.len())
+        .sum();
+    file + backups
+}
+
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+struct Sweep<'a> {
+    app: &'a tauri::AppHandle,
+    report: WipeReport,
+}
+
+impl Sweep<'_> {
+    fn file(&mut self, name: &str, wipe: impl FnOnce() -> PetResult<()>) {
+        let bytes = persist::app_data_file(self.app, name)
+            .map(|path| size_on_disk(&path))
+            .unwrap_or(0);
+        match wipe() {
+            Ok(()) => self.report.removed.push(WipedItem {
+                name: name.to_string(),
+                bytes,
+            }),
+            Err(e) => self.report.failed.push(WipeFailure {
+                name: name.to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    fn dir(&mut self, name: &str) {
+        let Ok(path) = persist::app_data_file(self.app, name) else {
+            return;
+        };
+        if !path.exists() {
+            return;
+        }
+        let bytes = dir_size(&path);
+        match fs::remove_dir_all(&path) {
+            Ok(()) => self.report.removed.push(WipedItem {
+                name: format!("{}/", name),
+                bytes,
+            }),
+            Err(e) => self.report.failed.push(WipeFailure {
+                name: format!("{}/", name),
+                error: e.to_string(),
+            }),
+        }
+    }
+}
+
+fn wipe(app: &tauri::AppHandle) -> WipeReport {
+    let mut sweep = Sweep {
+        app,
+        report: WipeReport {
+            removed: Vec::new(),
+            failed: Vec::new(),
+            webview_keys: WEBVIEW_KEYS.to_vec(),
+            kept: KEPT.to_vec(),
+        },
+    };
+    // Every store is emptied under its own lock before its file goes, so
+    // nothing in memory can write the old data back afterwards
+    sweep.file(memory::MEMORY_FILE, || {
+        app.state::<memory::MemoryStore>().wipe(app)
+    });
+    sweep.file(usage::USAGE_FILE, || {
+        app.state::<usage::UsageStore>().wipe()
+    });
+    sweep.file(activity::ACTIVITY_FILE, || {
+        app.state::<activity::ActivityStore>().wipe()
+    });
+    sweep.file(notes::NOTES_FILE, || {
+        app.state::<notes::NotesStore>().wipe()
+    });
+    sweep.file(stickies::STICKIES_FILE, || {
+        app.state::<stickies::StickyStore>().wipe()
+    });
+    sweep.file(tools::TOOL_AUDIT_FILE, || {
+        app.state::<tools::ToolAuditStore>().wipe()
+    });
+    sweep.file(github::GITHUB_FILE, || {
+        app.state::<github::GithubStore>().wipe()
+    });
+    sweep.file(moods::MOODS_FILE, || app.state::<moods::MoodStore>().wipe());
+    sweep.file(journal::DIGESTS_FILE, || {
+        app.state::<journal::DigestStore>().wipe()
+    });
+    sweep.file(milestones::MILESTONES_FILE, || {
+        app.state::<milestones::MilestoneStore>().wipe()
+    });
+    sweep.file(reactions::REACTIONS_FILE, || {
+        app.state::<reactions::ReactionStore>().wipe()
+    });
+    sweep.file(watchers::WATCHERS_FILE, || {
+        app.state::<watchers::WatcherStore>().wipe()
+    });
+    sweep.file(trackers::TRACKERS_FILE, || {
+        app.state::<trackers::TrackerStore>().wipe()
+    });
+    sweep.file(tickers::WATCHLIST_FILE, || {
+        app.state::<tickers::WatchlistStore>().wipe()
+    });
+    sweep.file(sports::TEAMS_FILE, || {
+        app.state::<sports::TeamStore>().wipe()
+    });
+    sweep.file(countdowns::COUNTDOWNS_FILE, || {
+        app.state::<countdowns::CountdownStore>().wipe()
+    });
+    sweep.file(wrapped::WRAPPED_FILE, || {
+        app.state::<wrapped::WrappedStore>().wipe()
+    });
+    sweep.dir(wrapped::WRAPPED_DIR);
+    sweep.dir(card::CARDS_DIR);
+    // The other cats' memory and mood
+    sweep.dir(pets::PETS_DIR);
+    sweep.report
+}
+
+/// Delete chat memory and facts, usage and activity stats, reminders,
+/// sticky notes, moods, digests, milestones, trackers and the generated recaps and cards
+/// in one go, e.g. before handing the machine back. The webview is told
+/// with `data-wiped` to clear the journal, achievements and stats it keeps.
+/// Anything that couldn't be deleted is listed under `failed`.
+#[tauri::command]
+pub async fn wipe_all_data(app: tauri::AppHandle) -> PetResult<WipeReport> {
+    let report = crate::run_blocking({
+        let app = app.clone();
+        move || wipe(&app)
+    })
+    .await?;
+    crate::tray::refresh(&app);
+    crate::stickies::close_all(&app);
+    app.emit("data-wiped", &report.webview_keys)?;
+    Ok(report)
+}
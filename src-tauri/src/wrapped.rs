@@ -0,0 +1,392 @@
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::error::{PetError, PetResult};
+use crate::journal::JournalEntry;
+use crate::moods::MoodStore;
+use crate::notes::NotesStore;
+use crate::usage::{self, UsageStore};
+use crate::{notifications, persist, scheduler};
+
+pub const WRAPPED_FILE: &str = "wrapped.json";
+pub const WRAPPED_DIR: &str = "wrapped";
+/// Enough judge lines to pick a favourite from without the file growing forever.
+const MAX_ROASTS: usize = 500;
+/// How many of the latest roasts compete for the pet card's quote.
+const RECENT_ROASTS: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Roast {
+    /// Local date (YYYY-MM-DD)
+    pub date: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedData {
+    pub roasts: Vec<Roast>,
+    /// Year whose recap the cat last announced
+    pub last_announced_year: Option<i32>,
+}
+
+pub struct WrappedStore {
+    data: Mutex<WrappedData>,
+    path: Option<PathBuf>,
+}
+
+impl WrappedStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = persist::app_data_file(app, WRAPPED_FILE).ok();
+        let data = path.as_deref().and_then(persist::load).unwrap_or_default();
+        Self {
+            data: Mutex::new(data),
+            path,
+        }
+    }
+
+    /// Forget everything and delete the file, holding the lock so nothing
+    /// is written back.
+    pub fn wipe(&self) -> PetResult<()> {
+        let mut data = self.data.lock().unwrap();
+        *data = WrappedData::default();
+        match &self.path {
+            Some(path) => persist::remove(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self) -> WrappedData {
+        self.data.lock().unwrap().clone()
+    }
+
+    pub fn replace(&self, data: WrappedData) {
+        self.update(|d| *d = data);
+    }
+
+    fn update(&self, f: impl FnOnce(&mut WrappedData)) {
+        let mut data = self.data.lock().unwrap();
+        f(&mut data);
+        if let Some(path) = &self.path {
+            let _ = persist::save(path, &*data);
+        }
+    }
+
+    /// Keep a judge-mode line as a candidate for the year's funniest roast.
+    pub fn record_roast(&self, text: &str) {
+        self.update(|d| {
+            d.roasts.push(Roast {
+                date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+                text: text.to_string(),
+            });
+            let excess = d.roasts.len().saturating_sub(MAX_ROASTS);
+            d.roasts.drain(..excess);
+        });
+    }
+
+    /// Drop roasts from before `cutoff` (YYYY-MM-DD).
+    pub fn prune_before(&self, cutoff: &str) {
+        if self.get().roasts.iter().any(|r| r.date.as_str() < cutoff) {
+            self.update(|d| d.roasts.retain(|r| r.date.as_str() >= cutoff));
+        }
+    }
+}
+
+/// What only the webview knows: event counters, achievements, journal
+/// entries and app usage all live in its storage.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WrappedInput {
+    pub pet_name: String,
+    pub chats: u32,
+    pub searches: u32,
+    pub naps: u32,
+    pub longest_streak: u32,
+    /// "Figma (1200m)" style lines, most used first
+    pub top_apps: Vec<String>,
+    /// Names of achievements unlocked this year
+    pub achievements: Vec<String>,
+    pub journal_entries: Vec<JournalEntry>,
+}
+
+struct Section {
+    title: &'static str,
+    items: Vec<String>,
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+fn mood_section(moods: &BTreeMap<String, u8>, year: i32) -> Option<Section> {
+    let prefix = format!("{}-", year);
+    let scores: Vec<(&String, u8)> = moods
+        .iter()
+        .filter(|(date, _)| date.starts_with(&prefix))
+        .map(|(date, score)| (date, *score))
+        .collect();
+    if scores.is_empty() {
+        return None;
+    }
+    let average = scores.iter().map(|(_, s)| *s as f64).sum::<f64>() / scores.len() as f64;
+
+    let mut by_month: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+    for (date, score) in &scores {
+        if let Some(month) = date.get(5..7).and_then(|m| m.parse::<usize>().ok()) {
+            by_month.entry(month).or_default().push(*score);
+        }
+    }
+    let best = by_month
+        .iter()
+        .map(|(month, s)| {
+            (
+                *month,
+                s.iter().map(|&v| v as f64).sum::<f64>() / s.len() as f64,
+            )
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .and_then(|(month, _)| MONTHS.get(month.wrapping_sub(1)));
+
+    let mut items = vec![format!(
+        "Average mood {:.1} out of {} across {} journaled days",
+        average,
+        crate::moods::MAX_MOOD,
+        scores.len()
+    )];
+    if let Some(best) = best {
+        items.push(format!("Best month: {}", best));
+    }
+    Some(Section {
+        title: "Vibes",
+        items,
+    })
+}
+
+fn render(year: i32, pet_name: &str, sections: &[Section]) -> String {
+    let body: String = sections
+        .iter()
+        .filter(|s| !s.items.is_empty())
+        .map(|s| {
+            let items: String = s
+                .items
+                .iter()
+                .map(|item| format!("<li>{}</li>", escape(item)))
+                .collect();
+            format!("<section><h2>{}</h2><ul>{}</ul></section>", s.title, items)
+        })
+        .collect();
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{name}'s Pet Wrapped {year}</title>
+<style>
+body {{ font-family: -apple-system, system-ui, sans-serif; background: #1d1b2f; color: #f6f1e7; max-width: 640px; margin: 0 auto; padding: 48px 24px; }}
+h1 {{ font-size: 40px; margin: 0 0 32px; color: #ffb347; }}
+section {{ background: #2b2843; border-radius: 16px; padding: 20px 24px; margin-bottom: 20px; }}
+h2 {{ margin: 0 0 12px; font-size: 20px; color: #ffd28a; }}
+ul {{ margin: 0; padding-left: 20px; line-height: 1.6; }}
+footer {{ opacity: 0.6; font-size: 13px; text-align: center; margin-top: 32px; }}
+</style>
+</head>
+<body>
+<h1>{name}'s Pet Wrapped {year}</h1>
+{body}
+<footer>Made by Desktop Pet</footer>
+</body>
+</html>
+"#,
+        name = escape(pet_name),
+        year = year,
+        body = body
+    )
+}
+
+async fn funniest_roast(app: &tauri::AppHandle, roasts: &[Roast]) -> Option<String> {
+    let last = roasts.last()?.text.clone();
+    let api_key = match crate::config::anthropic_api_key() {
+        Some(key) => key,
+        None => return Some(last),
+    };
+    if app.state::<UsageStore>().budget_exceeded() || roasts.len() == 1 {
+        return Some(last);
+    }
+    let numbered: Vec<String> = roasts
+        .iter()
+        .enumerate()
+        .map(|(i, r)| format!("{}. {}", i + 1, r.text))
+        .collect();
+    let pick = crate::dialogue::run_background_prompt(
+        app,
+        &api_key,
+        "You judge comedy. Reply with only the number of the funniest line.",
+        numbered.join("\n"),
+        10,
+    )
+    .await
+    .ok()
+    .and_then(|answer| answer.trim().trim_end_matches('.').parse::<usize>().ok())
+    .and_then(|n| roasts.get(n.checked_sub(1)?));
+    Some(pick.map_or(last, |r| r.text.clone()))
+}
+
+/// The funniest of the recent judge-mode lines, for the pet's card.
+pub async fn favourite_roast(app: &tauri::AppHandle) -> Option<String> {
+    let roasts = app.state::<WrappedStore>().get().roasts;
+    let recent = &roasts[roasts.len().saturating_sub(RECENT_ROASTS)..];
+    funniest_roast(app, recent).await
+}
+
+/// Crunch a year into a Pet Wrapped report, write it as a standalone HTML
+/// file and return its path.
+async fn build(app: &tauri::AppHandle, year: i32, input: WrappedInput) -> PetResult<PathBuf> {
+    let prefix = format!("{}-", year);
+    let stats = usage::get_usage_stats(app.state());
+    let requests: u32 = stats
+        .days
+        .iter()
+        .filter(|(date, _)| date.starts_with(&prefix))
+        .map(|(_, day)| day.requests)
+        .sum();
+    let notes = app
+        .state::<NotesStore>()
+        .all()
+        .iter()
+        .filter(|n| {
+            chrono::DateTime::from_timestamp_millis(n.created_at).is_some_and(|d| d.year() == year)
+        })
+        .count();
+    let moods = app.state::<MoodStore>().all();
+    let roasts: Vec<Roast> = app
+        .state::<WrappedStore>()
+        .get()
+        .roasts
+        .into_iter()
+        .filter(|r| r.date.starts_with(&prefix))
+        .collect();
+    let roast = funniest_roast(app, &roasts).await;
+
+    let happiest_entry = input
+        .journal_entries
+        .iter()
+        .filter(|e| e.date.starts_with(&prefix))
+        .max_by_key(|e| moods.get(&e.date).copied().unwrap_or(0));
+
+    let mut sections = vec![
+        Section {
+            title: "Top apps",
+            items: input.top_apps.iter().take(5).cloned().collect(),
+        },
+        Section {
+            title: "By the numbers",
+            items: vec![
+                format!("{} chats", input.chats),
+                format!("{} searches", input.searches),
+                format!("{} naps", input.naps),
+                format!("{} times the cat thought out loud", requests),
+                format!("{} reminders stuck on the fridge", notes),
+                format!("Longest streak: {} days", input.longest_streak),
+            ],
+        },
+        Section {
+            title: "Achievements unlocked",
+            items: input.achievements.clone(),
+        },
+    ];
+    sections.extend(mood_section(&moods, year));
+    if let Some(roast) = roast {
+        sections.push(Section {
+            title: "Funniest roast",
+            items: vec![roast],
+        });
+    }
+    if let Some(entry) = happiest_entry {
+        sections.push(Section {
+            title: "Dear diary",
+            items: vec![format!("{}: {}", entry.date, entry.text)],
+        });
+    }
+
+    let pet_name = if input.pet_name.trim().is_empty() {
+        "Cat"
+    } else {
+        input.pet_name.trim()
+    };
+    let html = render(year, pet_name, &sections);
+    let path = persist::app_data_file(app, WRAPPED_DIR)?.join(format!("wrapped-{}.html", year));
+    crate::run_blocking(move || {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, html)?;
+        Ok::<_, PetError>(path)
+    })
+    .await?
+}
+
+/// The first week of January, nudge the webview to build last year's recap.
+async fn announce_recap(app: tauri::AppHandle) {
+    let today = chrono::Local::now().date_naive();
+    let year = today.year() - 1;
+    if today.month() != 1 || today.day() > 7 {
+        return;
+    }
+    if app.state::<WrappedStore>().get().last_announced_year >= Some(year) {
+        return;
+    }
+    notifications::announce(
+        &app,
+        &format!("Your Pet Wrapped {} is ready. I have thoughts.", year),
+    );
+    let _ = app.emit("wrapped-ready", year);
+    let handle = app.clone();
+    let _ = crate::run_blocking(move || {
+        handle
+            .state::<WrappedStore>()
+            .update(|d| d.last_announced_year = Some(year))
+    })
+    .await;
+}
+
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    scheduler::every_tick(app, announce_recap);
+}
+
+/// Build the recap for `year` (default: this year), open it in the browser
+/// and return where it was saved.
+#[tauri::command]
+pub async fn generate_wrapped(
+    app: tauri::AppHandle,
+    year: Option<i32>,
+    input: WrappedInput,
+) -> PetResult<String> {
+    let year = year.unwrap_or_else(|| chrono::Local::now().year());
+    let path = build(&app, year, input).await?;
+    crate::opener::open_path(&path)?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -1,6 +1,6 @@
 import { useState, useCallback, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { register, unregister } from "@tauri-apps/plugin-global-shortcut";
+import { emit, listen } from "@tauri-apps/api/event";
 import Pet from "./components/Pet";
 import SpeechBubble from "./components/SpeechBubble";
 import RadialMenu, { MenuAction } from "./components/RadialMenu";
@@ -11,57 +11,219 @@ import AchievementsPanel from "./components/AchievementsPanel";
 import AchievementToast from "./components/AchievementToast";
 import FriendsPanel from "./components/FriendsPanel";
 import VisitingPet from "./components/VisitingPet";
+import CoworkBuddy from "./components/CoworkBuddy";
 import StickyNoteComponent from "./components/StickyNote";
 import NotesPanel from "./components/NotesPanel";
+import StartupChecklist from "./components/StartupChecklist";
 import { usePetMovement } from "./hooks/usePetMovement";
-import { useActiveWindow } from "./hooks/useActiveWindow";
+import { useActiveWindow, topAppsThisYear } from "./hooks/useActiveWindow";
 import { useDialogue } from "./hooks/useDialogue";
 import { useCursorPassthrough } from "./hooks/useCursorPassthrough";
-import { useTheme } from "./hooks/useTheme";
+import { useTheme, BREED_LABELS, COLOR_LABELS } from "./hooks/useTheme";
 import { useAmbientMusic } from "./hooks/useAmbientMusic";
 import { useEventTracker } from "./hooks/useEventTracker";
 import { useAchievements } from "./hooks/useAchievements";
 import { useJournal } from "./hooks/useJournal";
 import { useFriends } from "./hooks/useFriends";
+import { useCowork } from "./hooks/useCowork";
+import { useAutoSync } from "./hooks/useSync";
+import { useToolPrompts } from "./hooks/useToolPrompts";
+import { useWeather } from "./hooks/useWeather";
 import { useNotes } from "./hooks/useNotes";
+import { usePowerSaving, usePowerSuspended } from "./hooks/usePowerState";
+import { useOcclusion } from "./hooks/useOcclusion";
+import { useFullscreenTuck } from "./hooks/useFullscreen";
+import { useClipboardOffer } from "./hooks/useClipboardOffer";
+import { useScreenshotOffer } from "./hooks/useScreenshotOffer";
+import { useDictation } from "./hooks/useDictation";
+import { useDarkSurroundings } from "./hooks/useAppearance";
+import { useStartupReport } from "./hooks/useStartupReport";
+import { useMood, recordInteraction } from "./hooks/useMood";
 
 const DEFAULT_SHORTCUT = "CommandOrControl+Shift+Space";
 
+interface ShortcutBindings {
+  openPalette: string | null;
+  toggleHide: string | null;
+  triggerJudge: string | null;
+  startFocusSession: string | null;
+  askAboutSelection: string | null;
+  dictate: string | null;
+}
+
+interface SelectedText {
+  text: string;
+  appName: string;
+}
+
+interface Rect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+interface TextField {
+  role: string;
+  frame: Rect;
+  caret: Rect | null;
+  appName: string;
+}
+
+/** Rough height of the open palette, to decide whether it fits below a field. */
+const PALETTE_HEIGHT = 320;
+
+/**
+ * Where to open the palette so it sits just under (or above) the field the
+ * user is typing in. CommandPalette puts its top-left 150px left of and
+ * 100px above the point it's given. Null when the field is on another display.
+ */
+function paletteAnchorFor(field: TextField): { x: number; y: number } | null {
+  const target = field.caret ?? field.frame;
+  if (target.x < 0 || target.x > window.innerWidth || target.y < 0 || target.y > window.innerHeight) {
+    return null;
+  }
+  const below = target.y + target.height + 8;
+  const top = below + PALETTE_HEIGHT <= window.innerHeight ? below : Math.max(10, target.y - PALETTE_HEIGHT - 8);
+  return { x: target.x + 150, y: top + 100 };
+}
+
+interface DockIcon {
+  title: string;
+  kind: "app" | "folder" | "trash" | "window" | "other";
+  frame: Rect;
+  running: boolean;
+}
+
+interface MenuBarLayout {
+  bar: Rect;
+  menus: Rect | null;
+  statusItems: Rect | null;
+  perchX: number | null;
+  side: "left" | "right";
+}
+
+/** Longest run of consecutive active days within `year`. */
+function longestStreak(activeDays: string[], year: number): number {
+  const days = [...new Set(activeDays)].filter((d) => d.startsWith(`${year}-`)).sort();
+  let best = 0;
+  let run = 0;
+  let prev = 0;
+  for (const day of days) {
+    const time = new Date(day).getTime();
+    run = time - prev === 1000 * 60 * 60 * 24 ? run + 1 : 1;
+    best = Math.max(best, run);
+    prev = time;
+  }
+  return best;
+}
+
+interface GuestEvent {
+  guestName: string;
+  action: "pet" | "treat" | "message";
+  message: string | null;
+}
+
+type DeepLink =
+  | { action: "addFriend"; code: string }
+  | { action: "chat"; message: string };
+
 export default function App() {
   const {
     position, state, facingLeft, dragging,
     setState, setPosition, setDragging,
-    goHome, leaveHome, nap, wake,
+    goHome, leaveHome, walkTo, nap, wake,
   } = usePetMovement();
   const { appName, windowTitle, appChanged } = useActiveWindow();
   const { notes, notesVisible, addNote, deleteNote, updateNotePosition, toggleNotesVisible } = useNotes();
-  const { text, visible, hiding, loading, generate, dismiss } = useDialogue(
+  const { text, source, emotion, mode, visible, hiding, loading, generate, translate, askAboutClipboard, askAboutScreenshot, systemReport, dismiss } = useDialogue(
     appName,
     windowTitle,
     appChanged,
     addNote,
   );
+  const { offer: clipOffer, accept: acceptClipOffer, decline: declineClipOffer } =
+    useClipboardOffer(askAboutClipboard);
+  const { offer: screenshotOffer, accept: acceptScreenshotOffer, decline: declineScreenshotOffer } =
+    useScreenshotOffer(askAboutScreenshot);
+  const [selection, setSelection] = useState<SelectedText | null>(null);
+  // A chat message from a desktoppet:// link, waiting for the owner to send it
+  const [linkMessage, setLinkMessage] = useState<string | null>(null);
+  // Quick reactions to the cat's current line, the user's favourites first
+  const [reactions, setReactions] = useState<string[]>([]);
+  useEffect(() => {
+    if (!mode) {
+      setReactions([]);
+      return;
+    }
+    invoke<{ reaction: string; score: number }[]>("get_reaction_suggestions", { context: mode })
+      .then((suggestions) => setReactions(suggestions.map((s) => s.reaction)))
+      .catch(() => setReactions([]));
+  }, [mode]);
+  const react = useCallback(
+    (reaction: string) => {
+      invoke("record_reaction", { reaction, context: mode }).catch(() => {});
+      setReactions([]);
+      generate("chat", `owner reacted to what you just said with ${reaction}`);
+    },
+    [mode, generate],
+  );
+  const announce = useCallback((message: string) => {
+    emit("pet-announce", message);
+  }, []);
+  const {
+    listening: dictating,
+    draft: dictationDraft,
+    toggle: toggleDictation,
+    confirm: confirmDictation,
+    discard: discardDictation,
+  } = useDictation(announce);
   const { breed, color, currentTheme, customThemes, selectBreed, selectColor, addCustomTheme, removeCustomTheme } =
     useTheme();
   const { playing: musicPlaying, toggle: toggleMusic } = useAmbientMusic();
   const { data: eventData, trackEvent } = useEventTracker();
   const { achievements, unlocked, newlyUnlocked, dismissToast, manualUnlock } = useAchievements(eventData);
   const { entries, generateToday, todayGenerated, loading: journalLoading } = useJournal(eventData);
+  useAutoSync();
+  const toolPrompt = useToolPrompts();
   const {
-    myPetCode, myPetName, registered, registering, friends, loadingFriends, connected,
-    register: registerPet, addFriend, acceptFriend, removeFriend, sendVisit, startHangout, setMyPetName, currentVisit, dismissVisit,
+    myPetId, myPetCode, myPetName, registered, registering, friends, loadingFriends, connected,
+    register: registerPet, addFriend, acceptFriend, removeFriend, blockFriend, sendVisit, startHangout, setMyPetName, currentVisit, dismissVisit,
   } = useFriends(breed, color);
+  const cowork = useCowork(myPetId);
+  const startCowork = useCallback((friendId: string) => {
+    const friend = friends.find((f) => f.id === friendId);
+    if (!myPetId || !friend) return;
+    cowork
+      .start(
+        { id: myPetId, name: myPetName, breed, color },
+        { id: friend.id, name: friend.name, breed: friend.breed, color: friend.color },
+      )
+      .then(() => setFriendsOpen(false))
+      .catch((e) => announce(String(e)));
+  }, [friends, myPetId, myPetName, breed, color, cowork.start, announce]);
 
-  const [shortcut, setShortcut] = useState(() =>
-    localStorage.getItem("chat-shortcut") || DEFAULT_SHORTCUT
-  );
+  // Global shortcuts are registered by the backend; this mirrors the palette binding for the settings UI
+  const [shortcut, setShortcut] = useState(DEFAULT_SHORTCUT);
+  useEffect(() => {
+    // One-time migration of the binding that used to live in localStorage
+    const legacy = localStorage.getItem("chat-shortcut");
+    localStorage.removeItem("chat-shortcut");
+    const load = legacy
+      ? invoke<ShortcutBindings>("set_shortcut", { action: "openPalette", accelerator: legacy })
+          .catch(() => invoke<ShortcutBindings>("get_shortcuts"))
+      : invoke<ShortcutBindings>("get_shortcuts");
+    load.then((b) => setShortcut(b.openPalette ?? "")).catch(console.error);
+  }, []);
   const handleChangeShortcut = useCallback((s: string) => {
-    setShortcut(s);
-    localStorage.setItem("chat-shortcut", s);
+    invoke<ShortcutBindings>("set_shortcut", { action: "openPalette", accelerator: s })
+      .then((b) => setShortcut(b.openPalette ?? ""))
+      .catch((e) => console.error("Failed to set shortcut:", e));
   }, []);
 
   const [menuOpen, setMenuOpen] = useState(false);
   const [paletteOpen, setPaletteOpen] = useState(false);
+  const [paletteAnchor, setPaletteAnchor] = useState<{ x: number; y: number } | null>(null);
   const [settingsOpen, setSettingsOpen] = useState(false);
   const [journalOpen, setJournalOpen] = useState(false);
   const [achievementsOpen, setAchievementsOpen] = useState(false);
@@ -71,10 +233,22 @@ export default function App() {
   const [visitorOverlay, setVisitorOverlay] = useState(false);
   const [notePositions, setNotePositions] = useState<Map<string, { x: number; y: number }>>(new Map());
 
-  const overlayOpen = menuOpen || paletteOpen || dragging || settingsOpen || journalOpen || achievementsOpen || friendsOpen || notesOpen || visitorOverlay;
+  const startup = useStartupReport();
+  const mood = useMood();
+  const startupOpen = startup.open && startup.report !== null;
+
+  const overlayOpen = startupOpen || menuOpen || paletteOpen || dragging || settingsOpen || journalOpen || achievementsOpen || friendsOpen || notesOpen || visitorOverlay;
 
   const extraHitZones = [
     ...(visitorPos ? [visitorPos] : []),
+    // The co-working buddy beside the pet, with its stop and reaction buttons
+    ...(cowork.session ? [{ x: position.x, y: position.y, w: 400, h: 160 }] : []),
+    // Keep the bubble clickable while it shows a source link or reactions
+    ...(visible && (source || reactions.length > 0) ? [{ x: position.x, y: position.y - 90, w: 240, h: 120 }] : []),
+    // And while it asks before using a tool
+    ...(toolPrompt.request ? [{ x: position.x, y: position.y - 90, w: 240, h: 120 }] : []),
+    // And while it asks about something just copied, highlighted, dictated or screenshotted
+    ...((clipOffer || screenshotOffer || selection || dictationDraft || linkMessage) && !visible ? [{ x: position.x, y: position.y - 90, w: 240, h: 120 }] : []),
     ...(notesVisible ? notes.map((n) => {
       const pos = notePositions.get(n.id);
       // Center of the note (180px wide, ~80px tall) with generous hit zone
@@ -97,32 +271,429 @@ export default function App() {
       const key = `${latest.id}-${latest.tier || ""}`;
       if (lastToastRef.current !== key) {
         lastToastRef.current = key;
+        invoke("play_sound", { name: "chime" }).catch(() => {});
         generate("achievement", `${latest.name}${latest.tier ? ` (${latest.tier})` : ""}`);
       }
     }
   }, [newlyUnlocked, generate]);
 
-  // Global shortcut to toggle command palette (user-configurable)
+  // Global shortcuts are handled in Rust and forwarded here (except hide/show)
   const paletteOpenRef = useRef(paletteOpen);
   paletteOpenRef.current = paletteOpen;
   useEffect(() => {
-    register(shortcut, (e) => {
-      if (e.state !== "Pressed") return;
-      if (paletteOpenRef.current) {
-        setPaletteOpen(false);
-      } else {
-        setMenuOpen(false);
-        setSettingsOpen(false);
-        setJournalOpen(false);
-        setAchievementsOpen(false);
-        setFriendsOpen(false);
-        setNotesOpen(false);
-        setPaletteOpen(true);
-        trackEvent("menuOpen", "palette");
+    const unlisten = listen<string>("shortcut-triggered", (event) => {
+      switch (event.payload) {
+        case "openPalette":
+          if (paletteOpenRef.current) {
+            setPaletteOpen(false);
+          } else {
+            setMenuOpen(false);
+            setSettingsOpen(false);
+            setJournalOpen(false);
+            setAchievementsOpen(false);
+            setFriendsOpen(false);
+            setNotesOpen(false);
+            // Open next to wherever the user is typing, or by the cat otherwise
+            invoke<TextField | null>("get_focused_text_field")
+              .catch(() => null)
+              .then((field) => {
+                setPaletteAnchor(field ? paletteAnchorFor(field) : null);
+                setPaletteOpen(true);
+              });
+            trackEvent("menuOpen", "palette");
+          }
+          break;
+        case "triggerJudge":
+          generate("judge", "user pressed the judge shortcut");
+          break;
+        case "dictate":
+          toggleDictation();
+          break;
+      }
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, [trackEvent, generate, toggleDictation]);
+
+  // After wipe_all_data the webview's own history goes too; reloading drops
+  // whatever the hooks still hold in memory
+  useEffect(() => {
+    const unlisten = listen<string[]>("data-wiped", (event) => {
+      event.payload.forEach((key) => localStorage.removeItem(key));
+      window.location.reload();
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  // Highlighted text sent over by the selection hotkey, waiting for a mode
+  useEffect(() => {
+    const unlisten = listen<SelectedText>("selection-captured", (event) => setSelection(event.payload));
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+  const askAboutSelection = useCallback(
+    (mode: "explain" | "translate" | "summarize") => {
+      if (!selection) return;
+      if (mode === "translate") translate(selection.text, null, selection.appName);
+      else generate(mode, `text highlighted in ${selection.appName}`, selection.text);
+      setSelection(null);
+    },
+    [selection, generate, translate],
+  );
+
+  // Tray menu: focus sessions and reminders are driven from the backend
+  useEffect(() => {
+    const unlistenFocus = listen<boolean>("focus-session", (event) => {
+      generate("chat", event.payload ? "user started a focus session" : "user ended their focus session");
+    });
+    const unlistenNotes = listen("open-notes", () => setNotesOpen(true));
+    return () => {
+      unlistenFocus.then((fn) => fn());
+      unlistenNotes.then((fn) => fn());
+    };
+  }, [generate]);
+
+  // petctl and Shortcuts commands that need the pet itself rather than backend state
+  useEffect(() => {
+    const unlisten = listen<string>("pet-command", (event) => {
+      if (event.payload === "nap") {
+        nap();
+        trackEvent("nap");
+        dismiss();
+      } else if (event.payload === "wake" && state === "napping") {
+        wake();
+        trackEvent("wake");
+        generate("chat", "cat was woken up from a nap");
+      }
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, [nap, wake, state, trackEvent, dismiss, generate]);
+
+  // desktoppet:// links are queued in Rust until handled here
+  useEffect(() => {
+    const handleLinks = async () => {
+      const links = await invoke<DeepLink[]>("take_deep_links");
+      for (const link of links) {
+        if (link.action === "addFriend") {
+          setFriendsOpen(true);
+          const result = await addFriend(link.code);
+          generate(
+            "chat",
+            result.ok ? "user added a new friend from an invite link" : `an invite link didn't work: ${result.error}`,
+          );
+        } else {
+          // Any web page can open a link, so the owner sends it, not the page
+          setLinkMessage(link.message);
+        }
+      }
+    };
+    handleLinks();
+    const unlisten = listen("deep-link", handleLinks);
+    return () => { unlisten.then((fn) => fn()); };
+  }, [addFriend, generate]);
+
+  // Pet Wrapped: the backend renders the report, but the counters live here
+  const buildWrapped = useCallback(
+    (year: number) => {
+      const achievementNames = achievements
+        .filter((a) => {
+          const at = unlocked[a.id]?.unlockedAt;
+          return at !== undefined && new Date(at).getFullYear() === year;
+        })
+        .map((a) => a.name);
+      invoke<string>("generate_wrapped", {
+        year,
+        input: {
+          petName: myPetName,
+          chats: eventData.chats,
+          searches: eventData.searches,
+          naps: eventData.naps,
+          longestStreak: longestStreak(eventData.activeDays, year),
+          topApps: topAppsThisYear(),
+          achievements: achievementNames,
+          journalEntries: entries.map((e) => ({ date: e.date, text: e.text })),
+        },
+      }).catch((e) => console.error("Failed to build Pet Wrapped:", e));
+    },
+    [achievements, unlocked, myPetName, eventData, entries],
+  );
+  useEffect(() => {
+    const unlisten = listen<number>("wrapped-ready", (event) => buildWrapped(event.payload));
+    return () => { unlisten.then((fn) => fn()); };
+  }, [buildWrapped]);
+
+  // The leaderboard only ever sees the count; the backend decides whether to send it
+  const unlockedCount = Object.keys(unlocked).length;
+  useEffect(() => {
+    invoke("sync_leaderboard_achievements", { achievements: unlockedCount }).catch((e) =>
+      console.error("Failed to sync leaderboard:", e),
+    );
+  }, [unlockedCount]);
+
+  // Pet card: a shareable PNG of the current sprite and lifetime stats
+  const exportCard = useCallback(async () => {
+    let sprite: string | null = null;
+    try {
+      const blob = await (await fetch(currentTheme.idle)).blob();
+      sprite = await new Promise<string>((resolve, reject) => {
+        const reader = new FileReader();
+        reader.onload = () => resolve(reader.result as string);
+        reader.onerror = () => reject(reader.error);
+        reader.readAsDataURL(blob);
+      });
+    } catch (e) {
+      console.error("Failed to read sprite for pet card:", e);
+    }
+    const years = new Set(eventData.activeDays.map((d) => Number(d.slice(0, 4))));
+    const streak = Math.max(0, ...[...years].map((y) => longestStreak(eventData.activeDays, y)));
+    invoke<string>("export_pet_card", {
+      card: {
+        name: myPetName || "My Cat",
+        breed: BREED_LABELS[breed],
+        color: COLOR_LABELS[color],
+        level: Object.keys(unlocked).length + 1,
+        stats: [
+          { label: "Chats", value: String(eventData.chats) },
+          { label: "Searches", value: String(eventData.searches) },
+          { label: "Naps", value: String(eventData.naps) },
+          { label: "Best streak", value: `${streak}d` },
+          { label: "Trophies", value: `${Object.keys(unlocked).length}/${achievements.length}` },
+        ],
+        sprite,
+      },
+    }).catch((e) => console.error("Failed to export pet card:", e));
+  }, [currentTheme, eventData, myPetName, breed, color, unlocked, achievements]);
+
+  // Climb up onto the free stretch of menu bar, clear of menus and the clock
+  const perchOnMenuBar = useCallback(async () => {
+    try {
+      const layout = await invoke<MenuBarLayout | null>("get_menu_bar_layout");
+      if (!layout) return;
+      if (layout.perchX === null) {
+        generate("react", "cat wanted to sit on the menu bar but it's too crowded");
+        return;
+      }
+      walkTo({ x: layout.perchX, y: layout.bar.y + layout.bar.height });
+      invoke("record_milestone", { milestone: "perch" }).catch(() => {});
+    } catch (e) {
+      console.error("Failed to read the menu bar:", e);
+    }
+  }, [generate, walkTo]);
+
+  // The cat goes to bed when the Mac sleeps or locks, and stretches when it's back
+  const suspended = usePowerSuspended();
+  const powerSaving = usePowerSaving();
+  const wasSuspendedRef = useRef(false);
+  useEffect(() => {
+    if (suspended === wasSuspendedRef.current) return;
+    wasSuspendedRef.current = suspended;
+    if (suspended) {
+      dismiss();
+      goHome();
+    } else {
+      leaveHome();
+      generate("react", "the computer just woke up and the cat is stretching after a long sleep");
+    }
+  }, [suspended, dismiss, goHome, leaveHome, generate]);
+
+  // Thunderstorms send the cat home to hide; a sunny afternoon is for
+  // sunbathing. Only the change of weather does anything, not every refresh
+  const weather = useWeather();
+  const weatherMood = !weather
+    ? null
+    : weather.condition === "thunderstorm"
+      ? "storm"
+      : weather.condition === "clear" && weather.isDay && new Date().getHours() >= 12 && new Date().getHours() < 18
+        ? "sunny"
+        : null;
+  const weatherMoodRef = useRef<typeof weatherMood>(null);
+  useEffect(() => {
+    const previous = weatherMoodRef.current;
+    if (weatherMood === previous) return;
+    weatherMoodRef.current = weatherMood;
+    if (weatherMood === "storm") {
+      goHome();
+      generate("react", "there's a thunderstorm outside and the cat is hiding at home");
+    } else if (previous === "storm") {
+      leaveHome();
+    } else if (weatherMood === "sunny" && state === "idle") {
+      nap();
+      generate("react", "it's a sunny afternoon and the cat is sunbathing");
+    }
+  }, [weatherMood, state, goHome, leaveHome, nap, generate]);
+
+  // One of the owner's pull requests got approved (the cat runs over to
+  // celebrate) or failed CI (it sulks about it)
+  useEffect(() => {
+    const unlisten = listen<{ kind: "approved" | "checksFailed"; repo: string; number: number; title: string }>(
+      "github-pull-news",
+      (event) => {
+        const { kind, repo, number, title } = event.payload;
+        if (kind === "approved") {
+          walkTo({ x: window.innerWidth / 2, y: window.innerHeight * 0.75 });
+          generate("react", `the owner's pull request "${title}" (${repo}#${number}) just got approved and the cat ran over, thrilled`);
+        } else {
+          generate("react", `CI just failed on the owner's pull request "${title}" (${repo}#${number}) and the cat is disappointed`);
+        }
+      },
+    );
+    return () => { unlisten.then((fn) => fn()); };
+  }, [walkTo, generate]);
+
+  // The build or test command from Settings finished: celebrate a pass,
+  // flop over dramatically on a failure
+  useEffect(() => {
+    const unlistenPassed = listen<{ command: string; summary: string | null }>("dev-build-passed", (event) => {
+      walkTo({ x: window.innerWidth / 2, y: window.innerHeight * 0.75 });
+      generate("react", `the owner's \`${event.payload.command}\` just passed and the cat is celebrating`);
+    });
+    const unlistenFailed = listen<{ command: string; summary: string | null }>("dev-build-failed", (event) => {
+      const { command, summary } = event.payload;
+      nap();
+      generate(
+        "react",
+        `the owner's \`${command}\` just failed${summary ? ` ("${summary}")` : ""} and the cat flopped over dramatically`,
+      );
+    });
+    return () => {
+      unlistenPassed.then((fn) => fn());
+      unlistenFailed.then((fn) => fn());
+    };
+  }, [walkTo, nap, generate]);
+
+  // The startup disk crossed under 10 GB free
+  useEffect(() => {
+    const unlisten = listen<{ freeBytes: number }>("disk-space-low", (event) => {
+      const freeGb = (event.payload.freeBytes / 1e9).toFixed(1);
+      generate("react", `the computer's disk is down to ${freeGb} GB free and the cat feels cramped`);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [generate]);
+
+  // Something has pegged the CPU for minutes: the cat sprawls out in the
+  // heat and names the culprit, and gets up once it cools down
+  const heatNapRef = useRef(false);
+  useEffect(() => {
+    const unlistenHot = listen<{ appName: string; minutes: number }>("cpu-hot", (event) => {
+      const { appName, minutes } = event.payload;
+      heatNapRef.current = true;
+      nap();
+      generate(
+        "react",
+        `${appName} has had the computer running hot for ${minutes} minutes and the cat is sprawled out dramatically because it's so hot in here`,
+      );
+    });
+    const unlistenCooled = listen("cpu-cooled", () => {
+      if (heatNapRef.current && state === "napping") wake();
+      heatNapRef.current = false;
+    });
+    return () => {
+      unlistenHot.then((fn) => fn());
+      unlistenCooled.then((fn) => fn());
+    };
+  }, [nap, wake, state, generate]);
+
+  // Stream guests, already checked and paced by the backend
+  useEffect(() => {
+    const unlisten = listen<GuestEvent>("guest-interaction", (event) => {
+      const { guestName, action, message } = event.payload;
+      if (action === "pet") {
+        generate("guest", `${guestName} petted the cat`);
+      } else if (action === "treat") {
+        generate("guest", `${guestName} tossed the cat a treat`);
+      } else if (message) {
+        generate("guest", `${guestName} says: "${message}"`);
       }
     });
-    return () => { unregister(shortcut); };
-  }, [shortcut, trackEvent]);
+    return () => { unlisten.then((fn) => fn()); };
+  }, [generate]);
+
+  // Cursor updates only arrive during a chase, which ends by itself once
+  // the cursor rests
+  useEffect(() => {
+    const unlisten = listen<{ x: number; y: number }>("cursor-moved", (event) => {
+      walkTo(event.payload);
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, [walkTo]);
+
+  // An app bouncing in the Dock wants attention; the cat goes to bat at it
+  useEffect(() => {
+    const unlisten = listen<DockIcon>("dock-icon-bouncing", (event) => {
+      if (state !== "idle") return;
+      const { title, frame } = event.payload;
+      walkTo({ x: frame.x + frame.width / 2, y: frame.y });
+      generate("react", `${title} is bouncing in the Dock asking for attention and the cat is batting at it`);
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, [state, walkTo, generate]);
+
+  // Curl up on the current app's Dock icon, or the first one
+  const napOnDock = useCallback(async () => {
+    try {
+      const icons = await invoke<DockIcon[]>("get_dock_icons");
+      const apps = icons.filter((icon) => icon.kind === "app");
+      const icon = apps.find((icon) => icon.title === appName) ?? apps[0];
+      if (!icon) return;
+      const target = { x: icon.frame.x + icon.frame.width / 2, y: icon.frame.y };
+      walkTo(target);
+      // Walking covers 2px a frame; nap once it gets there
+      const distance = Math.hypot(target.x - position.x, target.y - position.y);
+      setTimeout(nap, (distance / 2) * (1000 / 60) + 300);
+    } catch (e) {
+      console.error("Failed to read the Dock:", e);
+    }
+  }, [appName, walkTo, nap, position]);
+
+  // LAN presence: the cat walked over from (or off to) another Mac
+  useEffect(() => {
+    const unlistenArrived = listen<{ from: string }>("pet-arrived", (event) => {
+      generate("react", `cat just walked over from the user's other computer, ${event.payload.from}`);
+    });
+    const unlistenLeft = listen<string>("pet-left", () => dismiss());
+    return () => {
+      unlistenArrived.then((fn) => fn());
+      unlistenLeft.then((fn) => fn());
+    };
+  }, [generate, dismiss]);
+
+  // Movies and slides: curl up small in the corner until fullscreen ends.
+  // In "hide" mode the backend hides the whole overlay instead.
+  const tucked = useFullscreenTuck();
+  const wasTuckedRef = useRef(false);
+  useEffect(() => {
+    if (tucked === wasTuckedRef.current) return;
+    wasTuckedRef.current = tucked;
+    if (tucked) {
+      dismiss();
+      goHome();
+    } else {
+      leaveHome();
+    }
+  }, [tucked, dismiss, goHome, leaveHome]);
+
+  const darkSurroundings = useDarkSurroundings(position.x, position.y, state === "idle" && !dragging);
+
+  // Step off the window the user is working in, to whichever side is
+  // closer; if it fills the screen, retreat to the corner
+  useOcclusion(position.x, position.y, state === "idle" && !dragging && !tucked, (bounds) => {
+    const margin = 60;
+    const left = bounds.x - margin;
+    const right = bounds.x + bounds.width + margin;
+    const leftFits = left >= margin;
+    const rightFits = right <= window.innerWidth - margin;
+    if (!leftFits && !rightFits) {
+      goHome();
+      return;
+    }
+    const goLeft = leftFits && (!rightFits || position.x - left < right - position.x);
+    walkTo({ x: goLeft ? left : right, y: position.y });
+  });
+
+  // Mirror the pet's state into the tray's status line and icon
+  useEffect(() => {
+    invoke("set_pet_animation", { animation: state }).catch(() => {});
+  }, [state]);
 
   const handleDragStart = useCallback(() => {
     setDragging(true);
@@ -144,6 +715,8 @@ export default function App() {
   const handlePetClick = useCallback(() => {
     if (settingsOpen || journalOpen || achievementsOpen || friendsOpen || notesOpen) return;
     trackEvent("petClick");
+    recordInteraction("petted");
+    invoke("purr").catch(() => {});
     if (state === "napping") {
       wake();
       trackEvent("wake");
@@ -166,10 +739,12 @@ export default function App() {
       switch (action) {
         case "chat":
           trackEvent("menuOpen", "chat");
+          setPaletteAnchor(null);
           setPaletteOpen(true);
           break;
         case "search":
           trackEvent("menuOpen", "search");
+          setPaletteAnchor(null);
           setPaletteOpen(true);
           break;
         case "music":
@@ -191,6 +766,7 @@ export default function App() {
         case "nap":
           nap();
           trackEvent("nap");
+          recordInteraction("napped");
           dismiss();
           break;
         case "home":
@@ -218,9 +794,31 @@ export default function App() {
           trackEvent("notes");
           setNotesOpen(true);
           break;
+        case "wrapped":
+          buildWrapped(new Date().getFullYear());
+          break;
+        case "card":
+          exportCard();
+          break;
+        case "perch":
+          dismiss();
+          perchOnMenuBar();
+          break;
+        case "dock":
+          dismiss();
+          napOnDock();
+          break;
+        case "chase":
+          dismiss();
+          invoke("set_performance_mode", { mode: "game" }).catch(() => {});
+          break;
+        case "report":
+          trackEvent("report");
+          systemReport();
+          break;
       }
     },
-    [setState, generate, nap, goHome, dismiss, toggleMusic, musicPlaying, trackEvent, manualUnlock, state],
+    [setState, generate, nap, goHome, dismiss, toggleMusic, musicPlaying, trackEvent, manualUnlock, state, buildWrapped, exportCard, perchOnMenuBar, napOnDock, systemReport],
   );
 
   const handlePaletteChat = useCallback(
@@ -245,6 +843,40 @@ export default function App() {
     [setState, generate, trackEvent],
   );
 
+  const handlePaletteTranslate = useCallback(
+    (text: string, target: string | null) => {
+      setPaletteOpen(false);
+      setState("talking");
+      translate(text, target);
+      setTimeout(() => setState("idle"), 3000);
+    },
+    [setState, translate],
+  );
+
+  const handlePaletteOpenFile = useCallback(
+    (path: string) => {
+      setPaletteOpen(false);
+      trackEvent("openFile");
+      invoke("open_file", { path }).catch(() => {});
+    },
+    [trackEvent],
+  );
+
+  // "open github.com" is a site, "open Safari" an app. Either asks first
+  // unless it's on the allowlist in Settings
+  const handlePaletteOpen = useCallback(
+    (target: string) => {
+      setPaletteOpen(false);
+      const hasScheme = /^https?:\/\//i.test(target);
+      const site = hasScheme || (/^[\w-]+(\.[\w-]+)+(\/\S*)?$/.test(target) && !/\.app$/i.test(target));
+      const opened = site
+        ? invoke<boolean>("open_url", { url: hasScheme ? target : `https://${target}` })
+        : invoke<boolean>("open_application", { name: target });
+      opened.catch((e) => announce(String(e)));
+    },
+    [announce],
+  );
+
   const handleVisitorChat = useCallback(
     (message: string) => {
       if (currentVisit) {
@@ -282,6 +914,9 @@ export default function App() {
         state={state}
         facingLeft={facingLeft}
         theme={currentTheme}
+        lowPower={powerSaving}
+        tucked={tucked}
+        emotion={visible ? emotion : mood?.face ?? null}
         onClick={handlePetClick}
         onDragStart={handleDragStart}
         onDrag={handleDrag}
@@ -294,6 +929,113 @@ export default function App() {
           x={position.x}
           y={position.y}
           hiding={hiding}
+          source={loading ? null : source}
+          dark={darkSurroundings}
+          actions={loading ? undefined : reactions.map((reaction) => ({ label: reaction, onClick: () => react(reaction) }))}
+        />
+      )}
+
+      {selection && !visible && !overlayOpen && (
+        <SpeechBubble
+          text="What should I do with that?"
+          x={position.x}
+          y={position.y}
+          hiding={false}
+          dark={darkSurroundings}
+          actions={[
+            { label: "Explain", onClick: () => askAboutSelection("explain") },
+            { label: "Translate", onClick: () => askAboutSelection("translate") },
+            { label: "Summarize", onClick: () => askAboutSelection("summarize") },
+            { label: "Nah", onClick: () => setSelection(null) },
+          ]}
+        />
+      )}
+
+      {linkMessage && !selection && !visible && !overlayOpen && (
+        <SpeechBubble
+          text={`A link wants you to say: "${linkMessage.length > 80 ? `${linkMessage.slice(0, 77)}...` : linkMessage}"`}
+          x={position.x}
+          y={position.y}
+          hiding={false}
+          dark={darkSurroundings}
+          actions={[
+            {
+              label: "Say it",
+              onClick: () => {
+                generate("chat", `user said: ${linkMessage}`, linkMessage);
+                setLinkMessage(null);
+              },
+            },
+            { label: "Nah", onClick: () => setLinkMessage(null) },
+          ]}
+        />
+      )}
+
+      {toolPrompt.request && !menuOpen && (
+        <SpeechBubble
+          text={`${toolPrompt.request.summary}?`}
+          x={position.x}
+          y={position.y}
+          hiding={false}
+          dark={darkSurroundings}
+          actions={[
+            { label: "Go ahead", onClick: () => toolPrompt.answer(true) },
+            { label: "Always", onClick: () => toolPrompt.answer(true, true) },
+            { label: "Nah", onClick: () => toolPrompt.answer(false) },
+          ]}
+        />
+      )}
+
+      {dictating && !visible && !overlayOpen && (
+        <SpeechBubble
+          text="I'm listening... what should I type?"
+          x={position.x}
+          y={position.y}
+          hiding={false}
+          dark={darkSurroundings}
+        />
+      )}
+
+      {dictationDraft && !dictating && !visible && !overlayOpen && (
+        <SpeechBubble
+          text={`Type "${dictationDraft.preview}" into ${dictationDraft.appName}?`}
+          x={position.x}
+          y={position.y}
+          hiding={false}
+          dark={darkSurroundings}
+          actions={[
+            { label: "Type it", onClick: confirmDictation },
+            { label: "Nah", onClick: discardDictation },
+          ]}
+        />
+      )}
+
+      {clipOffer && !selection && !dictationDraft && !linkMessage && !visible && !overlayOpen && (
+        <SpeechBubble
+          text={clipOffer.suggestion}
+          x={position.x}
+          y={position.y}
+          hiding={false}
+          dark={darkSurroundings}
+          actions={[
+            { label: "Sure", onClick: acceptClipOffer },
+            { label: "Nah", onClick: declineClipOffer },
+          ]}
+        />
+      )}
+
+      {screenshotOffer && !clipOffer && !selection && !dictationDraft && !linkMessage && !visible && !overlayOpen && (
+        <SpeechBubble
+          text="Ooh, a screenshot. Want me to take a look?"
+          x={position.x}
+          y={position.y}
+          hiding={false}
+          dark={darkSurroundings}
+          actions={[
+            { label: "What do you see?", onClick: () => acceptScreenshotOffer("remark") },
+            { label: "Read the text", onClick: () => acceptScreenshotOffer("text") },
+            { label: "Nah", onClick: declineScreenshotOffer },
+          ]}
         />
       )}
 
@@ -309,12 +1051,15 @@ export default function App() {
 
       {paletteOpen && (
         <CommandPalette
-          x={position.x}
-          y={position.y}
+          x={paletteAnchor?.x ?? position.x}
+          y={paletteAnchor?.y ?? position.y}
           musicPlaying={musicPlaying}
           onExecute={handleMenuSelect}
           onChat={handlePaletteChat}
           onSearch={handlePaletteSearch}
+          onTranslate={handlePaletteTranslate}
+          onOpenFile={handlePaletteOpenFile}
+          onOpen={handlePaletteOpen}
           onClose={() => setPaletteOpen(false)}
         />
       )}
@@ -370,7 +1115,10 @@ export default function App() {
           onAddFriend={addFriend}
           onAcceptFriend={acceptFriend}
           onRemoveFriend={removeFriend}
+          onBlockFriend={blockFriend}
           onHangout={startHangout}
+          coworking={cowork.session !== null}
+          onCowork={startCowork}
           onClose={() => setFriendsOpen(false)}
         />
       )}
@@ -386,6 +1134,16 @@ export default function App() {
         />
       )}
 
+      {startupOpen && startup.report && (
+        <StartupChecklist
+          report={startup.report}
+          fixing={startup.fixing}
+          error={startup.error}
+          onFix={startup.runFix}
+          onClose={startup.close}
+        />
+      )}
+
       {notesVisible && notes.length > 0 && !notesOpen && notes.map((note) => (
         <StickyNoteComponent
           key={note.id}
@@ -410,6 +1168,16 @@ export default function App() {
         />
       )}
 
+      {cowork.session && (
+        <CoworkBuddy
+          session={cowork.session}
+          petX={position.x}
+          petY={position.y}
+          onStop={cowork.stop}
+          onReact={cowork.react}
+        />
+      )}
+
       {newlyUnlocked.length > 0 && (
         <AchievementToast
           unlock={newlyUnlocked[0]}
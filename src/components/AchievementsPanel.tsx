@@ -1,4 +1,5 @@
-import { useEffect } from "react";
+import { useEffect, useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { AchievementDef, UnlockedMap } from "../hooks/useAchievements";
 import { EventData } from "../hooks/useEventTracker";
 import "../styles/achievements.css";
@@ -19,6 +20,24 @@ const TIER_LABELS: Record<string, string> = {
 
 const TIER_ORDER = ["bronze", "silver", "gold"];
 
+interface LeaderboardEntry {
+  rank: number;
+  playtimeMinutes: number;
+  achievements: number;
+  you: boolean;
+}
+
+interface Leaderboard {
+  enabled: boolean;
+  entries: LeaderboardEntry[];
+  playtimeMinutes: number;
+  achievements: number;
+}
+
+function formatPlaytime(minutes: number): string {
+  return minutes < 60 ? `${minutes}m` : `${Math.floor(minutes / 60)}h`;
+}
+
 function metricForAchievement(id: string, data: EventData): number {
   switch (id) {
     case "chatterbox": return data.chats;
@@ -48,6 +67,24 @@ export default function AchievementsPanel({
     onOpen();
   }, []);
 
+  const [leaderboard, setLeaderboard] = useState<Leaderboard | null>(null);
+  const [leaderboardError, setLeaderboardError] = useState<string | null>(null);
+  const loadLeaderboard = useCallback(() => {
+    invoke<Leaderboard>("get_leaderboard")
+      .then((board) => {
+        setLeaderboard(board);
+        setLeaderboardError(null);
+      })
+      .catch((e) => setLeaderboardError(e?.message ?? String(e)));
+  }, []);
+  useEffect(loadLeaderboard, [loadLeaderboard]);
+
+  const toggleLeaderboard = (enabled: boolean) => {
+    invoke("set_leaderboard_enabled", { enabled })
+      .then(loadLeaderboard)
+      .catch((e) => setLeaderboardError(e?.message ?? String(e)));
+  };
+
   const unlockedCount = Object.keys(unlocked).length;
 
   return (
@@ -116,6 +153,36 @@ export default function AchievementsPanel({
             );
           })}
         </div>
+
+        <div className="leaderboard">
+          <div className="leaderboard-header">
+            <span className="leaderboard-title">Leaderboard</span>
+            <label className="leaderboard-toggle">
+              <input
+                type="checkbox"
+                checked={leaderboard?.enabled ?? false}
+                onChange={(e) => toggleLeaderboard(e.target.checked)}
+              />
+              Join anonymously
+            </label>
+          </div>
+          {leaderboardError && <div className="leaderboard-note">{leaderboardError}</div>}
+          {leaderboard && !leaderboard.enabled && (
+            <div className="leaderboard-note">
+              Only a random id, your playtime ({formatPlaytime(leaderboard.playtimeMinutes)}) and
+              trophy count are shared. Leaving deletes them.
+            </div>
+          )}
+          {leaderboard?.enabled &&
+            leaderboard.entries.map((entry) => (
+              <div key={entry.rank} className={`leaderboard-row ${entry.you ? "you" : ""}`}>
+                <span className="leaderboard-rank">#{entry.rank}</span>
+                <span className="leaderboard-name">{entry.you ? "Your cat" : "A cat"}</span>
+                <span className="leaderboard-stat">🏆 {entry.achievements}</span>
+                <span className="leaderboard-stat">⏱ {formatPlaytime(entry.playtimeMinutes)}</span>
+              </div>
+            ))}
+        </div>
       </div>
     </div>
   );
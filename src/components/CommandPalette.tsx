@@ -1,4 +1,5 @@
 import { useState, useEffect, useRef, useMemo } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import type { MenuAction } from "./RadialMenu";
 import { commands, Command } from "../lib/commands";
 import { fuzzyMatch } from "../lib/fuzzyMatch";
@@ -11,11 +12,21 @@ interface CommandPaletteProps {
   onExecute: (action: MenuAction) => void;
   onChat: (text: string) => void;
   onSearch: (query: string) => void;
+  onTranslate: (text: string, target: string | null) => void;
+  onOpenFile: (path: string) => void;
+  onOpen: (target: string) => void;
   onClose: () => void;
 }
 
+interface FileHit {
+  path: string;
+  name: string;
+  folder: string;
+  modified: number | null;
+}
+
 export default function CommandPalette({
-  x, y, musicPlaying, onExecute, onChat, onSearch, onClose,
+  x, y, musicPlaying, onExecute, onChat, onSearch, onTranslate, onOpenFile, onOpen, onClose,
 }: CommandPaletteProps) {
   const [query, setQuery] = useState("");
   const [selectedIndex, setSelectedIndex] = useState(0);
@@ -67,10 +78,32 @@ export default function CommandPalette({
     return scored.map((s) => s.cmd);
   }, [query, activeCommands, argumentMatch]);
 
+  // "files <query>" searches as you type, once typing pauses
+  const fileQuery = argumentMatch?.command.id === "files" ? argumentMatch.argument.trim() : "";
+  const [fileHits, setFileHits] = useState<FileHit[]>([]);
+  useEffect(() => {
+    if (!fileQuery) {
+      setFileHits([]);
+      return;
+    }
+    let stale = false;
+    const timer = setTimeout(() => {
+      invoke<FileHit[]>("search_files", { query: fileQuery })
+        .then((hits) => { if (!stale) setFileHits(hits); })
+        .catch(() => { if (!stale) setFileHits([]); });
+    }, 250);
+    return () => {
+      stale = true;
+      clearTimeout(timer);
+    };
+  }, [fileQuery]);
+  const showingFiles = fileQuery.length > 0 && fileHits.length > 0;
+  const itemCount = showingFiles ? fileHits.length : results.length;
+
   // Reset selection when query changes
   useEffect(() => {
     setSelectedIndex(0);
-  }, [query]);
+  }, [query, fileHits]);
 
   // Scroll selected item into view
   useEffect(() => {
@@ -87,6 +120,12 @@ export default function CommandPalette({
       if (arg) {
         if (cmd.id === "chat") onChat(arg);
         else if (cmd.id === "search") onSearch(arg);
+        else if (cmd.id === "open") onOpen(arg);
+        else if (cmd.id === "translate") {
+          // "translate es: text" picks the language; otherwise the pet's own
+          const m = arg.match(/^([a-z]{2,3}(?:-[a-z0-9]+)*):\s*([\s\S]+)$/i);
+          onTranslate(m ? m[2] : arg, m ? m[1] : null);
+        }
         return;
       }
     }
@@ -102,26 +141,28 @@ export default function CommandPalette({
     switch (e.key) {
       case "ArrowDown":
         e.preventDefault();
-        if (results.length > 0) {
-          setSelectedIndex((prev) => (prev + 1) % results.length);
+        if (itemCount > 0) {
+          setSelectedIndex((prev) => (prev + 1) % itemCount);
         }
         break;
       case "ArrowUp":
         e.preventDefault();
-        if (results.length > 0) {
-          setSelectedIndex((prev) => (prev - 1 + results.length) % results.length);
+        if (itemCount > 0) {
+          setSelectedIndex((prev) => (prev - 1 + itemCount) % itemCount);
         }
         break;
       case "Tab":
         e.preventDefault();
-        if (results.length > 0) {
+        if (!showingFiles && results.length > 0) {
           const cmd = results[selectedIndex];
           setQuery(cmd.label.toLowerCase() + (cmd.takesArgument ? " " : ""));
         }
         break;
       case "Enter":
         e.preventDefault();
-        if (results.length > 0) {
+        if (showingFiles) {
+          onOpenFile(fileHits[selectedIndex].path);
+        } else if (results.length > 0) {
           execute(results[selectedIndex]);
         } else {
           // Fallback: send as chat
@@ -169,7 +210,20 @@ export default function CommandPalette({
             />
           </div>
           <div className="command-palette-results" ref={listRef}>
-            {results.map((cmd, i) => (
+            {showingFiles && fileHits.map((hit, i) => (
+              <div
+                key={hit.path}
+                className={`command-palette-item${i === selectedIndex ? " selected" : ""}`}
+                title={hit.path}
+                onMouseEnter={() => setSelectedIndex(i)}
+                onClick={() => onOpenFile(hit.path)}
+              >
+                <span className="command-palette-item-icon">📄</span>
+                <span className="command-palette-item-label">{hit.name}</span>
+                <span className="command-palette-item-hint">{hit.folder}</span>
+              </div>
+            ))}
+            {!showingFiles && results.map((cmd, i) => (
               <div
                 key={cmd.id}
                 className={`command-palette-item${i === selectedIndex ? " selected" : ""}`}
@@ -0,0 +1,52 @@
+import { getSpritePaths, Breed, Color } from "../hooks/useTheme";
+import { CoworkSession, COWORK_REACTIONS } from "../hooks/useCowork";
+
+interface CoworkBuddyProps {
+  session: CoworkSession;
+  petX: number;
+  petY: number;
+  onStop: () => void;
+  onReact: (emoji: string) => void;
+}
+
+/** The friend's cat, napping beside ours for a co-working session. */
+export default function CoworkBuddy({ session, petX, petY, onStop, onReact }: CoworkBuddyProps) {
+  const paths = getSpritePaths(session.friend.breed as Breed, session.friend.color as Color);
+  const active = session.phase === "active";
+  // Sit on whichever side has room
+  const left = petX + 72 + 64 > window.innerWidth ? petX - 72 : petX + 72;
+  const minutesLeft = Math.max(0, Math.ceil((session.endsAt - Date.now()) / 60000));
+
+  return (
+    <div className="cowork-buddy" style={{ left: left - 32, top: petY - 32 }}>
+      <div
+        className={`visiting-pet-sprite ${active ? "nap" : "idle"}`}
+        style={{ backgroundImage: `url(${active ? paths.sleep : paths.idle})` }}
+        title={active ? `Co-working with ${session.friend.name}, ${minutesLeft} min left` : undefined}
+      />
+      {active && <span className="nap-zzz">z</span>}
+      <div className="visiting-pet-nametag">{session.friend.name}</div>
+      {active ? (
+        <button className="cowork-stop-btn" onClick={onStop} title="End the session for both of you">
+          Stop
+        </button>
+      ) : (
+        <div className="cowork-reactions">
+          {session.friendReaction && <span className="cowork-friend-reaction">{session.friendReaction}</span>}
+          {session.myReaction ? (
+            <span className="cowork-sent">Sent {session.myReaction}</span>
+          ) : (
+            COWORK_REACTIONS.map((emoji) => (
+              <button key={emoji} className="cowork-reaction-btn" onClick={() => onReact(emoji)}>
+                {emoji}
+              </button>
+            ))
+          )}
+          <button className="cowork-stop-btn" onClick={onStop} title="Done">
+            x
+          </button>
+        </div>
+      )}
+    </div>
+  );
+}
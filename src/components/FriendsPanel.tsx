@@ -16,7 +16,10 @@ interface FriendsPanelProps {
   onAddFriend: (code: string) => Promise<{ ok: boolean; error?: string }>;
   onAcceptFriend: (id: string) => void;
   onRemoveFriend: (id: string) => void;
+  onBlockFriend: (id: string, name: string) => void;
   onHangout: (id: string) => void;
+  coworking: boolean;
+  onCowork: (id: string) => void;
   onClose: () => void;
 }
 
@@ -33,20 +36,24 @@ export default function FriendsPanel({
   onAddFriend,
   onAcceptFriend,
   onRemoveFriend,
+  onBlockFriend,
   onHangout,
+  coworking,
+  onCowork,
   onClose,
 }: FriendsPanelProps) {
   const [addCode, setAddCode] = useState("");
   const [addMessage, setAddMessage] = useState<{ text: string; type: "error" | "success" } | null>(null);
   const [adding, setAdding] = useState(false);
-  const [copied, setCopied] = useState(false);
+  const [copied, setCopied] = useState<"code" | "link" | null>(null);
   const [editingName, setEditingName] = useState(myPetName);
 
-  const handleCopy = useCallback(() => {
+  const handleCopy = useCallback((what: "code" | "link") => {
     if (!myPetCode) return;
-    navigator.clipboard.writeText(myPetCode);
-    setCopied(true);
-    setTimeout(() => setCopied(false), 2000);
+    // Invite links open the app and add the friend via the desktoppet:// scheme
+    navigator.clipboard.writeText(what === "link" ? `desktoppet://friend/${myPetCode}` : myPetCode);
+    setCopied(what);
+    setTimeout(() => setCopied(null), 2000);
   }, [myPetCode]);
 
   const handleAddFriend = useCallback(async () => {
@@ -114,10 +121,16 @@ export default function FriendsPanel({
             <div className="friends-code-row">
               <span className="friends-code-display">{myPetCode}</span>
               <button
-                className={`friends-copy-btn ${copied ? "copied" : ""}`}
-                onClick={handleCopy}
+                className={`friends-copy-btn ${copied === "code" ? "copied" : ""}`}
+                onClick={() => handleCopy("code")}
               >
-                {copied ? "Copied!" : "Copy"}
+                {copied === "code" ? "Copied!" : "Copy"}
+              </button>
+              <button
+                className={`friends-copy-btn ${copied === "link" ? "copied" : ""}`}
+                onClick={() => handleCopy("link")}
+              >
+                {copied === "link" ? "Copied!" : "Copy Link"}
               </button>
             </div>
             <div className="friends-name-row">
@@ -211,6 +224,22 @@ export default function FriendsPanel({
                           Hangout
                         </button>
                       )}
+                      {friend.status === "mutual" && friend.online && !coworking && (
+                        <button
+                          className="friend-visit-btn"
+                          title="Focus together for 25 minutes; their cat naps next to yours"
+                          onClick={() => onCowork(friend.id)}
+                        >
+                          Co-work
+                        </button>
+                      )}
+                      <button
+                        className="friend-block-btn"
+                        title="Block: drop their visits and messages"
+                        onClick={() => onBlockFriend(friend.id, friend.name)}
+                      >
+                        Block
+                      </button>
                       <button
                         className="friend-remove-btn"
                         onClick={() => onRemoveFriend(friend.id)}
@@ -1,4 +1,5 @@
-import { useEffect } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { JournalEntry } from "../hooks/useJournal";
 import "../styles/journal.css";
 
@@ -11,6 +12,19 @@ interface JournalPanelProps {
   onClose: () => void;
 }
 
+interface WeeklyDigest {
+  weekStart: string;
+  weekEnd: string;
+  text: string;
+  entries: number;
+  generatedAt: number;
+}
+
+interface JournalExport {
+  folder: string;
+  files: number;
+}
+
 function formatDate(dateStr: string): string {
   const date = new Date(dateStr + "T12:00:00");
   return date.toLocaleDateString("en-US", {
@@ -38,6 +52,46 @@ export default function JournalPanel({
 
   const sorted = [...entries].sort((a, b) => b.date.localeCompare(a.date));
 
+  const [digest, setDigest] = useState<WeeklyDigest | null>(null);
+  const [digesting, setDigesting] = useState(false);
+  const [folder, setFolder] = useState("");
+  const [status, setStatus] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<WeeklyDigest[]>("get_weekly_digests")
+      .then((digests) => setDigest(digests[digests.length - 1] ?? null))
+      .catch(() => {});
+    invoke<{ journal: { exportFolder?: string | null } }>("get_settings")
+      .then((settings) => setFolder(settings.journal.exportFolder ?? ""))
+      .catch(() => {});
+  }, []);
+
+  const generateDigest = async () => {
+    setDigesting(true);
+    setStatus(null);
+    try {
+      setDigest(await invoke<WeeklyDigest>("generate_weekly_digest", { entries }));
+    } catch (e) {
+      setStatus(String(e));
+    } finally {
+      setDigesting(false);
+    }
+  };
+
+  const exportJournal = async (format: "markdown" | "html") => {
+    setStatus(null);
+    try {
+      const result = await invoke<JournalExport>("export_journal", {
+        format,
+        entries,
+        folder: folder.trim() || null,
+      });
+      setStatus(`Saved ${result.files} ${result.files === 1 ? "file" : "files"} to ${result.folder}`);
+    } catch (e) {
+      setStatus(String(e));
+    }
+  };
+
   return (
     <div className="journal-overlay" onClick={onClose}>
       <div className="journal-panel" onClick={(e) => e.stopPropagation()}>
@@ -52,6 +106,34 @@ export default function JournalPanel({
           <div className="journal-loading">Writing today's entry...</div>
         )}
 
+        <div className="journal-tools">
+          <button className="journal-tool" onClick={generateDigest} disabled={digesting || entries.length === 0}>
+            {digesting ? "Looking back..." : "This week's digest"}
+          </button>
+          <div className="journal-export">
+            <input
+              className="journal-folder"
+              value={folder}
+              onChange={(e) => setFolder(e.target.value)}
+              placeholder="/Users/you/Obsidian/Cat"
+            />
+            <button className="journal-tool" onClick={() => exportJournal("markdown")} disabled={entries.length === 0}>
+              Markdown
+            </button>
+            <button className="journal-tool" onClick={() => exportJournal("html")} disabled={entries.length === 0}>
+              HTML
+            </button>
+          </div>
+          {status && <div className="journal-status">{status}</div>}
+        </div>
+
+        {digest && (
+          <div className="journal-entry journal-digest">
+            <div className="journal-date">Week of {formatDate(digest.weekStart)}</div>
+            <div className="journal-text">{digest.text}</div>
+          </div>
+        )}
+
         <div className="journal-entries">
           {sorted.map((entry) => (
             <div key={entry.date} className="journal-entry">
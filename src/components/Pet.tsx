@@ -1,6 +1,7 @@
 import { useRef, useCallback } from "react";
 import { PetState } from "../hooks/usePetMovement";
 import { SpriteTheme } from "../hooks/useTheme";
+import type { Emotion } from "../hooks/useDialogue";
 import "../styles/pet.css";
 
 interface PetProps {
@@ -9,13 +10,25 @@ interface PetProps {
   state: PetState;
   facingLeft: boolean;
   theme: SpriteTheme;
+  /** Low battery: animate at half speed */
+  lowPower?: boolean;
+  /** Another app is fullscreen: curl up small */
+  tucked?: boolean;
+  /** How the cat feels about what it's saying */
+  emotion?: Emotion | null;
   onClick: () => void;
   onDragStart: () => void;
   onDrag: (x: number, y: number) => void;
   onDragEnd: () => void;
 }
 
-function getSpriteUrl(state: PetState, theme: SpriteTheme): string {
+/** Standing still while talking is where an emotion shows. */
+function showsEmotion(state: PetState): boolean {
+  return state === "idle" || state === "talking";
+}
+
+function getSpriteUrl(state: PetState, theme: SpriteTheme, emotion?: Emotion | null): string {
+  if (emotion === "sleepy" && showsEmotion(state)) return theme.sleep;
   switch (state) {
     case "walking":
       return theme.walk;
@@ -27,7 +40,10 @@ function getSpriteUrl(state: PetState, theme: SpriteTheme): string {
   }
 }
 
-function getAnimClass(state: PetState): string {
+function getAnimClass(state: PetState, emotion?: Emotion | null): string {
+  if (emotion && showsEmotion(state)) {
+    return emotion === "sleepy" ? "nap" : `idle ${emotion}`;
+  }
   switch (state) {
     case "walking":
       return "walk";
@@ -40,11 +56,11 @@ function getAnimClass(state: PetState): string {
 }
 
 export default function Pet({
-  x, y, state, facingLeft, theme,
+  x, y, state, facingLeft, theme, lowPower, tucked, emotion,
   onClick, onDragStart, onDrag, onDragEnd,
 }: PetProps) {
-  const animClass = getAnimClass(state);
-  const spriteUrl = getSpriteUrl(state, theme);
+  const animClass = getAnimClass(state, emotion);
+  const spriteUrl = getSpriteUrl(state, theme, emotion);
   const isDragging = useRef(false);
   const didDrag = useRef(false);
   const startPos = useRef({ mx: 0, my: 0 });
@@ -84,12 +100,12 @@ export default function Pet({
 
   return (
     <div
-      className={`pet-container ${isDragging.current ? "dragging" : ""}`}
+      className={`pet-container ${isDragging.current ? "dragging" : ""} ${tucked ? "tucked" : ""}`}
       style={{ left: x - 32, top: y - 32 }}
       onMouseDown={handleMouseDown}
     >
       <div
-        className={`pet-sprite ${animClass} ${facingLeft ? "flip" : ""}`}
+        className={`pet-sprite ${animClass} ${facingLeft ? "flip" : ""} ${lowPower ? "low-power" : ""}`}
         style={{ backgroundImage: `url(${spriteUrl})` }}
       />
       {(state === "napping" || state === "home") && (
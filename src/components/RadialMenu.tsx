@@ -1,6 +1,6 @@
 import "../styles/menu.css";
 
-export type MenuAction = "chat" | "search" | "music" | "nap" | "home" | "settings" | "journal" | "achievements" | "friends" | "notes";
+export type MenuAction = "chat" | "search" | "music" | "nap" | "home" | "settings" | "journal" | "achievements" | "friends" | "notes" | "wrapped" | "card" | "perch" | "dock" | "chase" | "report";
 
 interface MenuItem {
   action: MenuAction;
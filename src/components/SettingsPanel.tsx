@@ -1,5 +1,7 @@
 import { useRef, useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useSound, Soundscape, SOUNDSCAPE_LABELS } from "../hooks/useSound";
 import {
   SpriteTheme,
   Breed,
@@ -10,6 +12,7 @@ import {
   COLOR_LABELS,
   getSpritePaths,
 } from "../hooks/useTheme";
+import { SyncTarget, syncNow, useSyncStatus } from "../hooks/useSync";
 import "../styles/settings.css";
 
 const DEFAULT_SHORTCUT = "CommandOrControl+Shift+Space";
@@ -64,6 +67,86 @@ function buildShortcutString(e: KeyboardEvent): string | null {
   return [...modifiers, key].join("+");
 }
 
+interface Keybinding {
+  action: string;
+  accelerator: string | null;
+  description: string;
+  registered: boolean;
+}
+
+interface Display {
+  id: string;
+  primary: boolean;
+  home: boolean;
+  allowed: boolean;
+  current: boolean;
+}
+
+interface PersonalityInfo {
+  id: string;
+  name: string;
+  description: string;
+  active: boolean;
+  error: string | null;
+}
+
+interface Language {
+  tag: string;
+  name: string;
+}
+
+interface LanguageStatus {
+  setting: string | null;
+  detected: Language;
+  current: Language;
+  choices: Language[];
+}
+
+interface PetProfile {
+  id: string;
+  name: string;
+  createdAt: number;
+}
+
+interface ChatSessionInfo {
+  id: string;
+  title: string;
+  startedAt: number | null;
+  lastAt: number | null;
+  messageCount: number;
+  active: boolean;
+}
+
+type ToolPermission = "allow" | "ask" | "deny";
+
+interface ToolInfo {
+  name: string;
+  label: string;
+  permission: ToolPermission;
+}
+
+interface ToolAuditEntry {
+  at: number;
+  tool: string;
+  summary: string;
+  outcome: "ran" | "failed" | "denied";
+  detail: string | null;
+}
+
+interface GithubStatus {
+  connected: boolean;
+  login: string | null;
+  pulls: { repo: string; number: number; title: string }[];
+}
+
+interface DevWatcherStatus {
+  command: string | null;
+  directory: string | null;
+  watch: boolean;
+  running: boolean;
+  lastRun: { success: boolean; summary: string | null; finishedAt: number } | null;
+}
+
 interface SettingsPanelProps {
   currentBreed: Breed;
   currentColor: Color;
@@ -98,11 +181,339 @@ export default function SettingsPanel({
   const recorderInputRef = useRef<HTMLInputElement>(null);
   const [memoryStats, setMemoryStats] = useState({ messageCount: 0, factCount: 0 });
   const [confirmingClear, setConfirmingClear] = useState(false);
+  const [confirmingWipe, setConfirmingWipe] = useState(false);
 
   useEffect(() => {
     invoke<{ messageCount: number; factCount: number }>("get_memory_stats").then(setMemoryStats);
   }, []);
 
+  // Conversations, newest first; the cat only sees the active one
+  const [sessions, setSessions] = useState<ChatSessionInfo[]>([]);
+  const refreshSessions = () => {
+    invoke<ChatSessionInfo[]>("list_sessions").then(setSessions).catch(() => {});
+    invoke<{ messageCount: number; factCount: number }>("get_memory_stats").then(setMemoryStats);
+  };
+  useEffect(refreshSessions, []);
+  // The household's cats; each has its own memory, facts and mood
+  const [pets, setPets] = useState<{ pets: PetProfile[]; active: string } | null>(null);
+  const [newPetName, setNewPetName] = useState("");
+  const refreshPets = () => {
+    invoke<{ pets: PetProfile[]; active: string }>("list_pets").then(setPets).catch(() => {});
+  };
+  useEffect(refreshPets, []);
+  const switchPet = (id: string) => {
+    invoke("switch_pet", { id })
+      .then(() => {
+        refreshPets();
+        refreshSessions();
+      })
+      .catch(() => {});
+  };
+  const addPet = () => {
+    const name = newPetName.trim();
+    if (!name) return;
+    invoke<PetProfile>("create_pet", { name })
+      .then((pet) => {
+        setNewPetName("");
+        switchPet(pet.id);
+      })
+      .catch(() => {});
+  };
+
+  // Sync between your machines; the form is only shown while it's off
+  const syncStatus = useSyncStatus();
+  const [syncKind, setSyncKind] = useState<SyncTarget["kind"]>("relay");
+  const [syncFields, setSyncFields] = useState<Record<string, string>>({});
+  const [syncPassphrase, setSyncPassphrase] = useState("");
+  const [syncSecret, setSyncSecret] = useState("");
+  const [syncError, setSyncError] = useState<string | null>(null);
+  const syncField = (name: string) => syncFields[name] ?? "";
+  const setSyncField = (name: string, value: string) =>
+    setSyncFields((fields) => ({ ...fields, [name]: value }));
+  const syncTarget = (): SyncTarget => {
+    switch (syncKind) {
+      case "webDav":
+        return { kind: "webDav", url: syncField("url"), username: syncField("username") };
+      case "s3":
+        return {
+          kind: "s3",
+          endpoint: syncField("endpoint"),
+          region: syncField("region"),
+          bucket: syncField("bucket"),
+          accessKeyId: syncField("accessKeyId"),
+        };
+      default:
+        return { kind: "relay", code: syncField("code") };
+    }
+  };
+  const enableSync = () => {
+    setSyncError(null);
+    invoke("enable_sync", {
+      target: syncTarget(),
+      passphrase: syncPassphrase,
+      secret: syncKind === "relay" ? null : syncSecret,
+    })
+      .then(() => {
+        setSyncPassphrase("");
+        setSyncSecret("");
+        return syncNow();
+      })
+      .catch((e) => setSyncError(e?.message ?? String(e)));
+  };
+  const syncFieldInputs: Record<SyncTarget["kind"], [string, string][]> = {
+    relay: [["code", "Sync code from your other Mac (blank for the first)"]],
+    webDav: [["url", "https://dav.example.com/pet"], ["username", "Username"]],
+    s3: [
+      ["endpoint", "https://s3.us-east-1.amazonaws.com"],
+      ["region", "Region, e.g. us-east-1"],
+      ["bucket", "Bucket"],
+      ["accessKeyId", "Access key id"],
+    ],
+  };
+
+  // Facts the model thinks have run their course, waiting for a yes
+  const [factProposals, setFactProposals] = useState<{ text: string; reason: string }[] | null>(null);
+  const [reviewingFacts, setReviewingFacts] = useState(false);
+  const reviewFacts = () => {
+    setReviewingFacts(true);
+    invoke<{ text: string; reason: string }[]>("review_facts")
+      .then(setFactProposals)
+      .catch(() => setFactProposals(null))
+      .finally(() => setReviewingFacts(false));
+  };
+  const archiveProposedFacts = () => {
+    const texts = (factProposals ?? []).map((proposal) => proposal.text);
+    invoke("archive_facts", { texts }).then(refreshSessions).catch(() => {});
+    setFactProposals(null);
+  };
+  const switchSession = (command: "start_new_session" | "load_session", id?: string) => {
+    invoke(command, id ? { id } : {}).then(refreshSessions).catch(() => {});
+  };
+
+  // What the backend actually registered, so this list can't drift from it
+  const [keybindings, setKeybindings] = useState<Keybinding[]>([]);
+  useEffect(() => {
+    invoke<Keybinding[]>("get_keybindings").then(setKeybindings).catch(() => {});
+    const unlisten = listen<Keybinding[]>("keybindings-changed", (event) => {
+      setKeybindings(event.payload);
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  const [displays, setDisplays] = useState<Display[]>([]);
+  useEffect(() => {
+    invoke<Display[]>("get_displays").then(setDisplays).catch(() => {});
+    const unlisten = listen<Display[]>("displays-changed", (event) => {
+      setDisplays(event.payload);
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  const [perchSide, setPerchSide] = useState<"left" | "right">("right");
+  const [dictation, setDictation] = useState(false);
+  const [screenshots, setScreenshots] = useState({ enabled: false, remark: false });
+  const [opacity, setOpacity] = useState(1);
+  const [exclusions, setExclusions] = useState("");
+  const [allowedApps, setAllowedApps] = useState("");
+  const [allowedSites, setAllowedSites] = useState("");
+  const [weatherLocation, setWeatherLocation] = useState("");
+  const [fahrenheit, setFahrenheit] = useState(false);
+  const [weatherError, setWeatherError] = useState<string | null>(null);
+  useEffect(() => {
+    invoke<{
+      display: { perchSide: "left" | "right"; opacity: number };
+      dictation: { enabled: boolean };
+      screenshots: { enabled: boolean; remark: boolean };
+      windows: { exclude: string[] };
+      tools: { apps: string[]; sites: string[] };
+      weather: { location: { name: string } | null; fahrenheit: boolean };
+    }>("get_settings")
+      .then((settings) => {
+        setPerchSide(settings.display.perchSide);
+        setOpacity(settings.display.opacity);
+        setDictation(settings.dictation.enabled);
+        setScreenshots(settings.screenshots);
+        setExclusions(settings.windows.exclude.join("\n"));
+        setAllowedApps(settings.tools.apps.join("\n"));
+        setAllowedSites(settings.tools.sites.join("\n"));
+        setWeatherLocation(settings.weather.location?.name ?? "");
+        setFahrenheit(settings.weather.fahrenheit);
+      })
+      .catch(() => {});
+  }, []);
+
+  const [personalities, setPersonalities] = useState<PersonalityInfo[]>([]);
+  const [personalityDir, setPersonalityDir] = useState("");
+  useEffect(() => {
+    invoke<PersonalityInfo[]>("get_personalities").then(setPersonalities).catch(() => {});
+    invoke<string>("get_personalities_dir").then(setPersonalityDir).catch(() => {});
+  }, []);
+
+  const [language, setLanguage] = useState<LanguageStatus | null>(null);
+  useEffect(() => {
+    invoke<LanguageStatus>("get_language").then(setLanguage).catch(() => {});
+  }, []);
+
+  const changeLanguage = (tag: string | null) => {
+    invoke<LanguageStatus>("set_language", { tag }).then(setLanguage).catch(() => {});
+  };
+
+  const changePersonality = (id: string | null) => {
+    invoke<PersonalityInfo[]>("set_personality", { id }).then(setPersonalities).catch(() => {});
+  };
+
+  const commitExclusions = () => {
+    invoke<string[]>("set_window_exclusions", { patterns: exclusions.split("\n") })
+      .then((patterns) => setExclusions(patterns.join("\n")))
+      .catch(() => {});
+  };
+
+  const [tools, setTools] = useState<ToolInfo[]>([]);
+  const [toolAudit, setToolAudit] = useState<ToolAuditEntry[]>([]);
+  useEffect(() => {
+    invoke<ToolInfo[]>("get_tools").then(setTools).catch(() => {});
+    invoke<ToolAuditEntry[]>("get_tool_audit").then(setToolAudit).catch(() => {});
+  }, []);
+
+  const changeToolPermission = (tool: string, permission: ToolPermission) => {
+    invoke("set_tool_permission", { tool, permission })
+      .then(() => invoke<ToolInfo[]>("get_tools"))
+      .then(setTools)
+      .catch(() => {});
+  };
+
+  const commitAllowlist = () => {
+    invoke<{ apps: string[]; sites: string[] }>("set_launch_allowlist", {
+      apps: allowedApps.split("\n"),
+      sites: allowedSites.split("\n"),
+    })
+      .then((allowlist) => {
+        setAllowedApps(allowlist.apps.join("\n"));
+        setAllowedSites(allowlist.sites.join("\n"));
+      })
+      .catch(() => {});
+  };
+
+  const [github, setGithub] = useState<GithubStatus | null>(null);
+  const [githubToken, setGithubToken] = useState("");
+  const [githubError, setGithubError] = useState<string | null>(null);
+  useEffect(() => {
+    invoke<GithubStatus>("get_github_status").then(setGithub).catch(() => {});
+  }, []);
+
+  const changeGithubToken = (token: string | null) => {
+    invoke<GithubStatus>("set_github_token", { token })
+      .then((status) => {
+        setGithub(status);
+        setGithubToken("");
+        setGithubError(null);
+      })
+      .catch((e) => setGithubError(String(e)));
+  };
+
+  const [devWatcher, setDevWatcher] = useState<DevWatcherStatus | null>(null);
+  const [devCommand, setDevCommand] = useState("");
+  const [devDirectory, setDevDirectory] = useState("");
+  const [devError, setDevError] = useState<string | null>(null);
+  useEffect(() => {
+    invoke<DevWatcherStatus>("get_dev_watcher")
+      .then((status) => {
+        setDevWatcher(status);
+        setDevCommand(status.command ?? "");
+        setDevDirectory(status.directory ?? "");
+      })
+      .catch(() => {});
+  }, []);
+
+  const commitDevWatcher = (watch: boolean = devWatcher?.watch ?? false) => {
+    invoke<DevWatcherStatus>("set_dev_watcher", {
+      command: devCommand || null,
+      directory: devDirectory || null,
+      watch,
+    })
+      .then((status) => {
+        setDevWatcher(status);
+        setDevError(null);
+      })
+      .catch((e) => setDevError(String(e)));
+  };
+
+  const runDevCommand = () => {
+    setDevWatcher((status) => status && { ...status, running: true });
+    invoke("run_dev_command")
+      .then(() => invoke<DevWatcherStatus>("get_dev_watcher"))
+      .then(setDevWatcher)
+      .catch((e) => setDevError(String(e)));
+  };
+
+  // Looked up by name; the backend keeps the geocoder's spelling
+  const commitWeather = (units: boolean = fahrenheit) => {
+    setFahrenheit(units);
+    invoke<{ location: string } | null>("set_weather", { location: weatherLocation || null, fahrenheit: units })
+      .then((weather) => {
+        setWeatherError(null);
+        if (weather) setWeatherLocation(weather.location);
+      })
+      .catch((e) => setWeatherError(String(e)));
+  };
+
+  const changeDictation = (enabled: boolean) => {
+    setDictation(enabled);
+    invoke("set_dictation_enabled", { enabled }).catch(() => setDictation(!enabled));
+  };
+
+  const changeScreenshots = (next: { enabled: boolean; remark: boolean }) => {
+    const previous = screenshots;
+    setScreenshots(next);
+    invoke("set_screenshot_watch", next).catch(() => setScreenshots(previous));
+  };
+
+  const sound = useSound();
+  const [volume, setVolume] = useState<number | null>(null);
+  const [musicFolder, setMusicFolder] = useState<string | null>(null);
+  const [soundError, setSoundError] = useState<string | null>(null);
+
+  // Saved once the slider is let go, like opacity
+  const commitVolume = () => {
+    if (volume === null) return;
+    invoke("set_volume", { volume })
+      .then(() => setVolume(null))
+      .catch(() => setVolume(null));
+  };
+
+  const commitMusicFolder = () => {
+    if (musicFolder === null) return;
+    invoke("set_music_folder", { folder: musicFolder || null })
+      .then(() => {
+        setMusicFolder(null);
+        setSoundError(null);
+      })
+      .catch((e) => setSoundError(String(e)));
+  };
+
+  // Saved once the slider is let go, not on every step of the drag
+  const commitOpacity = () => {
+    invoke("set_overlay_opacity", { opacity }).catch(() => {});
+  };
+
+  const goGhost = () => {
+    invoke("set_ghost_mode", { enabled: true }).catch(() => {});
+    onClose();
+  };
+
+  const changePerchSide = (side: "left" | "right") => {
+    setPerchSide(side);
+    invoke("set_perch_side", { side }).catch(() => {});
+  };
+
+  const setHomeDisplay = (id: string | null) => {
+    invoke<Display[]>("set_home_display", { id }).then(setDisplays).catch(() => {});
+  };
+
+  const setDisplayAllowed = (id: string, allowed: boolean) => {
+    invoke<Display[]>("set_display_allowed", { id, allowed }).then(setDisplays).catch(() => {});
+  };
+
   const handleImport = () => {
     const idleFile = idleInputRef.current?.files?.[0];
     const walkFile = walkInputRef.current?.files?.[0];
@@ -249,6 +660,487 @@ export default function SettingsPanel({
               Reset to Default
             </button>
           )}
+          <div className="keybinding-list">
+            {keybindings.map((binding) => (
+              <div key={binding.action} className="keybinding-row">
+                <span className="keybinding-description">{binding.description}</span>
+                <span
+                  className={`keybinding-keys ${binding.accelerator && !binding.registered ? "unavailable" : ""}`}
+                  title={binding.accelerator && !binding.registered ? "Taken by another app" : undefined}
+                >
+                  {binding.accelerator ? formatShortcut(binding.accelerator) : "—"}
+                </span>
+              </div>
+            ))}
+          </div>
+        </div>
+
+        {displays.length > 1 && (
+          <div className="display-section">
+            <div className="section-label">Displays</div>
+            {displays.map((display) => (
+              <div key={display.id} className="display-row">
+                <span className="display-name">
+                  {display.id}
+                  {display.primary ? " (main)" : ""}
+                  {display.current ? " \u{1F408}" : ""}
+                </span>
+                <label className="display-option">
+                  <input
+                    type="radio"
+                    name="home-display"
+                    checked={display.home}
+                    disabled={!display.allowed}
+                    onChange={() => setHomeDisplay(display.id)}
+                  />
+                  Home
+                </label>
+                <label className="display-option">
+                  <input
+                    type="checkbox"
+                    checked={display.allowed}
+                    onChange={(e) => setDisplayAllowed(display.id, e.target.checked)}
+                  />
+                  Allowed
+                </label>
+              </div>
+            ))}
+            {displays.some((display) => display.home) && (
+              <button className="shortcut-reset" onClick={() => setHomeDisplay(null)}>
+                No Home Display
+              </button>
+            )}
+          </div>
+        )}
+
+        <div className="display-section">
+          <div className="section-label">Menu Bar Perch</div>
+          <div className="display-row">
+            <label className="display-option">
+              <input
+                type="radio"
+                name="perch-side"
+                checked={perchSide === "left"}
+                onChange={() => changePerchSide("left")}
+              />
+              Next to the menus
+            </label>
+            <label className="display-option">
+              <input
+                type="radio"
+                name="perch-side"
+                checked={perchSide === "right"}
+                onChange={() => changePerchSide("right")}
+              />
+              Next to the clock
+            </label>
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Visibility</div>
+          <div className="display-row">
+            <label className="display-option">
+              Opacity
+              <input
+                type="range"
+                className="opacity-slider"
+                min={20}
+                max={100}
+                step={5}
+                value={Math.round(opacity * 100)}
+                onChange={(e) => setOpacity(Number(e.target.value) / 100)}
+                onPointerUp={commitOpacity}
+                onKeyUp={commitOpacity}
+              />
+              {Math.round(opacity * 100)}%
+            </label>
+          </div>
+          <div className="display-row">
+            <button className="shortcut-reset" onClick={goGhost}>
+              Ghost Mode
+            </button>
+          </div>
+          <div className="personality-hint">
+            See-through and never clickable; turn it off from the menu bar icon
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Personality</div>
+          <div className="display-row">
+            <select
+              className="personality-select"
+              value={personalities.find((p) => p.active)?.id ?? ""}
+              onChange={(e) => changePersonality(e.target.value || null)}
+            >
+              <option value="">Plain cat</option>
+              {personalities.map((p) => (
+                <option key={p.id} value={p.id} disabled={p.error !== null} title={p.error ?? p.description}>
+                  {p.name}
+                  {p.error ? " (can't read file)" : ""}
+                </option>
+              ))}
+            </select>
+          </div>
+          {personalityDir && (
+            <div className="personality-hint" title={personalityDir}>
+              Add or edit packs as .toml files in {personalityDir}
+            </div>
+          )}
+        </div>
+
+        {language && (
+          <div className="display-section">
+            <div className="section-label">Language</div>
+            <div className="display-row">
+              <select
+                className="personality-select"
+                value={language.setting ?? ""}
+                onChange={(e) => changeLanguage(e.target.value || null)}
+              >
+                <option value="">Same as the Mac ({language.detected.name})</option>
+                {language.setting && !language.choices.some((c) => c.tag === language.setting) && (
+                  <option value={language.setting}>{language.current.name}</option>
+                )}
+                {language.choices.map((choice) => (
+                  <option key={choice.tag} value={choice.tag}>
+                    {choice.name}
+                  </option>
+                ))}
+              </select>
+            </div>
+          </div>
+        )}
+
+        <div className="display-section">
+          <div className="section-label">Weather</div>
+          <div className="display-row">
+            <input
+              className="pet-add-input"
+              type="text"
+              value={weatherLocation}
+              placeholder="Your town"
+              onChange={(e) => setWeatherLocation(e.target.value)}
+              onBlur={() => commitWeather()}
+              onKeyDown={(e) => { if (e.key === "Enter") commitWeather(); }}
+            />
+            <label className="display-option">
+              <input
+                type="checkbox"
+                checked={fahrenheit}
+                onChange={(e) => commitWeather(e.target.checked)}
+              />
+              °F
+            </label>
+          </div>
+          {weatherError && <div className="sync-error">{weatherError}</div>}
+          <div className="personality-hint">
+            From Open-Meteo every half hour. Leave empty to skip it
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">GitHub</div>
+          {github?.connected ? (
+            <div className="display-row">
+              <span className="tool-label">
+                Connected{github.login ? ` as ${github.login}` : ""}, following {github.pulls.length} open pull request
+                {github.pulls.length === 1 ? "" : "s"}
+              </span>
+              <button className="memory-confirm-no" onClick={() => changeGithubToken(null)}>
+                Disconnect
+              </button>
+            </div>
+          ) : (
+            <div className="display-row">
+              <input
+                className="pet-add-input"
+                type="password"
+                value={githubToken}
+                placeholder="Personal access token"
+                onChange={(e) => setGithubToken(e.target.value)}
+              />
+              <button
+                className="memory-confirm-no"
+                disabled={!githubToken.trim()}
+                onClick={() => changeGithubToken(githubToken)}
+              >
+                Connect
+              </button>
+            </div>
+          )}
+          {githubError && <div className="sync-error">{githubError}</div>}
+          <div className="personality-hint">
+            The cat cheers when your pull requests are approved and sulks when CI fails. The token stays in the Keychain
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Builds</div>
+          <div className="display-row">
+            <input
+              className="pet-add-input"
+              type="text"
+              value={devCommand}
+              placeholder="cargo build, npm test…"
+              onChange={(e) => setDevCommand(e.target.value)}
+              onBlur={() => commitDevWatcher()}
+              onKeyDown={(e) => { if (e.key === "Enter") commitDevWatcher(); }}
+            />
+          </div>
+          <div className="display-row">
+            <input
+              className="pet-add-input"
+              type="text"
+              value={devDirectory}
+              placeholder="/path/to/project"
+              onChange={(e) => setDevDirectory(e.target.value)}
+              onBlur={() => commitDevWatcher()}
+              onKeyDown={(e) => { if (e.key === "Enter") commitDevWatcher(); }}
+            />
+          </div>
+          <div className="display-row">
+            <label className="display-option">
+              <input
+                type="checkbox"
+                checked={devWatcher?.watch ?? false}
+                onChange={(e) => commitDevWatcher(e.target.checked)}
+              />
+              Run when files change
+            </label>
+            <button
+              className="memory-confirm-no"
+              disabled={!devWatcher?.command || !devWatcher?.directory || devWatcher.running}
+              onClick={runDevCommand}
+            >
+              {devWatcher?.running ? "Running…" : "Run now"}
+            </button>
+          </div>
+          {devWatcher?.lastRun && (
+            <div className="tool-audit">
+              Last run {devWatcher.lastRun.success ? "passed" : "failed"}
+              {devWatcher.lastRun.summary ? `: ${devWatcher.lastRun.summary}` : ""}
+            </div>
+          )}
+          {devError && <div className="sync-error">{devError}</div>}
+          <div className="personality-hint">
+            The cat celebrates green builds and flops over when they fail
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Ignored Apps</div>
+          <textarea
+            className="exclusion-list"
+            rows={4}
+            value={exclusions}
+            onChange={(e) => setExclusions(e.target.value)}
+            onBlur={commitExclusions}
+          />
+          <div className="personality-hint">
+            One app per line, * for anything. Never perched on or logged
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Dictation</div>
+          <div className="display-row">
+            <label className="display-option">
+              <input
+                type="checkbox"
+                checked={dictation}
+                onChange={(e) => changeDictation(e.target.checked)}
+              />
+              Type what I tell the cat (asks first, every time)
+            </label>
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Screenshots</div>
+          <div className="display-row">
+            <label className="display-option">
+              <input
+                type="checkbox"
+                checked={screenshots.enabled}
+                onChange={(e) => changeScreenshots({ ...screenshots, enabled: e.target.checked })}
+              />
+              Notice new screenshots
+            </label>
+            <label className="display-option">
+              <input
+                type="checkbox"
+                checked={screenshots.remark}
+                disabled={!screenshots.enabled}
+                onChange={(e) => changeScreenshots({ ...screenshots, remark: e.target.checked })}
+              />
+              Comment without asking
+            </label>
+          </div>
+          <div className="personality-hint">
+            A screenshot is only sent to the model when you ask about it, or as soon as it's taken with commenting on
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Sound</div>
+          <div className="display-row">
+            <label className="display-option">
+              Volume
+              <input
+                type="range"
+                className="opacity-slider"
+                min={0}
+                max={100}
+                step={5}
+                disabled={!sound}
+                value={Math.round((volume ?? sound?.volume ?? 0) * 100)}
+                onChange={(e) => setVolume(Number(e.target.value) / 100)}
+                onPointerUp={commitVolume}
+                onKeyUp={commitVolume}
+              />
+              {Math.round((volume ?? sound?.volume ?? 0) * 100)}%
+            </label>
+            <label className="display-option">
+              <input
+                type="checkbox"
+                checked={sound?.muted ?? false}
+                disabled={!sound}
+                onChange={(e) => invoke("set_sound_muted", { muted: e.target.checked }).catch(() => {})}
+              />
+              Mute
+            </label>
+          </div>
+          <div className="display-row">
+            <label className="display-option">
+              Soundscape
+              <select
+                className="personality-select"
+                disabled={!sound}
+                value={sound?.musicPlaying ? sound.soundscape : ""}
+                onChange={(e) => invoke("set_soundscape", { soundscape: e.target.value || null }).catch(() => {})}
+              >
+                <option value="">Off</option>
+                {(Object.keys(SOUNDSCAPE_LABELS) as Soundscape[]).map((scene) => (
+                  <option key={scene} value={scene} disabled={scene === "lofi" && !sound?.musicFolder}>
+                    {SOUNDSCAPE_LABELS[scene]}
+                  </option>
+                ))}
+              </select>
+            </label>
+            <label className="display-option">
+              Stop after
+              <select
+                className="personality-select"
+                disabled={!sound?.musicPlaying}
+                value={sound?.sleepMinutes ?? ""}
+                onChange={(e) =>
+                  invoke("set_sleep_timer", { minutes: e.target.value ? Number(e.target.value) : null }).catch(() => {})
+                }
+              >
+                <option value="">Never</option>
+                <option value={15}>15 minutes</option>
+                <option value={30}>30 minutes</option>
+                <option value={60}>1 hour</option>
+                <option value={120}>2 hours</option>
+              </select>
+            </label>
+          </div>
+          <div className="display-row">
+            <input
+              className="pet-add-input"
+              type="text"
+              value={musicFolder ?? sound?.musicFolder ?? ""}
+              placeholder="Music folder for lo-fi"
+              onChange={(e) => setMusicFolder(e.target.value)}
+              onBlur={commitMusicFolder}
+              onKeyDown={(e) => { if (e.key === "Enter") commitMusicFolder(); }}
+            />
+          </div>
+          {sound?.musicPlaying && sound.track && <div className="tool-audit">Playing {sound.track}</div>}
+          {sound && !sound.available && <div className="sync-error">No audio output found</div>}
+          {soundError && <div className="sync-error">{soundError}</div>}
+          <div className="personality-hint">
+            MP3, WAV, FLAC and Ogg files in the folder play in name order, crossfading. Music pauses during calls and
+            fades out over the sleep timer's last minute
+          </div>
+        </div>
+
+        <div className="display-section">
+          <div className="section-label">Things the Cat Can Do</div>
+          {tools.map((tool) => (
+            <div className="display-row" key={tool.name}>
+              <span className="tool-label">{tool.label}</span>
+              <select
+                className="personality-select"
+                value={tool.permission}
+                onChange={(e) => changeToolPermission(tool.name, e.target.value as ToolPermission)}
+              >
+                <option value="allow">Just do it</option>
+                <option value="ask">Ask me first</option>
+                <option value="deny">Never</option>
+              </select>
+            </div>
+          ))}
+          <textarea
+            className="exclusion-list"
+            rows={3}
+            value={allowedApps}
+            placeholder="Apps"
+            onChange={(e) => setAllowedApps(e.target.value)}
+            onBlur={commitAllowlist}
+          />
+          <textarea
+            className="exclusion-list"
+            rows={3}
+            value={allowedSites}
+            placeholder="Sites, like github.com"
+            onChange={(e) => setAllowedSites(e.target.value)}
+            onBlur={commitAllowlist}
+          />
+          <div className="personality-hint">
+            One per line. These open without asking, from the palette or the cat
+          </div>
+          {toolAudit.length > 0 && (
+            <ul className="tool-audit">
+              {toolAudit.slice(-5).reverse().map((entry) => (
+                <li key={`${entry.at}-${entry.tool}`} title={entry.detail ?? undefined}>
+                  {new Date(entry.at).toLocaleString()}: {entry.summary}
+                  {entry.outcome === "denied" ? " (not allowed)" : entry.outcome === "failed" ? " (failed)" : ""}
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+
+        <div className="memory-section">
+          <div className="section-label">Cats</div>
+          {pets && pets.pets.length > 1 && (
+            <select
+              className="memory-session-select"
+              value={pets.active}
+              onChange={(e) => switchPet(e.target.value)}
+            >
+              {pets.pets.map((pet) => (
+                <option key={pet.id} value={pet.id}>{pet.name}</option>
+              ))}
+            </select>
+          )}
+          <div className="pet-add-row">
+            <input
+              className="pet-add-input"
+              type="text"
+              value={newPetName}
+              maxLength={30}
+              placeholder="Another cat's name"
+              onChange={(e) => setNewPetName(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && addPet()}
+            />
+            <button className="memory-confirm-no" onClick={addPet} disabled={!newPetName.trim()}>
+              Add Cat
+            </button>
+          </div>
         </div>
 
         <div className="memory-section">
@@ -258,6 +1150,61 @@ export default function SettingsPanel({
             <span className="memory-dot">&middot;</span>
             <span>{memoryStats.factCount} remembered fact{memoryStats.factCount !== 1 ? "s" : ""}</span>
           </div>
+          {sessions.length > 1 && (
+            <select
+              className="memory-session-select"
+              value={sessions.find((session) => session.active)?.id ?? ""}
+              onChange={(e) => switchSession("load_session", e.target.value)}
+            >
+              {sessions.map((session) => (
+                <option key={session.id} value={session.id}>
+                  {session.title}
+                  {session.lastAt ? ` (${new Date(session.lastAt).toLocaleDateString()})` : ""}
+                </option>
+              ))}
+            </select>
+          )}
+          <button
+            className="memory-clear-btn"
+            onClick={() => switchSession("start_new_session")}
+            disabled={memoryStats.messageCount === 0}
+            title="Start fresh; remembered facts carry over"
+          >
+            New Conversation
+          </button>
+          {factProposals ? (
+            <div className="fact-review">
+              {factProposals.length === 0 ? (
+                <div className="fact-review-empty">Everything the cat remembers still looks current.</div>
+              ) : (
+                factProposals.map((proposal) => (
+                  <div key={proposal.text} className="fact-review-item">
+                    <span className="fact-review-text">{proposal.text}</span>
+                    <span className="fact-review-reason">{proposal.reason}</span>
+                  </div>
+                ))
+              )}
+              <div className="memory-confirm">
+                {factProposals.length > 0 && (
+                  <button className="memory-confirm-yes" onClick={archiveProposedFacts}>
+                    Archive {factProposals.length}
+                  </button>
+                )}
+                <button className="memory-confirm-no" onClick={() => setFactProposals(null)}>
+                  {factProposals.length > 0 ? "Keep all" : "OK"}
+                </button>
+              </div>
+            </div>
+          ) : (
+            <button
+              className="memory-clear-btn"
+              onClick={reviewFacts}
+              disabled={reviewingFacts || memoryStats.factCount === 0}
+              title="Ask the cat which facts are out of date; nothing changes until you say so"
+            >
+              {reviewingFacts ? "Reviewing..." : "Review Facts"}
+            </button>
+          )}
           {confirmingClear ? (
             <div className="memory-confirm">
               <span className="memory-confirm-text">Clear all memory?</span>
@@ -287,6 +1234,119 @@ export default function SettingsPanel({
               Clear Memory
             </button>
           )}
+          {confirmingWipe ? (
+            <div className="memory-confirm">
+              <span className="memory-confirm-text">Delete all history, stats and the diary?</span>
+              <button
+                className="memory-confirm-yes"
+                onClick={() => {
+                  invoke("wipe_all_data").catch(() => {});
+                  setConfirmingWipe(false);
+                }}
+              >
+                Yes, wipe
+              </button>
+              <button
+                className="memory-confirm-no"
+                onClick={() => setConfirmingWipe(false)}
+              >
+                Cancel
+              </button>
+            </div>
+          ) : (
+            <button className="memory-clear-btn" onClick={() => setConfirmingWipe(true)}>
+              Wipe All Data
+            </button>
+          )}
+        </div>
+
+        <div className="memory-section">
+          <div className="section-label">Sync</div>
+          {syncStatus?.target ? (
+            <>
+              <div className="memory-stats">
+                <span>
+                  {syncStatus.target.kind === "relay"
+                    ? "Through the friends relay"
+                    : syncStatus.target.kind === "webDav"
+                      ? syncStatus.target.url
+                      : `s3://${syncStatus.target.bucket}`}
+                </span>
+                <span className="memory-dot">&middot;</span>
+                <span>
+                  {syncStatus.syncing
+                    ? "Syncing..."
+                    : syncStatus.lastSyncedAt
+                      ? `Synced ${new Date(syncStatus.lastSyncedAt).toLocaleString()}`
+                      : "Not synced yet"}
+                </span>
+              </div>
+              {syncStatus.target.kind === "relay" && (
+                <div className="personality-hint">
+                  Sync code for your other Macs: <code>{syncStatus.target.code}</code>
+                </div>
+              )}
+              {syncStatus.lastError && <div className="sync-error">{syncStatus.lastError}</div>}
+              <div className="memory-confirm">
+                <button
+                  className="memory-confirm-no"
+                  onClick={() => syncNow().catch(() => {})}
+                  disabled={syncStatus.syncing}
+                >
+                  Sync Now
+                </button>
+                <button className="memory-confirm-no" onClick={() => invoke("disable_sync").catch(() => {})}>
+                  Turn Off
+                </button>
+              </div>
+            </>
+          ) : (
+            <div className="sync-form">
+              <select
+                className="memory-session-select"
+                value={syncKind}
+                onChange={(e) => setSyncKind(e.target.value as SyncTarget["kind"])}
+              >
+                <option value="relay">Friends relay</option>
+                <option value="webDav">WebDAV</option>
+                <option value="s3">S3 bucket</option>
+              </select>
+              {syncFieldInputs[syncKind].map(([name, placeholder]) => (
+                <input
+                  key={name}
+                  className="pet-add-input"
+                  type="text"
+                  value={syncField(name)}
+                  placeholder={placeholder}
+                  onChange={(e) => setSyncField(name, e.target.value)}
+                />
+              ))}
+              {syncKind !== "relay" && (
+                <input
+                  className="pet-add-input"
+                  type="password"
+                  value={syncSecret}
+                  placeholder={syncKind === "s3" ? "Secret access key" : "Password"}
+                  onChange={(e) => setSyncSecret(e.target.value)}
+                />
+              )}
+              <input
+                className="pet-add-input"
+                type="password"
+                value={syncPassphrase}
+                placeholder="Passphrase, the same on every Mac"
+                onChange={(e) => setSyncPassphrase(e.target.value)}
+              />
+              {syncError && <div className="sync-error">{syncError}</div>}
+              <button className="memory-clear-btn" onClick={enableSync} disabled={syncPassphrase.length < 8}>
+                Turn On Sync
+              </button>
+              <div className="personality-hint">
+                Memory, facts, achievements and settings, encrypted before they leave. A Mac joining
+                takes what's already synced
+              </div>
+            </div>
+          )}
         </div>
 
         <div className="import-section">
@@ -1,4 +1,6 @@
 import { useRef, useEffect, useState } from "react";
+import { openUrl } from "../lib/opener";
+import type { Citation } from "../hooks/useDialogue";
 import "../styles/bubble.css";
 
 interface SpeechBubbleProps {
@@ -6,9 +8,22 @@ interface SpeechBubbleProps {
   x: number;
   y: number;
   hiding: boolean;
+  source?: Citation | null;
+  /** Dark wallpaper or dark mode behind the cat: use the dark bubble */
+  dark?: boolean;
+  /** Buttons under the text, for questions the cat asks */
+  actions?: { label: string; onClick: () => void }[];
 }
 
-export default function SpeechBubble({ text, x, y, hiding }: SpeechBubbleProps) {
+function hostname(url: string): string {
+  try {
+    return new URL(url).hostname.replace(/^www\./, "");
+  } catch {
+    return url;
+  }
+}
+
+export default function SpeechBubble({ text, x, y, hiding, source, dark, actions }: SpeechBubbleProps) {
   const contentRef = useRef<HTMLDivElement>(null);
   const [height, setHeight] = useState(40);
 
@@ -16,7 +31,7 @@ export default function SpeechBubble({ text, x, y, hiding }: SpeechBubbleProps)
     if (contentRef.current) {
       setHeight(contentRef.current.offsetHeight);
     }
-  }, [text]);
+  }, [text, source, actions]);
 
   // Position bubble so the tail points at the cat, bubble goes upward
   const bubbleTop = y - 32 - height - 20; // 32 = half pet, 20 = tail + gap
@@ -26,7 +41,7 @@ export default function SpeechBubble({ text, x, y, hiding }: SpeechBubbleProps)
 
   return (
     <div
-      className={`speech-bubble ${hiding ? "hiding" : ""} ${aboveScreen ? "below" : ""}`}
+      className={`speech-bubble ${hiding ? "hiding" : ""} ${aboveScreen ? "below" : ""} ${dark ? "dark" : ""}`}
       style={{
         left: x,
         top: finalTop,
@@ -36,6 +51,28 @@ export default function SpeechBubble({ text, x, y, hiding }: SpeechBubbleProps)
       {!aboveScreen && <div className="bubble-tail-top" />}
       <div ref={contentRef} className="bubble-content">
         {text}
+        {source && (
+          <a
+            className="bubble-source"
+            href={source.url}
+            title={source.title ?? source.url}
+            onClick={(e) => {
+              e.preventDefault();
+              openUrl(source.url).catch(() => {});
+            }}
+          >
+            {hostname(source.url)}
+          </a>
+        )}
+        {actions && actions.length > 0 && (
+          <div className="bubble-actions">
+            {actions.map((action) => (
+              <button key={action.label} className="bubble-action" onClick={action.onClick}>
+                {action.label}
+              </button>
+            ))}
+          </div>
+        )}
       </div>
       {aboveScreen && <div className="bubble-tail-bottom" />}
     </div>
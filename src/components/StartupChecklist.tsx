@@ -0,0 +1,74 @@
+import { FixIt, StartupReport } from "../hooks/useStartupReport";
+import "../styles/startup.css";
+
+interface StartupChecklistProps {
+  report: StartupReport;
+  fixing: string | null;
+  error: string | null;
+  onFix: (fix: FixIt) => void;
+  onClose: () => void;
+}
+
+const STATUS_ICONS: Record<string, string> = {
+  ok: "✓",
+  warning: "!",
+  error: "✕",
+};
+
+export default function StartupChecklist({
+  report,
+  fixing,
+  error,
+  onFix,
+  onClose,
+}: StartupChecklistProps) {
+  // Problems first, in the order they were checked
+  const checks = [
+    ...report.checks.filter((c) => c.status === "error"),
+    ...report.checks.filter((c) => c.status === "warning"),
+    ...report.checks.filter((c) => c.status === "ok"),
+  ];
+
+  return (
+    <div className="startup-overlay" onClick={onClose}>
+      <div className="startup-panel" onClick={(e) => e.stopPropagation()}>
+        <div className="startup-header">
+          <span className="startup-title">
+            {report.ok ? "All set!" : "A few things need a look"}
+          </span>
+          <button className="startup-close" onClick={onClose}>
+            x
+          </button>
+        </div>
+
+        <ul className="startup-checks">
+          {checks.map((check, i) => (
+            <li key={`${check.id}-${i}`} className={`startup-check ${check.status}`}>
+              <span className="startup-icon">{STATUS_ICONS[check.status]}</span>
+              <div className="startup-body">
+                <div className="startup-check-title">{check.title}</div>
+                {check.detail && <div className="startup-detail">{check.detail}</div>}
+                {check.fixes.length > 0 && (
+                  <div className="startup-fixes">
+                    {check.fixes.map((fix) => (
+                      <button
+                        key={fix.label}
+                        className="startup-fix"
+                        disabled={fixing !== null}
+                        onClick={() => onFix(fix)}
+                      >
+                        {fixing === fix.label ? "..." : fix.label}
+                      </button>
+                    ))}
+                  </div>
+                )}
+              </div>
+            </li>
+          ))}
+        </ul>
+
+        {error && <div className="startup-error">{error}</div>}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,37 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import "../styles/notes.css";
+
+interface Sticky {
+  id: string;
+  text: string;
+  x: number;
+  y: number;
+  createdAt: number;
+}
+
+// A note the cat taped to the screen, alone in its own small window
+export default function StickyWindow() {
+  const id = getCurrentWindow().label.slice("sticky-".length);
+  const [text, setText] = useState("");
+
+  useEffect(() => {
+    invoke<Sticky[]>("get_sticky_notes")
+      .then((stickies) => setText(stickies.find((s) => s.id === id)?.text ?? ""))
+      .catch(() => {});
+  }, [id]);
+
+  return (
+    <div className="sticky-note pinned" data-tauri-drag-region>
+      <button
+        className="sticky-note-close"
+        title="Take it down"
+        onClick={() => invoke("remove_sticky_note", { id }).catch(() => getCurrentWindow().close())}
+      >
+        x
+      </button>
+      <div className="sticky-note-text" data-tauri-drag-region>{text}</div>
+    </div>
+  );
+}
@@ -0,0 +1,80 @@
+import { useState, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import type { DialogueResponse } from "../hooks/useDialogue";
+import "../styles/popover.css";
+
+interface Exchange {
+  user: string;
+  cat: string;
+}
+
+// Chat window shown under the tray icon in menu-bar-only mode
+export default function TrayPopover() {
+  const [input, setInput] = useState("");
+  const [history, setHistory] = useState<Exchange[]>([]);
+  const [loading, setLoading] = useState(false);
+  const inputRef = useRef<HTMLInputElement>(null);
+  const logRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) inputRef.current?.focus();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    logRef.current?.scrollTo({ top: logRef.current.scrollHeight });
+  }, [history, loading]);
+
+  const send = async () => {
+    const text = input.trim();
+    if (!text || loading) return;
+    setInput("");
+    setLoading(true);
+    try {
+      const response = await invoke<DialogueResponse>("generate_pet_dialogue", {
+        appName: "",
+        windowTitle: "",
+        trigger: `user said: ${text}`,
+        mode: "chat",
+        userInput: text,
+      });
+      setHistory((prev) => [...prev, { user: text, cat: response.text }]);
+    } catch {
+      setHistory((prev) => [...prev, { user: text, cat: "*ears flatten* Something went wrong." }]);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div className="tray-popover">
+      <div className="tray-popover-log" ref={logRef}>
+        {history.length === 0 && !loading && <div className="tray-popover-empty">Mrrp? Say something.</div>}
+        {history.map((exchange, i) => (
+          <div key={i}>
+            <div className="tray-popover-user">{exchange.user}</div>
+            <div className="tray-popover-cat">{exchange.cat}</div>
+          </div>
+        ))}
+        {loading && <div className="tray-popover-cat">...</div>}
+      </div>
+      <input
+        ref={inputRef}
+        className="tray-popover-input"
+        value={input}
+        placeholder="Chat with your cat"
+        onChange={(e) => setInput(e.target.value)}
+        onKeyDown={(e) => {
+          if (e.key === "Enter") send();
+          if (e.key === "Escape") getCurrentWindow().hide();
+        }}
+        autoFocus
+      />
+    </div>
+  );
+}
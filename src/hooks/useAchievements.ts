@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { EventData } from "./useEventTracker";
 
 export interface AchievementDef {
@@ -135,6 +136,8 @@ function persistUnlocked(map: UnlockedMap) {
   localStorage.setItem(STORAGE_KEY, JSON.stringify(map));
 }
 
+const TIER_RANK: Record<string, number> = { bronze: 1, silver: 2, gold: 3 };
+
 // Count total unlocked (tiered gold counts as 1, any tier counts as 1)
 function countUnlocked(map: UnlockedMap): number {
   return Object.keys(map).length;
@@ -174,7 +177,9 @@ export function useAchievements(eventData: EventData) {
       }
       if (highestTier) {
         const existing = current[def.id];
-        if (!existing || existing.tier !== highestTier) {
+        // Only ever upward: a tier synced from another machine can be ahead
+        // of this one's counters
+        if (!existing || TIER_RANK[highestTier] > (TIER_RANK[existing.tier ?? ""] ?? 0)) {
           updates[def.id] = { tier: highestTier, unlockedAt: Date.now() };
           newUnlocks.push({ id: def.id, name: def.name, icon: def.icon, tier: highestTier });
         }
@@ -226,6 +231,17 @@ export function useAchievements(eventData: EventData) {
     }
   }, [eventData]);
 
+  // Another machine had unlocked more; the backend sends the merged set
+  useEffect(() => {
+    const unlisten = listen<UnlockedMap>("achievements-synced", (event) => {
+      persistUnlocked(event.payload);
+      setUnlocked(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const dismissToast = useCallback(() => {
     setNewlyUnlocked((q) => q.slice(1));
   }, []);
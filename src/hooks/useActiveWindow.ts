@@ -1,11 +1,81 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { usePowerSaving, usePowerSuspended } from "./usePowerState";
 
 interface WindowInfo {
   app_name: string;
   window_title: string;
 }
 
+interface AppUsage {
+  date: string;
+  seconds: Record<string, number>;
+}
+
+const USAGE_KEY = "pet-app-usage";
+const YEAR_USAGE_KEY = "pet-app-usage-year";
+const POLL_MS = 5000;
+const SAVING_POLL_MS = 15000;
+
+// YYYY-MM-DD in local time, so the day rolls over at the owner's midnight
+function today(): string {
+  const now = new Date();
+  const month = String(now.getMonth() + 1).padStart(2, "0");
+  const day = String(now.getDate()).padStart(2, "0");
+  return `${now.getFullYear()}-${month}-${day}`;
+}
+
+function loadUsage(): AppUsage {
+  try {
+    const raw = localStorage.getItem(USAGE_KEY);
+    if (raw) {
+      const usage: AppUsage = JSON.parse(raw);
+      if (usage.date === today()) return usage;
+    }
+  } catch { /* ignore */ }
+  return { date: today(), seconds: {} };
+}
+
+function recordUsage(appName: string, seconds: number) {
+  const usage = loadUsage();
+  usage.seconds[appName] = (usage.seconds[appName] ?? 0) + seconds;
+  localStorage.setItem(USAGE_KEY, JSON.stringify(usage));
+
+  const year = loadYearUsage();
+  year.seconds[appName] = (year.seconds[appName] ?? 0) + seconds;
+  localStorage.setItem(YEAR_USAGE_KEY, JSON.stringify(year));
+}
+
+/** Same tally as loadUsage, but reset on January 1st; `date` holds the year. */
+function loadYearUsage(): AppUsage {
+  const year = today().slice(0, 4);
+  try {
+    const raw = localStorage.getItem(YEAR_USAGE_KEY);
+    if (raw) {
+      const usage: AppUsage = JSON.parse(raw);
+      if (usage.date === year) return usage;
+    }
+  } catch { /* ignore */ }
+  return { date: year, seconds: {} };
+}
+
+function formatTop(seconds: Record<string, number>, limit: number): string[] {
+  return Object.entries(seconds)
+    .sort(([, a], [, b]) => b - a)
+    .slice(0, limit)
+    .map(([app, s]) => `${app} (${Math.round(s / 60)}m)`);
+}
+
+/** Today's most-used apps, e.g. ["Figma (95m)", "Slack (40m)"]. */
+export function topAppsToday(limit = 5): string[] {
+  return formatTop(loadUsage().seconds, limit);
+}
+
+/** This year's most-used apps, same format as topAppsToday. */
+export function topAppsThisYear(limit = 5): string[] {
+  return formatTop(loadYearUsage().seconds, limit);
+}
+
 interface ActiveWindowState {
   appName: string;
   windowTitle: string;
@@ -17,13 +87,19 @@ export function useActiveWindow(): ActiveWindowState {
   const [windowTitle, setWindowTitle] = useState("");
   const [appChanged, setAppChanged] = useState(false);
   const prevAppRef = useRef("Unknown");
+  const suspended = usePowerSuspended();
+  const saving = usePowerSaving();
 
   useEffect(() => {
+    // Nothing to watch behind a locked screen
+    if (suspended) return;
+    const pollMs = saving ? SAVING_POLL_MS : POLL_MS;
     const poll = async () => {
       try {
         const info = await invoke<WindowInfo>("get_active_window_info");
         setAppName(info.app_name);
         setWindowTitle(info.window_title);
+        recordUsage(info.app_name, pollMs / 1000);
 
         if (info.app_name !== prevAppRef.current) {
           setAppChanged(true);
@@ -37,9 +113,9 @@ export function useActiveWindow(): ActiveWindowState {
     };
 
     poll();
-    const interval = setInterval(poll, 5000);
+    const interval = setInterval(poll, pollMs);
     return () => clearInterval(interval);
-  }, []);
+  }, [suspended, saving]);
 
   return { appName, windowTitle, appChanged };
 }
@@ -0,0 +1,45 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface Appearance {
+  dark: boolean;
+}
+
+interface WallpaperColors {
+  colors: string[];
+  luminance: number;
+  dark: boolean;
+}
+
+/**
+ * Whether the pet's surroundings are dark: the wallpaper right behind it
+ * when the backend can read it, otherwise the system's dark mode.
+ */
+export function useDarkSurroundings(x: number, y: number, settled: boolean): boolean {
+  const [systemDark, setSystemDark] = useState(false);
+  const [wallpaperDark, setWallpaperDark] = useState<boolean | null>(null);
+
+  useEffect(() => {
+    invoke<Appearance>("get_system_appearance")
+      .then((appearance) => setSystemDark(appearance.dark))
+      .catch(() => {});
+    const unlisten = listen<Appearance>("appearance-changed", (event) => {
+      setSystemDark(event.payload.dark);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Resample once the pet settles somewhere new, or the system flips
+  // (which often swaps the wallpaper too)
+  useEffect(() => {
+    if (!settled) return;
+    invoke<WallpaperColors>("sample_wallpaper_colors", { x, y })
+      .then((colors) => setWallpaperDark(colors.dark))
+      .catch(() => setWallpaperDark(null));
+  }, [x, y, settled, systemDark]);
+
+  return wallpaperDark ?? systemDark;
+}
@@ -0,0 +1,58 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface ClipOffer {
+  id: string;
+  kind: "url" | "article" | "code";
+  preview: string;
+  chars: number;
+  suggestion: string;
+}
+
+/** How long the cat waits for an answer before dropping the offer. */
+const OFFER_TIMEOUT_MS = 20000;
+
+/**
+ * The latest thing the backend offered to help with after a copy. Accepting
+ * hands it back by id; the copied text itself never passes through here.
+ */
+export function useClipboardOffer(onAccept: (id: string) => void) {
+  const [offer, setOffer] = useState<ClipOffer | null>(null);
+  const timerRef = useRef<ReturnType<typeof setTimeout>>();
+
+  const decline = useCallback(() => {
+    if (timerRef.current) clearTimeout(timerRef.current);
+    setOffer(null);
+    invoke("dismiss_clipboard_offer").catch(() => {});
+  }, []);
+
+  const accept = useCallback(() => {
+    if (!offer) return;
+    if (timerRef.current) clearTimeout(timerRef.current);
+    setOffer(null);
+    onAccept(offer.id);
+  }, [offer, onAccept]);
+
+  useEffect(() => {
+    const unlistenOffer = listen<ClipOffer>("clipboard-offer", (event) => {
+      if (timerRef.current) clearTimeout(timerRef.current);
+      setOffer(event.payload);
+      timerRef.current = setTimeout(decline, OFFER_TIMEOUT_MS);
+    });
+    // The kill switch in the tray drops whatever is showing too
+    const unlistenWatch = listen<boolean>("clipboard-watch-changed", (event) => {
+      if (!event.payload) {
+        if (timerRef.current) clearTimeout(timerRef.current);
+        setOffer(null);
+      }
+    });
+    return () => {
+      unlistenOffer.then((fn) => fn());
+      unlistenWatch.then((fn) => fn());
+      if (timerRef.current) clearTimeout(timerRef.current);
+    };
+  }, [decline]);
+
+  return { offer, accept, decline };
+}
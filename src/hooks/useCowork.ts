@@ -0,0 +1,57 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface CoworkPet {
+  id: string;
+  name: string;
+  breed: string;
+  color: string;
+}
+
+export interface CoworkSession {
+  id: string;
+  friend: CoworkPet;
+  hosting: boolean;
+  startedAt: number;
+  endsAt: number;
+  phase: "active" | "ended";
+  myReaction: string | null;
+  friendReaction: string | null;
+}
+
+export const COWORK_REACTIONS = ["🐾", "🔥", "💪", "🎉", "😺", "😴"];
+
+/** The co-working session with a friend, kept by the backend and synced over the relay. */
+export function useCowork(myPetId: string | null) {
+  const [session, setSession] = useState<CoworkSession | null>(null);
+
+  // The backend only looks for sessions once it knows who we are
+  useEffect(() => {
+    invoke("set_cowork_pet", { petId: myPetId }).catch(() => {});
+  }, [myPetId]);
+
+  useEffect(() => {
+    invoke<CoworkSession | null>("get_cowork").then(setSession).catch(() => {});
+    const unlisten = listen<CoworkSession | null>("cowork-changed", (event) => {
+      setSession(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const start = useCallback(async (me: CoworkPet, friend: CoworkPet, minutes?: number) => {
+    await invoke("start_cowork", { me, friend, minutes });
+  }, []);
+
+  const stop = useCallback(() => {
+    invoke("stop_cowork").catch(() => {});
+  }, []);
+
+  const react = useCallback((emoji: string) => {
+    invoke("send_cowork_reaction", { emoji }).catch(() => {});
+  }, []);
+
+  return { session, start, stop, react };
+}
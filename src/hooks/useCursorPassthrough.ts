@@ -1,5 +1,7 @@
 import { useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { usePowerSaving, usePowerSuspended } from "./usePowerState";
 
 interface HitZone {
   x: number;
@@ -19,8 +21,25 @@ export function useCursorPassthrough({ petX, petY, overlayOpen, extraHitZones }:
   const ignoring = useRef(false); // match initial Rust state (false)
   const configRef = useRef({ petX, petY, overlayOpen, extraHitZones });
   configRef.current = { petX, petY, overlayOpen, extraHitZones };
+  const suspended = usePowerSuspended();
+  const saving = usePowerSaving();
 
+  // Entering or leaving ghost mode leaves the window click-through, so
+  // start from there rather than from what was last asked for
+  const ghost = useRef(false);
   useEffect(() => {
+    const unlisten = listen<{ ghost: boolean }>("overlay-changed", (event) => {
+      if (event.payload.ghost !== ghost.current) {
+        ghost.current = event.payload.ghost;
+        ignoring.current = true;
+      }
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  useEffect(() => {
+    // No cursor to track while the Mac is asleep or locked
+    if (suspended) return;
     let running = true;
     let consecutiveErrors = 0;
 
@@ -78,11 +97,11 @@ export function useCursorPassthrough({ petX, petY, overlayOpen, extraHitZones }:
             }
           }
         }
-        await new Promise((r) => setTimeout(r, 50));
+        await new Promise((r) => setTimeout(r, saving ? 150 : 50));
       }
     };
 
     poll();
     return () => { running = false; };
-  }, []);
+  }, [suspended, saving]);
 }
@@ -1,6 +1,9 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import { usePowerSuspended } from "./usePowerState";
+import { useMeeting } from "./useMeeting";
+import type { ScreenshotAction } from "./useScreenshotOffer";
 
 const FALLBACK_MESSAGES = [
   "Mrrrow... *stretches*",
@@ -13,13 +16,47 @@ const FALLBACK_MESSAGES = [
   "I see you working hard!",
 ];
 
+export interface Citation {
+  url: string;
+  title: string | null;
+}
+
+export type Emotion = "happy" | "sleepy" | "annoyed" | "curious";
+
+export interface DialogueResponse {
+  text: string;
+  mode: string;
+  model: string | null;
+  inputTokens: number;
+  outputTokens: number;
+  webSearchUsed: boolean;
+  citations: Citation[];
+  notes: { id: string; text: string; createdAt: number }[];
+  watchers: { id: string; query: string; intervalMinutes: number }[];
+  mood: number | null;
+  emotion: Emotion | null;
+  offline: boolean;
+}
+
 interface DialogueState {
   text: string;
+  /** Where a web search answer came from, shown as a link under the bubble */
+  source: Citation | null;
+  /** How the cat feels saying the current line, for picking an animation */
+  emotion: Emotion | null;
+  /** Mode the current line was generated in; null for announcements */
+  mode: string | null;
   visible: boolean;
   hiding: boolean;
   loading: boolean;
   muted: boolean;
   generate: (mode: string, trigger: string, userInput?: string) => void;
+  /** Translate into `target` (a tag like "es"), or the pet's language when null */
+  translate: (text: string, target: string | null, sourceApp?: string) => void;
+  /** Answer a clipboard offer the user accepted */
+  askAboutClipboard: (id: string) => void;
+  /** Remark on a new screenshot, or read the text out of it */
+  askAboutScreenshot: (id: string, action: ScreenshotAction) => void;
   dismiss: () => void;
 }
 
@@ -30,6 +67,9 @@ export function useDialogue(
   onNoteCreated?: (text: string) => void,
 ): DialogueState {
   const [text, setText] = useState("");
+  const [source, setSource] = useState<Citation | null>(null);
+  const [emotion, setEmotion] = useState<Emotion | null>(null);
+  const [mode, setMode] = useState<string | null>(null);
   const [visible, setVisible] = useState(false);
   const [hiding, setHiding] = useState(false);
   const [loading, setLoading] = useState(false);
@@ -37,10 +77,14 @@ export function useDialogue(
   const hideTimerRef = useRef<ReturnType<typeof setTimeout>>();
   const spontaneousTimerRef = useRef<ReturnType<typeof setTimeout>>();
   const busyRef = useRef(false);
+  const suspended = usePowerSuspended();
+  // On a call the cat only answers when spoken to
+  const inMeeting = useMeeting();
 
   useEffect(() => {
-    const unlisten = listen("toggle-mute", () => {
-      setMuted((prev) => !prev);
+    // "Pause Dialogue" lives in the tray menu, which owns the flag
+    const unlisten = listen<boolean>("dialogue-paused", (event) => {
+      setMuted(event.payload);
     });
     return () => {
       unlisten.then((fn) => fn());
@@ -54,14 +98,18 @@ export function useDialogue(
       setVisible(false);
       setHiding(false);
       setText("");
+      setSource(null);
+      setEmotion(null);
+      setMode(null);
     }, 400);
   }, []);
 
   const showDialogue = useCallback(
-    (message: string, duration?: number) => {
+    (message: string, duration?: number, citation?: Citation) => {
       if (hideTimerRef.current) clearTimeout(hideTimerRef.current);
       setHiding(false);
       setText(message);
+      setSource(citation ?? null);
       setVisible(true);
       setLoading(false);
 
@@ -73,34 +121,42 @@ export function useDialogue(
     [dismiss],
   );
 
-  const generate = useCallback(
-    async (mode: string, trigger: string, userInput?: string) => {
+  // Unprompted lines from backend jobs (watchers, trackers)
+  useEffect(() => {
+    const unlisten = listen<string>("pet-announce", (event) => {
+      if (muted) return;
+      const message = event.payload;
+      setMode(null);
+      showDialogue(message, Math.min(20000, Math.max(3000, message.length * 80)));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [muted, showDialogue]);
+
+  const respond = useCallback(
+    async (request: () => Promise<DialogueResponse>) => {
       if (busyRef.current || muted) return;
       busyRef.current = true;
       setLoading(true);
       setVisible(true);
       setHiding(false);
       setText("...");
+      setSource(null);
+      setEmotion(null);
+      setMode(null);
 
       try {
-        let response = await invoke<string>("generate_pet_dialogue", {
-          appName,
-          windowTitle,
-          trigger,
-          mode,
-          userInput: userInput ?? "",
-        });
+        const response = await request();
 
-        // Check for [NOTE: ...] tags and extract notes
-        const noteMatch = response.match(/\[NOTE:\s*(.+?)\]/);
-        if (noteMatch) {
-          onNoteCreated?.(noteMatch[1].trim());
-          response = response.replace(/\[NOTE:\s*.+?\]/, "").trim();
-        }
+        // Notes arrive pre-extracted from [NOTE: ...] tags
+        response.notes.forEach((note) => onNoteCreated?.(note.text));
 
         // Scale duration by message length: ~80ms per character, clamped to 3-20s
-        const duration = Math.min(20000, Math.max(3000, response.length * 80));
-        showDialogue(response, duration);
+        const duration = Math.min(20000, Math.max(3000, response.text.length * 80));
+        showDialogue(response.text, duration, response.citations[0]);
+        setEmotion(response.emotion);
+        setMode(response.mode);
       } catch {
         const fallback =
           FALLBACK_MESSAGES[Math.floor(Math.random() * FALLBACK_MESSAGES.length)];
@@ -109,7 +165,47 @@ export function useDialogue(
         busyRef.current = false;
       }
     },
-    [appName, windowTitle, muted, showDialogue, onNoteCreated],
+    [muted, showDialogue, onNoteCreated],
+  );
+
+  const generate = useCallback(
+    (mode: string, trigger: string, userInput?: string) => {
+      if (inMeeting && (mode === "spontaneous" || mode === "react")) return Promise.resolve();
+      return respond(() =>
+        invoke<DialogueResponse>("generate_pet_dialogue", {
+          appName,
+          windowTitle,
+          trigger,
+          mode,
+          userInput: userInput ?? "",
+        }),
+      );
+    },
+    [appName, windowTitle, inMeeting, respond],
+  );
+
+  const translate = useCallback(
+    (text: string, target: string | null, sourceApp?: string) =>
+      respond(() =>
+        invoke<DialogueResponse>("translate_text", { text, target, appName: sourceApp ?? null }),
+      ),
+    [respond],
+  );
+
+  const askAboutClipboard = useCallback(
+    (id: string) => respond(() => invoke<DialogueResponse>("ask_about_clipboard", { id })),
+    [respond],
+  );
+
+  const askAboutScreenshot = useCallback(
+    (id: string, action: ScreenshotAction) =>
+      respond(() => invoke<DialogueResponse>("ask_about_screenshot", { id, action })),
+    [respond],
+  );
+
+  const systemReport = useCallback(
+    () => respond(() => invoke<DialogueResponse>("generate_system_report")),
+    [respond],
   );
 
   // Spontaneous dialogue - much less frequent
@@ -124,11 +220,13 @@ export function useDialogue(
       }, delay);
     };
 
+    // Talking to an empty room still costs API calls
+    if (suspended) return;
     scheduleSpontaneous();
     return () => {
       if (spontaneousTimerRef.current) clearTimeout(spontaneousTimerRef.current);
     };
-  }, [visible, muted, generate]);
+  }, [visible, muted, suspended, generate]);
 
   // React to app changes (less frequent - 20% chance)
   useEffect(() => {
@@ -137,5 +235,5 @@ export function useDialogue(
     }
   }, [appChanged, appName, visible, muted, generate]);
 
-  return { text, visible, hiding, loading, muted, generate, dismiss };
+  return { text, source, emotion, mode, visible, hiding, loading, muted, generate, translate, askAboutClipboard, askAboutScreenshot, systemReport, dismiss };
 }
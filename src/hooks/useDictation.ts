@@ -0,0 +1,137 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface DictationDraft {
+  id: string;
+  preview: string;
+  chars: number;
+  appName: string;
+}
+
+// WebKit only ships the prefixed constructor, and TypeScript's DOM types
+// don't cover it
+interface Recognition {
+  lang: string;
+  interimResults: boolean;
+  continuous: boolean;
+  onresult: ((event: { results: ArrayLike<ArrayLike<{ transcript: string }>> }) => void) | null;
+  onerror: ((event: { error: string }) => void) | null;
+  onend: (() => void) | null;
+  start(): void;
+  stop(): void;
+}
+
+type RecognitionConstructor = new () => Recognition;
+
+function recognitionConstructor(): RecognitionConstructor | undefined {
+  const w = window as unknown as {
+    SpeechRecognition?: RecognitionConstructor;
+    webkitSpeechRecognition?: RecognitionConstructor;
+  };
+  return w.SpeechRecognition ?? w.webkitSpeechRecognition;
+}
+
+/** How long a draft waits for a yes before it's dropped. */
+const DRAFT_TIMEOUT_MS = 30000;
+
+/**
+ * "Tell the cat what to type": listens once, hands the transcription to the
+ * backend as a draft, and only has it typed after the user confirms it.
+ * `onProblem` gets anything worth saying out loud.
+ */
+export function useDictation(onProblem: (message: string) => void) {
+  const [listening, setListening] = useState(false);
+  const [draft, setDraft] = useState<DictationDraft | null>(null);
+  const recognitionRef = useRef<Recognition | null>(null);
+  const timerRef = useRef<ReturnType<typeof setTimeout>>();
+
+  const discard = useCallback(() => {
+    if (timerRef.current) clearTimeout(timerRef.current);
+    setDraft(null);
+    invoke("discard_dictation").catch(() => {});
+  }, []);
+
+  const stage = useCallback(
+    async (text: string) => {
+      try {
+        const staged = await invoke<DictationDraft>("stage_dictation", { text });
+        if (timerRef.current) clearTimeout(timerRef.current);
+        setDraft(staged);
+        timerRef.current = setTimeout(discard, DRAFT_TIMEOUT_MS);
+      } catch (e) {
+        onProblem((e as { message?: string }).message ?? String(e));
+      }
+    },
+    [discard, onProblem],
+  );
+
+  /** Start listening, or stop early and use what was heard so far. */
+  const toggle = useCallback(() => {
+    if (recognitionRef.current) {
+      recognitionRef.current.stop();
+      return;
+    }
+    const Constructor = recognitionConstructor();
+    if (!Constructor) {
+      onProblem("I can't hear anything from in here, sorry.");
+      return;
+    }
+    const recognition = new Constructor();
+    recognition.lang = navigator.language;
+    recognition.interimResults = false;
+    recognition.continuous = false;
+    let heard = "";
+    recognition.onresult = (event) => {
+      heard = Array.from(event.results, (result) => result[0].transcript).join(" ");
+    };
+    recognition.onerror = (event) => {
+      if (event.error === "not-allowed") {
+        onProblem("I need the microphone to hear you.");
+      }
+    };
+    recognition.onend = () => {
+      recognitionRef.current = null;
+      setListening(false);
+      if (heard.trim()) stage(heard);
+    };
+    recognitionRef.current = recognition;
+    setDraft(null);
+    setListening(true);
+    recognition.start();
+  }, [onProblem, stage]);
+
+  const confirm = useCallback(async () => {
+    if (!draft) return;
+    if (timerRef.current) clearTimeout(timerRef.current);
+    setDraft(null);
+    try {
+      await invoke("type_dictation", { id: draft.id });
+    } catch (e) {
+      onProblem((e as { message?: string }).message ?? String(e));
+    }
+  }, [draft, onProblem]);
+
+  useEffect(() => {
+    // Turning dictation off drops whatever is waiting
+    const unlisten = listen<boolean>("dictation-changed", (event) => {
+      if (event.payload) return;
+      if (recognitionRef.current) {
+        // Stop without staging what was heard
+        recognitionRef.current.onend = null;
+        recognitionRef.current.stop();
+        recognitionRef.current = null;
+        setListening(false);
+      }
+      if (timerRef.current) clearTimeout(timerRef.current);
+      setDraft(null);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+      recognitionRef.current?.stop();
+      if (timerRef.current) clearTimeout(timerRef.current);
+    };
+  }, []);
+
+  return { listening, draft, toggle, confirm, discard };
+}
@@ -1,5 +1,11 @@
 import { useState, useEffect, useCallback, useRef } from "react";
-import { supabase, generatePetCode, PetRow, VisitRow } from "../lib/supabase";
+import { invoke } from "@tauri-apps/api/core";
+import { supabase, generatePetCode, PetRow, VisitRow, GuestInteractionRow } from "../lib/supabase";
+
+interface ModerationVerdict {
+  showVisit: boolean;
+  showMessage: boolean;
+}
 
 export type FriendStatus = "mutual" | "pending_outgoing" | "pending_incoming";
 
@@ -255,6 +261,13 @@ export function useFriends(breed: string, color: string) {
     await refreshFriends();
   }, [refreshFriends]);
 
+  // Block: their visits and messages are dropped by the backend, and the
+  // friendship goes too
+  const blockFriend = useCallback(async (friendId: string, name: string) => {
+    await invoke("block_sender", { petId: friendId, name }).catch(() => {});
+    await removeFriend(friendId);
+  }, [removeFriend]);
+
   // Send visit
   const sendVisit = useCallback(async (toFriendId: string, message = "") => {
     if (!myPetIdRef.current) return;
@@ -304,7 +317,19 @@ export function useFriends(breed: string, color: string) {
   useEffect(() => {
     if (!myPetId) return;
 
-    const consumeVisit = async (visit: VisitRow) => {
+    const consumeVisit = async (raw: VisitRow) => {
+      // Blocked senders are dropped and filtered messages stripped before
+      // anything reaches the screen
+      const verdict = await invoke<ModerationVerdict>("moderate_visit_message", {
+        fromPetId: raw.from_pet_id,
+        fromName: raw.name,
+        message: raw.message,
+      }).catch(() => ({ showVisit: true, showMessage: false }));
+      if (!verdict.showVisit) {
+        await supabase.from("visits").update({ consumed: true }).eq("id", raw.id);
+        return;
+      }
+      const visit = verdict.showMessage ? raw : { ...raw, message: "" };
       const current = currentVisitRef.current;
 
       if (current && current.fromPetId === visit.from_pet_id) {
@@ -372,6 +397,40 @@ export function useFriends(breed: string, color: string) {
     };
   }, [myPetId]);
 
+  // Guest interactions from stream viewers: forwarded as-is, the backend
+  // decides what gets through (and nothing does unless guest mode is on)
+  useEffect(() => {
+    if (!myPetId) return;
+
+    const channel = supabase
+      .channel(`guests-${myPetId}`)
+      .on(
+        "postgres_changes",
+        {
+          event: "INSERT",
+          schema: "public",
+          table: "guest_interactions",
+          filter: `to_pet_id=eq.${myPetId}`,
+        },
+        (payload) => {
+          const row = payload.new as GuestInteractionRow;
+          invoke("submit_guest_interaction", {
+            interaction: {
+              guestName: row.guest_name,
+              action: row.action,
+              message: row.message,
+              sender: row.sender,
+            },
+          }).catch(() => {});
+        }
+      )
+      .subscribe();
+
+    return () => {
+      supabase.removeChannel(channel);
+    };
+  }, [myPetId]);
+
   return {
     myPetId,
     myPetCode,
@@ -385,6 +444,7 @@ export function useFriends(breed: string, color: string) {
     addFriend,
     acceptFriend,
     removeFriend,
+    blockFriend,
     sendVisit,
     startHangout,
     setMyPetName,
@@ -0,0 +1,29 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface FullscreenStatus {
+  active: boolean;
+  appName: string | null;
+  behavior: "ignore" | "corner" | "hide";
+}
+
+/** True while another app is fullscreen and the pet should tuck itself away. */
+export function useFullscreenTuck(): boolean {
+  const [tucked, setTucked] = useState(false);
+
+  useEffect(() => {
+    const apply = (status: FullscreenStatus) => {
+      setTucked(status.active && status.behavior === "corner");
+    };
+    invoke<FullscreenStatus>("get_fullscreen_status").then(apply).catch(() => {});
+    const unlisten = listen<FullscreenStatus>("fullscreen-changed", (event) => {
+      apply(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return tucked;
+}
@@ -1,11 +1,15 @@
 import { useState, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { EventData } from "./useEventTracker";
+import { topAppsToday } from "./useActiveWindow";
+import type { DialogueResponse } from "./useDialogue";
 
 export interface JournalEntry {
   date: string;
   text: string;
   generatedAt: number;
+  /** 1 (rough day) to 5 (great day); also kept in the backend for the mood calendar */
+  mood?: number;
 }
 
 const STORAGE_KEY = "pet-journal";
@@ -52,9 +56,11 @@ export function useJournal(eventData: EventData) {
         petClicks: eventData.petClicks,
         streak: eventData.currentStreak,
         activeDays: eventData.activeDays.length,
+        // Dropped by the backend unless the journal settings allow it
+        topApps: topAppsToday(),
       };
 
-      const text = await invoke<string>("generate_pet_dialogue", {
+      const { text, mood } = await invoke<DialogueResponse>("generate_pet_dialogue", {
         appName: "",
         windowTitle: "",
         trigger: JSON.stringify(summary),
@@ -66,6 +72,7 @@ export function useJournal(eventData: EventData) {
         date: today(),
         text,
         generatedAt: Date.now(),
+        mood: mood ?? undefined,
       };
 
       setEntries((prev) => {
@@ -0,0 +1,26 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface MeetingStatus {
+  inMeeting: boolean;
+  app: string | null;
+  since: number | null;
+}
+
+/** Whether the owner is on a video call, as the backend detects it. */
+export function useMeeting() {
+  const [inMeeting, setInMeeting] = useState(false);
+
+  useEffect(() => {
+    invoke<MeetingStatus>("get_meeting_status").then((status) => setInMeeting(status.inMeeting)).catch(() => {});
+    const unlistenStarted = listen<MeetingStatus>("meeting-started", () => setInMeeting(true));
+    const unlistenEnded = listen("meeting-ended", () => setInMeeting(false));
+    return () => {
+      unlistenStarted.then((fn) => fn());
+      unlistenEnded.then((fn) => fn());
+    };
+  }, []);
+
+  return inMeeting;
+}
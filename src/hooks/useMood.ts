@@ -0,0 +1,36 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { Emotion } from "./useDialogue";
+
+export interface Mood {
+  value: number;
+  label: string;
+  reason: string | null;
+  /** The face to wear when the cat isn't talking */
+  face: Emotion | null;
+  attention: number;
+  play: number;
+  rest: number;
+  ignoredMinutes: number;
+}
+
+export type Interaction = "chat" | "petted" | "played" | "napped" | "shooed";
+
+/** Tell the mood engine about something the owner did. */
+export function recordInteraction(interaction: Interaction) {
+  invoke("record_interaction", { interaction }).catch(() => {});
+}
+
+/** The cat's mood, kept by the backend and pushed on every change. */
+export function useMood() {
+  const [mood, setMood] = useState<Mood | null>(null);
+
+  useEffect(() => {
+    invoke<Mood>("get_mood").then(setMood).catch(() => {});
+    const unlisten = listen<Mood>("mood-changed", (event) => setMood(event.payload));
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  return mood;
+}
@@ -0,0 +1,46 @@
+import { useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface WindowRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+interface Occlusion {
+  occluded: boolean;
+  appName: string | null;
+  bounds: WindowRect | null;
+}
+
+/**
+ * Tell the backend where the pet has settled, and call `onOccluding` when
+ * it turns out to be standing on the window the user is working in.
+ */
+export function useOcclusion(
+  x: number,
+  y: number,
+  settled: boolean,
+  onOccluding: (bounds: WindowRect) => void,
+) {
+  const onOccludingRef = useRef(onOccluding);
+  onOccludingRef.current = onOccluding;
+
+  useEffect(() => {
+    if (!settled) return;
+    invoke("set_pet_spot", { spot: [x, y] }).catch(() => {});
+  }, [x, y, settled]);
+
+  useEffect(() => {
+    const unlisten = listen<Occlusion>("pet-occluding", (event) => {
+      if (event.payload.occluded && event.payload.bounds) {
+        onOccludingRef.current(event.payload.bounds);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+}
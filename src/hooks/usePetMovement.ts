@@ -17,6 +17,7 @@ interface PetMovement {
   setDragging: (d: boolean) => void;
   goHome: () => void;
   leaveHome: () => void;
+  walkTo: (pos: Position) => void;
   nap: () => void;
   wake: () => void;
 }
@@ -72,6 +73,14 @@ export function usePetMovement(): PetMovement {
     localStorage.setItem("pet-position", JSON.stringify(position));
   }, [position]);
 
+  // The overlay is moved to another display when the home one changes or a
+  // display is blocked; keep the cat inside the new bounds
+  useEffect(() => {
+    const onResize = () => setPositionRaw((prev) => clampPosition(prev));
+    window.addEventListener("resize", onResize);
+    return () => window.removeEventListener("resize", onResize);
+  }, []);
+
   // Walking animation loop
   useEffect(() => {
     if (state !== "walking") return;
@@ -141,6 +150,18 @@ export function usePetMovement(): PetMovement {
     setStateInternal("walking");
   }, []);
 
+  // Walk somewhere specific, staying home or idle on arrival as before
+  const walkTo = useCallback((pos: Position) => {
+    const target = clampPosition(pos);
+    prevStateRef.current = stateRef.current === "home" ? "home" : "idle";
+    targetRef.current = target;
+    setPositionRaw((prev) => {
+      setFacingLeft(target.x < prev.x);
+      return prev;
+    });
+    setStateInternal("walking");
+  }, []);
+
   const nap = useCallback(() => {
     setStateInternal("napping");
   }, []);
@@ -189,6 +210,6 @@ export function usePetMovement(): PetMovement {
   return {
     position, state, facingLeft, dragging,
     setState, setPosition, setDragging,
-    goHome, leaveHome, nap, wake,
+    goHome, leaveHome, walkTo, nap, wake,
   };
 }
@@ -0,0 +1,51 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface PowerStatus {
+  batteryPercent: number | null;
+  charging: boolean;
+  onBattery: boolean;
+  lowPowerMode: boolean;
+  saving: boolean;
+}
+
+interface PowerStatePayload {
+  event: "sleep" | "wake" | "lock" | "unlock";
+  suspended: boolean;
+}
+
+/** True while the Mac is asleep or locked, as reported by the backend. */
+export function usePowerSuspended(): boolean {
+  const [suspended, setSuspended] = useState(false);
+
+  useEffect(() => {
+    const unlisten = listen<PowerStatePayload>("power-state", (event) => {
+      setSuspended(event.payload.suspended);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return suspended;
+}
+
+/** True while the battery is low and the pet should poll less often. */
+export function usePowerSaving(): boolean {
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    invoke<PowerStatus>("get_power_status")
+      .then((status) => setSaving(status.saving))
+      .catch(() => {});
+    const unlisten = listen<boolean>("power-saving", (event) => {
+      setSaving(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return saving;
+}
@@ -0,0 +1,61 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface ScreenshotOffer {
+  id: string;
+  name: string;
+  remark: boolean;
+}
+
+export type ScreenshotAction = "remark" | "text";
+
+/** How long the cat waits for an answer before dropping the offer. */
+const OFFER_TIMEOUT_MS = 20000;
+
+/**
+ * The latest screenshot the backend noticed. Accepting hands it back by id
+ * with what to do; the image itself never passes through here. With remarks
+ * turned on the cat just comments, without offering.
+ */
+export function useScreenshotOffer(onAccept: (id: string, action: ScreenshotAction) => void) {
+  const [offer, setOffer] = useState<ScreenshotOffer | null>(null);
+  const timerRef = useRef<ReturnType<typeof setTimeout>>();
+  const onAcceptRef = useRef(onAccept);
+  onAcceptRef.current = onAccept;
+
+  const decline = useCallback(() => {
+    if (timerRef.current) clearTimeout(timerRef.current);
+    setOffer(null);
+    invoke("dismiss_screenshot_offer").catch(() => {});
+  }, []);
+
+  const accept = useCallback(
+    (action: ScreenshotAction) => {
+      if (!offer) return;
+      if (timerRef.current) clearTimeout(timerRef.current);
+      setOffer(null);
+      onAccept(offer.id, action);
+    },
+    [offer, onAccept],
+  );
+
+  useEffect(() => {
+    const unlisten = listen<ScreenshotOffer>("screenshot-taken", (event) => {
+      if (timerRef.current) clearTimeout(timerRef.current);
+      if (event.payload.remark) {
+        setOffer(null);
+        onAcceptRef.current(event.payload.id, "remark");
+        return;
+      }
+      setOffer(event.payload);
+      timerRef.current = setTimeout(decline, OFFER_TIMEOUT_MS);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+      if (timerRef.current) clearTimeout(timerRef.current);
+    };
+  }, [decline]);
+
+  return { offer, accept, decline };
+}
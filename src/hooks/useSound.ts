@@ -0,0 +1,37 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export type Soundscape = "drone" | "rain" | "cafe" | "lofi";
+
+export const SOUNDSCAPE_LABELS: Record<Soundscape, string> = {
+  drone: "Warm hum",
+  rain: "Rain",
+  cafe: "Café",
+  lofi: "Lo-fi (music folder)",
+};
+
+export interface SoundStatus {
+  volume: number;
+  muted: boolean;
+  soundscape: Soundscape;
+  musicFolder: string | null;
+  musicPlaying: boolean;
+  track: string | null;
+  sleepAt: number | null;
+  sleepMinutes: number | null;
+  available: boolean;
+}
+
+/** What the backend is playing; null until it first answers. */
+export function useSound() {
+  const [status, setStatus] = useState<SoundStatus | null>(null);
+
+  useEffect(() => {
+    invoke<SoundStatus>("get_sound_status").then(setStatus).catch(() => {});
+    const unlisten = listen<SoundStatus>("sound-changed", (event) => setStatus(event.payload));
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  return status;
+}
@@ -0,0 +1,75 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { openUrl } from "../lib/opener";
+
+export type CheckStatus = "ok" | "warning" | "error";
+
+export type FixAction =
+  | { type: "openUrl"; url: string }
+  | { type: "requestPermission"; kind: string }
+  | { type: "openPermissionSettings"; kind: string }
+  | { type: "openDataFolder" }
+  | { type: "migrateData" }
+  | { type: "useFreePort" };
+
+export interface FixIt {
+  label: string;
+  action: FixAction;
+}
+
+export interface StartupCheck {
+  id: string;
+  status: CheckStatus;
+  title: string;
+  detail: string | null;
+  fixes: FixIt[];
+}
+
+export interface StartupReport {
+  checks: StartupCheck[];
+  ok: boolean;
+  checkedAt: number;
+}
+
+/**
+ * The self-check the backend runs at launch. The checklist opens by itself
+ * when something needs attention and stays closed once dismissed.
+ */
+export function useStartupReport() {
+  const [report, setReport] = useState<StartupReport | null>(null);
+  const [open, setOpen] = useState(false);
+  const [fixing, setFixing] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const show = (next: StartupReport) => {
+      setReport(next);
+      if (!next.ok) setOpen(true);
+    };
+    const unlisten = listen<StartupReport>("startup-report", (event) => show(event.payload));
+    // The launch check may have finished before this listener existed
+    invoke<StartupReport>("get_startup_report").then(show).catch(() => {});
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  const runFix = useCallback(async (fix: FixIt) => {
+    setError(null);
+    if (fix.action.type === "openUrl") {
+      openUrl(fix.action.url).catch(() => {});
+      return;
+    }
+    setFixing(fix.label);
+    try {
+      setReport(await invoke<StartupReport>("run_fix_it", { action: fix.action }));
+    } catch (e) {
+      setError((e as { message?: string }).message ?? String(e));
+    } finally {
+      setFixing(null);
+    }
+  }, []);
+
+  const close = useCallback(() => setOpen(false), []);
+
+  return { report, open, fixing, error, runFix, close };
+}
@@ -0,0 +1,62 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export type SyncTarget =
+  | { kind: "relay"; code: string }
+  | { kind: "webDav"; url: string; username: string }
+  | { kind: "s3"; endpoint: string; region: string; bucket: string; accessKeyId: string };
+
+export interface SyncStatus {
+  target: SyncTarget | null;
+  lastSyncedAt: number | null;
+  lastError: string | null;
+  syncing: boolean;
+}
+
+export interface SyncReport {
+  pushed: string[];
+  pulled: string[];
+}
+
+const SYNC_INTERVAL_MS = 15 * 60 * 1000;
+
+/** Sync now, handing the backend the achievements it can't see itself. */
+export function syncNow(): Promise<SyncReport> {
+  let achievements = null;
+  try {
+    const raw = localStorage.getItem("pet-achievements");
+    if (raw) achievements = JSON.parse(raw);
+  } catch { /* ignore */ }
+  return invoke<SyncReport>("sync_now", { achievements });
+}
+
+/** Where sync goes and how the last round went, kept by the backend. */
+export function useSyncStatus() {
+  const [status, setStatus] = useState<SyncStatus | null>(null);
+
+  useEffect(() => {
+    invoke<SyncStatus>("get_sync_status").then(setStatus).catch(() => {});
+    const unlisten = listen<SyncStatus>("sync-changed", (event) => {
+      setStatus(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return status;
+}
+
+/** Sync at launch and every so often after, once sync is set up. */
+export function useAutoSync() {
+  const status = useSyncStatus();
+  const enabled = status?.target != null;
+
+  useEffect(() => {
+    if (!enabled) return;
+    syncNow().catch(() => {});
+    const id = setInterval(() => syncNow().catch(() => {}), SYNC_INTERVAL_MS);
+    return () => clearInterval(id);
+  }, [enabled]);
+}
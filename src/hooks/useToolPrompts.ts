@@ -0,0 +1,43 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface ToolRequest {
+  id: string;
+  tool: string;
+  summary: string;
+}
+
+/**
+ * Questions from the backend before the cat uses a tool ("Open
+ * ~/Documents/Timesheet.xlsx?"). Only the oldest is shown; the backend
+ * takes no answer within a minute as a no.
+ */
+export function useToolPrompts() {
+  const [requests, setRequests] = useState<ToolRequest[]>([]);
+
+  useEffect(() => {
+    const unlistenRequested = listen<ToolRequest>("tool-permission-requested", (event) => {
+      setRequests((queue) => [...queue, event.payload]);
+    });
+    const unlistenExpired = listen<string>("tool-permission-expired", (event) => {
+      setRequests((queue) => queue.filter((request) => request.id !== event.payload));
+    });
+    return () => {
+      unlistenRequested.then((fn) => fn());
+      unlistenExpired.then((fn) => fn());
+    };
+  }, []);
+
+  const answer = useCallback((allow: boolean, remember = false) => {
+    setRequests((queue) => {
+      const [current, ...rest] = queue;
+      if (current) {
+        invoke("answer_tool_permission", { id: current.id, allow, remember }).catch(() => {});
+      }
+      return rest;
+    });
+  }, []);
+
+  return { request: requests[0] ?? null, answer };
+}
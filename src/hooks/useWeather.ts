@@ -0,0 +1,35 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export type WeatherCondition =
+  | "clear"
+  | "partlyCloudy"
+  | "cloudy"
+  | "fog"
+  | "drizzle"
+  | "rain"
+  | "snow"
+  | "thunderstorm";
+
+export interface Weather {
+  location: string;
+  condition: WeatherCondition;
+  temperature: number;
+  fahrenheit: boolean;
+  isDay: boolean;
+  fetchedAt: number;
+}
+
+/** Conditions where the owner set their location; null until one is set. */
+export function useWeather() {
+  const [weather, setWeather] = useState<Weather | null>(null);
+
+  useEffect(() => {
+    invoke<Weather | null>("get_weather").then(setWeather).catch(() => {});
+    const unlisten = listen<Weather | null>("weather-changed", (event) => setWeather(event.payload));
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  return weather;
+}
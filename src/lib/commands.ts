@@ -1,7 +1,7 @@
 import type { MenuAction } from "../components/RadialMenu";
 
 export interface Command {
-  id: MenuAction | "chat-inline" | "search-inline";
+  id: MenuAction | "chat-inline" | "search-inline" | "translate" | "files" | "open";
   label: string;
   icon: string;
   keywords: string[];
@@ -12,6 +12,9 @@ export interface Command {
 export const commands: Command[] = [
   { id: "chat", label: "Chat", icon: "💬", keywords: ["talk", "say", "message"], hint: "chat <message>", takesArgument: true },
   { id: "search", label: "Search", icon: "🔍", keywords: ["find", "look", "query", "ask"], hint: "search <query>", takesArgument: true },
+  { id: "translate", label: "Translate", icon: "🌐", keywords: ["language", "meaning", "foreign"], hint: "translate [es:] <text>", takesArgument: true },
+  { id: "files", label: "Files", icon: "📁", keywords: ["find", "spotlight", "document", "pdf", "open"], hint: "files <query>", takesArgument: true },
+  { id: "open", label: "Open", icon: "🚀", keywords: ["launch", "app", "website", "url", "start"], hint: "open <app or site>", takesArgument: true },
   { id: "music", label: "Play Music", icon: "🎵", keywords: ["song", "audio", "sound", "mute", "stop"] },
   { id: "nap", label: "Nap", icon: "😴", keywords: ["sleep", "rest", "zzz"] },
   { id: "home", label: "Go Home", icon: "🏠", keywords: ["house", "return", "bed"] },
@@ -20,4 +23,10 @@ export const commands: Command[] = [
   { id: "achievements", label: "Achievements", icon: "🏆", keywords: ["trophies", "badges", "awards", "unlocks"] },
   { id: "friends", label: "Friends", icon: "👋", keywords: ["social", "visit", "hangout", "pets"] },
   { id: "notes", label: "Notes", icon: "📝", keywords: ["sticky", "memo", "write", "reminder"] },
+  { id: "wrapped", label: "Pet Wrapped", icon: "🎁", keywords: ["recap", "year", "review", "stats"] },
+  { id: "card", label: "Pet Card", icon: "🃏", keywords: ["trading", "profile", "share", "export", "png"] },
+  { id: "perch", label: "Sit on Menu Bar", icon: "🪜", keywords: ["perch", "top", "clock", "climb"] },
+  { id: "dock", label: "Nap on Dock", icon: "🛏️", keywords: ["icon", "sleep", "bottom", "app"] },
+  { id: "chase", label: "Chase the Cursor", icon: "🐭", keywords: ["play", "game", "mouse", "catch"] },
+  { id: "report", label: "How's the Computer?", icon: "🩺", keywords: ["health", "disk", "memory", "updates", "uptime", "system"] },
 ];
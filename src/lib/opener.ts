@@ -0,0 +1,9 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Open a web address in the default browser, through the opener plugin.
+ * Only http(s), mailto and tel links are allowed by the capability.
+ */
+export function openUrl(url: string): Promise<void> {
+  return invoke("plugin:opener|open_url", { url });
+}
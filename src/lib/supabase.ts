@@ -34,6 +34,17 @@ export interface VisitRow {
   consumed: boolean;
 }
 
+/** Stream viewers' pets, treats and messages, checked by the backend */
+export interface GuestInteractionRow {
+  id: string;
+  to_pet_id: string;
+  guest_name: string;
+  action: "pet" | "treat" | "message";
+  message: string | null;
+  sender: string | null;
+  created_at: string;
+}
+
 const CHARSET = "ABCDEFGHJKMNPQRSTUVWXYZ23456789";
 
 export function generatePetCode(): string {
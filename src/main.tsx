@@ -1,9 +1,18 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import App from "./App";
+import TrayPopover from "./components/TrayPopover";
+import StickyWindow from "./components/StickyWindow";
+
+// The same bundle serves the overlay, the menu-bar chat popover and the
+// sticky notes taped to the screen
+const label = getCurrentWindow().label;
+const isPopover = label === "popover";
+const isSticky = label.startsWith("sticky-");
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {isPopover ? <TrayPopover /> : isSticky ? <StickyWindow /> : <App />}
   </React.StrictMode>,
 );